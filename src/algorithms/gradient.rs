@@ -0,0 +1,319 @@
+use crate::{
+    circuit::Circuit,
+    components::{
+        gate::Gate,
+        operator::{Operator, ParameterisedKind, ParameterisedOperator},
+        parameter::{Parameter, ParameterExpression},
+        pauli_string::SumOp,
+        state::State,
+    },
+    errors::Error,
+};
+use num_complex::Complex;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::f64::consts::{FRAC_PI_2, SQRT_2};
+
+/// Methods for computing the gradient of an expectation value with respect to circuit parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GradientMethod {
+    /// The parameter-shift rule.
+    ///
+    /// Each derivative is a weighted sum of expectation values of the circuit with one gate's angle shifted, so it
+    /// can also be used with estimates from sampling. Uncontrolled gates need two circuit evaluations per
+    /// occurrence of a parameter, and controlled gates four.
+    ParameterShift,
+
+    /// The adjoint differentiation method.
+    ///
+    /// Computes the exact gradient from one forward and one backward pass over the circuit, storing only two state
+    /// vectors. The circuit must not contain measurements or classically controlled gates.
+    Adjoint,
+}
+
+/// A parameterised gate of a circuit, with the value of its angle.
+struct ParameterisedGate<'a> {
+    /// The index of the gate in the circuit.
+    index: usize,
+    /// The operator of the gate.
+    operator: &'a ParameterisedOperator,
+    /// The target qubit of the gate.
+    target: usize,
+    /// The control qubits of the gate.
+    controls: &'a [usize],
+    /// The value of the angle of the gate.
+    angle: f64,
+}
+
+/// Helper function to find the parameterised gates of a circuit and evaluate their angles.
+fn parameterised_gates<'a>(
+    circuit: &'a Circuit,
+    values: &HashMap<Parameter, f64>,
+) -> Result<Vec<ParameterisedGate<'a>>, Error> {
+    let mut gates: Vec<ParameterisedGate<'a>> = Vec::new();
+    for (index, gate) in circuit.gates.iter().enumerate() {
+        if gate.parameters().is_empty() {
+            continue;
+        }
+        let Gate::Operator(operator, targets, controls) = gate else {
+            // Classically controlled gates are not differentiated
+            return Err(Error::NonDifferentiableOperator(format!("{:?}", gate)));
+        };
+        let operator: &ParameterisedOperator = operator
+            .to_parameterised()
+            .ok_or_else(|| Error::NonDifferentiableOperator(format!("{:?}", operator)))?;
+        gates.push(ParameterisedGate {
+            index,
+            operator,
+            target: targets[0],
+            controls,
+            angle: operator.angle().evaluate(values)?,
+        });
+    }
+    Ok(gates)
+}
+
+/// Helper function to bind all parameters of a circuit, returning an error if any are left unbound.
+fn bind_all(circuit: &Circuit, values: &HashMap<Parameter, f64>) -> Result<Circuit, Error> {
+    let bound: Circuit = circuit.bind(values);
+    match bound.parameters().first() {
+        Some(parameter) => Err(Error::UnboundParameter(parameter.name().to_string())),
+        None => Ok(bound),
+    }
+}
+
+/// Computes the gradient of an expectation value with respect to the parameters of a circuit.
+///
+/// The expectation value is Re⟨ψ(θ)|H|ψ(θ)⟩, where |ψ(θ)⟩ is the result of executing the circuit on the initial state
+/// with the given parameter values.
+///
+/// # Arguments
+///
+/// * `circuit` - The parameterised circuit.
+/// * `observable` - The observable H, as a sum of Pauli strings.
+/// * `initial_state` - The state the circuit is executed on.
+/// * `values` - The values of all parameters of the circuit.
+/// * `method` - The method used to compute the gradient.
+///
+/// # Returns
+///
+/// * `Result<Vec<f64>, Error>` - The partial derivatives with respect to each parameter, in the order of
+///   `Circuit::parameters`.
+///
+/// # Errors
+///
+/// * Returns an error if a parameter of the circuit has no value.
+/// * Returns an error if a gate depends on parameters but is not a `ParameterisedOperator`.
+/// * Returns an error if the circuit or the observable cannot be applied to the initial state.
+/// * Returns an error if the adjoint method is used on a circuit with measurements or without known adjoints.
+pub fn expectation_gradient(
+    circuit: &Circuit,
+    observable: &SumOp,
+    initial_state: &State,
+    values: &HashMap<Parameter, f64>,
+    method: GradientMethod,
+) -> Result<Vec<f64>, Error> {
+    match method {
+        GradientMethod::ParameterShift => {
+            parameter_shift_gradient(circuit, observable, initial_state, values)
+        }
+        GradientMethod::Adjoint => adjoint_gradient(circuit, observable, initial_state, values),
+    }
+}
+
+/// Computes the gradient of an expectation value using the parameter-shift rule.
+///
+/// See `expectation_gradient` for details of the arguments and errors.
+///
+/// # Returns
+///
+/// * `Result<Vec<f64>, Error>` - The partial derivatives with respect to each parameter, in the order of
+///   `Circuit::parameters`.
+pub fn parameter_shift_gradient(
+    circuit: &Circuit,
+    observable: &SumOp,
+    initial_state: &State,
+    values: &HashMap<Parameter, f64>,
+) -> Result<Vec<f64>, Error> {
+    parameter_shift_gradient_with(circuit, values, |shifted: &Circuit| {
+        let state: State = shifted.execute(initial_state)?;
+        Ok(observable.expectation_value(&state)?.re)
+    })
+}
+
+/// Computes the gradient of a function of a circuit using the parameter-shift rule.
+///
+/// The estimator is called on copies of the circuit with all parameters bound and a single gate's angle shifted, and
+/// may return an estimate of the expectation value, such as one computed from measurement samples. The shifted
+/// circuits are evaluated in parallel.
+///
+/// The rule is exact for expectation values. Uncontrolled rotations and phase shifts use the two-term rule
+/// f'(θ) = [f(θ + π/2) - f(θ - π/2)] / 2, and controlled ones the four-term rule with additional shifts of ±3π/2.
+///
+/// # Arguments
+///
+/// * `circuit` - The parameterised circuit.
+/// * `values` - The values of all parameters of the circuit.
+/// * `estimator` - A function returning the value to differentiate for a circuit without parameters.
+///
+/// # Returns
+///
+/// * `Result<Vec<f64>, Error>` - The partial derivatives with respect to each parameter, in the order of
+///   `Circuit::parameters`.
+///
+/// # Errors
+///
+/// * Returns an error if a parameter of the circuit has no value.
+/// * Returns an error if a gate depends on parameters but is not a `ParameterisedOperator`.
+/// * Returns an error if the estimator returns an error.
+pub fn parameter_shift_gradient_with<F>(
+    circuit: &Circuit,
+    values: &HashMap<Parameter, f64>,
+    estimator: F,
+) -> Result<Vec<f64>, Error>
+where
+    F: Fn(&Circuit) -> Result<f64, Error> + Sync,
+{
+    let parameters: Vec<Parameter> = circuit.parameters();
+    let gates: Vec<ParameterisedGate> = parameterised_gates(circuit, values)?;
+    let bound: Circuit = bind_all(circuit, values)?;
+
+    // Coefficients of the four-term rule for generators with eigenvalues {0, ±1/2}
+    let c_plus: f64 = (SQRT_2 + 1.0) / (4.0 * SQRT_2);
+    let c_minus: f64 = (SQRT_2 - 1.0) / (4.0 * SQRT_2);
+    let two_term: [(f64, f64); 2] = [(FRAC_PI_2, 0.5), (-FRAC_PI_2, -0.5)];
+    let four_term: [(f64, f64); 4] = [
+        (FRAC_PI_2, c_plus),
+        (-FRAC_PI_2, -c_plus),
+        (3.0 * FRAC_PI_2, -c_minus),
+        (-3.0 * FRAC_PI_2, c_minus),
+    ];
+
+    // Each shifted evaluation contributes (weight * coefficient) * f(shifted) to the derivative of a parameter
+    let mut shifts: Vec<(usize, &ParameterisedGate, f64, f64)> = Vec::new();
+    for gate in &gates {
+        let rule: &[(f64, f64)] = if gate.controls.is_empty() { &two_term } else { &four_term };
+        for (parameter_index, parameter) in parameters.iter().enumerate() {
+            let coefficient: f64 = gate.operator.angle().coefficient(parameter);
+            if coefficient == 0.0 {
+                continue;
+            }
+            for &(shift, weight) in rule {
+                shifts.push((parameter_index, gate, shift, weight * coefficient));
+            }
+        }
+    }
+
+    let contributions: Vec<(usize, f64)> = shifts
+        .par_iter()
+        .map(|&(parameter_index, gate, shift, weight)| {
+            let mut shifted: Circuit = Circuit {
+                gates: bound.gates.clone(),
+                num_qubits: bound.num_qubits,
+                num_bits: bound.num_bits,
+            };
+            let angle: ParameterExpression = ParameterExpression::constant(gate.angle + shift);
+            shifted.gates[gate.index] = Gate::Operator(
+                gate.operator.kind().operator(angle),
+                vec![gate.target],
+                gate.controls.to_vec(),
+            );
+            Ok((parameter_index, weight * estimator(&shifted)?))
+        })
+        .collect::<Result<Vec<(usize, f64)>, Error>>()?;
+
+    let mut gradient: Vec<f64> = vec![0.0; parameters.len()];
+    for (parameter_index, contribution) in contributions {
+        gradient[parameter_index] += contribution;
+    }
+    Ok(gradient)
+}
+
+/// Computes the exact gradient of an expectation value using the adjoint differentiation method.
+///
+/// The state is evolved through the circuit once, then the state and H|ψ⟩ are evolved backwards through the adjoint
+/// of each gate, accumulating the derivative of every parameterised gate on the way. This needs memory for only two
+/// state vectors, and time proportional to the number of gates.
+///
+/// See `expectation_gradient` for details of the arguments and errors.
+///
+/// # Returns
+///
+/// * `Result<Vec<f64>, Error>` - The partial derivatives with respect to each parameter, in the order of
+///   `Circuit::parameters`.
+pub fn adjoint_gradient(
+    circuit: &Circuit,
+    observable: &SumOp,
+    initial_state: &State,
+    values: &HashMap<Parameter, f64>,
+) -> Result<Vec<f64>, Error> {
+    let parameters: Vec<Parameter> = circuit.parameters();
+    let mut gates: Vec<ParameterisedGate> = parameterised_gates(circuit, values)?;
+    let bound: Circuit = bind_all(circuit, values)?;
+    if bound.gates.iter().any(|gate| !gate.is_unitary()) {
+        return Err(Error::NonUnitaryCircuit);
+    }
+
+    let mut psi: State = bound.execute(initial_state)?;
+    let mut lambda: State = observable.apply(&psi)?;
+    let mut gradient: Vec<f64> = vec![0.0; parameters.len()];
+
+    for (index, gate) in bound.gates.iter().enumerate().rev() {
+        // Derivative of the gate at |ψ_i⟩ = U_i…U_1|ψ_0⟩ and ⟨λ_i| = ⟨ψ|H U_n…U_{i+1}
+        if gates.last().is_some_and(|parameterised| parameterised.index == index)
+            && let Some(parameterised) = gates.pop()
+        {
+            let derivative: f64 = gate_derivative(&lambda, &psi, &parameterised);
+            for (parameter_index, parameter) in parameters.iter().enumerate() {
+                gradient[parameter_index] +=
+                    parameterised.operator.angle().coefficient(parameter) * derivative;
+            }
+        }
+
+        let Gate::Operator(operator, targets, controls) = gate else {
+            // Only barriers remain, which have no effect on the state
+            continue;
+        };
+        let adjoint: Box<dyn Operator> = operator.adjoint().ok_or(Error::UnknownAdjoint)?;
+        psi = adjoint.apply(&psi, targets, controls)?;
+        lambda = adjoint.apply(&lambda, targets, controls)?;
+    }
+
+    Ok(gradient)
+}
+
+/// Helper function to compute the derivative d/dθ Re⟨λ|U(θ)|ψ'⟩ of a parameterised gate, where |ψ⟩ = U(θ)|ψ'⟩.
+///
+/// Rotations are U(θ) = exp(-iθG/2) for a Pauli operator G, so the derivative is Im⟨λ|G|ψ⟩, and phase shifts are
+/// U(θ) = exp(iθ|1⟩⟨1|), so the derivative is -2 Im⟨λ|1⟩⟨1|ψ⟩. The generators of controlled gates are projected onto
+/// the subspace where all control qubits are |1⟩.
+fn gate_derivative(lambda: &State, psi: &State, gate: &ParameterisedGate) -> f64 {
+    let control_mask: usize = gate.controls.iter().fold(0, |mask, &control| mask | (1 << control));
+    let target_bit: usize = 1 << gate.target;
+    let kind: ParameterisedKind = gate.operator.kind();
+    let i: Complex<f64> = Complex::new(0.0, 1.0);
+
+    let overlap: Complex<f64> = (0..psi.state_vector.len())
+        .into_par_iter()
+        .filter(|index| index & control_mask == control_mask)
+        .map(|index| {
+            let is_one: bool = index & target_bit != 0;
+            let flipped: Complex<f64> = psi.state_vector[index ^ target_bit];
+            let generated: Complex<f64> = match kind {
+                ParameterisedKind::RotateX => flipped,
+                ParameterisedKind::RotateY if is_one => i * flipped,
+                ParameterisedKind::RotateY => -i * flipped,
+                ParameterisedKind::RotateZ if is_one => -psi.state_vector[index],
+                ParameterisedKind::RotateZ => psi.state_vector[index],
+                ParameterisedKind::PhaseShift if is_one => psi.state_vector[index],
+                ParameterisedKind::PhaseShift => Complex::new(0.0, 0.0),
+            };
+            lambda.state_vector[index].conj() * generated
+        })
+        .sum();
+
+    match kind {
+        ParameterisedKind::PhaseShift => -2.0 * overlap.im,
+        _ => overlap.im,
+    }
+}
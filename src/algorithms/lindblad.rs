@@ -0,0 +1,480 @@
+use crate::{
+    components::{
+        density_matrix::{DensityMatrix, for_each_row, is_parallel},
+        operator::Pauli,
+        pauli_string::SumOp,
+    },
+    errors::Error,
+};
+use num_complex::Complex;
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+
+/// Integrators for the Lindblad master equation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LindbladIntegrator {
+    /// The classical fourth-order Runge-Kutta method with a fixed step.
+    ///
+    /// Each interval between output times is split into equal steps no longer than `dt`, with a global error of
+    /// O(dt⁴).
+    Rk4 {
+        /// The maximum step size.
+        dt: f64,
+    },
+
+    /// The Dormand-Prince 5(4) method with adaptive step size control.
+    ///
+    /// Each step is accepted if the difference between the fifth- and fourth-order solutions is at most `tolerance`
+    /// in every element of the density matrix, and the step size is adapted to keep it there.
+    Rk45 {
+        /// The maximum local error of an accepted step.
+        tolerance: f64,
+        /// The size of the first step attempted.
+        initial_dt: f64,
+    },
+}
+
+/// A sum of Pauli strings c X^x Z^z, with the X and Z factors of each string stored as bit masks.
+///
+/// X^x Z^z maps |b⟩ to (-1)^(b·z) |b ⊕ x⟩, and Y = i X Z, so products and adjoints only need bit operations.
+#[derive(Debug, Clone, PartialEq)]
+struct PauliSum {
+    /// The coefficient, X mask and Z mask of each string, with distinct masks.
+    terms: Vec<(Complex<f64>, usize, usize)>,
+}
+
+impl PauliSum {
+    // Helper function to collect terms, adding the coefficients of equal strings and dropping those that cancel
+    fn from_terms(terms: impl IntoIterator<Item = (Complex<f64>, usize, usize)>) -> Self {
+        let mut combined: BTreeMap<(usize, usize), Complex<f64>> = BTreeMap::new();
+        for (coefficient, x, z) in terms {
+            *combined.entry((x, z)).or_insert(Complex::new(0.0, 0.0)) += coefficient;
+        }
+        PauliSum {
+            terms: combined
+                .into_iter()
+                .filter(|(_, coefficient)| coefficient.norm() > 0.0)
+                .map(|((x, z), coefficient)| (coefficient, x, z))
+                .collect(),
+        }
+    }
+
+    // Helper function to convert a sum of Pauli strings
+    fn from_sum_op(operator: &SumOp) -> Self {
+        PauliSum::from_terms(operator.terms.iter().map(|term| {
+            let (mut coefficient, mut x, mut z): (Complex<f64>, usize, usize) = (term.coefficient(), 0, 0);
+            for (&qubit, op) in term.ops() {
+                match op {
+                    Pauli::X => x |= 1 << qubit,
+                    Pauli::Y => {
+                        x |= 1 << qubit;
+                        z |= 1 << qubit;
+                        coefficient *= Complex::new(0.0, 1.0);
+                    }
+                    Pauli::Z => z |= 1 << qubit,
+                }
+            }
+            (coefficient, x, z)
+        }))
+    }
+
+    // Helper function to get the adjoint, using (X^x Z^z)† = Z^z X^x = (-1)^(x·z) X^x Z^z
+    fn adjoint(&self) -> Self {
+        PauliSum::from_terms(
+            self.terms
+                .iter()
+                .map(|&(coefficient, x, z)| (parity_sign(x & z) * coefficient.conj(), x, z)),
+        )
+    }
+
+    // Helper function to multiply two sums, using Z^z X^x = (-1)^(x·z) X^x Z^z
+    fn product(&self, other: &PauliSum) -> Self {
+        PauliSum::from_terms(self.terms.iter().flat_map(|&(a, x1, z1)| {
+            other
+                .terms
+                .iter()
+                .map(move |&(b, x2, z2)| (parity_sign(z1 & x2) * a * b, x1 ^ x2, z1 ^ z2))
+        }))
+    }
+
+    // Helper function to multiply every coefficient by a factor
+    fn scale(&self, factor: Complex<f64>) -> Self {
+        PauliSum::from_terms(self.terms.iter().map(|&(coefficient, x, z)| (coefficient * factor, x, z)))
+    }
+
+    // Helper function to get the number of qubits needed for the highest qubit the sum acts on
+    fn min_qubits(&self) -> usize {
+        let mask: usize = self.terms.iter().fold(0, |mask, &(_, x, z)| mask | x | z);
+        (usize::BITS - mask.leading_zeros()) as usize
+    }
+
+    // Helper function to add A ρ to a row-major matrix, where row r of A ρ combines the rows r ⊕ x of ρ
+    fn add_left_product(&self, output: &mut [Complex<f64>], rho: &[Complex<f64>], num_qubits: usize) {
+        let dim: usize = 1 << num_qubits;
+        for_each_row(output, num_qubits, |r, row| {
+            for &(coefficient, x, z) in &self.terms {
+                let source: usize = r ^ x;
+                let factor: Complex<f64> = parity_sign(source & z) * coefficient;
+                for (element, &other) in row.iter_mut().zip(&rho[source * dim..(source + 1) * dim]) {
+                    *element += factor * other;
+                }
+            }
+        });
+    }
+
+    // Helper function to add ρ A to a row-major matrix, where column c of ρ A combines the columns c ⊕ x of ρ
+    fn add_right_product(&self, output: &mut [Complex<f64>], rho: &[Complex<f64>], num_qubits: usize) {
+        let dim: usize = 1 << num_qubits;
+        for_each_row(output, num_qubits, |r, row| {
+            let source: &[Complex<f64>] = &rho[r * dim..(r + 1) * dim];
+            for &(coefficient, x, z) in &self.terms {
+                for (c, element) in row.iter_mut().enumerate() {
+                    *element += parity_sign(c & z) * coefficient * source[c ^ x];
+                }
+            }
+        });
+    }
+}
+
+// Helper function to get (-1) to the power of the number of set bits
+fn parity_sign(bits: usize) -> f64 {
+    if bits.count_ones().is_multiple_of(2) { 1.0 } else { -1.0 }
+}
+
+// Helper function to compute y + Σ_i w_i k_i for row-major matrices on the given number of qubits
+fn linear_combination(y: &[Complex<f64>], terms: &[(f64, &[Complex<f64>])], num_qubits: usize) -> Vec<Complex<f64>> {
+    let dim: usize = 1 << num_qubits;
+    let mut result: Vec<Complex<f64>> = y.to_vec();
+    for_each_row(&mut result, num_qubits, |r, row| {
+        for (c, element) in row.iter_mut().enumerate() {
+            for &(weight, k) in terms {
+                if weight != 0.0 {
+                    *element += weight * k[r * dim + c];
+                }
+            }
+        }
+    });
+    result
+}
+
+/// The generator of the Lindblad master equation
+///
+/// dρ/dt = -i[H, ρ] + Σ_k (L_k ρ L_k† - ½ {L_k† L_k, ρ}),
+///
+/// for a Hamiltonian H and jump operators L_k given as sums of Pauli strings. Rates are absorbed into the jump
+/// operators, so a decay at rate γ is the jump operator √γ σ⁻ = √γ (X + iY) / 2, and dephasing at rate γ is √(γ/2) Z.
+///
+/// The Hamiltonian should be Hermitian, which is not checked. The jump operators need not be Hermitian.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lindbladian {
+    /// The effective non-Hermitian Hamiltonian H - i/2 Σ_k L_k† L_k, multiplied by -i.
+    left: PauliSum,
+    /// The adjoint of the effective Hamiltonian, multiplied by i.
+    right: PauliSum,
+    /// Each jump operator with its adjoint.
+    jumps: Vec<(PauliSum, PauliSum)>,
+    /// The number of qubits needed for the highest qubit the operators act on.
+    min_qubits: usize,
+}
+
+impl Lindbladian {
+    /// Creates the generator of the Lindblad master equation from a Hamiltonian and jump operators.
+    ///
+    /// # Arguments
+    ///
+    /// * `hamiltonian` - The Hamiltonian H, such as a model from `models::heisenberg` or `models::ising`.
+    /// * `jump_operators` - The jump operators L_k, including the square roots of their rates.
+    ///
+    /// # Returns
+    ///
+    /// * `Lindbladian` - A new instance of the Lindbladian struct.
+    pub fn new(hamiltonian: &SumOp, jump_operators: &[SumOp]) -> Self {
+        let jumps: Vec<(PauliSum, PauliSum)> = jump_operators
+            .iter()
+            .map(|operator| {
+                let jump: PauliSum = PauliSum::from_sum_op(operator);
+                let adjoint: PauliSum = jump.adjoint();
+                (jump, adjoint)
+            })
+            .collect();
+
+        // H_eff = H - i/2 Σ_k L_k† L_k, so that -i[H, ρ] - ½{L†L, ρ} = -i H_eff ρ + i ρ H_eff†
+        let hamiltonian: PauliSum = PauliSum::from_sum_op(hamiltonian);
+        let decay = jumps.iter().flat_map(|(jump, adjoint)| adjoint.product(jump).terms);
+        let effective: PauliSum = PauliSum::from_terms(
+            hamiltonian
+                .terms
+                .iter()
+                .copied()
+                .chain(decay.map(|(coefficient, x, z)| (Complex::new(0.0, -0.5) * coefficient, x, z))),
+        );
+
+        let min_qubits: usize = jumps
+            .iter()
+            .map(|(jump, _)| jump.min_qubits())
+            .fold(effective.min_qubits(), usize::max);
+        Lindbladian {
+            left: effective.scale(Complex::new(0.0, -1.0)),
+            right: effective.adjoint().scale(Complex::new(0.0, 1.0)),
+            jumps,
+            min_qubits,
+        }
+    }
+
+    /// Computes the time derivative dρ/dt of a density matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `density_matrix` - The density matrix ρ.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Complex<f64>>, Error>` - The derivative as a row-major matrix, like `DensityMatrix::matrix`. It
+    ///   is Hermitian with zero trace, so it is not a density matrix itself.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if an operator acts on a qubit that is not in the density matrix.
+    pub fn derivative(&self, density_matrix: &DensityMatrix) -> Result<Vec<Complex<f64>>, Error> {
+        self._validate(density_matrix.num_qubits())?;
+        Ok(self._derivative(&density_matrix.matrix, density_matrix.num_qubits()))
+    }
+
+    // Helper function to check that the operators fit in the given number of qubits
+    fn _validate(&self, num_qubits: usize) -> Result<(), Error> {
+        if self.min_qubits > num_qubits {
+            return Err(Error::InvalidQubitIndex(self.min_qubits - 1, num_qubits));
+        }
+        Ok(())
+    }
+
+    // Helper function to compute dρ/dt = -i H_eff ρ + i ρ H_eff† + Σ_k L_k ρ L_k† for a row-major matrix
+    fn _derivative(&self, rho: &[Complex<f64>], num_qubits: usize) -> Vec<Complex<f64>> {
+        let zero: Complex<f64> = Complex::new(0.0, 0.0);
+        let mut output: Vec<Complex<f64>> = vec![zero; rho.len()];
+        self.left.add_left_product(&mut output, rho, num_qubits);
+        self.right.add_right_product(&mut output, rho, num_qubits);
+
+        let mut half: Vec<Complex<f64>> = vec![zero; rho.len()];
+        for (jump, adjoint) in &self.jumps {
+            half.fill(zero);
+            jump.add_left_product(&mut half, rho, num_qubits);
+            adjoint.add_right_product(&mut output, &half, num_qubits);
+        }
+        output
+    }
+}
+
+/// The expectation values of observables at a series of times during the evolution of a density matrix.
+///
+/// # Fields
+///
+/// * `times` - The times at which the expectation values were recorded.
+/// * `expectations` - The expectation value of each observable at each time.
+/// * `final_state` - The density matrix at the last time.
+/// * `steps` - The number of integration steps taken.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LindbladTimeSeries {
+    /// The times at which the expectation values were recorded.
+    pub times: Vec<f64>,
+    /// The real parts of the expectation values, where `expectations[i][k]` is observable `k` at `times[i]`.
+    pub expectations: Vec<Vec<f64>>,
+    /// The density matrix at the last time.
+    pub final_state: DensityMatrix,
+    /// The number of accepted integration steps.
+    pub steps: usize,
+}
+
+impl LindbladTimeSeries {
+    /// Gets the expectation values of one observable over time.
+    ///
+    /// # Arguments
+    ///
+    /// * `observable` - The index of the observable, in the order in which the observables were given.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<f64>` - The expectation value of the observable at each time, or an empty vector if the index is out of
+    ///   range.
+    pub fn series(&self, observable: usize) -> Vec<f64> {
+        self.expectations
+            .iter()
+            .filter_map(|values| values.get(observable).copied())
+            .collect()
+    }
+}
+
+/// Dormand-Prince coefficients a_ij of the stages 2 to 7.
+const DORMAND_PRINCE_A: [[f64; 6]; 6] = [
+    [1.0 / 5.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [3.0 / 40.0, 9.0 / 40.0, 0.0, 0.0, 0.0, 0.0],
+    [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0, 0.0, 0.0, 0.0],
+    [19372.0 / 6561.0, -25360.0 / 2187.0, 64448.0 / 6561.0, -212.0 / 729.0, 0.0, 0.0],
+    [9017.0 / 3168.0, -355.0 / 33.0, 46732.0 / 5247.0, 49.0 / 176.0, -5103.0 / 18656.0, 0.0],
+    [35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0],
+];
+
+/// Differences between the fifth- and fourth-order Dormand-Prince weights, which estimate the local error.
+const DORMAND_PRINCE_ERROR: [f64; 7] = [
+    35.0 / 384.0 - 5179.0 / 57600.0,
+    0.0,
+    500.0 / 1113.0 - 7571.0 / 16695.0,
+    125.0 / 192.0 - 393.0 / 640.0,
+    -2187.0 / 6784.0 + 92097.0 / 339200.0,
+    11.0 / 84.0 - 187.0 / 2100.0,
+    -1.0 / 40.0,
+];
+
+/// Evolves a density matrix under the Lindblad master equation and records expectation values at the given times.
+///
+/// The evolution starts at time 0. Both integrators stop exactly at each output time.
+///
+/// # Arguments
+///
+/// * `lindbladian` - The generator of the master equation.
+/// * `initial_state` - The density matrix at time 0.
+/// * `times` - The non-decreasing, non-negative times at which to record the expectation values.
+/// * `observables` - The observables, as sums of Pauli strings.
+/// * `integrator` - The integrator and its step size or tolerance.
+///
+/// # Returns
+///
+/// * `Result<LindbladTimeSeries, Error>` - The real parts of the expectation values at each time, and the final
+///   density matrix.
+///
+/// # Errors
+///
+/// * Returns an error if a time is negative, not finite or smaller than the previous time.
+/// * Returns an error if the step size or tolerance is not positive.
+/// * Returns an error if an operator or observable acts on a qubit that is not in the density matrix.
+/// * Returns an error if the adaptive step size becomes too small to make progress.
+pub fn lindblad_evolve(
+    lindbladian: &Lindbladian,
+    initial_state: &DensityMatrix,
+    times: &[f64],
+    observables: &[SumOp],
+    integrator: LindbladIntegrator,
+) -> Result<LindbladTimeSeries, Error> {
+    let num_qubits: usize = initial_state.num_qubits();
+    lindbladian._validate(num_qubits)?;
+    let mut previous: f64 = 0.0;
+    for &time in times {
+        if !time.is_finite() || time < previous {
+            return Err(Error::InvalidEvolutionTime(time));
+        }
+        previous = time;
+    }
+    match integrator {
+        LindbladIntegrator::Rk4 { dt } if !(dt > 0.0 && dt.is_finite()) => return Err(Error::InvalidTimeStep(dt)),
+        LindbladIntegrator::Rk45 { initial_dt, .. } if !(initial_dt > 0.0 && initial_dt.is_finite()) => {
+            return Err(Error::InvalidTimeStep(initial_dt));
+        }
+        LindbladIntegrator::Rk45 { tolerance, .. } if !(tolerance > 0.0 && tolerance.is_finite()) => {
+            return Err(Error::InvalidTolerance(tolerance));
+        }
+        _ => {}
+    }
+
+    let dim: usize = 1 << num_qubits;
+    let mut state: DensityMatrix = initial_state.clone();
+    let mut time: f64 = 0.0;
+    let mut steps: usize = 0;
+    let mut expectations: Vec<Vec<f64>> = Vec::with_capacity(times.len());
+    // The proposed size of the next adaptive step, and the derivative at the current state if it is known
+    let mut next_dt: f64 = match integrator {
+        LindbladIntegrator::Rk4 { dt } => dt,
+        LindbladIntegrator::Rk45 { initial_dt, .. } => initial_dt,
+    };
+    let mut derivative: Option<Vec<Complex<f64>>> = None;
+
+    for &target in times {
+        match integrator {
+            LindbladIntegrator::Rk4 { dt } => {
+                let interval: f64 = target - time;
+                let num_steps: usize = (interval / dt).ceil() as usize;
+                let h: f64 = if num_steps > 0 { interval / num_steps as f64 } else { 0.0 };
+                for _ in 0..num_steps {
+                    let y: &[Complex<f64>] = &state.matrix;
+                    let stage = |terms: &[(f64, &[Complex<f64>])]| {
+                        lindbladian._derivative(&linear_combination(y, terms, num_qubits), num_qubits)
+                    };
+                    let k1: Vec<Complex<f64>> = lindbladian._derivative(y, num_qubits);
+                    let k2: Vec<Complex<f64>> = stage(&[(h / 2.0, &k1)]);
+                    let k3: Vec<Complex<f64>> = stage(&[(h / 2.0, &k2)]);
+                    let k4: Vec<Complex<f64>> = stage(&[(h, &k3)]);
+                    let terms: [(f64, &[Complex<f64>]); 4] = [(h / 6.0, &k1), (h / 3.0, &k2), (h / 3.0, &k3), (h / 6.0, &k4)];
+                    state.matrix = linear_combination(y, &terms, num_qubits);
+                }
+                steps += num_steps;
+            }
+            LindbladIntegrator::Rk45 { tolerance, .. } => {
+                while time < target {
+                    let h: f64 = next_dt.min(target - time);
+                    if h <= f64::EPSILON * time.max(1.0) {
+                        return Err(Error::InvalidTimeStep(h));
+                    }
+
+                    // The last stage of an accepted step is the derivative at the new state, so it is reused
+                    let y: &[Complex<f64>] = &state.matrix;
+                    let mut stages: Vec<Vec<Complex<f64>>> = Vec::with_capacity(7);
+                    stages.push(derivative.take().unwrap_or_else(|| lindbladian._derivative(y, num_qubits)));
+                    let mut candidate: Vec<Complex<f64>> = Vec::new();
+                    for coefficients in &DORMAND_PRINCE_A {
+                        let terms: Vec<(f64, &[Complex<f64>])> = coefficients
+                            .iter()
+                            .zip(&stages)
+                            .map(|(&a, k)| (h * a, k.as_slice()))
+                            .collect();
+                        candidate = linear_combination(y, &terms, num_qubits);
+                        stages.push(lindbladian._derivative(&candidate, num_qubits));
+                    }
+
+                    let error_at = |i: usize| -> f64 {
+                        DORMAND_PRINCE_ERROR
+                            .iter()
+                            .zip(&stages)
+                            .map(|(&e, k)| h * e * k[i])
+                            .sum::<Complex<f64>>()
+                            .norm()
+                    };
+                    let error: f64 = if is_parallel(num_qubits) {
+                        (0..dim * dim).into_par_iter().map(error_at).reduce(|| 0.0, f64::max)
+                    } else {
+                        (0..dim * dim).map(error_at).fold(0.0, f64::max)
+                    };
+
+                    let factor: f64 = if error == 0.0 { 5.0 } else { 0.9 * (tolerance / error).powf(0.2) };
+                    if error <= tolerance {
+                        state.matrix = candidate;
+                        derivative = stages.pop();
+                        // Rounding in `time + h` could leave the time an ulp short of the target
+                        time = if h == target - time { target } else { time + h };
+                        steps += 1;
+                        // A step shortened to reach the target does not limit the next one
+                        if h == next_dt {
+                            next_dt = h * factor.clamp(0.2, 5.0);
+                        }
+                    } else {
+                        derivative = Some(stages.swap_remove(0));
+                        next_dt = h * factor.clamp(0.2, 1.0);
+                    }
+                }
+            }
+        }
+        time = target;
+
+        expectations.push(
+            observables
+                .iter()
+                .map(|observable| state.expectation_value(observable).map(|value| value.re))
+                .collect::<Result<Vec<f64>, Error>>()?,
+        );
+    }
+
+    Ok(LindbladTimeSeries {
+        times: times.to_vec(),
+        expectations,
+        final_state: state,
+        steps,
+    })
+}
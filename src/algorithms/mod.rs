@@ -1 +1,4 @@
-pub mod time_evolution;
\ No newline at end of file
+pub mod time_evolution;
+pub mod gradient;
+pub mod trajectory;
+pub mod lindblad;
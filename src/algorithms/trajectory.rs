@@ -0,0 +1,176 @@
+use crate::{
+    circuit::Circuit,
+    components::{
+        classical::ClassicalRegister,
+        counts::Counts,
+        noise::NoiseModel,
+        pauli_string::SumOp,
+        state::{State, stream_rng},
+    },
+    errors::Error,
+};
+use rand::{Rng, rngs::StdRng};
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+
+/// An estimate of the mean of a quantity from independent samples, with its standard error.
+///
+/// # Fields
+///
+/// * `mean` - The sample mean.
+/// * `standard_error` - The standard error of the mean, s / √N for the sample standard deviation s of N samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    /// The sample mean.
+    pub mean: f64,
+    /// The standard error of the mean, which is infinite if there is a single sample.
+    pub standard_error: f64,
+}
+
+impl Estimate {
+    /// Estimates the mean of a quantity from independent samples.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - The samples of the quantity, of which there must be at least one.
+    ///
+    /// # Returns
+    ///
+    /// * `Estimate` - The sample mean, with a standard error computed from the unbiased sample variance.
+    pub fn from_samples(samples: &[f64]) -> Self {
+        let n: f64 = samples.len() as f64;
+        let mean: f64 = samples.iter().sum::<f64>() / n;
+        let standard_error: f64 = if samples.len() < 2 {
+            f64::INFINITY
+        } else {
+            let variance: f64 = samples.iter().map(|sample| (sample - mean).powi(2)).sum::<f64>() / (n - 1.0);
+            (variance / n).sqrt()
+        };
+        Estimate { mean, standard_error }
+    }
+
+    /// Checks whether a value lies within the given number of standard errors of the mean.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to compare with the mean.
+    /// * `num_standard_errors` - The number of standard errors allowed, such as 3 for a 99.7% confidence interval.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if |value - mean| ≤ num_standard_errors × standard_error.
+    pub fn is_consistent_with(&self, value: f64, num_standard_errors: f64) -> bool {
+        (value - self.mean).abs() <= num_standard_errors * self.standard_error
+    }
+}
+
+/// The aggregated results of many quantum trajectories of a noisy circuit.
+///
+/// # Fields
+///
+/// * `trajectories` - The number of trajectories.
+/// * `expectations` - The estimated expectation value of each observable in the final state.
+/// * `counts` - A histogram of the final values of the classical register.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrajectoryResult {
+    /// The number of trajectories.
+    pub trajectories: usize,
+    /// The estimated expectation value of each observable in the final state, in the order of the observables.
+    pub expectations: Vec<Estimate>,
+    /// A histogram of the final values of the classical register, with one entry per trajectory.
+    pub counts: Counts,
+}
+
+impl TrajectoryResult {
+    /// Estimates the probability of each observed value of the classical register.
+    ///
+    /// # Returns
+    ///
+    /// * `BTreeMap<String, Estimate>` - The frequency p of each observed bitstring, with the standard error
+    ///   √(p (1 - p) / (N - 1)) that `Estimate::from_samples` gives for N samples of 0 or 1.
+    pub fn probabilities(&self) -> BTreeMap<String, Estimate> {
+        let n: f64 = self.trajectories as f64;
+        self.counts
+            .iter()
+            .map(|(bitstring, count)| {
+                let mean: f64 = count as f64 / n;
+                let standard_error: f64 = if self.trajectories < 2 {
+                    f64::INFINITY
+                } else {
+                    (mean * (1.0 - mean) / (n - 1.0)).sqrt()
+                };
+                (bitstring.to_string(), Estimate { mean, standard_error })
+            })
+            .collect()
+    }
+}
+
+/// Runs many quantum trajectories of a noisy circuit in parallel and aggregates their results.
+///
+/// Each trajectory is a Monte Carlo wavefunction simulation with `Circuit::execute_trajectory`: every channel of the
+/// noise model applies one of its Kraus operators K_i, chosen with probability ‖K_i |ψ⟩‖², so only a state vector is
+/// stored per thread instead of a density matrix. The average over trajectories converges to the result of
+/// `Circuit::execute_noisy`, and the standard errors shrink as 1 / √N for N trajectories.
+///
+/// Trajectory `t` uses a random number generator derived from the seed and `t`, so the results do not depend on the
+/// number of threads.
+///
+/// # Arguments
+///
+/// * `circuit` - The circuit to execute.
+/// * `initial_state` - The initial state of the qubits in the circuit.
+/// * `noise_model` - The noise model of the device.
+/// * `observables` - The observables whose expectation values are estimated in the final state of each trajectory.
+/// * `trajectories` - The number of trajectories.
+/// * `seed` - The seed that makes the results reproducible, or `None` to draw one from the thread-local generator.
+///
+/// # Returns
+///
+/// * `Result<TrajectoryResult, Error>` - The estimated real parts of the expectation values and the counts of the
+///   final classical register.
+///
+/// # Errors
+///
+/// * Returns an error if `trajectories` is 0.
+/// * Returns an error if the circuit cannot be executed on the initial state with the noise model.
+/// * Returns an error if an observable acts on a qubit that is not in the circuit.
+pub fn run_trajectories(
+    circuit: &Circuit,
+    initial_state: &State,
+    noise_model: &NoiseModel,
+    observables: &[SumOp],
+    trajectories: usize,
+    seed: Option<u64>,
+) -> Result<TrajectoryResult, Error> {
+    if trajectories == 0 {
+        return Err(Error::InvalidNumberOfMeasurements(0));
+    }
+
+    let seed: u64 = seed.unwrap_or_else(|| rand::rng().random());
+    let samples: Vec<(Vec<f64>, ClassicalRegister)> = (0..trajectories)
+        .into_par_iter()
+        .map(|trajectory| {
+            let mut rng: StdRng = stream_rng(seed, trajectory as u64);
+            let (state, register) = circuit.execute_trajectory(initial_state, noise_model, &mut rng)?;
+            let values: Vec<f64> = observables
+                .iter()
+                .map(|observable| observable.expectation_value(&state).map(|value| value.re))
+                .collect::<Result<Vec<f64>, Error>>()?;
+            Ok((values, register))
+        })
+        .collect::<Result<Vec<(Vec<f64>, ClassicalRegister)>, Error>>()?;
+
+    let expectations: Vec<Estimate> = (0..observables.len())
+        .map(|k| Estimate::from_samples(&samples.iter().map(|(values, _)| values[k]).collect::<Vec<f64>>()))
+        .collect();
+    let mut counts: Counts = Counts::new(circuit.get_num_bits());
+    for (_, register) in &samples {
+        counts.record(register, 1);
+    }
+
+    Ok(TrajectoryResult {
+        trajectories,
+        expectations,
+        counts,
+    })
+}
@@ -1,10 +1,34 @@
 use crate::{
-    components::{gate::Gate, measurement::MeasurementBasis, operator::Operator, state::State},
-    errors::Error,
+    compiler::{
+        coupling_map::CouplingMap,
+        ir::CircuitIR,
+        parser::{parse_qasm2, parse_qasm3},
+        pass::Pass,
+        passes::DecomposeControlled,
+        routing::{Layout, RoutedCircuit, SabreRouter},
+    },
+    components::{
+        classical::{ClassicalCondition, ClassicalRegister},
+        counts::Counts,
+        density_matrix::DensityMatrix,
+        gate::Gate,
+        measurement::MeasurementBasis,
+        noise::NoiseModel,
+        operator::Operator,
+        parameter::{Parameter, ParameterExpression},
+        state::{State, stream_rng},
+    },
+    errors::{CompilerError, Error},
     subroutine::Subroutine,
 };
 
 use num_complex::Complex;
+use rand::{Rng, rngs::StdRng};
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// The basis, qubit and bit of a measurement at the end of a circuit.
+type TerminalMeasurement = (MeasurementBasis, usize, usize);
 
 /// Represents a quantum circuit as a vector of gates.
 ///
@@ -12,12 +36,16 @@ use num_complex::Complex;
 ///
 /// * `gates` - A vector of gates in the circuit.
 /// * `num_qubits` - The number of qubits in the circuit.
+/// * `num_bits` - The number of classical bits in the circuit.
 #[derive(Debug)]
 pub struct Circuit {
     /// A vector of gates in the circuit.
     pub gates: Vec<Gate>,
     /// The number of qubits in the circuit.
     pub num_qubits: usize,
+    /// The number of classical bits in the circuit, which record measurement outcomes and are read by classically
+    /// controlled gates.
+    pub num_bits: usize,
 }
 
 impl Circuit {
@@ -41,7 +69,27 @@ impl Circuit {
         Ok(())
     }
 
-    /// Creates a new circuit with the specified number of qubits.
+    // Helper function to validate the classical bits of a gate
+    fn _validate_gate_bits(gate: &Gate, circuit_num_bits: usize) -> Result<(), Error> {
+        for bit in gate.get_classical_bits() {
+            if bit >= circuit_num_bits {
+                return Err(Error::InvalidBitIndex(bit, circuit_num_bits));
+            }
+        }
+
+        match gate {
+            Gate::MeasurementInto(_, qubits, bits) if qubits.len() != bits.len() => {
+                Err(Error::InvalidNumberOfInputs(bits.len(), qubits.len()))
+            }
+            Gate::Conditional(condition, _) if !condition.is_valid() => Err(
+                Error::InvalidClassicalCondition(condition.value, condition.bits.len()),
+            ),
+            Gate::Conditional(_, gate) => Self::_validate_gate_bits(gate, circuit_num_bits),
+            _ => Ok(()),
+        }
+    }
+
+    /// Creates a new circuit with the specified number of qubits, and one classical bit per qubit.
     ///
     /// # Arguments
     ///
@@ -54,10 +102,11 @@ impl Circuit {
         Circuit {
             gates: Vec::new(),
             num_qubits,
+            num_bits: num_qubits,
         }
     }
 
-    /// Creates a new circuit with the specified gates and number of qubits.
+    /// Creates a new circuit with the specified gates and number of qubits, and one classical bit per qubit.
     ///
     /// # Arguments
     ///
@@ -68,11 +117,37 @@ impl Circuit {
     ///
     /// * `Result<Circuit, Error>` - A new instance of the Circuit struct or an error if the circuit cannot be created.
     pub fn with_gates(gates: Vec<Gate>, num_qubits: usize) -> Result<Circuit, Error> {
+        Self::with_bits(gates, num_qubits, num_qubits)
+    }
+
+    /// Creates a new circuit with the specified gates, number of qubits and number of classical bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `gates` - A vector of gates in the circuit.
+    /// * `num_qubits` - The number of qubits in the circuit.
+    /// * `num_bits` - The number of classical bits in the circuit.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Circuit, Error>` - A new instance of the Circuit struct or an error if the circuit cannot be created.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if a gate acts on a qubit or classical bit outside the circuit.
+    /// * Returns an error if a measurement has a different number of qubits and bits.
+    /// * Returns an error if the value of a classical condition does not fit into its bits.
+    pub fn with_bits(gates: Vec<Gate>, num_qubits: usize, num_bits: usize) -> Result<Circuit, Error> {
         for gate in &gates {
             Self::_validate_gate_qubits(gate, num_qubits)?;
+            Self::_validate_gate_bits(gate, num_bits)?;
         }
 
-        Ok(Circuit { gates, num_qubits })
+        Ok(Circuit {
+            gates,
+            num_qubits,
+            num_bits,
+        })
     }
 
     /// Adds a gate to the circuit.
@@ -86,6 +161,7 @@ impl Circuit {
     /// * `Result<(), Error>` - An empty result if the gate is added successfully, or an error if the gate cannot be added.
     pub fn add_gate(&mut self, gate: Gate) -> Result<(), Error> {
         Self::_validate_gate_qubits(&gate, self.num_qubits)?;
+        Self::_validate_gate_bits(&gate, self.num_bits)?;
         self.gates.push(gate);
         Ok(())
     }
@@ -108,6 +184,15 @@ impl Circuit {
         self.num_qubits
     }
 
+    /// Gets the number of classical bits in the circuit.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The number of classical bits in the circuit.
+    pub fn get_num_bits(&self) -> usize {
+        self.num_bits
+    }
+
     /// Gets the gates in the circuit.
     ///
     /// # Returns
@@ -119,6 +204,8 @@ impl Circuit {
 
     /// Executes the circuit with the given initial state, and returns the final state.
     ///
+    /// Measurement outcomes are discarded. Use `execute_with_register` to also get the values of the classical bits.
+    ///
     /// # Arguments
     ///
     /// * `initial_state` - The initial state of the qubits in the circuit.
@@ -132,17 +219,70 @@ impl Circuit {
     /// * Returns an error if the number of qubits in the initial state does not match the number of qubits in the circuit.
     /// * Returns an error if the circuit cannot be executed due to invalid gate operations.
     pub fn execute(&self, initial_state: &State) -> Result<State, Error> {
+        self.execute_with_register(initial_state).map(|(state, _)| state)
+    }
+
+    /// Executes the circuit with the given initial state, and returns the final state and classical register.
+    ///
+    /// The classical bits start at 0. Measurements record their outcomes in the register, and classically controlled
+    /// gates are only applied if their condition holds for the register at that point of the circuit.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_state` - The initial state of the qubits in the circuit.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(State, ClassicalRegister), Error>` - The final state of the qubits and the values of the classical
+    ///   bits after executing the circuit.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the number of qubits in the initial state does not match the number of qubits in the circuit.
+    /// * Returns an error if the circuit cannot be executed due to invalid gate operations.
+    pub fn execute_with_register(
+        &self,
+        initial_state: &State,
+    ) -> Result<(State, ClassicalRegister), Error> {
+        self.execute_with_rng(initial_state, &mut rand::rng())
+    }
+
+    /// Executes the circuit with the given initial state, using the given random number generator for measurements
+    /// and resets, and returns the final state and classical register.
+    ///
+    /// Passing a seeded generator, such as `StdRng::seed_from_u64`, makes the execution reproducible.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_state` - The initial state of the qubits in the circuit.
+    /// * `rng` - The random number generator used to sample measurement outcomes.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(State, ClassicalRegister), Error>` - The final state of the qubits and the values of the classical
+    ///   bits after executing the circuit.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the number of qubits in the initial state does not match the number of qubits in the circuit.
+    /// * Returns an error if the circuit cannot be executed due to invalid gate operations.
+    pub fn execute_with_rng(
+        &self,
+        initial_state: &State,
+        rng: &mut impl Rng,
+    ) -> Result<(State, ClassicalRegister), Error> {
         if initial_state.num_qubits() != self.num_qubits {
             return Err(Error::InvalidNumberOfQubits(initial_state.num_qubits()));
         }
 
         let mut current_state = initial_state.clone();
+        let mut register: ClassicalRegister = ClassicalRegister::new(self.num_bits);
 
         for gate in &self.gates {
-            current_state = gate.apply(&current_state)?;
+            current_state = gate.apply_classical_with_rng(&current_state, &mut register, rng)?;
         }
 
-        Ok(current_state)
+        Ok((current_state, register))
     }
 
     /// Executes the circuit with the given initial state, and returns all the intermediate states and the final state.
@@ -165,19 +305,838 @@ impl Circuit {
         }
 
         let mut current_state = initial_state.clone();
+        let mut register: ClassicalRegister = ClassicalRegister::new(self.num_bits);
         let mut states = vec![current_state.clone()];
 
         for gate in &self.gates {
-            current_state = gate.apply(&current_state)?;
+            current_state = gate.apply_classical(&current_state, &mut register)?;
             states.push(current_state.clone());
         }
 
         Ok(states)
     }
 
+    /// Executes the circuit repeatedly and counts the values of the classical register after each shot.
+    ///
+    /// If every measurement is terminal, i.e. no gate other than a measurement or barrier follows the first measurement
+    /// and no qubit is measured twice, the unitary prefix is simulated only once and all shots are sampled from the
+    /// resulting state. Otherwise, the circuit is executed once per shot, in parallel, with a random number generator
+    /// derived from the seed and the index of the shot, so the counts do not depend on the number of threads.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_state` - The initial state of the qubits in the circuit.
+    /// * `shots` - The number of times to execute the circuit.
+    /// * `seed` - The seed that makes the counts reproducible, or `None` to draw one from the thread-local generator.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Counts, Error>` - A histogram of the values of the classical bits over all shots.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if `shots` is 0.
+    /// * Returns an error if the number of qubits in the initial state does not match the number of qubits in the circuit.
+    /// * Returns an error if the circuit cannot be executed due to invalid gate operations.
+    pub fn run_shots(&self, initial_state: &State, shots: usize, seed: Option<u64>) -> Result<Counts, Error> {
+        if shots == 0 {
+            return Err(Error::InvalidNumberOfMeasurements(0));
+        }
+        if initial_state.num_qubits() != self.num_qubits {
+            return Err(Error::InvalidNumberOfQubits(initial_state.num_qubits()));
+        }
+
+        let seed: u64 = seed.unwrap_or_else(|| rand::rng().random());
+        let mut counts: Counts = Counts::new(self.num_bits);
+        if let Some((prefix, measurements)) = self.terminal_measurements() {
+            let mut state: State = initial_state.clone();
+            for gate in &self.gates[..prefix] {
+                state = gate.apply(&state)?;
+            }
+            // Rotate each measured qubit so that its measurement basis becomes the computational basis
+            for (basis, qubit, _) in &measurements {
+                state = match basis {
+                    MeasurementBasis::Computational => state,
+                    MeasurementBasis::X => state.h(*qubit)?,
+                    MeasurementBasis::Y => state.s_dag(*qubit)?.h(*qubit)?,
+                    MeasurementBasis::Custom(unitary) => state.unitary(*qubit, *unitary)?,
+                };
+            }
+
+            // Bit k of each outcome is the value of the k-th measured qubit
+            let qubits: Vec<usize> = measurements.iter().map(|&(_, qubit, _)| qubit).collect();
+            let frequencies: Vec<usize> = state.sample_frequencies(&qubits, shots, &mut stream_rng(seed, 0))?;
+            for (outcome, &frequency) in frequencies.iter().enumerate().filter(|&(_, &frequency)| frequency > 0) {
+                let mut register: ClassicalRegister = ClassicalRegister::new(self.num_bits);
+                for (k, &(_, _, bit)) in measurements.iter().enumerate() {
+                    register.set(bit, ((outcome >> k) & 1) as u8);
+                }
+                counts.record(&register, frequency);
+            }
+        } else {
+            let registers: Vec<ClassicalRegister> = (0..shots)
+                .into_par_iter()
+                .map(|shot| {
+                    let mut rng: StdRng = stream_rng(seed, shot as u64);
+                    self.execute_with_rng(initial_state, &mut rng).map(|(_, register)| register)
+                })
+                .collect::<Result<Vec<ClassicalRegister>, Error>>()?;
+            for register in &registers {
+                counts.record(register, 1);
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Executes the circuit on a density matrix, and returns the final density matrix.
+    ///
+    /// The result is exact: measurements and resets are applied to the whole ensemble, so the final density matrix
+    /// is the average over all measurement outcomes, weighted by their probabilities. If the circuit has classically
+    /// controlled gates, one density matrix is tracked per value of the classical register, and each gate only acts on
+    /// those whose register satisfies its condition.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_state` - The initial density matrix of the qubits in the circuit.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<DensityMatrix, Error>` - The final density matrix of the qubits after executing the circuit.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the number of qubits in the initial state does not match the number of qubits in the circuit.
+    /// * Returns an error if the circuit cannot be executed due to invalid gate operations.
+    pub fn execute_density_matrix(&self, initial_state: &DensityMatrix) -> Result<DensityMatrix, Error> {
+        self.execute_noisy(initial_state, &NoiseModel::new())
+    }
+
+    /// Executes the circuit on a density matrix with the errors of a noise model, and returns the final density matrix.
+    ///
+    /// As for `execute_density_matrix`, the result is exact. The channels of the noise model are applied after each
+    /// operator gate they follow, and readout errors change the values recorded in the classical register, so they
+    /// only affect the final density matrix through classically controlled gates.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_state` - The initial density matrix of the qubits in the circuit.
+    /// * `noise_model` - The noise model of the device.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<DensityMatrix, Error>` - The final density matrix of the qubits after executing the circuit.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the number of qubits in the initial state does not match the number of qubits in the circuit.
+    /// * Returns an error if the noise model refers to a qubit that is not in the circuit.
+    /// * Returns an error if the circuit cannot be executed due to invalid gate operations.
+    pub fn execute_noisy(
+        &self,
+        initial_state: &DensityMatrix,
+        noise_model: &NoiseModel,
+    ) -> Result<DensityMatrix, Error> {
+        let mut branches = self
+            .density_matrix_branches(initial_state, noise_model)?
+            .into_iter()
+            .map(|(_, branch)| branch);
+        let first: DensityMatrix = branches.next().ok_or(Error::UnknownError)?;
+        Ok(branches.fold(first, |sum, branch| sum + branch))
+    }
+
+    // Helper function to execute the circuit on a density matrix, returning the unnormalised density matrix of each
+    // distinct value of the classical register, whose trace is the probability of that value
+    //
+    // Measurements only split the ensemble if the circuit has classically controlled gates. Otherwise, they are
+    // non-selective and a single density matrix with a zero register is returned.
+    pub(crate) fn density_matrix_branches(
+        &self,
+        initial_state: &DensityMatrix,
+        noise_model: &NoiseModel,
+    ) -> Result<Vec<(ClassicalRegister, DensityMatrix)>, Error> {
+        if initial_state.num_qubits() != self.num_qubits {
+            return Err(Error::InvalidNumberOfQubits(initial_state.num_qubits()));
+        }
+        noise_model.validate(self.num_qubits)?;
+
+        let branching: bool = self.gates.iter().any(|gate| matches!(gate, Gate::Conditional(_, _)));
+        let mut branches: Vec<(ClassicalRegister, DensityMatrix)> =
+            vec![(ClassicalRegister::new(self.num_bits), initial_state.clone())];
+
+        for gate in &self.gates {
+            let mut merged: Vec<(ClassicalRegister, DensityMatrix)> = Vec::with_capacity(branches.len());
+            for (register, density_matrix) in branches {
+                let applied = Self::apply_to_branch(gate, register, density_matrix, branching, noise_model)?;
+                for (register, density_matrix) in applied {
+                    // Branches that end up with the same register are indistinguishable from now on
+                    match merged.iter_mut().find(|(other, _)| *other == register) {
+                        Some((_, existing)) => {
+                            for (element, other) in existing.matrix.iter_mut().zip(&density_matrix.matrix) {
+                                *element += other;
+                            }
+                        }
+                        None => merged.push((register, density_matrix)),
+                    }
+                }
+            }
+            branches = merged;
+        }
+        Ok(branches)
+    }
+
+    // Helper function to apply a gate and the channels that follow it to the density matrix of one value of the
+    // classical register
+    fn apply_to_branch(
+        gate: &Gate,
+        register: ClassicalRegister,
+        density_matrix: DensityMatrix,
+        branching: bool,
+        noise_model: &NoiseModel,
+    ) -> Result<Vec<(ClassicalRegister, DensityMatrix)>, Error> {
+        let (basis, measured): (MeasurementBasis, Vec<(usize, usize)>) = match gate {
+            Gate::Conditional(condition, gate) => {
+                return if condition.is_satisfied(&register) {
+                    Self::apply_to_branch(gate, register, density_matrix, branching, noise_model)
+                } else {
+                    Ok(vec![(register, density_matrix)])
+                };
+            }
+            Gate::Measurement(basis, _) | Gate::MeasurementInto(basis, _, _) if branching => {
+                (*basis, Self::measured_bits(gate, density_matrix.num_qubits()))
+            }
+            _ => {
+                let mut density_matrix: DensityMatrix = density_matrix.apply_gate(gate)?;
+                for (channel, qubits) in noise_model.channels_after(gate) {
+                    density_matrix = density_matrix.apply_channel(channel, &qubits)?;
+                }
+                return Ok(vec![(register, density_matrix)]);
+            }
+        };
+
+        let qubits: Vec<usize> = measured.iter().map(|&(qubit, _)| qubit).collect();
+        let mut branches: Vec<(ClassicalRegister, DensityMatrix)> = Vec::new();
+        for (outcome, branch) in density_matrix.measurement_branches(basis, &qubits) {
+            // Each outcome is recorded as every value allowed by the readout errors, weighted by its probability
+            let mut recorded: Vec<(ClassicalRegister, f64)> = vec![(register.clone(), 1.0)];
+            for (k, &(qubit, bit)) in measured.iter().enumerate() {
+                let actual: usize = (outcome >> k) & 1;
+                let confusion: [f64; 2] = match noise_model.readout_error(qubit) {
+                    Some(confusion) => confusion[actual],
+                    None if actual == 0 => [1.0, 0.0],
+                    None => [0.0, 1.0],
+                };
+                recorded = recorded
+                    .into_iter()
+                    .flat_map(|(register, weight)| {
+                        (0..2).filter(|&value| confusion[value] > 0.0).map(move |value| {
+                            let mut register: ClassicalRegister = register.clone();
+                            register.set(bit, value as u8);
+                            (register, weight * confusion[value])
+                        })
+                    })
+                    .collect();
+            }
+            branches.extend(recorded.into_iter().map(|(register, weight)| (register, branch.clone() * weight)));
+        }
+        Ok(branches)
+    }
+
+    // Helper function to get each qubit measured by a measurement gate with the bit that records its outcome
+    fn measured_bits(gate: &Gate, num_qubits: usize) -> Vec<(usize, usize)> {
+        match gate {
+            Gate::Measurement(_, qubits) if qubits.is_empty() => (0..num_qubits).map(|qubit| (qubit, qubit)).collect(),
+            Gate::Measurement(_, qubits) => qubits.iter().map(|&qubit| (qubit, qubit)).collect(),
+            Gate::MeasurementInto(_, qubits, bits) => qubits.iter().copied().zip(bits.iter().copied()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Executes the circuit on a pure state with the errors of a noise model, sampling a single quantum trajectory,
+    /// and returns the final state and classical register.
+    ///
+    /// Each channel of the noise model is applied by choosing one of its Kraus operators at random, so the final
+    /// density matrix of `execute_noisy` is the average of the final states of many trajectories. Readout errors flip
+    /// the outcomes recorded in the classical register.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_state` - The initial state of the qubits in the circuit.
+    /// * `noise_model` - The noise model of the device.
+    /// * `rng` - The random number generator used to sample measurement outcomes, Kraus operators and readout errors.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(State, ClassicalRegister), Error>` - The final state of the trajectory and the values of the
+    ///   classical bits after executing the circuit.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the number of qubits in the initial state does not match the number of qubits in the circuit.
+    /// * Returns an error if the noise model refers to a qubit that is not in the circuit.
+    /// * Returns an error if the circuit cannot be executed due to invalid gate operations.
+    pub fn execute_trajectory(
+        &self,
+        initial_state: &State,
+        noise_model: &NoiseModel,
+        rng: &mut impl Rng,
+    ) -> Result<(State, ClassicalRegister), Error> {
+        if initial_state.num_qubits() != self.num_qubits {
+            return Err(Error::InvalidNumberOfQubits(initial_state.num_qubits()));
+        }
+        noise_model.validate(self.num_qubits)?;
+
+        let mut current_state = initial_state.clone();
+        let mut register: ClassicalRegister = ClassicalRegister::new(self.num_bits);
+
+        for gate in &self.gates {
+            current_state = Self::apply_noisy_gate(gate, &current_state, &mut register, noise_model, rng)?;
+        }
+
+        Ok((current_state, register))
+    }
+
+    // Helper function to apply a gate and the channels that follow it to one trajectory
+    fn apply_noisy_gate(
+        gate: &Gate,
+        state: &State,
+        register: &mut ClassicalRegister,
+        noise_model: &NoiseModel,
+        rng: &mut impl Rng,
+    ) -> Result<State, Error> {
+        match gate {
+            Gate::Conditional(condition, gate) => {
+                if condition.is_satisfied(register) {
+                    Self::apply_noisy_gate(gate, state, register, noise_model, rng)
+                } else {
+                    Ok(state.clone())
+                }
+            }
+            Gate::Measurement(_, _) | Gate::MeasurementInto(_, _, _) => {
+                let state: State = gate.apply_classical_with_rng(state, register, rng)?;
+                noise_model.apply_readout_errors(register, &Self::measured_bits(gate, state.num_qubits()), rng);
+                Ok(state)
+            }
+            _ => {
+                let mut state: State = gate.apply_with_rng(state, rng)?;
+                for (channel, qubits) in noise_model.channels_after(gate) {
+                    state = channel.apply_with_rng(&state, &qubits, rng)?;
+                }
+                Ok(state)
+            }
+        }
+    }
+
+    /// Executes the circuit repeatedly with the errors of a noise model, and counts the values of the classical
+    /// register after each shot.
+    ///
+    /// Each shot samples one quantum trajectory with `execute_trajectory`, in parallel, with a random number generator
+    /// derived from the seed and the index of the shot, so the counts do not depend on the number of threads.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_state` - The initial state of the qubits in the circuit.
+    /// * `shots` - The number of times to execute the circuit.
+    /// * `noise_model` - The noise model of the device.
+    /// * `seed` - The seed that makes the counts reproducible, or `None` to draw one from the thread-local generator.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Counts, Error>` - A histogram of the values of the classical bits over all shots.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if `shots` is 0.
+    /// * Returns an error if the number of qubits in the initial state does not match the number of qubits in the circuit.
+    /// * Returns an error if the noise model refers to a qubit that is not in the circuit.
+    /// * Returns an error if the circuit cannot be executed due to invalid gate operations.
+    pub fn run_shots_noisy(
+        &self,
+        initial_state: &State,
+        shots: usize,
+        noise_model: &NoiseModel,
+        seed: Option<u64>,
+    ) -> Result<Counts, Error> {
+        if shots == 0 {
+            return Err(Error::InvalidNumberOfMeasurements(0));
+        }
+
+        let seed: u64 = seed.unwrap_or_else(|| rand::rng().random());
+        let registers: Vec<ClassicalRegister> = (0..shots)
+            .into_par_iter()
+            .map(|shot| {
+                let mut rng: StdRng = stream_rng(seed, shot as u64);
+                self.execute_trajectory(initial_state, noise_model, &mut rng).map(|(_, register)| register)
+            })
+            .collect::<Result<Vec<ClassicalRegister>, Error>>()?;
+
+        let mut counts: Counts = Counts::new(self.num_bits);
+        for register in &registers {
+            counts.record(register, 1);
+        }
+        Ok(counts)
+    }
+
+    // Helper function to find the measurements at the end of the circuit
+    //
+    // Returns the number of gates before the first measurement, and the basis, qubit and bit of each measured qubit
+    // in circuit order, or `None` if a measurement is followed by another kind of gate, a qubit is measured twice or a
+    // measurement is invalid, in which case executing the circuit shot by shot reports the error.
+    fn terminal_measurements(&self) -> Option<(usize, Vec<TerminalMeasurement>)> {
+        let prefix: usize = self.gates.iter().position(|gate| !gate.is_unitary()).unwrap_or(self.gates.len());
+        let mut measurements: Vec<TerminalMeasurement> = Vec::new();
+        let mut measured: Vec<bool> = vec![false; self.num_qubits];
+
+        for gate in &self.gates[prefix..] {
+            let (basis, qubits, bits): (MeasurementBasis, Vec<usize>, Vec<usize>) = match gate {
+                Gate::Barrier(_) => continue,
+                Gate::Measurement(basis, qubits) if qubits.is_empty() => {
+                    (*basis, (0..self.num_qubits).collect(), (0..self.num_qubits).collect())
+                }
+                Gate::Measurement(basis, qubits) => (*basis, qubits.clone(), qubits.clone()),
+                Gate::MeasurementInto(basis, qubits, bits) => (*basis, qubits.clone(), bits.clone()),
+                _ => return None,
+            };
+            if qubits.len() != bits.len() {
+                return None;
+            }
+            for (qubit, bit) in qubits.into_iter().zip(bits) {
+                if qubit >= self.num_qubits || bit >= self.num_bits || measured[qubit] {
+                    return None;
+                }
+                measured[qubit] = true;
+                measurements.push((basis, qubit, bit));
+            }
+        }
+        Some((prefix, measurements))
+    }
+
+    /// Gets the unbound parameters of the circuit.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Parameter>` - The parameters that gates of the circuit depend on, sorted by name.
+    pub fn parameters(&self) -> Vec<Parameter> {
+        let mut parameters: Vec<Parameter> = self
+            .gates
+            .iter()
+            .flat_map(|gate| gate.parameters())
+            .collect();
+        parameters.sort();
+        parameters.dedup();
+        parameters
+    }
+
+    /// Returns a copy of the circuit with values substituted for its parameters.
+    ///
+    /// Gates whose angles no longer depend on any parameter are replaced by the corresponding fixed-angle operators.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The values of the parameters. Parameters without a value are left unbound.
+    ///
+    /// # Returns
+    ///
+    /// * `Circuit` - The circuit with the values substituted.
+    pub fn bind(&self, values: &HashMap<Parameter, f64>) -> Circuit {
+        Circuit {
+            gates: self.gates.iter().map(|gate| gate.bind(values)).collect(),
+            num_qubits: self.num_qubits,
+            num_bits: self.num_bits,
+        }
+    }
+
+    /// Executes the circuit with the given initial state and parameter values, and returns the final state.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_state` - The initial state of the qubits in the circuit.
+    /// * `values` - The values of the parameters of the circuit.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<State, Error>` - The final state of the qubits after executing the circuit.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if a parameter of the circuit has no value.
+    /// * Returns an error if the number of qubits in the initial state does not match the number of qubits in the circuit.
+    /// * Returns an error if the circuit cannot be executed due to invalid gate operations.
+    pub fn execute_with_params(
+        &self,
+        initial_state: &State,
+        values: &HashMap<Parameter, f64>,
+    ) -> Result<State, Error> {
+        self.bind(values).execute(initial_state)
+    }
+
+    /// Computes the dense `2^n x 2^n` unitary matrix of the circuit.
+    ///
+    /// Each column is obtained by executing the circuit on the corresponding computational basis
+    /// state, with the columns computed in parallel. Following the qubit ordering of `State`,
+    /// qubit `k` of the circuit corresponds to bit `k` of the row and column indices.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Vec<Complex<f64>>>, Error>` - The unitary matrix of the circuit, as a vector of
+    ///   rows.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the circuit contains a measurement, a reset or a classically controlled gate.
+    /// * Returns an error if the circuit cannot be executed due to invalid gate operations.
+    pub fn unitary(&self) -> Result<Vec<Vec<Complex<f64>>>, Error> {
+        if self.gates.iter().any(|gate| !gate.is_unitary()) {
+            return Err(Error::NonUnitaryCircuit);
+        }
+
+        let dim: usize = 1 << self.num_qubits;
+        let columns: Vec<Vec<Complex<f64>>> = (0..dim)
+            .into_par_iter()
+            .map(|n| {
+                let basis_state: State = State::new_basis_n(self.num_qubits, n)?;
+                Ok(self.execute(&basis_state)?.state_vector)
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok((0..dim)
+            .map(|row| columns.iter().map(|column| column[row]).collect())
+            .collect())
+    }
+
+    /// Checks whether the circuit implements the same unitary as another circuit, up to a global
+    /// phase.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The circuit to compare against.
+    /// * `tol` - The maximum allowed difference between any two matrix entries, after the global
+    ///   phase has been removed.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool, Error>` - `true` if the circuits are equivalent, `false` otherwise. Circuits on
+    ///   different numbers of qubits are never equivalent.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if either circuit contains a measurement.
+    /// * Returns an error if either circuit cannot be executed due to invalid gate operations.
+    pub fn equivalent_to(&self, other: &Circuit, tol: f64) -> Result<bool, Error> {
+        if self.num_qubits != other.num_qubits {
+            return Ok(false);
+        }
+        let this: Vec<Vec<Complex<f64>>> = self.unitary()?;
+        let other: Vec<Vec<Complex<f64>>> = other.unitary()?;
+
+        // Estimate the relative phase from the largest entry, which is at least 2^(-n/2) in magnitude
+        let (row, column) = (0..this.len())
+            .flat_map(|row| (0..this.len()).map(move |column| (row, column)))
+            .max_by(|&(r1, c1), &(r2, c2)| this[r1][c1].norm().total_cmp(&this[r2][c2].norm()))
+            .unwrap_or((0, 0));
+        let ratio: Complex<f64> = other[row][column] / this[row][column];
+        if (ratio.norm() - 1.0).abs() > tol {
+            return Ok(false);
+        }
+        let phase: Complex<f64> = ratio / ratio.norm();
+
+        Ok(this
+            .iter()
+            .flatten()
+            .zip(other.iter().flatten())
+            .all(|(a, b)| (a * phase - b).norm() <= tol))
+    }
+
+    /// Returns the inverse of the circuit, which undoes its operation.
+    ///
+    /// The gates are applied in reverse order, each replaced by the adjoint of its operator on the
+    /// same target and control qubits. Barriers are kept in place.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Circuit, Error>` - The inverse circuit.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the circuit contains a measurement, a reset or a classically controlled gate.
+    /// * Returns an error if the circuit contains an operator without a known adjoint.
+    pub fn inverse(&self) -> Result<Circuit, Error> {
+        let gates: Vec<Gate> = self
+            .gates
+            .iter()
+            .rev()
+            .map(|gate| match gate {
+                Gate::Operator(operator, targets, controls) => operator
+                    .adjoint()
+                    .map(|adjoint| Gate::Operator(adjoint, targets.clone(), controls.clone()))
+                    .ok_or(Error::UnknownAdjoint),
+                Gate::Barrier(_) => Ok(gate.clone()),
+                _ => Err(Error::NonUnitaryCircuit),
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Circuit {
+            gates,
+            num_qubits: self.num_qubits,
+            num_bits: self.num_bits,
+        })
+    }
+
+    /// Returns the circuit repeated `k` times.
+    ///
+    /// A power of zero is the empty circuit. Negative powers can be built with `inverse().power(k)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - The number of repetitions of the circuit.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Circuit, Error>` - The circuit applied `k` times.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the circuit contains a measurement, a reset or a classically controlled gate.
+    pub fn power(&self, k: usize) -> Result<Circuit, Error> {
+        if self.gates.iter().any(|gate| !gate.is_unitary()) {
+            return Err(Error::NonUnitaryCircuit);
+        }
+
+        Ok(Circuit {
+            gates: self.gates.iter().cloned().cycle().take(self.gates.len() * k).collect(),
+            num_qubits: self.num_qubits,
+            num_bits: self.num_bits,
+        })
+    }
+
+    /// Returns a controlled version of the circuit, which only acts when all control qubits are in
+    /// the |1> state.
+    ///
+    /// The control qubits are added to every gate of the circuit, so any global phase of the
+    /// circuit becomes a relative phase of the controlled circuit. CNOT and Toffoli gates, which
+    /// take an exact number of control qubits, become Pauli-X gates with all of their controls.
+    ///
+    /// # Arguments
+    ///
+    /// * `control_qubits` - The control qubits, which must not be a target of any gate.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Circuit, Error>` - The controlled circuit.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the circuit contains a measurement, a reset or a classically controlled gate.
+    /// * Returns an error if a control qubit is out of range or repeated.
+    /// * Returns an error if a control qubit is a target of a gate in the circuit.
+    pub fn controlled(&self, control_qubits: &[usize]) -> Result<Circuit, Error> {
+        for (i, &control) in control_qubits.iter().enumerate() {
+            if control >= self.num_qubits || control_qubits[..i].contains(&control) {
+                return Err(Error::InvalidQubitIndex(control, self.num_qubits));
+            }
+        }
+
+        let gates: Vec<Gate> = self
+            .gates
+            .iter()
+            .map(|gate| match gate {
+                Gate::Operator(operator, targets, existing) => {
+                    if let Some(&control) = control_qubits.iter().find(|c| targets.contains(c)) {
+                        return Err(Error::OverlappingControlAndTargetQubits(control, control));
+                    }
+                    // Qubits that already control the gate are not repeated
+                    let mut controls: Vec<usize> = existing.clone();
+                    controls.extend(control_qubits.iter().filter(|c| !existing.contains(c)));
+                    // Operators such as CNOT and Toffoli require an exact number of controls, so their
+                    // uncontrolled cores are used instead
+                    let operator: Box<dyn Operator> = operator.uncontrolled().unwrap_or_else(|| operator.clone());
+                    Ok(Gate::Operator(operator, targets.clone(), controls))
+                }
+                Gate::Barrier(_) => Ok(gate.clone()),
+                _ => Err(Error::NonUnitaryCircuit),
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Circuit {
+            gates,
+            num_qubits: self.num_qubits,
+            num_bits: self.num_bits,
+        })
+    }
+
     /// Converts the circuit to its OpenQASM 3.0 (Quantum Assembly 3.0) representation.
-    pub fn to_qasm(&self) -> String {
-        unimplemented!("QASM conversion is not implemented yet");
+    ///
+    /// The program declares a qubit register `q` with one qubit per circuit qubit, and a bit register `c`
+    /// with one bit per classical bit if the circuit contains measurements or classically controlled gates.
+    /// Controlled gates are emitted with the `ctrl @` modifier, custom unitaries as `U(θ, φ, λ)` gates,
+    /// measurements in the X, Y or custom bases as a basis change around a computational basis `measure`, and
+    /// classically controlled gates as `if (c == v)` statements.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, CompilerError>` - The OpenQASM 3.0 source of the circuit.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the circuit contains an operator that does not implement `Compilable`.
+    /// * Returns an error if a gate has invalid operands (e.g. duplicate or out-of-range qubits).
+    /// * Returns an error if the name of an unbound parameter is not a valid identifier, or is reserved, e.g. `pi`,
+    ///   `rx` or the register names `q` and `c`.
+    pub fn to_qasm(&self) -> Result<String, CompilerError> {
+        let circuit_ir: CircuitIR = CircuitIR::try_from(self)?;
+        circuit_ir.to_qasm()
+    }
+
+    /// Parses an OpenQASM 3.0 (Quantum Assembly 3.0) program into a circuit.
+    ///
+    /// Supports qubit and bit register declarations, the gates of `stdgates.inc`, the built-in `U` and
+    /// `gphase` gates, the `ctrl @`, `negctrl @`, `inv @` and `pow(k) @` modifiers, `gate` definitions,
+    /// `const` declarations, `measure`, `reset` and `barrier` statements, `if` statements on bits and constant
+    /// expressions over `pi`, `tau` and `euler`. Each `input float` declaration adds a parameter to the circuit,
+    /// on which the angles of `rx`, `ry`, `rz`, `p` and their controlled versions may depend linearly. The qubits
+    /// (and bits) of all declared registers are numbered consecutively in declaration order.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The OpenQASM 3.0 source code.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Circuit, CompilerError>` - The parsed circuit.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `CompilerError::ParseError` with the line and column of the first syntax error, unknown
+    ///   identifier or unsupported statement.
+    pub fn from_qasm(source: &str) -> Result<Circuit, CompilerError> {
+        parse_qasm3(source)?.to_circuit()
+    }
+
+    /// Reads an OpenQASM 3.0 (Quantum Assembly 3.0) program from a file and parses it into a circuit.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file to read.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Circuit, CompilerError>` - The parsed circuit.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `CompilerError::IOError` if the file cannot be read.
+    /// * Returns `CompilerError::ParseError` if the program cannot be parsed (see `Circuit::from_qasm`).
+    pub fn from_qasm_file<P: AsRef<std::path::Path>>(path: P) -> Result<Circuit, CompilerError> {
+        let source: String = std::fs::read_to_string(path)
+            .map_err(|e| CompilerError::IOError(e.to_string()))?;
+        Circuit::from_qasm(&source)
+    }
+
+    /// Converts the circuit to its OpenQASM 2.0 (Quantum Assembly 2.0) representation.
+    ///
+    /// The program includes `qelib1.inc`, declares a quantum register `q` with one qubit per circuit qubit,
+    /// and a classical register `c` with one bit per classical bit if the circuit contains measurements or
+    /// classically controlled gates. Controlled gates are lowered to the controlled gates of `qelib1.inc`
+    /// (e.g. `cx`, `ccx`, `cu1`, `crz`, `cswap`), custom unitaries are emitted as `u3` gates, and classically
+    /// controlled gates as `if(c==v)` statements.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, CompilerError>` - The OpenQASM 2.0 source of the circuit.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the circuit contains an operator that does not implement `Compilable`.
+    /// * Returns an error if a gate has invalid operands (e.g. duplicate or out-of-range qubits).
+    /// * Returns `CompilerError::UnsupportedOperator` if a gate has no OpenQASM 2.0 spelling, such as a gate
+    ///   with more than two controls (or more than one control, for gates other than `x`), a measurement in
+    ///   a basis other than the computational basis, or a condition on only some of the classical bits.
+    pub fn to_qasm2(&self) -> Result<String, CompilerError> {
+        let circuit_ir: CircuitIR = CircuitIR::try_from(self)?;
+        circuit_ir.to_qasm2()
+    }
+
+    /// Parses an OpenQASM 2.0 (Quantum Assembly 2.0) program into a circuit.
+    ///
+    /// Supports `qreg` and `creg` declarations, the gates of `qelib1.inc`, the built-in `U` and `CX` gates,
+    /// `gate` definitions, `measure q -> c`, `reset` and `barrier` statements, `if(c==n)` statements and
+    /// constant expressions over `pi`. The qubits (and bits) of all declared registers are numbered consecutively in declaration order.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The OpenQASM 2.0 source code.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Circuit, CompilerError>` - The parsed circuit.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `CompilerError::ParseError` with the line and column of the first syntax error, unknown
+    ///   identifier or unsupported statement.
+    pub fn from_qasm2(source: &str) -> Result<Circuit, CompilerError> {
+        parse_qasm2(source)?.to_circuit()
+    }
+
+    /// Reads an OpenQASM 2.0 (Quantum Assembly 2.0) program from a file and parses it into a circuit.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file to read.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Circuit, CompilerError>` - The parsed circuit.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `CompilerError::IOError` if the file cannot be read.
+    /// * Returns `CompilerError::ParseError` if the program cannot be parsed (see `Circuit::from_qasm2`).
+    pub fn from_qasm2_file<P: AsRef<std::path::Path>>(path: P) -> Result<Circuit, CompilerError> {
+        let source: String = std::fs::read_to_string(path)
+            .map_err(|e| CompilerError::IOError(e.to_string()))?;
+        Circuit::from_qasm2(&source)
+    }
+
+    /// Maps the circuit onto a device with restricted connectivity.
+    ///
+    /// An initial layout of logical onto physical qubits is chosen, and SWAPs are inserted so that every
+    /// two-qubit gate acts on adjacent physical qubits, both using the SABRE heuristic (see `SabreRouter`).
+    /// If the circuit contains gates on more than two qubits, such as Toffoli gates, it is first lowered
+    /// to CNOTs and single-qubit gates with `DecomposeControlled`.
+    ///
+    /// The routed circuit acts on all physical qubits of the device. Its input state can be built with
+    /// `RoutedCircuit::to_physical_state`, and the inserted SWAPs undone on its output state with
+    /// `RoutedCircuit::to_logical_state`.
+    ///
+    /// # Arguments
+    ///
+    /// * `coupling_map` - The connectivity of the device.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<RoutedCircuit, CompilerError>` - The routed circuit, with its initial and final layouts.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the circuit contains an operator that does not implement `Compilable`.
+    /// * Returns an error if the circuit has more qubits than the device, or the coupling map is not
+    ///   connected.
+    pub fn route(&self, coupling_map: &CouplingMap) -> Result<RoutedCircuit, CompilerError> {
+        let mut circuit_ir: CircuitIR = CircuitIR::try_from(self)?;
+        if circuit_ir.instructions.iter().any(|instruction| instruction.qubits().len() > 2) {
+            DecomposeControlled::default().run(&mut circuit_ir)?;
+        }
+
+        let router: SabreRouter = SabreRouter::new(coupling_map.clone());
+        let initial_layout: Layout = router.layout(&circuit_ir)?;
+        let (routed_ir, final_layout, num_swaps) = router.route(&circuit_ir, &initial_layout)?;
+
+        Ok(RoutedCircuit {
+            circuit: routed_ir.to_circuit()?,
+            initial_layout,
+            final_layout,
+            num_swaps,
+        })
     }
 }
 
@@ -188,16 +1147,22 @@ impl Circuit {
 /// * `gates` - A vector of gates in the circuit.
 ///
 /// * `num_qubits` - The number of qubits in the circuit.
+///
+/// * `num_bits` - The number of classical bits in the circuit.
 pub struct CircuitBuilder {
     /// A vector of gates in the circuit builder.
     /// A temporary vector to hold gates before building the circuit.
     pub gates: Vec<Gate>,
     /// The number of qubits in the circuit builder.
     pub num_qubits: usize,
+    /// The number of classical bits in the circuit builder.
+    pub num_bits: usize,
+    /// The first invalid conditional body added to the builder, which `build` reports.
+    error: Option<Error>,
 }
 
 impl CircuitBuilder {
-    /// Creates a new circuit builder with the specified number of qubits.
+    /// Creates a new circuit builder with the specified number of qubits, and one classical bit per qubit.
     ///
     /// # Arguments
     ///
@@ -207,9 +1172,26 @@ impl CircuitBuilder {
     ///
     /// * `CircuitBuilder` - A new instance of the CircuitBuilder struct.
     pub fn new(num_qubits: usize) -> Self {
+        Self::with_bits(num_qubits, num_qubits)
+    }
+
+    /// Creates a new circuit builder with the specified number of qubits and classical bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_qubits` - The number of qubits in the circuit builder.
+    ///
+    /// * `num_bits` - The number of classical bits in the circuit builder.
+    ///
+    /// # Returns
+    ///
+    /// * `CircuitBuilder` - A new instance of the CircuitBuilder struct.
+    pub fn with_bits(num_qubits: usize, num_bits: usize) -> Self {
         CircuitBuilder {
             gates: Vec::new(),
             num_qubits,
+            num_bits,
+            error: None,
         }
     }
 
@@ -241,8 +1223,11 @@ impl CircuitBuilder {
     ///
     /// * `Result<Circuit, Error>` - A new instance of the Circuit struct or an error if the circuit cannot be built.
     pub fn build(&mut self) -> Result<Circuit, Error> {
+        if let Some(error) = &self.error {
+            return Err(error.clone());
+        }
         let gates_cloned = self.gates.clone();
-        Circuit::with_gates(gates_cloned, self.num_qubits)
+        Circuit::with_bits(gates_cloned, self.num_qubits, self.num_bits)
     }
 
     /// Builds the circuit from the gates in the circuit builder.
@@ -254,9 +1239,11 @@ impl CircuitBuilder {
     /// * `Result<Circuit, Error>` - A new instance of the Circuit struct or an error if the circuit cannot be built.
     pub fn build_final(&mut self) -> Circuit {
         let gates = std::mem::take(&mut self.gates);
+        self.error = None;
         Circuit {
             gates,
             num_qubits: self.num_qubits,
+            num_bits: self.num_bits,
         }
     }
 
@@ -594,8 +1581,8 @@ impl CircuitBuilder {
     /// # Arguments
     ///
     /// * `qubit` - The index of the qubit to which the Phase Shift gate will be applied.
-    /// * `angle` - The phase shift angle in radians.
-    pub fn p_gate(&mut self, qubit: usize, angle: f64) -> &mut Self {
+    /// * `angle` - The phase shift angle in radians, which may depend on parameters.
+    pub fn p_gate(&mut self, qubit: usize, angle: impl Into<ParameterExpression>) -> &mut Self {
         let gate: Gate = Gate::p_gate(qubit, angle);
         self.add_gate(gate);
         self
@@ -606,8 +1593,8 @@ impl CircuitBuilder {
     /// # Arguments
     ///
     /// * `qubits` - A vector of indices of the qubits to which the Phase Shift gates will be applied.
-    /// * `angle` - The phase shift angle in radians for all gates.
-    pub fn p_gates(&mut self, qubits: Vec<usize>, angle: f64) -> &mut Self {
+    /// * `angle` - The phase shift angle in radians for all gates, which may depend on parameters.
+    pub fn p_gates(&mut self, qubits: Vec<usize>, angle: impl Into<ParameterExpression>) -> &mut Self {
         let gates: Vec<Gate> = Gate::p_multi_gate(qubits, angle);
         self.add_gates(gates);
         self
@@ -619,8 +1606,8 @@ impl CircuitBuilder {
     ///
     /// * `target_qubits` - A vector of indices of the target qubits.
     /// * `control_qubits` - A vector of indices of the control qubits.
-    /// * `angle` - The phase shift angle in radians for all gates.
-    pub fn cp_gates(&mut self, target_qubits: Vec<usize>, control_qubits: Vec<usize>, angle: f64) -> &mut Self {
+    /// * `angle` - The phase shift angle in radians for all gates, which may depend on parameters.
+    pub fn cp_gates(&mut self, target_qubits: Vec<usize>, control_qubits: Vec<usize>, angle: impl Into<ParameterExpression>) -> &mut Self {
         let gates: Vec<Gate> = Gate::p_controlled_gates(target_qubits, control_qubits, angle);
         self.add_gates(gates);
         self
@@ -631,8 +1618,8 @@ impl CircuitBuilder {
     /// # Arguments
     ///
     /// * `qubit` - The index of the qubit to which the Rotate X gate will be applied.
-    /// * `angle` - The rotation angle in radians.
-    pub fn rx_gate(&mut self, qubit: usize, angle: f64) -> &mut Self {
+    /// * `angle` - The rotation angle in radians, which may depend on parameters.
+    pub fn rx_gate(&mut self, qubit: usize, angle: impl Into<ParameterExpression>) -> &mut Self {
         let gate: Gate = Gate::rx_gate(qubit, angle);
         self.add_gate(gate);
         self
@@ -643,8 +1630,8 @@ impl CircuitBuilder {
     /// # Arguments
     ///
     /// * `qubits` - A vector of indices of the qubits to which the Rotate X gates will be applied.
-    /// * `angle` - The rotation angle in radians for all gates.
-    pub fn rx_gates(&mut self, qubits: Vec<usize>, angle: f64) -> &mut Self {
+    /// * `angle` - The rotation angle in radians for all gates, which may depend on parameters.
+    pub fn rx_gates(&mut self, qubits: Vec<usize>, angle: impl Into<ParameterExpression>) -> &mut Self {
         let gates: Vec<Gate> = Gate::rx_multi_gate(qubits, angle);
         self.add_gates(gates);
         self
@@ -656,8 +1643,8 @@ impl CircuitBuilder {
     ///
     /// * `target_qubits` - A vector of indices of the target qubits.
     /// * `control_qubits` - A vector of indices of the control qubits.
-    /// * `angle` - The rotation angle in radians for all gates.
-    pub fn crx_gates(&mut self, target_qubits: Vec<usize>, control_qubits: Vec<usize>, angle: f64) -> &mut Self {
+    /// * `angle` - The rotation angle in radians for all gates, which may depend on parameters.
+    pub fn crx_gates(&mut self, target_qubits: Vec<usize>, control_qubits: Vec<usize>, angle: impl Into<ParameterExpression>) -> &mut Self {
         let gates: Vec<Gate> = Gate::rx_controlled_gates(target_qubits, control_qubits, angle);
         self.add_gates(gates);
         self
@@ -668,8 +1655,8 @@ impl CircuitBuilder {
     /// # Arguments
     ///
     /// * `qubit` - The index of the qubit to which the Rotate Y gate will be applied.
-    /// * `angle` - The rotation angle in radians.
-    pub fn ry_gate(&mut self, qubit: usize, angle: f64) -> &mut Self {
+    /// * `angle` - The rotation angle in radians, which may depend on parameters.
+    pub fn ry_gate(&mut self, qubit: usize, angle: impl Into<ParameterExpression>) -> &mut Self {
         let gate: Gate = Gate::ry_gate(qubit, angle);
         self.add_gate(gate);
         self
@@ -680,8 +1667,8 @@ impl CircuitBuilder {
     /// # Arguments
     ///
     /// * `qubits` - A vector of indices of the qubits to which the Rotate Y gates will be applied.
-    /// * `angle` - The rotation angle in radians for all gates.
-    pub fn ry_gates(&mut self, qubits: Vec<usize>, angle: f64) -> &mut Self {
+    /// * `angle` - The rotation angle in radians for all gates, which may depend on parameters.
+    pub fn ry_gates(&mut self, qubits: Vec<usize>, angle: impl Into<ParameterExpression>) -> &mut Self {
         let gates: Vec<Gate> = Gate::ry_multi_gate(qubits, angle);
         self.add_gates(gates);
         self
@@ -693,8 +1680,8 @@ impl CircuitBuilder {
     ///
     /// * `target_qubits` - A vector of indices of the target qubits.
     /// * `control_qubits` - A vector of indices of the control qubits.
-    /// * `angle` - The rotation angle in radians for all gates.
-    pub fn cry_gates(&mut self, target_qubits: Vec<usize>, control_qubits: Vec<usize>, angle: f64) -> &mut Self {
+    /// * `angle` - The rotation angle in radians for all gates, which may depend on parameters.
+    pub fn cry_gates(&mut self, target_qubits: Vec<usize>, control_qubits: Vec<usize>, angle: impl Into<ParameterExpression>) -> &mut Self {
         let gates: Vec<Gate> = Gate::ry_controlled_gates(target_qubits, control_qubits, angle);
         self.add_gates(gates);
         self
@@ -705,8 +1692,8 @@ impl CircuitBuilder {
     /// # Arguments
     ///
     /// * `qubit` - The index of the qubit to which the Rotate Z gate will be applied.
-    /// * `angle` - The rotation angle in radians.
-    pub fn rz_gate(&mut self, qubit: usize, angle: f64) -> &mut Self {
+    /// * `angle` - The rotation angle in radians, which may depend on parameters.
+    pub fn rz_gate(&mut self, qubit: usize, angle: impl Into<ParameterExpression>) -> &mut Self {
         let gate: Gate = Gate::rz_gate(qubit, angle);
         self.add_gate(gate);
         self
@@ -717,8 +1704,8 @@ impl CircuitBuilder {
     /// # Arguments
     ///
     /// * `qubits` - A vector of indices of the qubits to which the Rotate Z gates will be applied.
-    /// * `angle` - The rotation angle in radians for all gates.
-    pub fn rz_gates(&mut self, qubits: Vec<usize>, angle: f64) -> &mut Self {
+    /// * `angle` - The rotation angle in radians for all gates, which may depend on parameters.
+    pub fn rz_gates(&mut self, qubits: Vec<usize>, angle: impl Into<ParameterExpression>) -> &mut Self {
         let gates: Vec<Gate> = Gate::rz_multi_gate(qubits, angle);
         self.add_gates(gates);
         self
@@ -730,8 +1717,8 @@ impl CircuitBuilder {
     ///
     /// * `target_qubits` - A vector of indices of the target qubits.
     /// * `control_qubits` - A vector of indices of the control qubits.
-    /// * `angle` - The rotation angle in radians for all gates.
-    pub fn crz_gates(&mut self, target_qubits: Vec<usize>, control_qubits: Vec<usize>, angle: f64) -> &mut Self {
+    /// * `angle` - The rotation angle in radians for all gates, which may depend on parameters.
+    pub fn crz_gates(&mut self, target_qubits: Vec<usize>, control_qubits: Vec<usize>, angle: impl Into<ParameterExpression>) -> &mut Self {
         let gates: Vec<Gate> = Gate::rz_controlled_gates(target_qubits, control_qubits, angle);
         self.add_gates(gates);
         self
@@ -832,6 +1819,74 @@ impl CircuitBuilder {
         self
     }
 
+    /// Adds an arbitrary two-qubit unitary gate to the circuit builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `qubit1` - The index of the first qubit, corresponding to bit 0 of the matrix index.
+    /// * `qubit2` - The index of the second qubit, corresponding to bit 1 of the matrix index.
+    /// * `unitary` - The 4×4 matrix representing the unitary operator.
+    pub fn unitary4_gate(
+        &mut self,
+        qubit1: usize,
+        qubit2: usize,
+        unitary: [[Complex<f64>; 4]; 4],
+    ) -> &mut Self {
+        let gate: Gate = Gate::unitary4_gate(qubit1, qubit2, unitary);
+        self.add_gate(gate);
+        self
+    }
+
+    /// Adds a controlled arbitrary two-qubit unitary gate to the circuit builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `qubit1` - The index of the first target qubit, corresponding to bit 0 of the matrix index.
+    /// * `qubit2` - The index of the second target qubit, corresponding to bit 1 of the matrix index.
+    /// * `control_qubits` - A vector of indices of the control qubits.
+    /// * `unitary` - The 4×4 matrix representing the unitary operator.
+    pub fn cunitary4_gate(
+        &mut self,
+        qubit1: usize,
+        qubit2: usize,
+        control_qubits: Vec<usize>,
+        unitary: [[Complex<f64>; 4]; 4],
+    ) -> &mut Self {
+        let gate: Gate = Gate::unitary4_controlled_gate(qubit1, qubit2, control_qubits, unitary);
+        self.add_gate(gate);
+        self
+    }
+
+    /// Adds an arbitrary k-qubit unitary gate to the circuit builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `qubits` - The indices of the k target qubits, where the j-th qubit corresponds to bit j of the matrix index.
+    /// * `unitary` - The 2^k×2^k matrix representing the unitary operator.
+    pub fn unitary_n_gate(&mut self, qubits: Vec<usize>, unitary: Vec<Vec<Complex<f64>>>) -> &mut Self {
+        let gate: Gate = Gate::unitary_n_gate(qubits, unitary);
+        self.add_gate(gate);
+        self
+    }
+
+    /// Adds a controlled arbitrary k-qubit unitary gate to the circuit builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_qubits` - The indices of the k target qubits, where the j-th qubit corresponds to bit j of the matrix index.
+    /// * `control_qubits` - A vector of indices of the control qubits.
+    /// * `unitary` - The 2^k×2^k matrix representing the unitary operator.
+    pub fn cunitary_n_gate(
+        &mut self,
+        target_qubits: Vec<usize>,
+        control_qubits: Vec<usize>,
+        unitary: Vec<Vec<Complex<f64>>>,
+    ) -> &mut Self {
+        let gate: Gate = Gate::unitary_n_controlled_gate(target_qubits, control_qubits, unitary);
+        self.add_gate(gate);
+        self
+    }
+
     /// Adds a Toffoli (CCNOT) gate to the circuit builder.
     ///
     /// # Arguments
@@ -882,4 +1937,84 @@ impl CircuitBuilder {
         self.add_gate(gate);
         self
     }
+
+    /// Adds a measurement gate that records its outcomes in the specified classical bits to the circuit builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `basis` - The measurement basis (e.g., computational).
+    ///
+    /// * `qubits` - A vector of indices of the qubits to be measured.
+    ///
+    /// * `bits` - A vector of indices of the classical bits in which the outcomes are recorded, one per qubit.
+    pub fn measure_into_gate(
+        &mut self,
+        basis: MeasurementBasis,
+        qubits: Vec<usize>,
+        bits: Vec<usize>,
+    ) -> &mut Self {
+        let gate: Gate = Gate::MeasurementInto(basis, qubits, bits);
+        self.add_gate(gate);
+        self
+    }
+
+    /// Adds a reset gate to the circuit builder, which returns the qubits to the |0> state.
+    ///
+    /// # Arguments
+    ///
+    /// * `qubits` - A vector of indices of the qubits to be reset.
+    pub fn reset(&mut self, qubits: Vec<usize>) -> &mut Self {
+        let gate: Gate = Gate::Reset(qubits);
+        self.add_gate(gate);
+        self
+    }
+
+    /// Adds a barrier to the circuit builder, across which compiler passes do not move or merge gates.
+    ///
+    /// # Arguments
+    ///
+    /// * `qubits` - A vector of indices of the qubits covered by the barrier, or an empty vector for every qubit.
+    pub fn barrier(&mut self, qubits: Vec<usize>) -> &mut Self {
+        let gate: Gate = Gate::Barrier(qubits);
+        self.add_gate(gate);
+        self
+    }
+
+    /// Adds classically controlled gates to the circuit builder, which are only applied if the condition holds.
+    ///
+    /// # Arguments
+    ///
+    /// * `condition` - The condition on the classical bits.
+    ///
+    /// * `body` - A function adding the gates to apply if the condition holds to a builder on the same qubits,
+    ///   e.g. `|builder| { builder.x_gate(2); }`.
+    ///
+    /// Every gate of the body checks the condition again when it is applied, so only the last gate of the body may
+    /// write a bit of the condition. Otherwise `build` returns `Error::ConditionalBodyWritesCondition`.
+    pub fn conditional(
+        &mut self,
+        condition: ClassicalCondition,
+        body: impl FnOnce(&mut CircuitBuilder),
+    ) -> &mut Self {
+        let mut builder: CircuitBuilder = CircuitBuilder::with_bits(self.num_qubits, self.num_bits);
+        body(&mut builder);
+        let num_gates: usize = builder.gates.len();
+        let overwritten: Option<usize> = builder.gates[..num_gates.saturating_sub(1)]
+            .iter()
+            .flat_map(|gate| gate.written_bits(self.num_qubits))
+            .find(|bit| condition.bits.contains(bit));
+        if let Some(bit) = overwritten {
+            self.error.get_or_insert(Error::ConditionalBodyWritesCondition(bit));
+        }
+        if let Some(error) = builder.error {
+            self.error.get_or_insert(error);
+        }
+        let gates: Vec<Gate> = builder
+            .gates
+            .into_iter()
+            .map(|gate| Gate::Conditional(condition.clone(), Box::new(gate)))
+            .collect();
+        self.add_gates(gates);
+        self
+    }
 }
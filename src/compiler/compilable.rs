@@ -1,12 +1,17 @@
 use crate::{
     circuit::Circuit,
-    compiler::ir::InstructionIR,
+    compiler::{
+        ir::InstructionIR,
+        kak::KakDecomposition,
+    },
     components::gate::Gate,
     components::{
-        measurement::MeasurementOperation,
+        classical::ConditionalOperation,
+        measurement::{MeasurementIntoOperation, MeasurementOperation},
         operator::{
-            CNOT, Hadamard, Identity, Pauli, PhaseS, PhaseSdag, PhaseShift, PhaseT, PhaseTdag,
-            RotateX, RotateY, RotateZ, SWAP, Toffoli, Unitary2,
+            CNOT, Hadamard, Identity, ParameterisedKind, ParameterisedOperator, Pauli, PhaseS,
+            PhaseSdag, PhaseShift, PhaseT, PhaseTdag, RotateX, RotateY, RotateZ, SWAP, Toffoli,
+            Unitary2, Unitary4, UnitaryN,
         },
     },
     errors::CompilerError,
@@ -63,6 +68,7 @@ impl CompilableCircuit {
 /// Internal struct representing a gate that can be compiled into an IR representation
 ///
 /// This struct is used to hold the operator, target qubits, and control qubits of a gate.
+#[derive(Clone)]
 pub(crate) struct CompilableGate {
     /// The operator of the gate
     pub operator: Box<dyn Compilable>,
@@ -84,51 +90,124 @@ impl TryFrom<&Circuit> for CompilableCircuit {
     /// A `Result` containing the `CompilableCircuit` or an error message if conversion fails.
     fn try_from(circuit: &Circuit) -> Result<Self, CompilerError> {
         let num_qubits: usize = circuit.get_num_qubits();
-        let mut compilable_gates: Vec<CompilableGate> = Vec::with_capacity(circuit.gates.len() * 2); // Preallocate space for operators
-
-        for gate in &circuit.gates {
-            match gate {
-                Gate::Operator(op, _targets, _controls) => {
-                    if let Some(compilable_op) = op.to_compilable() {
-                        // Convert the operator to a compilable form
-                        let targets = _targets.clone();
-                        let controls = _controls.clone();
-                        let operator = dyn_clone::clone_box(compilable_op);
-
-                        // Create a CompilableGate for the operator
-                        let gate: CompilableGate = CompilableGate {
-                            operator,
-                            targets,
-                            controls,
-                        };
-                        compilable_gates.push(gate);
-                    } else {
-                        return Err(CompilerError::UnsupportedOperator(
-                            "Operator does not implement Compilable trait".to_string(),
-                        ));
-                    }
-                }
-                Gate::Measurement(measurement_basis, targets) => {
-                    // All measurement operations are compilable, no trait check needed
-                    let measurement_op = MeasurementOperation {
-                        basis: *measurement_basis,
-                    };
-
-                    // Create a CompilableGate for the measurement operation
-                    let gate = CompilableGate {
-                        operator: Box::new(measurement_op),
-                        targets: targets.clone(),
-                        controls: vec![],
-                    };
-
-                    compilable_gates.push(gate);
+        let gates: Vec<CompilableGate> = circuit
+            .gates
+            .iter()
+            .map(CompilableGate::try_from)
+            .collect::<Result<Vec<CompilableGate>, CompilerError>>()?;
+        Ok(CompilableCircuit { num_qubits, gates })
+    }
+}
+
+impl TryFrom<&Gate> for CompilableGate {
+    type Error = CompilerError;
+
+    /// Converts a `Gate` into a `CompilableGate`
+    ///
+    /// # Arguments
+    /// * `gate` - The gate to convert
+    ///
+    /// # Returns
+    /// A `Result` containing the `CompilableGate` or an error message if the gate's operator is not compilable.
+    fn try_from(gate: &Gate) -> Result<Self, CompilerError> {
+        match gate {
+            Gate::Operator(op, _targets, _controls) => {
+                if let Some(compilable_op) = op.to_compilable() {
+                    // Convert the operator to a compilable form
+                    let targets = _targets.clone();
+                    let controls = _controls.clone();
+                    let operator = dyn_clone::clone_box(compilable_op);
+
+                    // Create a CompilableGate for the operator
+                    Ok(CompilableGate {
+                        operator,
+                        targets,
+                        controls,
+                    })
+                } else if let Some(unitary) = op
+                    .matrix()
+                    .and_then(|matrix| UnitaryN::new(matrix).ok())
+                    .filter(|unitary| unitary.num_qubits <= 2 && unitary.num_qubits == _targets.len())
+                {
+                    // Operators with a known one- or two-qubit matrix are compiled through its Euler or KAK
+                    // decomposition, as for `UnitaryN`
+                    Ok(CompilableGate {
+                        operator: Box::new(unitary),
+                        targets: _targets.clone(),
+                        controls: _controls.clone(),
+                    })
+                } else {
+                    Err(CompilerError::UnsupportedOperator(
+                        "Operator does not implement Compilable trait".to_string(),
+                    ))
                 }
             }
+            Gate::Measurement(measurement_basis, targets) => {
+                // All measurement operations are compilable, no trait check needed
+                let measurement_op = MeasurementOperation {
+                    basis: *measurement_basis,
+                };
+
+                // Create a CompilableGate for the measurement operation
+                Ok(CompilableGate {
+                    operator: Box::new(measurement_op),
+                    targets: targets.clone(),
+                    controls: vec![],
+                })
+            }
+            Gate::MeasurementInto(measurement_basis, targets, bits) => Ok(CompilableGate {
+                operator: Box::new(MeasurementIntoOperation {
+                    basis: *measurement_basis,
+                    bits: bits.clone(),
+                }),
+                targets: targets.clone(),
+                controls: vec![],
+            }),
+            Gate::Conditional(condition, gate) => Ok(CompilableGate {
+                operator: Box::new(ConditionalOperation {
+                    condition: condition.clone(),
+                    gate: CompilableGate::try_from(gate.as_ref())?,
+                }),
+                targets: gate.get_target_qubits().clone(),
+                controls: vec![],
+            }),
+            Gate::Reset(targets) => Ok(CompilableGate {
+                operator: Box::new(ResetOperation),
+                targets: targets.clone(),
+                controls: vec![],
+            }),
+            Gate::Barrier(targets) => Ok(CompilableGate {
+                operator: Box::new(BarrierOperation),
+                targets: targets.clone(),
+                controls: vec![],
+            }),
         }
-        Ok(CompilableCircuit {
-            num_qubits,
-            gates: compilable_gates,
-        })
+    }
+}
+
+/// Represents a reset operation in a quantum circuit.
+///
+/// This is an internal struct strictly used for the IR representation of a reset operation.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ResetOperation;
+
+impl Compilable for ResetOperation {
+    fn to_ir(&self, targets: Vec<usize>, _controls: Vec<usize>) -> Vec<InstructionIR> {
+        // No controls for reset operations.
+        targets.into_iter().map(InstructionIR::Reset).collect()
+    }
+}
+
+/// Represents a barrier in a quantum circuit.
+///
+/// This is an internal struct strictly used for the IR representation of a barrier.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BarrierOperation;
+
+impl Compilable for BarrierOperation {
+    fn to_ir(&self, targets: Vec<usize>, _controls: Vec<usize>) -> Vec<InstructionIR> {
+        // A single barrier covers all of its qubits
+        vec![InstructionIR::Barrier(targets)]
     }
 }
 
@@ -293,3 +372,46 @@ impl Compilable for Unitary2 {
             .collect()
     }
 }
+
+impl Compilable for Unitary4 {
+    fn to_ir(&self, targets: Vec<usize>, controls: Vec<usize>) -> Vec<InstructionIR> {
+        self.kak_decomposition()
+            .to_instructions([targets[0], targets[1]], &controls)
+    }
+}
+
+impl Compilable for UnitaryN {
+    fn to_ir(&self, targets: Vec<usize>, controls: Vec<usize>) -> Vec<InstructionIR> {
+        let m = &self.matrix;
+        match self.num_qubits {
+            1 => vec![InstructionIR::Unitary(
+                [[m[0][0], m[0][1]], [m[1][0], m[1][1]]],
+                targets[0],
+                controls,
+            )],
+            2 => KakDecomposition::new(&std::array::from_fn(|i| std::array::from_fn(|j| m[i][j])))
+                .to_instructions([targets[0], targets[1]], &controls),
+            _ => unreachable!("UnitaryN is only compilable on one or two qubits"),
+        }
+    }
+}
+
+impl Compilable for ParameterisedOperator {
+    fn to_ir(&self, targets: Vec<usize>, controls: Vec<usize>) -> Vec<InstructionIR> {
+        if let Some(angle) = self.angle.value() {
+            return match self.kind {
+                ParameterisedKind::RotateX => RotateX::new(angle).to_ir(targets, controls),
+                ParameterisedKind::RotateY => RotateY::new(angle).to_ir(targets, controls),
+                ParameterisedKind::RotateZ => RotateZ::new(angle).to_ir(targets, controls),
+                ParameterisedKind::PhaseShift => PhaseShift::new(angle).to_ir(targets, controls),
+            };
+        }
+
+        targets
+            .iter()
+            .map(|&target| {
+                InstructionIR::Parameterised(self.kind, self.angle.clone(), target, controls.clone())
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,199 @@
+use crate::errors::CompilerError;
+use std::collections::VecDeque;
+
+/// The connectivity of a device, as an undirected graph over its physical qubits
+///
+/// Two-qubit instructions can only be applied to physical qubits joined by an edge. The direction of
+/// an edge is ignored, since a CNOT can be reversed with single-qubit gates.
+///
+/// # Fields
+///
+/// * `num_qubits` - The number of physical qubits on the device.
+/// * `edges` - The edges of the coupling graph, without duplicates.
+/// * `neighbours` - The qubits adjacent to each physical qubit, in ascending order.
+/// * `distances` - The shortest path length between each pair of physical qubits, or `usize::MAX` if
+///   there is no path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CouplingMap {
+    num_qubits: usize,
+    edges: Vec<(usize, usize)>,
+    neighbours: Vec<Vec<usize>>,
+    distances: Vec<Vec<usize>>,
+}
+
+impl CouplingMap {
+    /// Creates a coupling map from a list of edges between physical qubits
+    ///
+    /// # Arguments
+    ///
+    /// * `num_qubits` - The number of physical qubits on the device.
+    /// * `edges` - The pairs of physical qubits that can interact. Duplicate and reversed edges are
+    ///   merged.
+    ///
+    /// # Returns
+    /// The coupling map, or `CompilerError::InvalidOperands` if an edge joins a qubit to itself or refers
+    /// to a qubit out of range.
+    pub fn from_edges(num_qubits: usize, edges: &[(usize, usize)]) -> Result<Self, CompilerError> {
+        let mut neighbours: Vec<Vec<usize>> = vec![Vec::new(); num_qubits];
+        let mut unique_edges: Vec<(usize, usize)> = Vec::with_capacity(edges.len());
+        for &(a, b) in edges {
+            if a >= num_qubits || b >= num_qubits || a == b {
+                return Err(CompilerError::InvalidOperands(
+                    format!("edge ({}, {}) on {} qubits", a, b, num_qubits),
+                    "coupling map".to_string(),
+                ));
+            }
+            if !neighbours[a].contains(&b) {
+                neighbours[a].push(b);
+                neighbours[b].push(a);
+                unique_edges.push((a.min(b), a.max(b)));
+            }
+        }
+        for adjacent in &mut neighbours {
+            adjacent.sort_unstable();
+        }
+
+        let distances: Vec<Vec<usize>> = (0..num_qubits)
+            .map(|source| breadth_first_distances(&neighbours, source))
+            .collect();
+
+        Ok(CouplingMap {
+            num_qubits,
+            edges: unique_edges,
+            neighbours,
+            distances,
+        })
+    }
+
+    /// Creates a coupling map of qubits in a line, where qubit `i` is joined to qubit `i + 1`
+    ///
+    /// # Arguments
+    ///
+    /// * `num_qubits` - The number of physical qubits.
+    pub fn line(num_qubits: usize) -> Self {
+        let edges: Vec<(usize, usize)> = (1..num_qubits).map(|i| (i - 1, i)).collect();
+        CouplingMap::from_edges(num_qubits, &edges).expect("line edges are valid")
+    }
+
+    /// Creates a coupling map of qubits on a rectangular grid, with nearest-neighbour edges
+    ///
+    /// The qubit in row `r` and column `c` has index `r * cols + c`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The number of rows of the grid.
+    /// * `cols` - The number of columns of the grid.
+    pub fn grid(rows: usize, cols: usize) -> Self {
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        for row in 0..rows {
+            for col in 0..cols {
+                let qubit: usize = row * cols + col;
+                if col + 1 < cols {
+                    edges.push((qubit, qubit + 1));
+                }
+                if row + 1 < rows {
+                    edges.push((qubit, qubit + cols));
+                }
+            }
+        }
+        CouplingMap::from_edges(rows * cols, &edges).expect("grid edges are valid")
+    }
+
+    /// Creates a heavy-hexagon coupling map, as used by IBM devices
+    ///
+    /// The lattice is built from `rows` rows of `cols` vertices, laid out as a brick wall of hexagons,
+    /// with an extra qubit placed on every edge. Vertical edges join column `c` of rows `r` and `r + 1`
+    /// when `r + c` is even. The vertex in row `r` and column `c` has index `r * cols + c`; the qubits
+    /// on the horizontal edges follow, row by row, then the qubits on the vertical edges.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The number of rows of hexagon vertices.
+    /// * `cols` - The number of vertices in each row.
+    pub fn heavy_hex(rows: usize, cols: usize) -> Self {
+        let vertex = |row: usize, col: usize| row * cols + col;
+        let mut num_qubits: usize = rows * cols;
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+
+        // Each lattice edge is replaced by two edges through a new qubit in its middle
+        let mut add_heavy_edge = |a: usize, b: usize, edges: &mut Vec<(usize, usize)>| {
+            edges.push((a, num_qubits));
+            edges.push((num_qubits, b));
+            num_qubits += 1;
+        };
+        for row in 0..rows {
+            for col in 0..cols.saturating_sub(1) {
+                add_heavy_edge(vertex(row, col), vertex(row, col + 1), &mut edges);
+            }
+        }
+        for row in 0..rows.saturating_sub(1) {
+            for col in (0..cols).filter(|col| (row + col) % 2 == 0) {
+                add_heavy_edge(vertex(row, col), vertex(row + 1, col), &mut edges);
+            }
+        }
+
+        CouplingMap::from_edges(num_qubits, &edges).expect("heavy-hex edges are valid")
+    }
+
+    /// Returns the number of physical qubits
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    /// Returns the edges of the coupling map, each with the smaller qubit first
+    pub fn edges(&self) -> &[(usize, usize)] {
+        &self.edges
+    }
+
+    /// Returns the physical qubits adjacent to a qubit, in ascending order
+    ///
+    /// # Panics
+    /// Panics if `qubit` is out of range.
+    pub fn neighbours(&self, qubit: usize) -> &[usize] {
+        &self.neighbours[qubit]
+    }
+
+    /// Returns whether two physical qubits are joined by an edge
+    pub fn are_adjacent(&self, a: usize, b: usize) -> bool {
+        self.neighbours.get(a).is_some_and(|adjacent| adjacent.contains(&b))
+    }
+
+    /// Returns the number of edges on the shortest path between two physical qubits
+    ///
+    /// Returns `None` if either qubit is out of range or there is no path between them.
+    pub fn distance(&self, a: usize, b: usize) -> Option<usize> {
+        self.distances
+            .get(a)
+            .and_then(|row| row.get(b))
+            .copied()
+            .filter(|&distance| distance != usize::MAX)
+    }
+
+    /// Returns whether every physical qubit can be reached from every other one
+    pub fn is_connected(&self) -> bool {
+        self.distances
+            .first()
+            .is_none_or(|row| row.iter().all(|&distance| distance != usize::MAX))
+    }
+
+    /// Returns the full distance matrix, with `usize::MAX` for unreachable pairs
+    pub(crate) fn distance_matrix(&self) -> &[Vec<usize>] {
+        &self.distances
+    }
+}
+
+/// Returns the distance from `source` to every qubit, using a breadth-first search
+fn breadth_first_distances(neighbours: &[Vec<usize>], source: usize) -> Vec<usize> {
+    let mut distances: Vec<usize> = vec![usize::MAX; neighbours.len()];
+    let mut queue: VecDeque<usize> = VecDeque::from([source]);
+    distances[source] = 0;
+    while let Some(qubit) = queue.pop_front() {
+        for &next in &neighbours[qubit] {
+            if distances[next] == usize::MAX {
+                distances[next] = distances[qubit] + 1;
+                queue.push_back(next);
+            }
+        }
+    }
+    distances
+}
@@ -0,0 +1,211 @@
+use crate::compiler::{
+    ir::InstructionIR,
+    linalg::{instruction_matrix, multiply, u_matrix},
+    qasm::unitary_to_u_angles,
+};
+use num_complex::Complex;
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2, PI, TAU};
+
+/// Tolerance used when deciding whether an Euler angle can be dropped
+const EULER_TOLERANCE: f64 = 1e-10;
+
+/// The rotation axes of an Euler decomposition of a single-qubit unitary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EulerBasis {
+    /// `U = e^{iα} Rz(φ) Ry(θ) Rz(λ)`
+    ZYZ,
+    /// `U = e^{iα} Rz(φ) Rx(θ) Rz(λ)`
+    ZXZ,
+    /// `U = e^{iα} Rx(φ) Ry(θ) Rx(λ)`
+    XYX,
+    /// `U = e^{iα} U3(θ, φ, λ)`, using the OpenQASM definition of the `U` gate
+    U3,
+}
+
+/// The Euler angles and global phase of a single-qubit unitary in a given basis
+///
+/// The rotations are applied right to left: for `ZYZ`, `Rz(λ)` is applied first and `Rz(φ)` last.
+///
+/// # Fields
+///
+/// * `basis` - The rotation axes of the decomposition.
+/// * `theta` - The angle of the middle rotation.
+/// * `phi` - The angle of the last rotation.
+/// * `lambda` - The angle of the first rotation.
+/// * `global_phase` - The global phase α.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EulerDecomposition {
+    /// The rotation axes of the decomposition.
+    pub basis: EulerBasis,
+    /// The angle of the middle rotation.
+    pub theta: f64,
+    /// The angle of the last rotation.
+    pub phi: f64,
+    /// The angle of the first rotation.
+    pub lambda: f64,
+    /// The global phase α.
+    pub global_phase: f64,
+}
+
+impl EulerDecomposition {
+    /// Decomposes a 2×2 unitary matrix in the given basis
+    ///
+    /// The matrix is assumed to be unitary; it is not validated.
+    ///
+    /// # Arguments
+    ///
+    /// * `matrix` - The unitary matrix to decompose.
+    /// * `basis` - The rotation axes to decompose into.
+    pub fn new(matrix: &[[Complex<f64>; 2]; 2], basis: EulerBasis) -> Self {
+        let (theta, phi, lambda, global_phase) = match basis {
+            EulerBasis::U3 => unitary_to_u_angles(matrix),
+            EulerBasis::ZYZ => zyz_angles(matrix),
+            // Ry(θ) = Rz(π/2) Rx(θ) Rz(-π/2)
+            EulerBasis::ZXZ => {
+                let (theta, phi, lambda, global_phase) = zyz_angles(matrix);
+                (theta, phi + FRAC_PI_2, lambda - FRAC_PI_2, global_phase)
+            }
+            // H Rz(a) H = Rx(a) and H Ry(a) H = Ry(-a), so decompose H U H in ZYZ
+            EulerBasis::XYX => {
+                let s: Complex<f64> = Complex::new(FRAC_1_SQRT_2, 0.0);
+                let hadamard: [[Complex<f64>; 2]; 2] = [[s, s], [s, -s]];
+                let conjugated: [[Complex<f64>; 2]; 2] =
+                    multiply(&hadamard, &multiply(matrix, &hadamard));
+                let (theta, phi, lambda, global_phase) = zyz_angles(&conjugated);
+                (-theta, phi, lambda, global_phase)
+            }
+        };
+        EulerDecomposition {
+            basis,
+            theta,
+            phi,
+            lambda,
+            global_phase,
+        }
+    }
+
+    /// Returns the unitary matrix described by the decomposition, including its global phase
+    pub fn matrix(&self) -> [[Complex<f64>; 2]; 2] {
+        let phase: Complex<f64> = Complex::from_polar(1.0, self.global_phase);
+        let product: [[Complex<f64>; 2]; 2] = match self.basis {
+            EulerBasis::U3 => u_matrix(self.theta, self.phi, self.lambda, 0.0),
+            _ => self
+                .rotations(0)
+                .iter()
+                .map(|rotation| instruction_matrix(rotation).expect("rotations have a matrix"))
+                .fold(
+                    [
+                        [Complex::new(1.0, 0.0), Complex::new(0.0, 0.0)],
+                        [Complex::new(0.0, 0.0), Complex::new(1.0, 0.0)],
+                    ],
+                    |product, matrix| multiply(&matrix, &product),
+                ),
+        };
+        product.map(|row| row.map(|entry| entry * phase))
+    }
+
+    /// Returns the shortest rotation sequence implementing the decomposition, in order of application
+    ///
+    /// Angles are reduced to (-π, π]. Rotations by a multiple of 2π are dropped, and when the middle
+    /// angle is a multiple of 2π the outer rotations are merged into one. For the `U3` basis, a single
+    /// `Unitary` instruction is returned unless the gate is the identity. The global phase is not
+    /// included, so the sequence is only equal to the decomposed unitary up to a global phase.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The qubit the rotations act on.
+    pub fn to_instructions(&self, target: usize) -> Vec<InstructionIR> {
+        if self.basis == EulerBasis::U3 {
+            if is_multiple_of_tau(self.theta) && is_multiple_of_tau(self.phi + self.lambda) {
+                return vec![];
+            }
+            return vec![InstructionIR::Unitary(
+                u_matrix(self.theta, self.phi, self.lambda, 0.0),
+                target,
+                vec![],
+            )];
+        }
+        let mut rotations: Vec<InstructionIR> = self.rotations(target);
+        if is_multiple_of_tau(self.theta) {
+            rotations.truncate(1);
+            rotations[0] = with_angle(&rotations[0], self.phi + self.lambda);
+        }
+        rotations
+            .iter()
+            .filter(|rotation| !is_identity_rotation(rotation))
+            .map(|rotation| with_angle(rotation, reduce_angle(rotation_angle(rotation))))
+            .collect()
+    }
+
+    /// Returns the three rotations of a rotation basis, in order of application
+    fn rotations(&self, target: usize) -> Vec<InstructionIR> {
+        match self.basis {
+            EulerBasis::ZYZ => vec![
+                InstructionIR::Rz(self.lambda, target, vec![]),
+                InstructionIR::Ry(self.theta, target, vec![]),
+                InstructionIR::Rz(self.phi, target, vec![]),
+            ],
+            EulerBasis::ZXZ => vec![
+                InstructionIR::Rz(self.lambda, target, vec![]),
+                InstructionIR::Rx(self.theta, target, vec![]),
+                InstructionIR::Rz(self.phi, target, vec![]),
+            ],
+            EulerBasis::XYX => vec![
+                InstructionIR::Rx(self.lambda, target, vec![]),
+                InstructionIR::Ry(self.theta, target, vec![]),
+                InstructionIR::Rx(self.phi, target, vec![]),
+            ],
+            EulerBasis::U3 => unreachable!("U3 is not a sequence of rotations"),
+        }
+    }
+}
+
+/// Finds `(θ, φ, λ, α)` such that `matrix = e^{iα} Rz(φ) Ry(θ) Rz(λ)`
+fn zyz_angles(matrix: &[[Complex<f64>; 2]; 2]) -> (f64, f64, f64, f64) {
+    // U(θ, φ, λ) = e^{i(φ+λ)/2} Rz(φ) Ry(θ) Rz(λ)
+    let (theta, phi, lambda, global_phase) = unitary_to_u_angles(matrix);
+    (theta, phi, lambda, global_phase + (phi + lambda) / 2.0)
+}
+
+/// Returns a rotation of the same kind on the same qubit with a different angle
+fn with_angle(rotation: &InstructionIR, angle: f64) -> InstructionIR {
+    match rotation {
+        InstructionIR::Rx(_, target, controls) => {
+            InstructionIR::Rx(angle, *target, controls.clone())
+        }
+        InstructionIR::Ry(_, target, controls) => {
+            InstructionIR::Ry(angle, *target, controls.clone())
+        }
+        InstructionIR::Rz(_, target, controls) => {
+            InstructionIR::Rz(angle, *target, controls.clone())
+        }
+        _ => unreachable!("only rotations have an angle"),
+    }
+}
+
+/// Returns the angle of a rotation
+fn rotation_angle(rotation: &InstructionIR) -> f64 {
+    match rotation {
+        InstructionIR::Rx(angle, _, _)
+        | InstructionIR::Ry(angle, _, _)
+        | InstructionIR::Rz(angle, _, _) => *angle,
+        _ => unreachable!("only rotations have an angle"),
+    }
+}
+
+/// Returns whether a rotation is the identity up to a global phase
+fn is_identity_rotation(rotation: &InstructionIR) -> bool {
+    is_multiple_of_tau(rotation_angle(rotation))
+}
+
+/// Returns whether an angle is a multiple of 2π
+fn is_multiple_of_tau(angle: f64) -> bool {
+    let remainder: f64 = angle.rem_euclid(TAU);
+    remainder < EULER_TOLERANCE || TAU - remainder < EULER_TOLERANCE
+}
+
+/// Reduces an angle to (-π, π]
+fn reduce_angle(angle: f64) -> f64 {
+    let angle: f64 = angle.rem_euclid(TAU);
+    if angle > PI { angle - TAU } else { angle }
+}
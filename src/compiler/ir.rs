@@ -1,9 +1,46 @@
 use num_complex::Complex;
-use crate::components::measurement::MeasurementBasis;
+use crate::{
+    circuit::Circuit,
+    compiler::compilable::CompilableCircuit,
+    components::{
+        classical::ClassicalCondition,
+        gate::Gate,
+        measurement::MeasurementBasis,
+        operator::{
+            Hadamard, Identity, ParameterisedKind, Pauli, PhaseS, PhaseSdag, PhaseShift, PhaseT,
+            PhaseTdag, RotateX, RotateY, RotateZ, SWAP, Unitary2,
+        },
+        parameter::ParameterExpression,
+    },
+    errors::CompilerError,
+};
 
-pub(crate) struct CircuitIR {
-    pub(crate) num_qubits: usize,
-    pub(crate) instructions: Vec<InstructionIR>,
+/// Tolerance within which the matrices of unitary instructions are accepted as unitary, since those
+/// computed by decompositions and fusion are only unitary up to rounding
+const UNITARY_TOLERANCE: f64 = 1e-12;
+
+/// Intermediate representation of a quantum circuit as a flat list of instructions
+///
+/// A `CircuitIR` can be built from any `Circuit` whose operators implement `Compilable`, rewritten by
+/// the passes of a `PassManager`, then turned back into a `Circuit` or emitted as OpenQASM.
+///
+/// # Fields
+///
+/// * `num_qubits` - The number of qubits in the circuit.
+/// * `num_bits` - The number of classical bits in the circuit.
+/// * `instructions` - The instructions of the circuit, in order of application.
+/// * `parameters` - The names of the unbound parameters of the circuit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CircuitIR {
+    /// The number of qubits in the circuit.
+    pub num_qubits: usize,
+    /// The number of classical bits in the circuit.
+    pub num_bits: usize,
+    /// The instructions of the circuit, in order of application.
+    pub instructions: Vec<InstructionIR>,
+    /// The names of the unbound parameters of the circuit, which are declared as `input float[64]`
+    /// variables in OpenQASM 3.0.
+    pub parameters: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -40,11 +77,30 @@ pub enum InstructionIR {
     Swap(usize, usize, Vec<usize>),
     /// IR representation for a custom 2x2 Unitary gate, on qubit index with controls
     Unitary([[Complex<f64>; 2]; 2], usize, Vec<usize>),
+    /// IR representation for a rotation or phase gate whose angle depends on unbound parameters, on qubit
+    /// index with controls
+    Parameterised(ParameterisedKind, ParameterExpression, usize, Vec<usize>),
 
     // MEASUREMENT
 
-    /// IR representation for a measurement operation on qubit index with basis
+    /// IR representation for a measurement operation on qubit index with basis, storing the outcome in the
+    /// classical bit with the same index
     Measurement(usize, MeasurementBasis),
+    /// IR representation for a measurement operation on qubit index, storing the outcome in a classical bit index,
+    /// with basis
+    MeasurementInto(usize, usize, MeasurementBasis),
+    /// IR representation for a reset of qubit index to the |0> state
+    Reset(usize),
+
+    // CLASSICAL CONTROL
+
+    /// IR representation for an instruction applied only if a condition on the classical bits holds
+    Conditional(ClassicalCondition, Box<InstructionIR>),
+
+    // COMPILER DIRECTIVES
+
+    /// IR representation for a barrier on qubit indices, or on every qubit if empty
+    Barrier(Vec<usize>),
 
     // Allow Raw QASM Strings (without validation) for custom instructions
     /// IR representation for a raw QASM string
@@ -53,4 +109,429 @@ pub enum InstructionIR {
     /// 
     /// WARNING: This does not validate the QASM string, so it should be used with caution.
     RawQASM(String),
-}
\ No newline at end of file
+}
+
+impl CircuitIR {
+    /// Creates a new IR circuit from a list of instructions, with one classical bit per qubit
+    ///
+    /// # Arguments
+    ///
+    /// * `num_qubits` - The number of qubits in the circuit.
+    /// * `instructions` - The instructions of the circuit, in order of application.
+    pub fn new(num_qubits: usize, instructions: Vec<InstructionIR>) -> Self {
+        CircuitIR {
+            num_qubits,
+            num_bits: num_qubits,
+            instructions,
+            parameters: Vec::new(),
+        }
+    }
+
+    /// Returns the depth of the circuit
+    ///
+    /// The depth is the number of layers of instructions, where instructions in the same layer act on
+    /// disjoint qubits. Raw QASM and classically controlled instructions are assumed to act on every
+    /// qubit. Barriers do not add a layer, but later instructions on their qubits start after every
+    /// earlier instruction on them.
+    pub fn depth(&self) -> usize {
+        let mut layers: Vec<usize> = vec![0; self.num_qubits];
+        let mut depth: usize = 0;
+        for instruction in &self.instructions {
+            if let InstructionIR::Barrier(targets) = instruction {
+                let qubits: Vec<usize> = if targets.is_empty() {
+                    (0..self.num_qubits).collect()
+                } else {
+                    targets.clone()
+                };
+                let layer: usize = qubits.iter().filter_map(|&qubit| layers.get(qubit)).max().map_or(0, |&layer| layer);
+                for qubit in qubits {
+                    if let Some(qubit_layer) = layers.get_mut(qubit) {
+                        *qubit_layer = layer;
+                    }
+                }
+                continue;
+            }
+            let qubits: Vec<usize> = if instruction.is_barrier() {
+                (0..self.num_qubits).collect()
+            } else {
+                instruction.qubits()
+            };
+            let layer: usize = qubits
+                .iter()
+                .filter_map(|&qubit| layers.get(qubit))
+                .max()
+                .map_or(1, |&layer| layer + 1);
+            for qubit in qubits {
+                if let Some(qubit_layer) = layers.get_mut(qubit) {
+                    *qubit_layer = layer;
+                }
+            }
+            depth = depth.max(layer);
+        }
+        depth
+    }
+
+    /// Converts the IR back into a `Circuit`
+    ///
+    /// # Returns
+    /// The circuit, or a `CompilerError` if an instruction cannot be represented as a gate or has invalid
+    /// operands.
+    pub fn to_circuit(&self) -> Result<Circuit, CompilerError> {
+        let gates: Vec<Gate> = self
+            .instructions
+            .iter()
+            .map(|instruction| instruction.to_gate())
+            .collect::<Result<Vec<Gate>, CompilerError>>()?;
+        Circuit::with_bits(gates, self.num_qubits, self.num_bits)
+            .map_err(|e| CompilerError::InvalidOperands(e.to_string(), "circuit".to_string()))
+    }
+}
+
+impl TryFrom<&Circuit> for CircuitIR {
+    type Error = CompilerError;
+
+    /// Compiles a `Circuit` into its IR
+    ///
+    /// # Returns
+    /// The IR, or `CompilerError::UnsupportedOperator` if an operator in the circuit does not implement
+    /// `Compilable`.
+    fn try_from(circuit: &Circuit) -> Result<Self, CompilerError> {
+        let compilable_circuit: CompilableCircuit = CompilableCircuit::try_from(circuit)?;
+        Ok(CircuitIR {
+            num_qubits: compilable_circuit.num_qubits,
+            num_bits: circuit.num_bits,
+            instructions: compilable_circuit.to_ir(),
+            parameters: circuit
+                .parameters()
+                .iter()
+                .map(|parameter| parameter.name().to_string())
+                .collect(),
+        })
+    }
+}
+
+impl InstructionIR {
+    /// Returns the control qubits of the instruction
+    ///
+    /// Measurements, resets, barriers and raw QASM strings have no control qubits. Classically
+    /// controlled instructions have the control qubits of the instruction they wrap.
+    pub fn controls(&self) -> &[usize] {
+        match self {
+            InstructionIR::Hadamard(_, controls)
+            | InstructionIR::PauliX(_, controls)
+            | InstructionIR::PauliY(_, controls)
+            | InstructionIR::PauliZ(_, controls)
+            | InstructionIR::S(_, controls)
+            | InstructionIR::T(_, controls)
+            | InstructionIR::Sdg(_, controls)
+            | InstructionIR::Tdg(_, controls)
+            | InstructionIR::Phase(_, _, controls)
+            | InstructionIR::Rx(_, _, controls)
+            | InstructionIR::Ry(_, _, controls)
+            | InstructionIR::Rz(_, _, controls)
+            | InstructionIR::Id(_, controls)
+            | InstructionIR::Swap(_, _, controls)
+            | InstructionIR::Unitary(_, _, controls)
+            | InstructionIR::Parameterised(_, _, _, controls) => controls,
+            InstructionIR::Measurement(_, _)
+            | InstructionIR::MeasurementInto(_, _, _)
+            | InstructionIR::Reset(_)
+            | InstructionIR::Barrier(_)
+            | InstructionIR::RawQASM(_) => &[],
+            InstructionIR::Conditional(_, instruction) => instruction.controls(),
+        }
+    }
+
+    /// Returns the target qubits of the instruction
+    ///
+    /// Raw QASM strings have no known target qubits, and barriers on every qubit have none listed.
+    pub fn targets(&self) -> Vec<usize> {
+        match self {
+            InstructionIR::Hadamard(target, _)
+            | InstructionIR::PauliX(target, _)
+            | InstructionIR::PauliY(target, _)
+            | InstructionIR::PauliZ(target, _)
+            | InstructionIR::S(target, _)
+            | InstructionIR::T(target, _)
+            | InstructionIR::Sdg(target, _)
+            | InstructionIR::Tdg(target, _)
+            | InstructionIR::Phase(_, target, _)
+            | InstructionIR::Rx(_, target, _)
+            | InstructionIR::Ry(_, target, _)
+            | InstructionIR::Rz(_, target, _)
+            | InstructionIR::Id(target, _)
+            | InstructionIR::Unitary(_, target, _)
+            | InstructionIR::Parameterised(_, _, target, _)
+            | InstructionIR::Measurement(target, _)
+            | InstructionIR::MeasurementInto(target, _, _)
+            | InstructionIR::Reset(target) => vec![*target],
+            InstructionIR::Swap(target1, target2, _) => vec![*target1, *target2],
+            InstructionIR::Barrier(targets) => targets.clone(),
+            InstructionIR::Conditional(_, instruction) => instruction.targets(),
+            InstructionIR::RawQASM(_) => vec![],
+        }
+    }
+
+    /// Returns all qubits the instruction acts on, control qubits first
+    pub fn qubits(&self) -> Vec<usize> {
+        let mut qubits: Vec<usize> = self.controls().to_vec();
+        qubits.extend(self.targets());
+        qubits
+    }
+
+    /// Returns a mutable reference to the control qubits of the instruction
+    ///
+    /// Returns `None` for instructions that cannot be controlled (measurements, resets, barriers and raw
+    /// QASM strings), and for classically controlled instructions.
+    pub(crate) fn controls_mut(&mut self) -> Option<&mut Vec<usize>> {
+        match self {
+            InstructionIR::Hadamard(_, controls)
+            | InstructionIR::PauliX(_, controls)
+            | InstructionIR::PauliY(_, controls)
+            | InstructionIR::PauliZ(_, controls)
+            | InstructionIR::S(_, controls)
+            | InstructionIR::T(_, controls)
+            | InstructionIR::Sdg(_, controls)
+            | InstructionIR::Tdg(_, controls)
+            | InstructionIR::Phase(_, _, controls)
+            | InstructionIR::Rx(_, _, controls)
+            | InstructionIR::Ry(_, _, controls)
+            | InstructionIR::Rz(_, _, controls)
+            | InstructionIR::Id(_, controls)
+            | InstructionIR::Swap(_, _, controls)
+            | InstructionIR::Unitary(_, _, controls)
+            | InstructionIR::Parameterised(_, _, _, controls) => Some(controls),
+            InstructionIR::Measurement(_, _)
+            | InstructionIR::MeasurementInto(_, _, _)
+            | InstructionIR::Reset(_)
+            | InstructionIR::Conditional(_, _)
+            | InstructionIR::Barrier(_)
+            | InstructionIR::RawQASM(_) => None,
+        }
+    }
+
+    /// Returns whether the instruction must not be reordered with any other instruction
+    ///
+    /// Raw QASM strings act on unknown qubits, and classically controlled instructions depend on the
+    /// outcomes of earlier measurements on any qubit, so passes treat both as acting on every qubit.
+    /// Passes treat explicit barriers in the same way.
+    pub(crate) fn is_barrier(&self) -> bool {
+        matches!(
+            self,
+            InstructionIR::RawQASM(_) | InstructionIR::Conditional(_, _) | InstructionIR::Barrier(_)
+        )
+    }
+
+    /// Returns the classical bits that the instruction writes
+    ///
+    /// Plain measurements write the bit with the same index as their qubit. Raw QASM strings are assumed
+    /// not to write any bits.
+    pub(crate) fn written_bits(&self) -> Vec<usize> {
+        match self {
+            InstructionIR::Measurement(target, _) => vec![*target],
+            InstructionIR::MeasurementInto(_, bit, _) => vec![*bit],
+            InstructionIR::Conditional(_, instruction) => instruction.written_bits(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns a copy of the instruction with every qubit relabelled by `map`
+    ///
+    /// Raw QASM strings are returned unchanged, since their qubits are not known.
+    pub fn map_qubits(&self, map: impl Fn(usize) -> usize) -> InstructionIR {
+        let mut mapped: InstructionIR = match self {
+            InstructionIR::Hadamard(target, controls) => {
+                InstructionIR::Hadamard(map(*target), controls.clone())
+            }
+            InstructionIR::PauliX(target, controls) => {
+                InstructionIR::PauliX(map(*target), controls.clone())
+            }
+            InstructionIR::PauliY(target, controls) => {
+                InstructionIR::PauliY(map(*target), controls.clone())
+            }
+            InstructionIR::PauliZ(target, controls) => {
+                InstructionIR::PauliZ(map(*target), controls.clone())
+            }
+            InstructionIR::S(target, controls) => InstructionIR::S(map(*target), controls.clone()),
+            InstructionIR::T(target, controls) => InstructionIR::T(map(*target), controls.clone()),
+            InstructionIR::Sdg(target, controls) => {
+                InstructionIR::Sdg(map(*target), controls.clone())
+            }
+            InstructionIR::Tdg(target, controls) => {
+                InstructionIR::Tdg(map(*target), controls.clone())
+            }
+            InstructionIR::Phase(angle, target, controls) => {
+                InstructionIR::Phase(*angle, map(*target), controls.clone())
+            }
+            InstructionIR::Rx(angle, target, controls) => {
+                InstructionIR::Rx(*angle, map(*target), controls.clone())
+            }
+            InstructionIR::Ry(angle, target, controls) => {
+                InstructionIR::Ry(*angle, map(*target), controls.clone())
+            }
+            InstructionIR::Rz(angle, target, controls) => {
+                InstructionIR::Rz(*angle, map(*target), controls.clone())
+            }
+            InstructionIR::Id(target, controls) => {
+                InstructionIR::Id(map(*target), controls.clone())
+            }
+            InstructionIR::Swap(target1, target2, controls) => {
+                InstructionIR::Swap(map(*target1), map(*target2), controls.clone())
+            }
+            InstructionIR::Unitary(matrix, target, controls) => {
+                InstructionIR::Unitary(*matrix, map(*target), controls.clone())
+            }
+            InstructionIR::Parameterised(kind, angle, target, controls) => {
+                InstructionIR::Parameterised(*kind, angle.clone(), map(*target), controls.clone())
+            }
+            InstructionIR::Measurement(target, basis) => {
+                InstructionIR::Measurement(map(*target), *basis)
+            }
+            InstructionIR::MeasurementInto(target, bit, basis) => {
+                InstructionIR::MeasurementInto(map(*target), *bit, *basis)
+            }
+            InstructionIR::Reset(target) => InstructionIR::Reset(map(*target)),
+            InstructionIR::Barrier(targets) => {
+                InstructionIR::Barrier(targets.iter().map(|&target| map(target)).collect())
+            }
+            InstructionIR::Conditional(condition, instruction) => {
+                return InstructionIR::Conditional(
+                    condition.clone(),
+                    Box::new(instruction.map_qubits(map)),
+                );
+            }
+            InstructionIR::RawQASM(_) => self.clone(),
+        };
+        if let Some(controls) = mapped.controls_mut() {
+            for control in controls.iter_mut() {
+                *control = map(*control);
+            }
+        }
+        mapped
+    }
+
+    /// Returns the inverse of the instruction
+    ///
+    /// Returns `None` for instructions without an inverse (measurements, resets, classically controlled
+    /// instructions and raw QASM strings). Barriers are their own inverse.
+    pub fn inverse(&self) -> Option<InstructionIR> {
+        let inverse: InstructionIR = match self {
+            InstructionIR::Hadamard(_, _)
+            | InstructionIR::PauliX(_, _)
+            | InstructionIR::PauliY(_, _)
+            | InstructionIR::PauliZ(_, _)
+            | InstructionIR::Id(_, _)
+            | InstructionIR::Swap(_, _, _)
+            | InstructionIR::Barrier(_) => self.clone(),
+            InstructionIR::S(target, controls) => InstructionIR::Sdg(*target, controls.clone()),
+            InstructionIR::Sdg(target, controls) => InstructionIR::S(*target, controls.clone()),
+            InstructionIR::T(target, controls) => InstructionIR::Tdg(*target, controls.clone()),
+            InstructionIR::Tdg(target, controls) => InstructionIR::T(*target, controls.clone()),
+            InstructionIR::Phase(angle, target, controls) => {
+                InstructionIR::Phase(-angle, *target, controls.clone())
+            }
+            InstructionIR::Rx(angle, target, controls) => {
+                InstructionIR::Rx(-angle, *target, controls.clone())
+            }
+            InstructionIR::Ry(angle, target, controls) => {
+                InstructionIR::Ry(-angle, *target, controls.clone())
+            }
+            InstructionIR::Rz(angle, target, controls) => {
+                InstructionIR::Rz(-angle, *target, controls.clone())
+            }
+            InstructionIR::Unitary(matrix, target, controls) => {
+                let adjoint: [[Complex<f64>; 2]; 2] = [
+                    [matrix[0][0].conj(), matrix[1][0].conj()],
+                    [matrix[0][1].conj(), matrix[1][1].conj()],
+                ];
+                InstructionIR::Unitary(adjoint, *target, controls.clone())
+            }
+            InstructionIR::Parameterised(kind, angle, target, controls) => {
+                InstructionIR::Parameterised(*kind, -angle.clone(), *target, controls.clone())
+            }
+            InstructionIR::Measurement(_, _)
+            | InstructionIR::MeasurementInto(_, _, _)
+            | InstructionIR::Reset(_)
+            | InstructionIR::Conditional(_, _)
+            | InstructionIR::RawQASM(_) => return None,
+        };
+        Some(inverse)
+    }
+
+    /// Converts the instruction into a circuit gate
+    ///
+    /// # Returns
+    /// The gate, or `CompilerError::UnsupportedOperator` for raw QASM strings, which have no gate equivalent,
+    /// and `CompilerError::InvalidOperands` for a unitary instruction whose matrix is not unitary.
+    pub(crate) fn to_gate(&self) -> Result<Gate, CompilerError> {
+        let gate: Gate = match self {
+            InstructionIR::Hadamard(target, controls) => {
+                Gate::Operator(Box::new(Hadamard), vec![*target], controls.clone())
+            }
+            InstructionIR::PauliX(target, controls) => {
+                Gate::Operator(Box::new(Pauli::X), vec![*target], controls.clone())
+            }
+            InstructionIR::PauliY(target, controls) => {
+                Gate::Operator(Box::new(Pauli::Y), vec![*target], controls.clone())
+            }
+            InstructionIR::PauliZ(target, controls) => {
+                Gate::Operator(Box::new(Pauli::Z), vec![*target], controls.clone())
+            }
+            InstructionIR::S(target, controls) => {
+                Gate::Operator(Box::new(PhaseS), vec![*target], controls.clone())
+            }
+            InstructionIR::T(target, controls) => {
+                Gate::Operator(Box::new(PhaseT), vec![*target], controls.clone())
+            }
+            InstructionIR::Sdg(target, controls) => {
+                Gate::Operator(Box::new(PhaseSdag), vec![*target], controls.clone())
+            }
+            InstructionIR::Tdg(target, controls) => {
+                Gate::Operator(Box::new(PhaseTdag), vec![*target], controls.clone())
+            }
+            InstructionIR::Phase(angle, target, controls) => {
+                Gate::Operator(Box::new(PhaseShift::new(*angle)), vec![*target], controls.clone())
+            }
+            InstructionIR::Rx(angle, target, controls) => {
+                Gate::Operator(Box::new(RotateX::new(*angle)), vec![*target], controls.clone())
+            }
+            InstructionIR::Ry(angle, target, controls) => {
+                Gate::Operator(Box::new(RotateY::new(*angle)), vec![*target], controls.clone())
+            }
+            InstructionIR::Rz(angle, target, controls) => {
+                Gate::Operator(Box::new(RotateZ::new(*angle)), vec![*target], controls.clone())
+            }
+            InstructionIR::Id(target, controls) => {
+                Gate::Operator(Box::new(Identity), vec![*target], controls.clone())
+            }
+            InstructionIR::Swap(target1, target2, controls) => {
+                Gate::Operator(Box::new(SWAP), vec![*target1, *target2], controls.clone())
+            }
+            InstructionIR::Unitary(matrix, target, controls) => {
+                // The IR is public, so its matrices may not come from a validated `Unitary2`
+                let operator: Unitary2 = Unitary2::with_tolerance(*matrix, UNITARY_TOLERANCE).map_err(|e| {
+                    CompilerError::InvalidOperands(e.to_string(), "unitary".to_string())
+                })?;
+                Gate::Operator(Box::new(operator), vec![*target], controls.clone())
+            }
+            InstructionIR::Parameterised(kind, angle, target, controls) => {
+                Gate::Operator(kind.operator(angle.clone()), vec![*target], controls.clone())
+            }
+            InstructionIR::Measurement(target, basis) => Gate::Measurement(*basis, vec![*target]),
+            InstructionIR::MeasurementInto(target, bit, basis) => {
+                Gate::MeasurementInto(*basis, vec![*target], vec![*bit])
+            }
+            InstructionIR::Reset(target) => Gate::Reset(vec![*target]),
+            InstructionIR::Conditional(condition, instruction) => {
+                Gate::Conditional(condition.clone(), Box::new(instruction.to_gate()?))
+            }
+            InstructionIR::Barrier(targets) => Gate::Barrier(targets.clone()),
+            InstructionIR::RawQASM(_) => {
+                return Err(CompilerError::UnsupportedOperator(
+                    "Raw QASM cannot be converted to a gate".to_string(),
+                ));
+            }
+        };
+        Ok(gate)
+    }
+}
@@ -0,0 +1,499 @@
+use crate::compiler::{
+    ir::InstructionIR,
+    linalg::{instruction_matrix, multiply},
+};
+use num_complex::Complex;
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2, FRAC_PI_4, TAU};
+
+/// Tolerance used when deciding whether an interaction coefficient is zero or π/4
+const KAK_TOLERANCE: f64 = 1e-10;
+
+/// Real linear combinations of the real and imaginary parts of a symmetric matrix tried when looking
+/// for a common eigenbasis. A single generic combination almost always works; the others only matter
+/// when it happens to make distinct eigenvalues coincide.
+const DIAGONALISATION_WEIGHTS: [f64; 4] = [1.0, 0.5427, 1.9371, 0.2841];
+
+type Matrix2 = [[Complex<f64>; 2]; 2];
+type Matrix4 = [[Complex<f64>; 4]; 4];
+
+/// The KAK (Cartan) decomposition of a two-qubit unitary
+///
+/// Any two-qubit unitary can be written as
+/// `U = e^{iα} (A₁ ⊗ A₀) exp(i(a XX + b YY + c ZZ)) (B₁ ⊗ B₀)`, where `A₀` and `B₀` act on the first
+/// target qubit (bit 0 of the matrix index) and `A₁` and `B₁` on the second. The interaction
+/// coefficients are reduced to `(-π/4, π/4]`, and determine how many CNOTs the unitary needs.
+///
+/// # Fields
+///
+/// * `global_phase` - The global phase α.
+/// * `before` - The single-qubit unitaries applied before the interaction, on the first and second
+///   target qubits.
+/// * `a` - The coefficient of `XX` in the interaction.
+/// * `b` - The coefficient of `YY` in the interaction.
+/// * `c` - The coefficient of `ZZ` in the interaction.
+/// * `after` - The single-qubit unitaries applied after the interaction, on the first and second
+///   target qubits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KakDecomposition {
+    /// The global phase α.
+    pub global_phase: f64,
+    /// The single-qubit unitaries applied before the interaction.
+    pub before: [[[Complex<f64>; 2]; 2]; 2],
+    /// The coefficient of `XX` in the interaction.
+    pub a: f64,
+    /// The coefficient of `YY` in the interaction.
+    pub b: f64,
+    /// The coefficient of `ZZ` in the interaction.
+    pub c: f64,
+    /// The single-qubit unitaries applied after the interaction.
+    pub after: [[[Complex<f64>; 2]; 2]; 2],
+}
+
+impl KakDecomposition {
+    /// Decomposes a 4×4 unitary matrix
+    ///
+    /// The matrix is assumed to be unitary; it is not validated. Bit 0 of the row and column indices
+    /// corresponds to the first target qubit.
+    ///
+    /// # Arguments
+    ///
+    /// * `matrix` - The unitary matrix to decompose.
+    pub fn new(matrix: &[[Complex<f64>; 4]; 4]) -> Self {
+        // Scale into SU(4) and move to the magic basis, where local unitaries are real orthogonal
+        let mut global_phase: f64 = determinant(matrix).arg() / 4.0;
+        let special: Matrix4 = scale(matrix, Complex::from_polar(1.0, -global_phase));
+        let magic: Matrix4 = magic_basis();
+        let magic_adjoint: Matrix4 = adjoint4(&magic);
+        let in_magic: Matrix4 = multiply4(&magic_adjoint, &multiply4(&special, &magic));
+
+        // U = K₁ D Pᵀ in the magic basis, with K₁ and P real orthogonal and D diagonal
+        let symmetric: Matrix4 = multiply4(&transpose4(&in_magic), &in_magic);
+        let orthogonal: Matrix4 = diagonalise_symmetric_unitary(&symmetric);
+        let eigenvalues: Matrix4 = multiply4(
+            &transpose4(&orthogonal),
+            &multiply4(&symmetric, &orthogonal),
+        );
+        let mut roots: [Complex<f64>; 4] =
+            std::array::from_fn(|k| Complex::from_polar(1.0, eigenvalues[k][k].arg() / 2.0));
+        if roots.iter().product::<Complex<f64>>().re < 0.0 {
+            roots[0] = -roots[0];
+        }
+        let inverse_roots: Matrix4 = diagonal(&roots.map(|root| root.conj()));
+        let left: Matrix4 = multiply4(&in_magic, &multiply4(&orthogonal, &inverse_roots));
+
+        // Back in the computational basis, the orthogonal factors are tensor products
+        let to_computational = |m: &Matrix4| multiply4(&magic, &multiply4(m, &magic_adjoint));
+        let (after_high, after_low) = factor_tensor_product(&to_computational(&left));
+        let (before_high, before_low) =
+            factor_tensor_product(&to_computational(&transpose4(&orthogonal)));
+
+        // In the magic basis, exp(i(a XX + b YY + c ZZ)) = diag(a-b+c, -a+b+c, a+b-c, -a-b-c)
+        let phases: [f64; 4] = roots.map(|root| root.arg());
+        let mut decomposition: KakDecomposition = KakDecomposition {
+            global_phase: 0.0,
+            before: [before_low, before_high],
+            a: (phases[0] + phases[2]) / 2.0,
+            b: (phases[1] + phases[2]) / 2.0,
+            c: (phases[0] + phases[1]) / 2.0,
+            after: [after_low, after_high],
+        };
+
+        // exp(i(x + kπ/2) PP) = exp(ix PP) (iPP)^k, so each coefficient can be reduced to (-π/4, π/4]
+        let paulis: [Matrix2; 3] = [pauli('X'), pauli('Y'), pauli('Z')];
+        for (index, pauli) in paulis.iter().enumerate() {
+            let coefficient: &mut f64 = match index {
+                0 => &mut decomposition.a,
+                1 => &mut decomposition.b,
+                _ => &mut decomposition.c,
+            };
+            let shifts: i64 = ((*coefficient + FRAC_PI_4 - KAK_TOLERANCE) / FRAC_PI_2).floor() as i64;
+            *coefficient -= shifts as f64 * FRAC_PI_2;
+            if shifts.rem_euclid(2) == 1 {
+                for after in &mut decomposition.after {
+                    *after = multiply(after, pauli);
+                }
+            }
+            global_phase += shifts as f64 * FRAC_PI_2;
+        }
+        decomposition.global_phase = global_phase;
+        decomposition
+    }
+
+    /// Returns the unitary matrix described by the decomposition, including its global phase
+    pub fn matrix(&self) -> [[Complex<f64>; 4]; 4] {
+        let interaction: Matrix4 = {
+            let term = |coefficient: f64, pauli_name: char| {
+                let pauli_pair: Matrix4 = kron(&pauli(pauli_name), &pauli(pauli_name));
+                let mut term: Matrix4 = scale(&pauli_pair, Complex::new(0.0, coefficient.sin()));
+                for (k, row) in term.iter_mut().enumerate() {
+                    row[k] += coefficient.cos();
+                }
+                term
+            };
+            multiply4(&term(self.a, 'X'), &multiply4(&term(self.b, 'Y'), &term(self.c, 'Z')))
+        };
+        let product: Matrix4 = multiply4(
+            &kron(&self.after[1], &self.after[0]),
+            &multiply4(&interaction, &kron(&self.before[1], &self.before[0])),
+        );
+        scale(&product, Complex::from_polar(1.0, self.global_phase))
+    }
+
+    /// Returns the number of CNOTs needed to implement the unitary, from 0 to 3
+    pub fn num_cnots(&self) -> usize {
+        let nonzero: Vec<f64> = [self.a, self.b, self.c]
+            .into_iter()
+            .filter(|coefficient| coefficient.abs() > KAK_TOLERANCE)
+            .collect();
+        match nonzero[..] {
+            [] => 0,
+            [coefficient] if (coefficient - FRAC_PI_4).abs() < KAK_TOLERANCE => 1,
+            [_] | [_, _] => 2,
+            _ => 3,
+        }
+    }
+
+    /// Synthesises the unitary into CNOTs and single-qubit `Unitary` instructions
+    ///
+    /// The circuit uses `num_cnots()` CNOTs. Without controls, it is equal to the decomposed unitary up
+    /// to a global phase. With controls, every instruction is controlled and the global phase is
+    /// applied as a phase gate on the controls, so the result is exact.
+    ///
+    /// # Arguments
+    ///
+    /// * `targets` - The first and second target qubits.
+    /// * `controls` - The control qubits.
+    pub fn to_instructions(&self, targets: [usize; 2], controls: &[usize]) -> Vec<InstructionIR> {
+        let (interaction, interaction_phase) = self.interaction_instructions();
+
+        let mut local: Vec<InstructionIR> = vec![
+            InstructionIR::Unitary(self.before[0], 0, vec![]),
+            InstructionIR::Unitary(self.before[1], 1, vec![]),
+        ];
+        local.extend(interaction);
+        local.push(InstructionIR::Unitary(self.after[0], 0, vec![]));
+        local.push(InstructionIR::Unitary(self.after[1], 1, vec![]));
+
+        let mut instructions: Vec<InstructionIR> = fuse_local_runs(&local)
+            .iter()
+            .map(|instruction| {
+                let mut mapped: InstructionIR = instruction.map_qubits(|qubit| targets[qubit]);
+                if let Some(mapped_controls) = mapped.controls_mut() {
+                    mapped_controls.extend_from_slice(controls);
+                }
+                mapped
+            })
+            .collect();
+
+        let phase: f64 = (self.global_phase + interaction_phase).rem_euclid(TAU);
+        if let Some((&control, rest)) = controls.split_first()
+            && phase.min(TAU - phase) > KAK_TOLERANCE
+        {
+            instructions.push(InstructionIR::Phase(phase, control, rest.to_vec()));
+        }
+        instructions
+    }
+
+    /// Returns instructions on qubits 0 and 1 implementing the interaction, and the global phase `ψ`
+    /// such that the interaction is `e^{iψ}` times the circuit
+    fn interaction_instructions(&self) -> (Vec<InstructionIR>, f64) {
+        let (a, b, c) = (self.a, self.b, self.c);
+        let is_zero = |coefficient: f64| coefficient.abs() <= KAK_TOLERANCE;
+        match self.num_cnots() {
+            0 => (vec![], 0.0),
+            // Move the non-zero coefficient to XX
+            1 if !is_zero(b) => conjugated(swap_xy(), one_cnot()),
+            1 if !is_zero(c) => conjugated(swap_xz(), one_cnot()),
+            1 => one_cnot(),
+            // Move a zero coefficient to YY
+            2 if is_zero(a) => conjugated(swap_xy(), two_cnots(b, c)),
+            2 if is_zero(c) => conjugated(swap_yz(), two_cnots(a, b)),
+            2 => two_cnots(a, c),
+            _ => three_cnots(a, b, c),
+        }
+    }
+}
+
+/// Returns `exp(iπ/4 XX)` as `e^{iψ}` times a circuit with one CNOT
+fn one_cnot() -> (Vec<InstructionIR>, f64) {
+    // CX = e^{iπ/4} exp(-iπ/4 Z₀) exp(-iπ/4 X₁) exp(iπ/4 Z₀X₁), conjugated by H on qubit 0
+    let instructions: Vec<InstructionIR> = vec![
+        InstructionIR::Hadamard(0, vec![]),
+        InstructionIR::PauliX(1, vec![0]),
+        InstructionIR::Rx(-FRAC_PI_2, 1, vec![]),
+        InstructionIR::Rz(-FRAC_PI_2, 0, vec![]),
+        InstructionIR::Hadamard(0, vec![]),
+    ];
+    (instructions, -FRAC_PI_4)
+}
+
+/// Returns `exp(i(a XX + c ZZ))` as a circuit with two CNOTs
+fn two_cnots(a: f64, c: f64) -> (Vec<InstructionIR>, f64) {
+    // Conjugating by CX₀₁ maps X₀ to X₀X₁ and Z₁ to Z₀Z₁
+    let instructions: Vec<InstructionIR> = vec![
+        InstructionIR::PauliX(1, vec![0]),
+        InstructionIR::Rx(-2.0 * a, 0, vec![]),
+        InstructionIR::Rz(-2.0 * c, 1, vec![]),
+        InstructionIR::PauliX(1, vec![0]),
+    ];
+    (instructions, 0.0)
+}
+
+/// Returns `exp(i(a XX + b YY + c ZZ))` as `e^{iψ}` times a circuit with three CNOTs
+///
+/// This is the circuit of Vatan and Williams, "Optimal quantum circuits for general two-qubit gates".
+fn three_cnots(a: f64, b: f64, c: f64) -> (Vec<InstructionIR>, f64) {
+    let instructions: Vec<InstructionIR> = vec![
+        InstructionIR::Rz(-FRAC_PI_2, 1, vec![]),
+        InstructionIR::PauliX(0, vec![1]),
+        InstructionIR::Rz(FRAC_PI_2 - 2.0 * c, 0, vec![]),
+        InstructionIR::Ry(2.0 * a - FRAC_PI_2, 1, vec![]),
+        InstructionIR::PauliX(1, vec![0]),
+        InstructionIR::Ry(FRAC_PI_2 - 2.0 * b, 1, vec![]),
+        InstructionIR::PauliX(0, vec![1]),
+        InstructionIR::Rz(FRAC_PI_2, 0, vec![]),
+    ];
+    (instructions, FRAC_PI_4)
+}
+
+/// Rz(π/2) on both qubits, which exchanges XX and YY
+fn swap_xy() -> [InstructionIR; 2] {
+    [InstructionIR::Rz(FRAC_PI_2, 0, vec![]), InstructionIR::Rz(FRAC_PI_2, 1, vec![])]
+}
+
+/// Rx(π/2) on both qubits, which exchanges YY and ZZ
+fn swap_yz() -> [InstructionIR; 2] {
+    [InstructionIR::Rx(FRAC_PI_2, 0, vec![]), InstructionIR::Rx(FRAC_PI_2, 1, vec![])]
+}
+
+/// H on both qubits, which exchanges XX and ZZ
+fn swap_xz() -> [InstructionIR; 2] {
+    [InstructionIR::Hadamard(0, vec![]), InstructionIR::Hadamard(1, vec![])]
+}
+
+/// Conjugates a circuit by a product of single-qubit gates `V`, giving `V C V†`
+fn conjugated(
+    conjugation: [InstructionIR; 2],
+    (circuit, phase): (Vec<InstructionIR>, f64),
+) -> (Vec<InstructionIR>, f64) {
+    let mut instructions: Vec<InstructionIR> =
+        conjugation.iter().map(|gate| gate.inverse().expect("rotations have inverses")).collect();
+    instructions.extend(circuit);
+    instructions.extend(conjugation);
+    (instructions, phase)
+}
+
+/// Merges the single-qubit instructions between CNOTs into one `Unitary` per qubit
+///
+/// Unlike the fusion pass, this keeps the exact phase of every run, since the instructions may later
+/// be controlled.
+fn fuse_local_runs(instructions: &[InstructionIR]) -> Vec<InstructionIR> {
+    let mut pending: [Option<Matrix2>; 2] = [None, None];
+    let mut out: Vec<InstructionIR> = Vec::with_capacity(instructions.len());
+    let flush = |pending: &mut [Option<Matrix2>; 2], qubit: usize, out: &mut Vec<InstructionIR>| {
+        if let Some(matrix) = pending[qubit].take()
+            && !is_identity(&matrix)
+        {
+            out.push(InstructionIR::Unitary(matrix, qubit, vec![]));
+        }
+    };
+
+    for instruction in instructions {
+        if instruction.qubits().len() == 1 {
+            let qubit: usize = instruction.targets()[0];
+            let matrix: Matrix2 = instruction_matrix(instruction).expect("local gates have a matrix");
+            pending[qubit] = Some(match &pending[qubit] {
+                Some(previous) => multiply(&matrix, previous),
+                None => matrix,
+            });
+        } else {
+            flush(&mut pending, 0, &mut out);
+            flush(&mut pending, 1, &mut out);
+            out.push(instruction.clone());
+        }
+    }
+    flush(&mut pending, 0, &mut out);
+    flush(&mut pending, 1, &mut out);
+    out
+}
+
+/// Returns whether a 2×2 matrix is the identity, including its phase
+fn is_identity(matrix: &Matrix2) -> bool {
+    (matrix[0][0] - 1.0).norm() < KAK_TOLERANCE
+        && (matrix[1][1] - 1.0).norm() < KAK_TOLERANCE
+        && matrix[0][1].norm() < KAK_TOLERANCE
+        && matrix[1][0].norm() < KAK_TOLERANCE
+}
+
+/// Finds a real orthogonal matrix `P` with determinant 1 such that `Pᵀ M P` is diagonal
+///
+/// For a symmetric unitary `M`, the real and imaginary parts are commuting real symmetric matrices, so
+/// they share an orthonormal eigenbasis, found here by diagonalising a real combination of the two.
+fn diagonalise_symmetric_unitary(symmetric: &Matrix4) -> Matrix4 {
+    let mut best: ([[f64; 4]; 4], f64) = ([[0.0; 4]; 4], f64::INFINITY);
+    for weight in DIAGONALISATION_WEIGHTS {
+        let combined: [[f64; 4]; 4] =
+            symmetric.map(|row| row.map(|entry| entry.re + weight * entry.im));
+        let eigenvectors: [[f64; 4]; 4] = jacobi_eigenvectors(combined);
+        let as_complex: Matrix4 = eigenvectors.map(|row| row.map(|entry| Complex::new(entry, 0.0)));
+        let diagonalised: Matrix4 =
+            multiply4(&transpose4(&as_complex), &multiply4(symmetric, &as_complex));
+        let off_diagonal: f64 = (0..4)
+            .flat_map(|i| (0..4).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| diagonalised[i][j].norm())
+            .fold(0.0, f64::max);
+        if off_diagonal < best.1 {
+            best = (eigenvectors, off_diagonal);
+        }
+        if off_diagonal < KAK_TOLERANCE {
+            break;
+        }
+    }
+
+    let mut eigenvectors: [[f64; 4]; 4] = best.0;
+    let as_complex: Matrix4 = eigenvectors.map(|row| row.map(|entry| Complex::new(entry, 0.0)));
+    if determinant(&as_complex).re < 0.0 {
+        for row in &mut eigenvectors {
+            row[0] = -row[0];
+        }
+    }
+    eigenvectors.map(|row| row.map(|entry| Complex::new(entry, 0.0)))
+}
+
+/// Returns the eigenvectors of a real symmetric matrix as columns, using cyclic Jacobi rotations
+fn jacobi_eigenvectors(mut matrix: [[f64; 4]; 4]) -> [[f64; 4]; 4] {
+    let mut eigenvectors: [[f64; 4]; 4] = std::array::from_fn(|i| {
+        std::array::from_fn(|j| if i == j { 1.0 } else { 0.0 })
+    });
+    for _ in 0..64 {
+        let off_diagonal: f64 = (0..4)
+            .flat_map(|i| (i + 1..4).map(move |j| (i, j)))
+            .map(|(i, j)| matrix[i][j] * matrix[i][j])
+            .sum();
+        if off_diagonal < 1e-30 {
+            break;
+        }
+        for p in 0..4 {
+            for q in p + 1..4 {
+                if matrix[p][q].abs() < f64::MIN_POSITIVE {
+                    continue;
+                }
+                let theta: f64 = (matrix[q][q] - matrix[p][p]) / (2.0 * matrix[p][q]);
+                let t: f64 = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let cos: f64 = 1.0 / (t * t + 1.0).sqrt();
+                let sin: f64 = t * cos;
+                for row in matrix.iter_mut() {
+                    let (kp, kq) = (row[p], row[q]);
+                    row[p] = cos * kp - sin * kq;
+                    row[q] = sin * kp + cos * kq;
+                }
+                let (row_p, row_q) = (matrix[p], matrix[q]);
+                matrix[p] = std::array::from_fn(|k| cos * row_p[k] - sin * row_q[k]);
+                matrix[q] = std::array::from_fn(|k| sin * row_p[k] + cos * row_q[k]);
+                for row in eigenvectors.iter_mut() {
+                    let (kp, kq) = (row[p], row[q]);
+                    row[p] = cos * kp - sin * kq;
+                    row[q] = sin * kp + cos * kq;
+                }
+            }
+        }
+    }
+    eigenvectors
+}
+
+/// Splits a 4×4 tensor product `H ⊗ L` into `(H, L)`, with `L` acting on bit 0 of the index
+///
+/// The factors are normalised to determinant 1 up to sign, and their product reconstructs the matrix.
+fn factor_tensor_product(matrix: &Matrix4) -> (Matrix2, Matrix2) {
+    let (row, column) = (0..16)
+        .map(|k| (k / 4, k % 4))
+        .max_by(|&(i, j), &(k, l)| matrix[i][j].norm().total_cmp(&matrix[k][l].norm()))
+        .expect("the matrix is not empty");
+    let (row_high, row_low, column_high, column_low) = (row >> 1, row & 1, column >> 1, column & 1);
+
+    let low: Matrix2 = std::array::from_fn(|i| {
+        std::array::from_fn(|j| matrix[2 * row_high + i][2 * column_high + j])
+    });
+    let determinant: Complex<f64> = low[0][0] * low[1][1] - low[0][1] * low[1][0];
+    let low: Matrix2 = low.map(|row| row.map(|entry| entry / determinant.sqrt()));
+    let high: Matrix2 = std::array::from_fn(|i| {
+        std::array::from_fn(|j| matrix[2 * i + row_low][2 * j + column_low] / low[row_low][column_low])
+    });
+    (high, low)
+}
+
+/// Returns the magic basis as columns of a matrix, in which local unitaries are real orthogonal
+fn magic_basis() -> Matrix4 {
+    let r: Complex<f64> = Complex::new(FRAC_1_SQRT_2, 0.0);
+    let i: Complex<f64> = Complex::new(0.0, FRAC_1_SQRT_2);
+    let zero: Complex<f64> = Complex::new(0.0, 0.0);
+    [
+        [r, i, zero, zero],
+        [zero, zero, i, r],
+        [zero, zero, i, -r],
+        [r, -i, zero, zero],
+    ]
+}
+
+fn pauli(name: char) -> Matrix2 {
+    let instruction: InstructionIR = match name {
+        'X' => InstructionIR::PauliX(0, vec![]),
+        'Y' => InstructionIR::PauliY(0, vec![]),
+        _ => InstructionIR::PauliZ(0, vec![]),
+    };
+    instruction_matrix(&instruction).expect("Paulis have a matrix")
+}
+
+/// Returns `high ⊗ low`, with `low` acting on bit 0 of the index
+fn kron(high: &Matrix2, low: &Matrix2) -> Matrix4 {
+    std::array::from_fn(|i| std::array::from_fn(|j| high[i >> 1][j >> 1] * low[i & 1][j & 1]))
+}
+
+fn multiply4(a: &Matrix4, b: &Matrix4) -> Matrix4 {
+    std::array::from_fn(|i| std::array::from_fn(|j| (0..4).map(|k| a[i][k] * b[k][j]).sum()))
+}
+
+fn adjoint4(matrix: &Matrix4) -> Matrix4 {
+    std::array::from_fn(|i| std::array::from_fn(|j| matrix[j][i].conj()))
+}
+
+fn transpose4(matrix: &Matrix4) -> Matrix4 {
+    std::array::from_fn(|i| std::array::from_fn(|j| matrix[j][i]))
+}
+
+fn scale(matrix: &Matrix4, factor: Complex<f64>) -> Matrix4 {
+    matrix.map(|row| row.map(|entry| entry * factor))
+}
+
+fn diagonal(entries: &[Complex<f64>; 4]) -> Matrix4 {
+    std::array::from_fn(|i| {
+        std::array::from_fn(|j| if i == j { entries[i] } else { Complex::new(0.0, 0.0) })
+    })
+}
+
+/// Returns the determinant of a 4×4 matrix, using Gaussian elimination with partial pivoting
+fn determinant(matrix: &Matrix4) -> Complex<f64> {
+    let mut m: Matrix4 = *matrix;
+    let mut result: Complex<f64> = Complex::new(1.0, 0.0);
+    for column in 0..4 {
+        let pivot: usize = (column..4)
+            .max_by(|&i, &j| m[i][column].norm().total_cmp(&m[j][column].norm()))
+            .expect("the range is not empty");
+        if m[pivot][column].norm() == 0.0 {
+            return Complex::new(0.0, 0.0);
+        }
+        if pivot != column {
+            m.swap(pivot, column);
+            result = -result;
+        }
+        result *= m[column][column];
+        let pivot_row: [Complex<f64>; 4] = m[column];
+        for row in m.iter_mut().skip(column + 1) {
+            let factor: Complex<f64> = row[column] / pivot_row[column];
+            for (entry, pivot_entry) in row.iter_mut().zip(pivot_row).skip(column) {
+                *entry -= factor * pivot_entry;
+            }
+        }
+    }
+    result
+}
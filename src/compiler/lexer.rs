@@ -0,0 +1,249 @@
+use crate::errors::CompilerError;
+
+/// The kinds of tokens produced by the OpenQASM lexer
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TokenKind {
+    /// An identifier or keyword, e.g. `qubit`, `h` or `pi`
+    Identifier(String),
+    /// A non-negative integer literal
+    Integer(u64),
+    /// A non-negative real literal, e.g. `0.5` or `1e-3`
+    Real(f64),
+    /// A string literal, without the surrounding quotes
+    Str(String),
+    Semicolon,
+    Comma,
+    Colon,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    LBrace,
+    RBrace,
+    At,
+    Equals,
+    DoubleEquals,
+    DoubleAmpersand,
+    Arrow,
+    Plus,
+    Minus,
+    Star,
+    DoubleStar,
+    Slash,
+    Caret,
+    /// End of input
+    Eof,
+}
+
+impl std::fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenKind::Identifier(name) => write!(f, "identifier '{}'", name),
+            TokenKind::Integer(value) => write!(f, "integer {}", value),
+            TokenKind::Real(value) => write!(f, "number {}", value),
+            TokenKind::Str(value) => write!(f, "string \"{}\"", value),
+            TokenKind::Semicolon => write!(f, "';'"),
+            TokenKind::Comma => write!(f, "','"),
+            TokenKind::Colon => write!(f, "':'"),
+            TokenKind::LParen => write!(f, "'('"),
+            TokenKind::RParen => write!(f, "')'"),
+            TokenKind::LBracket => write!(f, "'['"),
+            TokenKind::RBracket => write!(f, "']'"),
+            TokenKind::LBrace => write!(f, "'{{'"),
+            TokenKind::RBrace => write!(f, "'}}'"),
+            TokenKind::At => write!(f, "'@'"),
+            TokenKind::Equals => write!(f, "'='"),
+            TokenKind::DoubleEquals => write!(f, "'=='"),
+            TokenKind::DoubleAmpersand => write!(f, "'&&'"),
+            TokenKind::Arrow => write!(f, "'->'"),
+            TokenKind::Plus => write!(f, "'+'"),
+            TokenKind::Minus => write!(f, "'-'"),
+            TokenKind::Star => write!(f, "'*'"),
+            TokenKind::DoubleStar => write!(f, "'**'"),
+            TokenKind::Slash => write!(f, "'/'"),
+            TokenKind::Caret => write!(f, "'^'"),
+            TokenKind::Eof => write!(f, "end of input"),
+        }
+    }
+}
+
+/// A token together with its position in the source (1-based line and column)
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Token {
+    pub(crate) kind: TokenKind,
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+}
+
+/// Splits OpenQASM source code into tokens
+///
+/// Line (`//`) and block (`/* */`) comments are skipped. The returned vector always ends with an
+/// `Eof` token.
+///
+/// # Errors
+/// Returns `CompilerError::ParseError` for unexpected characters, malformed numbers and
+/// unterminated strings or comments.
+pub(crate) fn tokenise(source: &str) -> Result<Vec<Token>, CompilerError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut pos: usize = 0;
+    let mut line: usize = 1;
+    let mut column: usize = 1;
+
+    // Advances over `count` characters, keeping track of line and column
+    let advance = |pos: &mut usize, line: &mut usize, column: &mut usize, count: usize| {
+        for _ in 0..count {
+            if chars[*pos] == '\n' {
+                *line += 1;
+                *column = 1;
+            } else {
+                *column += 1;
+            }
+            *pos += 1;
+        }
+    };
+
+    while pos < chars.len() {
+        let c: char = chars[pos];
+        let next: Option<char> = chars.get(pos + 1).copied();
+        let (start_line, start_column) = (line, column);
+
+        // Whitespace and comments
+        if c.is_whitespace() {
+            advance(&mut pos, &mut line, &mut column, 1);
+            continue;
+        }
+        if c == '/' && next == Some('/') {
+            while pos < chars.len() && chars[pos] != '\n' {
+                advance(&mut pos, &mut line, &mut column, 1);
+            }
+            continue;
+        }
+        if c == '/' && next == Some('*') {
+            advance(&mut pos, &mut line, &mut column, 2);
+            loop {
+                if pos + 1 >= chars.len() {
+                    return Err(CompilerError::ParseError(
+                        start_line,
+                        start_column,
+                        "unterminated block comment".to_string(),
+                    ));
+                }
+                if chars[pos] == '*' && chars[pos + 1] == '/' {
+                    advance(&mut pos, &mut line, &mut column, 2);
+                    break;
+                }
+                advance(&mut pos, &mut line, &mut column, 1);
+            }
+            continue;
+        }
+
+        let kind: TokenKind = if c.is_alphabetic() || c == '_' {
+            let start: usize = pos;
+            while pos < chars.len() && (chars[pos].is_alphanumeric() || chars[pos] == '_') {
+                advance(&mut pos, &mut line, &mut column, 1);
+            }
+            TokenKind::Identifier(chars[start..pos].iter().collect())
+        } else if c.is_ascii_digit() || (c == '.' && next.is_some_and(|n| n.is_ascii_digit())) {
+            let start: usize = pos;
+            let mut is_real: bool = false;
+            while pos < chars.len() && chars[pos].is_ascii_digit() {
+                advance(&mut pos, &mut line, &mut column, 1);
+            }
+            if pos < chars.len() && chars[pos] == '.' {
+                is_real = true;
+                advance(&mut pos, &mut line, &mut column, 1);
+                while pos < chars.len() && chars[pos].is_ascii_digit() {
+                    advance(&mut pos, &mut line, &mut column, 1);
+                }
+            }
+            if pos < chars.len() && (chars[pos] == 'e' || chars[pos] == 'E') {
+                let sign_offset: usize = match chars.get(pos + 1) {
+                    Some('+') | Some('-') => 2,
+                    _ => 1,
+                };
+                if chars.get(pos + sign_offset).is_some_and(|d| d.is_ascii_digit()) {
+                    is_real = true;
+                    advance(&mut pos, &mut line, &mut column, sign_offset);
+                    while pos < chars.len() && chars[pos].is_ascii_digit() {
+                        advance(&mut pos, &mut line, &mut column, 1);
+                    }
+                }
+            }
+            let text: String = chars[start..pos].iter().collect();
+            let invalid_number = || {
+                CompilerError::ParseError(
+                    start_line,
+                    start_column,
+                    format!("invalid number '{}'", text),
+                )
+            };
+            if is_real {
+                TokenKind::Real(text.parse::<f64>().map_err(|_| invalid_number())?)
+            } else {
+                TokenKind::Integer(text.parse::<u64>().map_err(|_| invalid_number())?)
+            }
+        } else if c == '"' {
+            advance(&mut pos, &mut line, &mut column, 1);
+            let start: usize = pos;
+            while pos < chars.len() && chars[pos] != '"' && chars[pos] != '\n' {
+                advance(&mut pos, &mut line, &mut column, 1);
+            }
+            if pos >= chars.len() || chars[pos] != '"' {
+                return Err(CompilerError::ParseError(
+                    start_line,
+                    start_column,
+                    "unterminated string literal".to_string(),
+                ));
+            }
+            let value: String = chars[start..pos].iter().collect();
+            advance(&mut pos, &mut line, &mut column, 1);
+            TokenKind::Str(value)
+        } else {
+            let (kind, length) = match (c, next) {
+                ('-', Some('>')) => (TokenKind::Arrow, 2),
+                ('=', Some('=')) => (TokenKind::DoubleEquals, 2),
+                ('&', Some('&')) => (TokenKind::DoubleAmpersand, 2),
+                ('*', Some('*')) => (TokenKind::DoubleStar, 2),
+                (';', _) => (TokenKind::Semicolon, 1),
+                (',', _) => (TokenKind::Comma, 1),
+                (':', _) => (TokenKind::Colon, 1),
+                ('(', _) => (TokenKind::LParen, 1),
+                (')', _) => (TokenKind::RParen, 1),
+                ('[', _) => (TokenKind::LBracket, 1),
+                (']', _) => (TokenKind::RBracket, 1),
+                ('{', _) => (TokenKind::LBrace, 1),
+                ('}', _) => (TokenKind::RBrace, 1),
+                ('@', _) => (TokenKind::At, 1),
+                ('=', _) => (TokenKind::Equals, 1),
+                ('+', _) => (TokenKind::Plus, 1),
+                ('-', _) => (TokenKind::Minus, 1),
+                ('*', _) => (TokenKind::Star, 1),
+                ('/', _) => (TokenKind::Slash, 1),
+                ('^', _) => (TokenKind::Caret, 1),
+                _ => {
+                    return Err(CompilerError::ParseError(
+                        start_line,
+                        start_column,
+                        format!("unexpected character '{}'", c),
+                    ));
+                }
+            };
+            advance(&mut pos, &mut line, &mut column, length);
+            kind
+        };
+
+        tokens.push(Token {
+            kind,
+            line: start_line,
+            column: start_column,
+        });
+    }
+
+    tokens.push(Token {
+        kind: TokenKind::Eof,
+        line,
+        column,
+    });
+    Ok(tokens)
+}
@@ -0,0 +1,144 @@
+use crate::compiler::{ir::InstructionIR, qasm::ANGLE_TOLERANCE};
+use num_complex::Complex;
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2, FRAC_PI_4, PI};
+
+/// Returns e^{iγ} U(θ, φ, λ), using the OpenQASM definition of the `U` gate
+pub(crate) fn u_matrix(theta: f64, phi: f64, lambda: f64, gamma: f64) -> [[Complex<f64>; 2]; 2] {
+    let (sin_half, cos_half) = (theta / 2.0).sin_cos();
+    [
+        [
+            Complex::from_polar(cos_half, gamma),
+            -Complex::from_polar(sin_half, gamma + lambda),
+        ],
+        [
+            Complex::from_polar(sin_half, gamma + phi),
+            Complex::from_polar(cos_half, gamma + phi + lambda),
+        ],
+    ]
+}
+
+/// Returns the matrix of the square root of X gate, `sx`
+pub(crate) fn sx_matrix() -> [[Complex<f64>; 2]; 2] {
+    let plus: Complex<f64> = Complex::new(0.5, 0.5);
+    let minus: Complex<f64> = Complex::new(0.5, -0.5);
+    [[plus, minus], [minus, plus]]
+}
+
+/// Returns whether a matrix is exactly the `sx` gate of `stdgates.inc`, including its phase
+pub(crate) fn is_sx(matrix: &[[Complex<f64>; 2]; 2]) -> bool {
+    let sx: [[Complex<f64>; 2]; 2] = sx_matrix();
+    (0..2).all(|row| (0..2).all(|col| (matrix[row][col] - sx[row][col]).norm() < ANGLE_TOLERANCE))
+}
+
+/// Returns the matrix of a single-qubit instruction, ignoring its controls
+///
+/// Returns `None` for swaps, measurements and raw QASM strings.
+pub(crate) fn instruction_matrix(instruction: &InstructionIR) -> Option<[[Complex<f64>; 2]; 2]> {
+    let zero: Complex<f64> = Complex::new(0.0, 0.0);
+    let one: Complex<f64> = Complex::new(1.0, 0.0);
+    let i: Complex<f64> = Complex::new(0.0, 1.0);
+    let s: Complex<f64> = Complex::new(FRAC_1_SQRT_2, 0.0);
+    let phase = |angle: f64| [[one, zero], [zero, Complex::from_polar(1.0, angle)]];
+    let matrix: [[Complex<f64>; 2]; 2] = match instruction {
+        InstructionIR::Hadamard(_, _) => [[s, s], [s, -s]],
+        InstructionIR::PauliX(_, _) => [[zero, one], [one, zero]],
+        InstructionIR::PauliY(_, _) => [[zero, -i], [i, zero]],
+        InstructionIR::PauliZ(_, _) => phase(PI),
+        InstructionIR::S(_, _) => phase(FRAC_PI_2),
+        InstructionIR::Sdg(_, _) => phase(-FRAC_PI_2),
+        InstructionIR::T(_, _) => phase(FRAC_PI_4),
+        InstructionIR::Tdg(_, _) => phase(-FRAC_PI_4),
+        InstructionIR::Phase(angle, _, _) => phase(*angle),
+        InstructionIR::Rx(angle, _, _) => {
+            let (sin_half, cos_half) = (angle / 2.0).sin_cos();
+            [
+                [Complex::new(cos_half, 0.0), Complex::new(0.0, -sin_half)],
+                [Complex::new(0.0, -sin_half), Complex::new(cos_half, 0.0)],
+            ]
+        }
+        InstructionIR::Ry(angle, _, _) => {
+            let (sin_half, cos_half) = (angle / 2.0).sin_cos();
+            [
+                [Complex::new(cos_half, 0.0), Complex::new(-sin_half, 0.0)],
+                [Complex::new(sin_half, 0.0), Complex::new(cos_half, 0.0)],
+            ]
+        }
+        InstructionIR::Rz(angle, _, _) => [
+            [Complex::from_polar(1.0, -angle / 2.0), zero],
+            [zero, Complex::from_polar(1.0, angle / 2.0)],
+        ],
+        InstructionIR::Id(_, _) => [[one, zero], [zero, one]],
+        InstructionIR::Unitary(matrix, _, _) => *matrix,
+        InstructionIR::Swap(_, _, _)
+        | InstructionIR::Measurement(_, _)
+        | InstructionIR::MeasurementInto(_, _, _)
+        | InstructionIR::Reset(_)
+        | InstructionIR::Parameterised(_, _, _, _)
+        | InstructionIR::Conditional(_, _)
+        | InstructionIR::Barrier(_)
+        | InstructionIR::RawQASM(_) => return None,
+    };
+    Some(matrix)
+}
+
+/// Returns the product `a · b` of two 2×2 matrices
+pub(crate) fn multiply(
+    a: &[[Complex<f64>; 2]; 2],
+    b: &[[Complex<f64>; 2]; 2],
+) -> [[Complex<f64>; 2]; 2] {
+    let mut result: [[Complex<f64>; 2]; 2] = [[Complex::new(0.0, 0.0); 2]; 2];
+    for row in 0..2 {
+        for col in 0..2 {
+            result[row][col] = a[row][0] * b[0][col] + a[row][1] * b[1][col];
+        }
+    }
+    result
+}
+
+/// Returns the conjugate transpose of a 2×2 matrix
+pub(crate) fn adjoint(matrix: &[[Complex<f64>; 2]; 2]) -> [[Complex<f64>; 2]; 2] {
+    [
+        [matrix[0][0].conj(), matrix[1][0].conj()],
+        [matrix[0][1].conj(), matrix[1][1].conj()],
+    ]
+}
+
+/// Raises a 2×2 unitary to a real power using its eigendecomposition and the principal branch
+pub(crate) fn matrix_power(matrix: &[[Complex<f64>; 2]; 2], exponent: f64) -> [[Complex<f64>; 2]; 2] {
+    let [[a, b], [c, d]] = *matrix;
+    let trace: Complex<f64> = a + d;
+    let determinant: Complex<f64> = a * d - b * c;
+    let discriminant: Complex<f64> = (trace * trace - 4.0 * determinant).sqrt();
+    let eigenvalue1: Complex<f64> = (trace + discriminant) / 2.0;
+    let eigenvalue2: Complex<f64> = (trace - discriminant) / 2.0;
+    let power = |eigenvalue: Complex<f64>| Complex::from_polar(1.0, eigenvalue.arg() * exponent);
+
+    if discriminant.norm() < 1e-12 {
+        // Degenerate eigenvalues: a normal matrix is then a multiple of the identity
+        let scalar: Complex<f64> = power(eigenvalue1);
+        return [[scalar, Complex::new(0.0, 0.0)], [Complex::new(0.0, 0.0), scalar]];
+    }
+
+    // Unit eigenvector for the first eigenvalue, from whichever row of (M - λI) is better
+    // conditioned; the second eigenvector is orthogonal to it
+    let from_first_row: [Complex<f64>; 2] = [b, eigenvalue1 - a];
+    let from_second_row: [Complex<f64>; 2] = [eigenvalue1 - d, c];
+    let row_norm = |v: &[Complex<f64>; 2]| v[0].norm_sqr() + v[1].norm_sqr();
+    let vector: [Complex<f64>; 2] = if row_norm(&from_first_row) > row_norm(&from_second_row) {
+        from_first_row
+    } else {
+        from_second_row
+    };
+    let norm: f64 = (vector[0].norm_sqr() + vector[1].norm_sqr()).sqrt();
+    let v: [Complex<f64>; 2] = [vector[0] / norm, vector[1] / norm];
+    let w: [Complex<f64>; 2] = [-v[1].conj(), v[0].conj()];
+
+    let (p1, p2) = (power(eigenvalue1), power(eigenvalue2));
+    let mut result: [[Complex<f64>; 2]; 2] = [[Complex::new(0.0, 0.0); 2]; 2];
+    for row in 0..2 {
+        for col in 0..2 {
+            result[row][col] = p1 * v[row] * v[col].conj() + p2 * w[row] * w[col].conj();
+        }
+    }
+    result
+}
@@ -1,2 +1,13 @@
-pub(crate) mod ir;
-pub mod compilable;
\ No newline at end of file
+pub mod ir;
+pub mod compilable;
+pub mod coupling_map;
+pub mod euler;
+pub mod kak;
+pub(crate) mod lexer;
+pub(crate) mod linalg;
+pub mod pass;
+pub mod passes;
+pub(crate) mod parser;
+pub(crate) mod qasm;
+pub(crate) mod qasm2;
+pub mod routing;
@@ -0,0 +1,1708 @@
+use crate::{
+    compiler::{
+        ir::{CircuitIR, InstructionIR},
+        lexer::{Token, TokenKind, tokenise},
+        linalg::{instruction_matrix, matrix_power, sx_matrix, u_matrix},
+    },
+    components::{
+        classical::ClassicalCondition,
+        measurement::MeasurementBasis,
+        operator::ParameterisedKind,
+        parameter::{Parameter, ParameterExpression},
+    },
+    errors::CompilerError,
+};
+use num_complex::Complex;
+use std::collections::HashMap;
+use std::f64::consts::{E, PI, TAU};
+
+/// Tolerance used when deciding whether a `pow` exponent is an integer
+const INTEGER_TOLERANCE: f64 = 1e-12;
+
+/// Parses an OpenQASM 3.0 program into the IR
+///
+/// The supported subset covers qubit and bit register declarations (`qubit`, `bit`, `qreg`,
+/// `creg`), the gates of `stdgates.inc` and the built-in `U` and `gphase` gates, the `ctrl @`,
+/// `negctrl @`, `inv @` and `pow(k) @` modifiers, `gate` definitions, `const` declarations,
+/// `measure`, `reset` and `barrier` statements, `if` statements comparing bits or bit registers to
+/// integers, and constant expressions over `pi`, `tau` and `euler`. `input float` declarations add a
+/// parameter to the circuit, on which the arguments of `rx`, `ry`, `rz`, `p` and their controlled
+/// versions may depend linearly.
+///
+/// Qubits of all declared registers are numbered consecutively in declaration order.
+///
+/// # Returns
+/// The parsed circuit, or `CompilerError::ParseError` with the line and column of the first error.
+pub(crate) fn parse_qasm3(source: &str) -> Result<CircuitIR, CompilerError> {
+    let tokens: Vec<Token> = tokenise(source)?;
+    Parser::new(tokens, Dialect::Qasm3).parse_program()
+}
+
+/// Parses an OpenQASM 2.0 program into the IR
+///
+/// The supported subset covers `qreg` and `creg` declarations, the gates of `qelib1.inc` and the
+/// built-in `U` and `CX` gates, `gate` definitions, `measure q -> c`, `reset` and `barrier`
+/// statements, `if(c==n)` statements and constant expressions over `pi`.
+///
+/// Qubits of all declared registers are numbered consecutively in declaration order.
+///
+/// # Returns
+/// The parsed circuit, or `CompilerError::ParseError` with the line and column of the first error.
+pub(crate) fn parse_qasm2(source: &str) -> Result<CircuitIR, CompilerError> {
+    let tokens: Vec<Token> = tokenise(source)?;
+    Parser::new(tokens, Dialect::Qasm2).parse_program()
+}
+
+/// The version of the OpenQASM language being parsed
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Dialect {
+    Qasm2,
+    Qasm3,
+}
+
+impl Dialect {
+    fn major_version(self) -> u64 {
+        match self {
+            Dialect::Qasm2 => 2,
+            Dialect::Qasm3 => 3,
+        }
+    }
+
+    /// The standard library that programs in this dialect may include
+    fn standard_library(self) -> &'static str {
+        match self {
+            Dialect::Qasm2 => "qelib1.inc",
+            Dialect::Qasm3 => "stdgates.inc",
+        }
+    }
+
+    /// Returns the number of parameters and qubits of a built-in or standard library gate
+    fn gate_signature(self, name: &str) -> Option<(usize, usize)> {
+        let is_available: bool = match self {
+            Dialect::Qasm2 => matches!(
+                name,
+                "U" | "CX" | "u3" | "u2" | "u1" | "p" | "id" | "x" | "y" | "z" | "h" | "s" | "sdg"
+                    | "t" | "tdg" | "sx" | "rx" | "ry" | "rz" | "cx" | "cy" | "cz" | "ch" | "cp"
+                    | "crx" | "cry" | "crz" | "cu1" | "cu3" | "swap" | "ccx" | "cswap"
+            ),
+            Dialect::Qasm3 => !matches!(name, "cu1" | "cu3"),
+        };
+        if is_available {
+            builtin_gate_signature(name)
+        } else {
+            None
+        }
+    }
+}
+
+/// A constant expression, evaluated once the values of all identifiers are known
+#[derive(Debug, Clone)]
+enum Expr {
+    Number(f64),
+    Identifier(String, usize, usize),
+    Negate(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>, usize, usize),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+/// A gate modifier, as written in the source
+#[derive(Debug, Clone)]
+enum Modifier {
+    Ctrl(Option<Expr>),
+    NegCtrl(Option<Expr>),
+    Inv,
+    Pow(Expr),
+}
+
+/// A gate modifier with its arguments evaluated
+#[derive(Debug, Clone, Copy)]
+enum ResolvedModifier {
+    Ctrl(usize),
+    NegCtrl(usize),
+    Inv,
+    Pow(f64),
+}
+
+/// A qubit or bit operand, either a whole register (`q`) or a single element (`q[1]`)
+#[derive(Debug, Clone)]
+struct Operand {
+    name: String,
+    index: Option<Expr>,
+    line: usize,
+    column: usize,
+}
+
+/// An argument of a gate call, with the position of its first token
+#[derive(Debug, Clone)]
+struct Argument {
+    expr: Expr,
+    line: usize,
+    column: usize,
+}
+
+/// A (possibly modified) gate call, as written in the source
+#[derive(Debug, Clone)]
+struct GateCall {
+    modifiers: Vec<Modifier>,
+    name: String,
+    params: Vec<Argument>,
+    operands: Vec<Operand>,
+    line: usize,
+    column: usize,
+}
+
+/// A user-defined gate from a `gate` statement
+#[derive(Debug, Clone)]
+struct GateDefinition {
+    params: Vec<String>,
+    qubits: Vec<String>,
+    body: Vec<GateCall>,
+}
+
+/// A declared qubit or bit register
+#[derive(Debug, Clone, Copy)]
+struct Register {
+    /// Index of the first element of the register
+    offset: usize,
+    /// Number of elements in the register
+    size: usize,
+    /// Whether the register was declared without a size (e.g. `qubit q;`) and cannot be indexed
+    is_scalar: bool,
+}
+
+/// An operation produced while expanding gate calls
+///
+/// A global phase is only observable once it is controlled, so it is kept separate from the IR
+/// until all modifiers have been applied.
+#[derive(Debug, Clone)]
+enum Element {
+    Instruction(InstructionIR),
+    GlobalPhase(f64, Vec<usize>),
+}
+
+impl Element {
+    fn add_controls(&mut self, new_controls: &[usize]) {
+        match self {
+            Element::Instruction(instruction) => {
+                if let Some(controls) = instruction.controls_mut() {
+                    controls.splice(0..0, new_controls.iter().copied());
+                }
+            }
+            Element::GlobalPhase(_, controls) => {
+                controls.splice(0..0, new_controls.iter().copied());
+            }
+        }
+    }
+
+    fn inverse(&self) -> Element {
+        match self {
+            Element::Instruction(instruction) => Element::Instruction(
+                instruction
+                    .inverse()
+                    .expect("gate bodies only contain invertible instructions"),
+            ),
+            Element::GlobalPhase(angle, controls) => Element::GlobalPhase(-angle, controls.clone()),
+        }
+    }
+
+    /// Raises the element to a non-integer power using the principal branch
+    fn power(&self, exponent: f64) -> Option<Element> {
+        let instruction: &InstructionIR = match self {
+            Element::GlobalPhase(angle, controls) => {
+                return Some(Element::GlobalPhase(angle * exponent, controls.clone()));
+            }
+            Element::Instruction(instruction) => instruction,
+        };
+        let powered: InstructionIR = match instruction {
+            InstructionIR::Id(_, _) => instruction.clone(),
+            InstructionIR::Phase(angle, target, controls) => {
+                InstructionIR::Phase(angle * exponent, *target, controls.clone())
+            }
+            InstructionIR::Rx(angle, target, controls) => {
+                InstructionIR::Rx(angle * exponent, *target, controls.clone())
+            }
+            InstructionIR::Ry(angle, target, controls) => {
+                InstructionIR::Ry(angle * exponent, *target, controls.clone())
+            }
+            InstructionIR::Rz(angle, target, controls) => {
+                InstructionIR::Rz(angle * exponent, *target, controls.clone())
+            }
+            InstructionIR::Parameterised(kind, angle, target, controls) => {
+                InstructionIR::Parameterised(*kind, angle.clone() * exponent, *target, controls.clone())
+            }
+            InstructionIR::PauliZ(target, controls) => {
+                InstructionIR::Phase(PI * exponent, *target, controls.clone())
+            }
+            InstructionIR::S(target, controls) => {
+                InstructionIR::Phase(PI / 2.0 * exponent, *target, controls.clone())
+            }
+            InstructionIR::Sdg(target, controls) => {
+                InstructionIR::Phase(-PI / 2.0 * exponent, *target, controls.clone())
+            }
+            InstructionIR::T(target, controls) => {
+                InstructionIR::Phase(PI / 4.0 * exponent, *target, controls.clone())
+            }
+            InstructionIR::Tdg(target, controls) => {
+                InstructionIR::Phase(-PI / 4.0 * exponent, *target, controls.clone())
+            }
+            InstructionIR::Hadamard(target, controls)
+            | InstructionIR::PauliX(target, controls)
+            | InstructionIR::PauliY(target, controls)
+            | InstructionIR::Unitary(_, target, controls) => {
+                let matrix: [[Complex<f64>; 2]; 2] = instruction_matrix(instruction)?;
+                InstructionIR::Unitary(matrix_power(&matrix, exponent), *target, controls.clone())
+            }
+            _ => return None,
+        };
+        Some(Element::Instruction(powered))
+    }
+}
+
+/// Recursive descent parser for OpenQASM 2.0 and 3.0
+struct Parser {
+    dialect: Dialect,
+    tokens: Vec<Token>,
+    pos: usize,
+    qubit_registers: HashMap<String, Register>,
+    bit_registers: HashMap<String, Register>,
+    num_qubits: usize,
+    num_bits: usize,
+    constants: HashMap<String, f64>,
+    parameters: Vec<String>,
+    gates: HashMap<String, GateDefinition>,
+    instructions: Vec<InstructionIR>,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>, dialect: Dialect) -> Self {
+        Parser {
+            dialect,
+            tokens,
+            pos: 0,
+            qubit_registers: HashMap::new(),
+            bit_registers: HashMap::new(),
+            num_qubits: 0,
+            num_bits: 0,
+            constants: HashMap::new(),
+            parameters: Vec::new(),
+            gates: HashMap::new(),
+            instructions: Vec::new(),
+        }
+    }
+
+    // -- TOKEN HELPERS --
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn peek_kind_at(&self, offset: usize) -> &TokenKind {
+        let index: usize = (self.pos + offset).min(self.tokens.len() - 1);
+        &self.tokens[index].kind
+    }
+
+    fn next_token(&mut self) -> Token {
+        let token: Token = self.tokens[self.pos].clone();
+        if self.pos < self.tokens.len() - 1 {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn check(&self, kind: &TokenKind) -> bool {
+        &self.peek().kind == kind
+    }
+
+    fn check_identifier(&self, name: &str) -> bool {
+        matches!(&self.peek().kind, TokenKind::Identifier(identifier) if identifier == name)
+    }
+
+    fn consume(&mut self, kind: &TokenKind) -> bool {
+        if self.check(kind) {
+            self.next_token();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, kind: TokenKind) -> Result<Token, CompilerError> {
+        if self.check(&kind) {
+            Ok(self.next_token())
+        } else {
+            let token: &Token = self.peek();
+            Err(error_at(
+                token,
+                format!("expected {}, found {}", kind, token.kind),
+            ))
+        }
+    }
+
+    fn expect_identifier(&mut self) -> Result<(String, usize, usize), CompilerError> {
+        let token: Token = self.next_token();
+        match token.kind {
+            TokenKind::Identifier(name) => Ok((name, token.line, token.column)),
+            other => Err(CompilerError::ParseError(
+                token.line,
+                token.column,
+                format!("expected identifier, found {}", other),
+            )),
+        }
+    }
+
+    // -- STATEMENTS --
+
+    fn parse_program(mut self) -> Result<CircuitIR, CompilerError> {
+        if self.check_identifier("OPENQASM") {
+            self.parse_version()?;
+        }
+
+        while !self.check(&TokenKind::Eof) {
+            self.parse_statement()?;
+        }
+
+        Ok(CircuitIR {
+            num_qubits: self.num_qubits,
+            num_bits: self.num_bits,
+            instructions: self.instructions,
+            parameters: self.parameters,
+        })
+    }
+
+    fn parse_version(&mut self) -> Result<(), CompilerError> {
+        self.next_token();
+        let token: Token = self.next_token();
+        let major: Option<u64> = match token.kind {
+            TokenKind::Integer(version) => Some(version),
+            TokenKind::Real(version) => Some(version.trunc() as u64),
+            _ => None,
+        };
+        match major {
+            Some(version) if version == self.dialect.major_version() => {}
+            Some(version) => {
+                return Err(error_at(
+                    &token,
+                    format!("unsupported OpenQASM version {}", version),
+                ));
+            }
+            None => return Err(error_at(&token, "expected version number".to_string())),
+        }
+        self.expect(TokenKind::Semicolon)?;
+        Ok(())
+    }
+
+    fn parse_statement(&mut self) -> Result<(), CompilerError> {
+        let token: Token = self.peek().clone();
+        let keyword: String = match &token.kind {
+            TokenKind::Identifier(name) => name.clone(),
+            other => {
+                return Err(error_at(
+                    &token,
+                    format!("expected statement, found {}", other),
+                ));
+            }
+        };
+
+        let is_qasm3: bool = self.dialect == Dialect::Qasm3;
+        match keyword.as_str() {
+            "OPENQASM" => Err(error_at(
+                &token,
+                "version declaration must be the first statement".to_string(),
+            )),
+            "include" => self.parse_include(),
+            "qubit" if is_qasm3 => self.parse_new_style_declaration(true),
+            "bit" if is_qasm3 => self.parse_new_style_declaration(false),
+            "qreg" => self.parse_old_style_declaration(true),
+            "creg" => self.parse_old_style_declaration(false),
+            "const" if is_qasm3 => self.parse_const(),
+            "gate" => self.parse_gate_definition(),
+            "measure" => self.parse_measure_arrow(),
+            "barrier" => self.parse_barrier(),
+            "reset" => self.parse_reset(),
+            "if" => self.parse_if(),
+            "input" if is_qasm3 => self.parse_input(),
+            "else" | "for" | "while" | "def" | "opaque" | "input"
+            | "output" | "box" | "let" | "defcal" | "cal" | "extern" | "delay" => Err(error_at(
+                &token,
+                format!("unsupported statement '{}'", keyword),
+            )),
+            name if is_qasm3
+                && self.bit_registers.contains_key(name)
+                && matches!(
+                    self.peek_kind_at(1),
+                    TokenKind::Equals | TokenKind::LBracket
+                ) =>
+            {
+                self.parse_measure_assignment()
+            }
+            _ => {
+                let call: GateCall = self.parse_gate_call()?;
+                self.check_gate_exists(&call)?;
+                self.apply_top_level_call(&call)
+            }
+        }
+    }
+
+    fn parse_include(&mut self) -> Result<(), CompilerError> {
+        self.next_token();
+        let token: Token = self.next_token();
+        match &token.kind {
+            TokenKind::Str(file) if file == self.dialect.standard_library() => {}
+            TokenKind::Str(file) => {
+                return Err(error_at(&token, format!("cannot include '{}'", file)));
+            }
+            other => {
+                return Err(error_at(
+                    &token,
+                    format!("expected file name, found {}", other),
+                ));
+            }
+        }
+        self.expect(TokenKind::Semicolon)?;
+        Ok(())
+    }
+
+    /// Parses `qubit[n] name;`, `qubit name;`, `bit[n] name;` or `bit name;`
+    fn parse_new_style_declaration(&mut self, is_qubit: bool) -> Result<(), CompilerError> {
+        self.next_token();
+        let size: Option<usize> = if self.consume(&TokenKind::LBracket) {
+            let size: usize = self.parse_size()?;
+            self.expect(TokenKind::RBracket)?;
+            Some(size)
+        } else {
+            None
+        };
+        let (name, line, column) = self.expect_identifier()?;
+        self.expect(TokenKind::Semicolon)?;
+        self.declare_register(name, size, is_qubit, line, column)
+    }
+
+    /// Parses `qreg name[n];` or `creg name[n];`
+    fn parse_old_style_declaration(&mut self, is_qubit: bool) -> Result<(), CompilerError> {
+        self.next_token();
+        let (name, line, column) = self.expect_identifier()?;
+        let size: Option<usize> = if self.consume(&TokenKind::LBracket) {
+            let size: usize = self.parse_size()?;
+            self.expect(TokenKind::RBracket)?;
+            Some(size)
+        } else {
+            None
+        };
+        self.expect(TokenKind::Semicolon)?;
+        self.declare_register(name, size, is_qubit, line, column)
+    }
+
+    fn parse_size(&mut self) -> Result<usize, CompilerError> {
+        let token: Token = self.peek().clone();
+        let expr: Expr = self.parse_expr()?;
+        let value: f64 = self.evaluate(&expr, &HashMap::new())?;
+        to_index(value).ok_or_else(|| error_at(&token, format!("invalid size {}", value)))
+    }
+
+    fn declare_register(
+        &mut self,
+        name: String,
+        size: Option<usize>,
+        is_qubit: bool,
+        line: usize,
+        column: usize,
+    ) -> Result<(), CompilerError> {
+        if self.is_name_taken(&name) {
+            return Err(CompilerError::ParseError(
+                line,
+                column,
+                format!("'{}' is already declared", name),
+            ));
+        }
+        let (registers, count) = if is_qubit {
+            (&mut self.qubit_registers, &mut self.num_qubits)
+        } else {
+            (&mut self.bit_registers, &mut self.num_bits)
+        };
+        let register: Register = Register {
+            offset: *count,
+            size: size.unwrap_or(1),
+            is_scalar: size.is_none(),
+        };
+        *count += register.size;
+        registers.insert(name, register);
+        Ok(())
+    }
+
+    fn is_name_taken(&self, name: &str) -> bool {
+        self.qubit_registers.contains_key(name)
+            || self.bit_registers.contains_key(name)
+            || self.constants.contains_key(name)
+            || self.parameters.iter().any(|parameter| parameter == name)
+            || self.gates.contains_key(name)
+            || self.dialect.gate_signature(name).is_some()
+    }
+
+    /// Parses `const <type> name = expr;`
+    fn parse_const(&mut self) -> Result<(), CompilerError> {
+        self.next_token();
+        // The type is only used for documentation; all constants are evaluated as reals
+        self.expect_identifier()?;
+        if self.consume(&TokenKind::LBracket) {
+            self.parse_expr()?;
+            self.expect(TokenKind::RBracket)?;
+        }
+        let (name, line, column) = self.expect_identifier()?;
+        self.expect(TokenKind::Equals)?;
+        let expr: Expr = self.parse_expr()?;
+        self.expect(TokenKind::Semicolon)?;
+
+        if self.is_name_taken(&name) || is_builtin_constant(&name) {
+            return Err(CompilerError::ParseError(
+                line,
+                column,
+                format!("'{}' is already declared", name),
+            ));
+        }
+        let value: f64 = self.evaluate(&expr, &HashMap::new())?;
+        self.constants.insert(name, value);
+        Ok(())
+    }
+
+    /// Parses `input float[64] name;` or `input float name;`, which declares a parameter of the circuit
+    fn parse_input(&mut self) -> Result<(), CompilerError> {
+        self.next_token();
+        let token: Token = self.peek().clone();
+        let (input_type, _, _) = self.expect_identifier()?;
+        if input_type != "float" {
+            return Err(error_at(&token, format!("unsupported input type '{}'", input_type)));
+        }
+        if self.consume(&TokenKind::LBracket) {
+            self.parse_size()?;
+            self.expect(TokenKind::RBracket)?;
+        }
+        let (name, line, column) = self.expect_identifier()?;
+        self.expect(TokenKind::Semicolon)?;
+
+        if self.is_name_taken(&name) || is_builtin_constant(&name) {
+            return Err(CompilerError::ParseError(
+                line,
+                column,
+                format!("'{}' is already declared", name),
+            ));
+        }
+        self.parameters.push(name);
+        Ok(())
+    }
+
+    /// Parses `gate name(params) qubits { body }`
+    fn parse_gate_definition(&mut self) -> Result<(), CompilerError> {
+        self.next_token();
+        let (name, line, column) = self.expect_identifier()?;
+        if self.is_name_taken(&name) {
+            return Err(CompilerError::ParseError(
+                line,
+                column,
+                format!("'{}' is already declared", name),
+            ));
+        }
+
+        let mut params: Vec<String> = Vec::new();
+        if self.consume(&TokenKind::LParen) {
+            if !self.check(&TokenKind::RParen) {
+                loop {
+                    params.push(self.parse_unique_name(&params)?);
+                    if !self.consume(&TokenKind::Comma) {
+                        break;
+                    }
+                }
+            }
+            self.expect(TokenKind::RParen)?;
+        }
+
+        let mut qubits: Vec<String> = Vec::new();
+        loop {
+            qubits.push(self.parse_unique_name(&qubits)?);
+            if !self.consume(&TokenKind::Comma) {
+                break;
+            }
+        }
+
+        self.expect(TokenKind::LBrace)?;
+        let mut body: Vec<GateCall> = Vec::new();
+        while !self.consume(&TokenKind::RBrace) {
+            if self.check_identifier("barrier") {
+                // Barriers have no effect on the unitary of a gate
+                while !self.consume(&TokenKind::Semicolon) {
+                    if self.check(&TokenKind::Eof) {
+                        return Err(error_at(self.peek(), "expected ';'".to_string()));
+                    }
+                    self.next_token();
+                }
+                continue;
+            }
+            let call: GateCall = self.parse_gate_call()?;
+            self.check_gate_exists(&call)?;
+            for operand in &call.operands {
+                if operand.index.is_some() || !qubits.contains(&operand.name) {
+                    return Err(CompilerError::ParseError(
+                        operand.line,
+                        operand.column,
+                        format!("'{}' is not a qubit argument of gate '{}'", operand.name, name),
+                    ));
+                }
+            }
+            body.push(call);
+        }
+
+        self.gates.insert(
+            name,
+            GateDefinition {
+                params,
+                qubits,
+                body,
+            },
+        );
+        Ok(())
+    }
+
+    fn parse_unique_name(&mut self, existing: &[String]) -> Result<String, CompilerError> {
+        let (name, line, column) = self.expect_identifier()?;
+        if existing.contains(&name) {
+            return Err(CompilerError::ParseError(
+                line,
+                column,
+                format!("duplicate argument '{}'", name),
+            ));
+        }
+        Ok(name)
+    }
+
+    /// Parses `measure q -> c;`
+    ///
+    /// A bare `measure q;` discards its outcome in OpenQASM 3.0, which no instruction can express,
+    /// so it is rejected rather than stored in a bit that the program did not name.
+    fn parse_measure_arrow(&mut self) -> Result<(), CompilerError> {
+        let token: Token = self.next_token();
+        let qubit_operand: Operand = self.parse_operand()?;
+        if self.dialect == Dialect::Qasm3 && !self.check(&TokenKind::Arrow) {
+            return Err(error_at(
+                &token,
+                "measurement outcomes must be stored, as in 'c = measure q;'".to_string(),
+            ));
+        }
+        self.expect(TokenKind::Arrow)?;
+        let bit_operand: Operand = self.parse_operand()?;
+        self.expect(TokenKind::Semicolon)?;
+        self.apply_measurement(&qubit_operand, &bit_operand)
+    }
+
+    /// Parses `c = measure q;`
+    fn parse_measure_assignment(&mut self) -> Result<(), CompilerError> {
+        let bit_operand: Operand = self.parse_operand()?;
+        self.expect(TokenKind::Equals)?;
+        let token: Token = self.peek().clone();
+        if !self.check_identifier("measure") {
+            return Err(error_at(
+                &token,
+                format!("expected 'measure', found {}", token.kind),
+            ));
+        }
+        self.next_token();
+        let qubit_operand: Operand = self.parse_operand()?;
+        self.expect(TokenKind::Semicolon)?;
+        self.apply_measurement(&qubit_operand, &bit_operand)
+    }
+
+    fn apply_measurement(&mut self, qubit_operand: &Operand, bit_operand: &Operand) -> Result<(), CompilerError> {
+        let qubits: Vec<usize> = self.resolve_operand(qubit_operand, true)?;
+        let bits: Vec<usize> = self.resolve_operand(bit_operand, false)?;
+        if bits.len() != qubits.len() {
+            return Err(CompilerError::ParseError(
+                bit_operand.line,
+                bit_operand.column,
+                format!(
+                    "cannot store {} measurement outcomes in {} bits",
+                    qubits.len(),
+                    bits.len()
+                ),
+            ));
+        }
+        for (qubit, bit) in qubits.into_iter().zip(bits) {
+            // Outcomes stored in the bit of the same index are plain measurements
+            let instruction: InstructionIR = if bit == qubit {
+                InstructionIR::Measurement(qubit, MeasurementBasis::Computational)
+            } else {
+                InstructionIR::MeasurementInto(qubit, bit, MeasurementBasis::Computational)
+            };
+            self.instructions.push(instruction);
+        }
+        Ok(())
+    }
+
+    /// Parses `if (c == 1) x q;`, `if (c[0] == 1 && c[1] == 0) { ... }` or, in OpenQASM 2.0,
+    /// `if(c==1) x q;`
+    ///
+    /// Every instruction of the body is wrapped in a classically controlled instruction with the
+    /// same condition. Since the condition is then checked before each instruction, bodies that write
+    /// the bits of the condition before their last instruction are rejected.
+    fn parse_if(&mut self) -> Result<(), CompilerError> {
+        self.next_token();
+        self.expect(TokenKind::LParen)?;
+        let mut bits: Vec<usize> = Vec::new();
+        let mut value: u64 = 0;
+        loop {
+            let operand: Operand = self.parse_operand()?;
+            if self.dialect == Dialect::Qasm2 && (operand.index.is_some() || !bits.is_empty()) {
+                return Err(CompilerError::ParseError(
+                    operand.line,
+                    operand.column,
+                    "OpenQASM 2.0 conditions must compare a whole classical register".to_string(),
+                ));
+            }
+            let operand_bits: Vec<usize> = self.resolve_operand(&operand, false)?;
+            self.expect(TokenKind::DoubleEquals)?;
+            let token: Token = self.next_token();
+            let operand_value: u64 = match &token.kind {
+                TokenKind::Integer(operand_value) => *operand_value,
+                other => {
+                    return Err(error_at(&token, format!("expected integer, found {}", other)));
+                }
+            };
+            if bits.len() + operand_bits.len() > u64::BITS as usize
+                || (operand_bits.len() < u64::BITS as usize && operand_value >> operand_bits.len() != 0)
+            {
+                return Err(error_at(
+                    &token,
+                    format!("value {} does not fit into {} bits", operand_value, operand_bits.len()),
+                ));
+            }
+            value |= operand_value << bits.len();
+            bits.extend(operand_bits);
+            if !self.consume(&TokenKind::DoubleAmpersand) {
+                break;
+            }
+        }
+        self.expect(TokenKind::RParen)?;
+        let condition: ClassicalCondition = ClassicalCondition::new(bits, value);
+
+        let start: usize = self.instructions.len();
+        // The first token and first instruction of every statement in the body
+        let mut statements: Vec<(Token, usize)> = Vec::new();
+        if self.dialect == Dialect::Qasm3 && self.consume(&TokenKind::LBrace) {
+            while !self.check(&TokenKind::RBrace) && !self.check(&TokenKind::Eof) {
+                statements.push((self.peek().clone(), self.instructions.len()));
+                self.parse_conditional_statement()?;
+            }
+            self.expect(TokenKind::RBrace)?;
+        } else {
+            statements.push((self.peek().clone(), start));
+            self.parse_conditional_statement()?;
+        }
+
+        let body: Vec<InstructionIR> = self.instructions.split_off(start);
+        // The condition is checked again before every instruction of the body, so only the last
+        // instruction may write the bits that the condition reads
+        if let Some(position) = body[..body.len().saturating_sub(1)].iter().position(|instruction| {
+            instruction.written_bits().iter().any(|bit| condition.bits.contains(bit))
+        }) {
+            let (token, _) = statements
+                .iter()
+                .rev()
+                .find(|(_, first)| *first <= start + position)
+                .expect("every instruction of the body belongs to a statement");
+            return Err(error_at(
+                token,
+                "only the last instruction of an if body may write the bits of its condition".to_string(),
+            ));
+        }
+        self.instructions.extend(
+            body.into_iter()
+                .map(|instruction| InstructionIR::Conditional(condition.clone(), Box::new(instruction))),
+        );
+        Ok(())
+    }
+
+    /// Parses a statement in the body of an `if`, which may not declare anything
+    fn parse_conditional_statement(&mut self) -> Result<(), CompilerError> {
+        let token: Token = self.peek().clone();
+        if let TokenKind::Identifier(keyword) = &token.kind
+            && matches!(
+                keyword.as_str(),
+                "OPENQASM" | "include" | "qubit" | "bit" | "qreg" | "creg" | "const" | "input" | "gate"
+            )
+        {
+            return Err(error_at(
+                &token,
+                format!("'{}' is not allowed inside an if statement", keyword),
+            ));
+        }
+        self.parse_statement()
+    }
+
+    /// Parses `barrier q, r[0];`, or `barrier;` for a barrier on every qubit
+    fn parse_barrier(&mut self) -> Result<(), CompilerError> {
+        self.next_token();
+        let mut qubits: Vec<usize> = Vec::new();
+        if !self.check(&TokenKind::Semicolon) {
+            loop {
+                let operand: Operand = self.parse_operand()?;
+                for qubit in self.resolve_operand(&operand, true)? {
+                    if !qubits.contains(&qubit) {
+                        qubits.push(qubit);
+                    }
+                }
+                if !self.consume(&TokenKind::Comma) {
+                    break;
+                }
+            }
+        }
+        self.expect(TokenKind::Semicolon)?;
+        self.instructions.push(InstructionIR::Barrier(qubits));
+        Ok(())
+    }
+
+    /// Parses `reset q;` or `reset q[0];`
+    fn parse_reset(&mut self) -> Result<(), CompilerError> {
+        self.next_token();
+        let operand: Operand = self.parse_operand()?;
+        self.expect(TokenKind::Semicolon)?;
+        for qubit in self.resolve_operand(&operand, true)? {
+            self.instructions.push(InstructionIR::Reset(qubit));
+        }
+        Ok(())
+    }
+
+    // -- GATE CALLS --
+
+    fn parse_gate_call(&mut self) -> Result<GateCall, CompilerError> {
+        let mut modifiers: Vec<Modifier> = Vec::new();
+        loop {
+            let is_modifier_keyword: bool = self.dialect == Dialect::Qasm3
+                && matches!(
+                    &self.peek().kind,
+                    TokenKind::Identifier(name) if matches!(name.as_str(), "ctrl" | "negctrl" | "inv" | "pow")
+                );
+            if !is_modifier_keyword
+                || !matches!(self.peek_kind_at(1), TokenKind::At | TokenKind::LParen)
+            {
+                break;
+            }
+            let (keyword, _, _) = self.expect_identifier()?;
+            let argument: Option<Expr> = if self.consume(&TokenKind::LParen) {
+                let expr: Expr = self.parse_expr()?;
+                self.expect(TokenKind::RParen)?;
+                Some(expr)
+            } else {
+                None
+            };
+            let token: Token = self.peek().clone();
+            let modifier: Modifier = match (keyword.as_str(), argument) {
+                ("ctrl", argument) => Modifier::Ctrl(argument),
+                ("negctrl", argument) => Modifier::NegCtrl(argument),
+                ("inv", None) => Modifier::Inv,
+                ("pow", Some(argument)) => Modifier::Pow(argument),
+                ("inv", Some(_)) => {
+                    return Err(error_at(&token, "'inv' takes no argument".to_string()));
+                }
+                _ => return Err(error_at(&token, "'pow' requires an exponent".to_string())),
+            };
+            self.expect(TokenKind::At)?;
+            modifiers.push(modifier);
+        }
+
+        let (name, line, column) = self.expect_identifier()?;
+
+        let mut params: Vec<Argument> = Vec::new();
+        if self.consume(&TokenKind::LParen) {
+            if !self.check(&TokenKind::RParen) {
+                loop {
+                    let token: Token = self.peek().clone();
+                    params.push(Argument {
+                        expr: self.parse_expr()?,
+                        line: token.line,
+                        column: token.column,
+                    });
+                    if !self.consume(&TokenKind::Comma) {
+                        break;
+                    }
+                }
+            }
+            self.expect(TokenKind::RParen)?;
+        }
+
+        let mut operands: Vec<Operand> = Vec::new();
+        if !self.check(&TokenKind::Semicolon) {
+            loop {
+                operands.push(self.parse_operand()?);
+                if !self.consume(&TokenKind::Comma) {
+                    break;
+                }
+            }
+        }
+        self.expect(TokenKind::Semicolon)?;
+
+        Ok(GateCall {
+            modifiers,
+            name,
+            params,
+            operands,
+            line,
+            column,
+        })
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, CompilerError> {
+        let (name, line, column) = self.expect_identifier()?;
+        let index: Option<Expr> = if self.consume(&TokenKind::LBracket) {
+            let expr: Expr = self.parse_expr()?;
+            self.expect(TokenKind::RBracket)?;
+            Some(expr)
+        } else {
+            None
+        };
+        Ok(Operand {
+            name,
+            index,
+            line,
+            column,
+        })
+    }
+
+    fn check_gate_exists(&self, call: &GateCall) -> Result<(), CompilerError> {
+        if self.dialect.gate_signature(&call.name).is_some() || self.gates.contains_key(&call.name) {
+            Ok(())
+        } else {
+            Err(CompilerError::ParseError(
+                call.line,
+                call.column,
+                format!("unknown gate '{}'", call.name),
+            ))
+        }
+    }
+
+    /// Resolves an operand to the indices of the qubits (or bits) it refers to
+    fn resolve_operand(&self, operand: &Operand, is_qubit: bool) -> Result<Vec<usize>, CompilerError> {
+        let registers: &HashMap<String, Register> = if is_qubit {
+            &self.qubit_registers
+        } else {
+            &self.bit_registers
+        };
+        let kind: &str = if is_qubit { "qubit" } else { "bit" };
+        let register: &Register = registers.get(&operand.name).ok_or_else(|| {
+            CompilerError::ParseError(
+                operand.line,
+                operand.column,
+                format!("unknown {} register '{}'", kind, operand.name),
+            )
+        })?;
+
+        match &operand.index {
+            None => Ok((register.offset..register.offset + register.size).collect()),
+            Some(_) if register.is_scalar => Err(CompilerError::ParseError(
+                operand.line,
+                operand.column,
+                format!("{} '{}' cannot be indexed", kind, operand.name),
+            )),
+            Some(expr) => {
+                let value: f64 = self.evaluate(expr, &HashMap::new())?;
+                match to_index(value) {
+                    Some(index) if index < register.size => Ok(vec![register.offset + index]),
+                    _ => Err(CompilerError::ParseError(
+                        operand.line,
+                        operand.column,
+                        format!(
+                            "index {} out of range for {} register '{}' of size {}",
+                            value, kind, operand.name, register.size
+                        ),
+                    )),
+                }
+            }
+        }
+    }
+
+    /// Applies a gate call outside of a gate definition, broadcasting over whole-register operands
+    fn apply_top_level_call(&mut self, call: &GateCall) -> Result<(), CompilerError> {
+        let scope: HashMap<String, ParameterExpression> = HashMap::new();
+        let params: Vec<ParameterExpression> = call
+            .params
+            .iter()
+            .map(|param| self.evaluate_argument(param, &scope))
+            .collect::<Result<Vec<ParameterExpression>, CompilerError>>()?;
+        let modifiers: Vec<ResolvedModifier> = self.resolve_modifiers(call, &scope)?;
+
+        let operands: Vec<Vec<usize>> = call
+            .operands
+            .iter()
+            .map(|operand| self.resolve_operand(operand, true))
+            .collect::<Result<Vec<Vec<usize>>, CompilerError>>()?;
+
+        // All register operands must have the same size; single qubits are reused for every element
+        let mut broadcast_size: usize = 1;
+        for (operand, qubits) in call.operands.iter().zip(&operands) {
+            if qubits.len() != 1 {
+                if broadcast_size != 1 && broadcast_size != qubits.len() {
+                    return Err(CompilerError::ParseError(
+                        operand.line,
+                        operand.column,
+                        "registers of different sizes cannot be broadcast together".to_string(),
+                    ));
+                }
+                broadcast_size = qubits.len();
+            }
+        }
+
+        let mut elements: Vec<Element> = Vec::new();
+        for i in 0..broadcast_size {
+            let qubits: Vec<usize> = operands
+                .iter()
+                .map(|qubits| if qubits.len() == 1 { qubits[0] } else { qubits[i] })
+                .collect();
+            elements.extend(self.expand(call, &params, &modifiers, &qubits)?);
+        }
+
+        for element in elements {
+            match element {
+                Element::Instruction(instruction) => self.instructions.push(instruction),
+                // An uncontrolled global phase has no observable effect
+                Element::GlobalPhase(_, controls) if controls.is_empty() => {}
+                Element::GlobalPhase(angle, mut controls) => {
+                    let target: usize = controls.pop().expect("controls are not empty");
+                    self.instructions
+                        .push(InstructionIR::Phase(angle, target, controls));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve_modifiers(
+        &self,
+        call: &GateCall,
+        scope: &HashMap<String, ParameterExpression>,
+    ) -> Result<Vec<ResolvedModifier>, CompilerError> {
+        let count = |expr: &Option<Expr>| -> Result<usize, CompilerError> {
+            match expr {
+                None => Ok(1),
+                Some(expr) => {
+                    let value: f64 = self.evaluate(expr, scope)?;
+                    match to_index(value) {
+                        Some(count) if count > 0 => Ok(count),
+                        _ => Err(CompilerError::ParseError(
+                            call.line,
+                            call.column,
+                            format!("invalid number of control qubits {}", value),
+                        )),
+                    }
+                }
+            }
+        };
+
+        call.modifiers
+            .iter()
+            .map(|modifier| {
+                Ok(match modifier {
+                    Modifier::Ctrl(expr) => ResolvedModifier::Ctrl(count(expr)?),
+                    Modifier::NegCtrl(expr) => ResolvedModifier::NegCtrl(count(expr)?),
+                    Modifier::Inv => ResolvedModifier::Inv,
+                    Modifier::Pow(expr) => {
+                        let exponent: f64 = self.evaluate(expr, scope)?;
+                        if !exponent.is_finite() {
+                            return Err(CompilerError::ParseError(
+                                call.line,
+                                call.column,
+                                format!("invalid exponent {}", exponent),
+                            ));
+                        }
+                        ResolvedModifier::Pow(exponent)
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Expands a gate call on concrete qubits into elementary operations
+    ///
+    /// Modifiers are applied from the outermost (leftmost) inwards; control modifiers consume
+    /// qubits from the front of the operand list.
+    fn expand(
+        &self,
+        call: &GateCall,
+        params: &[ParameterExpression],
+        modifiers: &[ResolvedModifier],
+        qubits: &[usize],
+    ) -> Result<Vec<Element>, CompilerError> {
+        let error = |message: String| CompilerError::ParseError(call.line, call.column, message);
+
+        for (i, qubit) in qubits.iter().enumerate() {
+            if qubits[..i].contains(qubit) {
+                return Err(error(format!("qubit {} is used more than once", qubit)));
+            }
+        }
+
+        let (modifier, rest) = match modifiers.split_first() {
+            None => return self.expand_base(call, params, qubits),
+            Some(split) => split,
+        };
+
+        match *modifier {
+            ResolvedModifier::Ctrl(count) | ResolvedModifier::NegCtrl(count) => {
+                if qubits.len() < count {
+                    return Err(error(format!(
+                        "gate '{}' with {} control qubits cannot be applied to {} qubits",
+                        call.name,
+                        count,
+                        qubits.len()
+                    )));
+                }
+                let (controls, targets) = qubits.split_at(count);
+                let mut elements: Vec<Element> = self.expand(call, params, rest, targets)?;
+                for element in elements.iter_mut() {
+                    element.add_controls(controls);
+                }
+                if matches!(modifier, ResolvedModifier::NegCtrl(_)) {
+                    let flips: Vec<Element> = controls
+                        .iter()
+                        .map(|&control| Element::Instruction(InstructionIR::PauliX(control, vec![])))
+                        .collect();
+                    let mut negated: Vec<Element> = flips.clone();
+                    negated.extend(elements);
+                    negated.extend(flips);
+                    elements = negated;
+                }
+                Ok(elements)
+            }
+            ResolvedModifier::Inv => {
+                let elements: Vec<Element> = self.expand(call, params, rest, qubits)?;
+                Ok(elements.iter().rev().map(Element::inverse).collect())
+            }
+            ResolvedModifier::Pow(exponent) => {
+                let rounded: f64 = exponent.round();
+                if (exponent - rounded).abs() < INTEGER_TOLERANCE {
+                    let mut elements: Vec<Element> = self.expand(call, params, rest, qubits)?;
+                    if rounded < 0.0 {
+                        elements = elements.iter().rev().map(Element::inverse).collect();
+                    }
+                    let repetitions: usize = rounded.abs() as usize;
+                    Ok(elements
+                        .iter()
+                        .cycle()
+                        .take(elements.len() * repetitions)
+                        .cloned()
+                        .collect())
+                } else {
+                    let elements: Vec<Element> = self.expand(call, params, rest, qubits)?;
+                    let single: Option<Element> = match elements.as_slice() {
+                        [element] => Some(element.clone()),
+                        // A global phase on the controls of a gate, as in `u3`, is raised to the power with it
+                        [Element::GlobalPhase(angle, controls), Element::Instruction(instruction)] => {
+                            with_global_phase(instruction, *angle, controls)
+                        }
+                        _ => None,
+                    };
+                    single.and_then(|element| element.power(exponent)).map(|e| vec![e]).ok_or_else(|| {
+                        error(format!(
+                            "non-integer power of gate '{}' is not supported",
+                            call.name
+                        ))
+                    })
+                }
+            }
+        }
+    }
+
+    /// Expands an unmodified gate call on concrete qubits
+    fn expand_base(
+        &self,
+        call: &GateCall,
+        params: &[ParameterExpression],
+        qubits: &[usize],
+    ) -> Result<Vec<Element>, CompilerError> {
+        let error = |message: String| CompilerError::ParseError(call.line, call.column, message);
+
+        let (expected_params, expected_qubits) = match self.dialect.gate_signature(&call.name) {
+            Some(signature) => signature,
+            None => {
+                let definition: &GateDefinition = self
+                    .gates
+                    .get(&call.name)
+                    .ok_or_else(|| error(format!("unknown gate '{}'", call.name)))?;
+                (definition.params.len(), definition.qubits.len())
+            }
+        };
+        if params.len() != expected_params {
+            return Err(error(format!(
+                "gate '{}' expects {} parameters, got {}",
+                call.name,
+                expected_params,
+                params.len()
+            )));
+        }
+        if qubits.len() != expected_qubits {
+            return Err(error(format!(
+                "gate '{}' expects {} qubits, got {}",
+                call.name,
+                expected_qubits,
+                qubits.len()
+            )));
+        }
+
+        let values: Option<Vec<f64>> = params.iter().map(ParameterExpression::value).collect();
+        let builtin: Option<Vec<Element>> = match &values {
+            Some(values) => builtin_gate(&call.name, values, qubits, self.dialect),
+            None => parameterised_builtin_gate(&call.name, params, qubits),
+        };
+        if let Some(elements) = builtin {
+            return Ok(elements);
+        }
+
+        // User-defined gate: bind the arguments and expand each call in the body
+        let definition: &GateDefinition = self
+            .gates
+            .get(&call.name)
+            .ok_or_else(|| error(format!("gate '{}' does not support input parameters", call.name)))?;
+        let scope: HashMap<String, ParameterExpression> = definition
+            .params
+            .iter()
+            .cloned()
+            .zip(params.iter().cloned())
+            .collect();
+        let qubit_map: HashMap<&str, usize> = definition
+            .qubits
+            .iter()
+            .map(String::as_str)
+            .zip(qubits.iter().copied())
+            .collect();
+
+        let mut elements: Vec<Element> = Vec::new();
+        for inner in &definition.body {
+            let inner_params: Vec<ParameterExpression> = inner
+                .params
+                .iter()
+                .map(|param| self.evaluate_argument(param, &scope))
+                .collect::<Result<Vec<ParameterExpression>, CompilerError>>()?;
+            let inner_modifiers: Vec<ResolvedModifier> = self.resolve_modifiers(inner, &scope)?;
+            let inner_qubits: Vec<usize> = inner
+                .operands
+                .iter()
+                .map(|operand| qubit_map[operand.name.as_str()])
+                .collect();
+            elements.extend(self.expand(inner, &inner_params, &inner_modifiers, &inner_qubits)?);
+        }
+        Ok(elements)
+    }
+
+    // -- EXPRESSIONS --
+
+    fn parse_expr(&mut self) -> Result<Expr, CompilerError> {
+        let mut lhs: Expr = self.parse_term()?;
+        loop {
+            let op: BinaryOp = match self.peek().kind {
+                TokenKind::Plus => BinaryOp::Add,
+                TokenKind::Minus => BinaryOp::Sub,
+                _ => return Ok(lhs),
+            };
+            self.next_token();
+            let rhs: Expr = self.parse_term()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<Expr, CompilerError> {
+        let mut lhs: Expr = self.parse_unary()?;
+        loop {
+            let op: BinaryOp = match self.peek().kind {
+                TokenKind::Star => BinaryOp::Mul,
+                TokenKind::Slash => BinaryOp::Div,
+                _ => return Ok(lhs),
+            };
+            self.next_token();
+            let rhs: Expr = self.parse_unary()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, CompilerError> {
+        if self.consume(&TokenKind::Minus) {
+            return Ok(Expr::Negate(Box::new(self.parse_unary()?)));
+        }
+        if self.consume(&TokenKind::Plus) {
+            return self.parse_unary();
+        }
+        self.parse_power()
+    }
+
+    fn parse_power(&mut self) -> Result<Expr, CompilerError> {
+        let base: Expr = self.parse_primary()?;
+        let power_operator: TokenKind = match self.dialect {
+            Dialect::Qasm2 => TokenKind::Caret,
+            Dialect::Qasm3 => TokenKind::DoubleStar,
+        };
+        if self.consume(&power_operator) {
+            // Exponentiation is right-associative
+            let exponent: Expr = self.parse_unary()?;
+            return Ok(Expr::Binary(BinaryOp::Pow, Box::new(base), Box::new(exponent)));
+        }
+        Ok(base)
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, CompilerError> {
+        let token: Token = self.next_token();
+        match token.kind {
+            TokenKind::Integer(value) => Ok(Expr::Number(value as f64)),
+            TokenKind::Real(value) => Ok(Expr::Number(value)),
+            TokenKind::LParen => {
+                let expr: Expr = self.parse_expr()?;
+                self.expect(TokenKind::RParen)?;
+                Ok(expr)
+            }
+            TokenKind::Identifier(name) => {
+                if self.consume(&TokenKind::LParen) {
+                    let mut args: Vec<Expr> = Vec::new();
+                    if !self.check(&TokenKind::RParen) {
+                        loop {
+                            args.push(self.parse_expr()?);
+                            if !self.consume(&TokenKind::Comma) {
+                                break;
+                            }
+                        }
+                    }
+                    self.expect(TokenKind::RParen)?;
+                    Ok(Expr::Call(name, args, token.line, token.column))
+                } else {
+                    Ok(Expr::Identifier(name, token.line, token.column))
+                }
+            }
+            other => Err(CompilerError::ParseError(
+                token.line,
+                token.column,
+                format!("expected expression, found {}", other),
+            )),
+        }
+    }
+
+    /// Evaluates a gate argument, which must be finite and may depend linearly on the input parameters
+    fn evaluate_argument(
+        &self,
+        argument: &Argument,
+        scope: &HashMap<String, ParameterExpression>,
+    ) -> Result<ParameterExpression, CompilerError> {
+        let value: ParameterExpression = self.evaluate_expression(&argument.expr, scope)?;
+        if value.is_finite() {
+            Ok(value)
+        } else {
+            Err(CompilerError::ParseError(
+                argument.line,
+                argument.column,
+                format!("gate argument evaluates to {}", value),
+            ))
+        }
+    }
+
+    /// Evaluates a constant expression, looking identifiers up in `scope`, then in the declared constants
+    fn evaluate(&self, expr: &Expr, scope: &HashMap<String, ParameterExpression>) -> Result<f64, CompilerError> {
+        self.evaluate_expression(expr, scope)?.value().ok_or_else(|| {
+            let (line, column) = self.parameter_position(expr, scope);
+            CompilerError::ParseError(line, column, "expected a constant expression".to_string())
+        })
+    }
+
+    /// Evaluates an expression that may depend linearly on the input parameters, looking identifiers
+    /// up in `scope`, then in the declared constants and parameters
+    fn evaluate_expression(
+        &self,
+        expr: &Expr,
+        scope: &HashMap<String, ParameterExpression>,
+    ) -> Result<ParameterExpression, CompilerError> {
+        let not_linear = |expr: &Expr| {
+            let (line, column) = self.parameter_position(expr, scope);
+            CompilerError::ParseError(
+                line,
+                column,
+                "expression is not linear in the input parameters".to_string(),
+            )
+        };
+
+        match expr {
+            Expr::Number(value) => Ok(ParameterExpression::constant(*value)),
+            Expr::Identifier(name, line, column) => {
+                if let Some(value) = scope.get(name) {
+                    return Ok(value.clone());
+                }
+                if let Some(value) = self.constants.get(name).copied().or_else(|| builtin_constant(name)) {
+                    return Ok(ParameterExpression::constant(value));
+                }
+                if self.parameters.contains(name) {
+                    return Ok(ParameterExpression::from(Parameter::new(name)));
+                }
+                Err(CompilerError::ParseError(*line, *column, format!("unknown identifier '{}'", name)))
+            }
+            Expr::Negate(inner) => Ok(-self.evaluate_expression(inner, scope)?),
+            Expr::Binary(op, lhs_expr, rhs_expr) => {
+                let lhs: ParameterExpression = self.evaluate_expression(lhs_expr, scope)?;
+                let rhs: ParameterExpression = self.evaluate_expression(rhs_expr, scope)?;
+                match (op, lhs.value(), rhs.value()) {
+                    (_, Some(lhs), Some(rhs)) => Ok(ParameterExpression::constant(match op {
+                        BinaryOp::Add => lhs + rhs,
+                        BinaryOp::Sub => lhs - rhs,
+                        BinaryOp::Mul => lhs * rhs,
+                        BinaryOp::Div => lhs / rhs,
+                        BinaryOp::Pow => lhs.powf(rhs),
+                    })),
+                    (BinaryOp::Add, _, _) => Ok(lhs + rhs),
+                    (BinaryOp::Sub, _, _) => Ok(lhs - rhs),
+                    (BinaryOp::Mul, Some(factor), None) => Ok(rhs * factor),
+                    (BinaryOp::Mul | BinaryOp::Div, None, Some(factor)) => Ok(match op {
+                        BinaryOp::Div => lhs * (1.0 / factor),
+                        _ => lhs * factor,
+                    }),
+                    (BinaryOp::Pow, None, _) => Err(not_linear(lhs_expr)),
+                    _ => Err(not_linear(rhs_expr)),
+                }
+            }
+            Expr::Call(name, args, line, column) => {
+                let error = |message: String| CompilerError::ParseError(*line, *column, message);
+                let function: fn(f64) -> f64 = match name.as_str() {
+                    "sin" => f64::sin,
+                    "cos" => f64::cos,
+                    "tan" => f64::tan,
+                    "arcsin" => f64::asin,
+                    "arccos" => f64::acos,
+                    "arctan" => f64::atan,
+                    "exp" => f64::exp,
+                    "ln" => f64::ln,
+                    "sqrt" => f64::sqrt,
+                    _ => return Err(error(format!("unknown function '{}'", name))),
+                };
+                match args.as_slice() {
+                    [arg] => match self.evaluate_expression(arg, scope)?.value() {
+                        Some(value) => Ok(ParameterExpression::constant(function(value))),
+                        None => Err(not_linear(arg)),
+                    },
+                    _ => Err(error(format!(
+                        "function '{}' expects 1 argument, got {}",
+                        name,
+                        args.len()
+                    ))),
+                }
+            }
+        }
+    }
+
+    /// Returns the position of the first identifier in an expression whose value depends on the
+    /// input parameters
+    fn parameter_position(&self, expr: &Expr, scope: &HashMap<String, ParameterExpression>) -> (usize, usize) {
+        let mut pending: Vec<&Expr> = vec![expr];
+        while let Some(expr) = pending.pop() {
+            match expr {
+                Expr::Number(_) => {}
+                Expr::Identifier(name, line, column) => {
+                    let is_symbolic: bool = match scope.get(name) {
+                        Some(value) => value.value().is_none(),
+                        None => {
+                            !self.constants.contains_key(name)
+                                && !is_builtin_constant(name)
+                                && self.parameters.contains(name)
+                        }
+                    };
+                    if is_symbolic {
+                        return (*line, *column);
+                    }
+                }
+                Expr::Negate(inner) => pending.push(inner),
+                Expr::Binary(_, lhs, rhs) => {
+                    pending.push(rhs);
+                    pending.push(lhs);
+                }
+                Expr::Call(_, args, _, _) => pending.extend(args.iter().rev()),
+            }
+        }
+        unreachable!("expressions that are not constant refer to a parameter")
+    }
+}
+
+fn error_at(token: &Token, message: String) -> CompilerError {
+    CompilerError::ParseError(token.line, token.column, message)
+}
+
+/// Converts a real value to a non-negative integer index, if it is one
+fn to_index(value: f64) -> Option<usize> {
+    if value >= 0.0 && value.fract() == 0.0 && value < usize::MAX as f64 {
+        Some(value as usize)
+    } else {
+        None
+    }
+}
+
+/// Keywords and types of OpenQASM 3.0, which cannot be declared as identifiers
+const QASM3_KEYWORDS: &[&str] = &[
+    "OPENQASM", "include", "qubit", "bit", "qreg", "creg", "const", "gate", "measure", "barrier", "reset", "if",
+    "else", "for", "while", "in", "def", "return", "break", "continue", "end", "opaque", "input", "output", "box",
+    "let", "defcal", "cal", "defcalgrammar", "extern", "delay", "ctrl", "negctrl", "inv", "pow", "int", "uint",
+    "float", "angle", "bool", "complex", "duration", "stretch", "array", "true", "false",
+];
+
+/// Returns whether a name can be declared as an identifier in an OpenQASM 3.0 program
+///
+/// The name must be a single identifier token that is not a keyword, a built-in constant or a
+/// built-in or standard library gate.
+pub(crate) fn is_valid_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+        && !QASM3_KEYWORDS.contains(&name)
+        && !is_builtin_constant(name)
+        && Dialect::Qasm3.gate_signature(name).is_none()
+}
+
+fn is_builtin_constant(name: &str) -> bool {
+    builtin_constant(name).is_some()
+}
+
+fn builtin_constant(name: &str) -> Option<f64> {
+    match name {
+        "pi" | "π" => Some(PI),
+        "tau" | "τ" => Some(TAU),
+        "euler" | "ℇ" => Some(E),
+        _ => None,
+    }
+}
+
+/// Folds a global phase into a single-qubit gate with the same controls, as a unitary instruction
+fn with_global_phase(instruction: &InstructionIR, angle: f64, phase_controls: &[usize]) -> Option<Element> {
+    let matrix: [[Complex<f64>; 2]; 2] = instruction_matrix(instruction)?;
+    let targets: Vec<usize> = instruction.targets();
+    if instruction.controls() != phase_controls || targets.len() != 1 {
+        return None;
+    }
+    let phase: Complex<f64> = Complex::from_polar(1.0, angle);
+    Some(Element::Instruction(InstructionIR::Unitary(
+        matrix.map(|row| row.map(|element| element * phase)),
+        targets[0],
+        phase_controls.to_vec(),
+    )))
+}
+
+/// Expands a rotation or phase gate of the standard library whose angle depends on the input parameters
+///
+/// Returns `None` for other gates, which cannot take symbolic arguments.
+fn parameterised_builtin_gate(name: &str, params: &[ParameterExpression], qubits: &[usize]) -> Option<Vec<Element>> {
+    let (kind, target, controls) = match name {
+        "p" | "phase" | "u1" => (ParameterisedKind::PhaseShift, qubits[0], vec![]),
+        "rx" => (ParameterisedKind::RotateX, qubits[0], vec![]),
+        "ry" => (ParameterisedKind::RotateY, qubits[0], vec![]),
+        "rz" => (ParameterisedKind::RotateZ, qubits[0], vec![]),
+        "cp" | "cphase" => (ParameterisedKind::PhaseShift, qubits[1], vec![qubits[0]]),
+        "crx" => (ParameterisedKind::RotateX, qubits[1], vec![qubits[0]]),
+        "cry" => (ParameterisedKind::RotateY, qubits[1], vec![qubits[0]]),
+        "crz" => (ParameterisedKind::RotateZ, qubits[1], vec![qubits[0]]),
+        _ => return None,
+    };
+    Some(vec![Element::Instruction(InstructionIR::Parameterised(
+        kind,
+        params[0].clone(),
+        target,
+        controls,
+    ))])
+}
+
+/// Returns the number of parameters and qubits of a built-in or standard library gate of either
+/// dialect; see `Dialect::gate_signature` for the gates available in each
+fn builtin_gate_signature(name: &str) -> Option<(usize, usize)> {
+    let signature: (usize, usize) = match name {
+        "gphase" => (1, 0),
+        "x" | "y" | "z" | "h" | "s" | "sdg" | "t" | "tdg" | "sx" | "id" => (0, 1),
+        "p" | "phase" | "u1" | "rx" | "ry" | "rz" => (1, 1),
+        "u2" => (2, 1),
+        "U" | "u3" => (3, 1),
+        "cx" | "CX" | "cy" | "cz" | "ch" | "swap" => (0, 2),
+        "cp" | "cphase" | "crx" | "cry" | "crz" => (1, 2),
+        "cu1" => (1, 2),
+        "cu3" => (3, 2),
+        "cu" => (4, 2),
+        "ccx" | "cswap" => (0, 3),
+        _ => return None,
+    };
+    Some(signature)
+}
+
+/// Expands a built-in or standard library gate, whose signature has already been checked
+///
+/// In OpenQASM 3, `stdgates.inc` defines `u3` and `u2` with a global phase relative to `U`, which
+/// becomes a relative phase under `ctrl @`; the `qelib1.inc` gates of OpenQASM 2 have none.
+fn builtin_gate(name: &str, params: &[f64], qubits: &[usize], dialect: Dialect) -> Option<Vec<Element>> {
+    let instruction: InstructionIR = match name {
+        "gphase" => return Some(vec![Element::GlobalPhase(params[0], vec![])]),
+        "u3" if dialect == Dialect::Qasm3 => {
+            return Some(vec![
+                Element::GlobalPhase(-(params[1] + params[2]) / 2.0, vec![]),
+                Element::Instruction(InstructionIR::Unitary(
+                    u_matrix(params[0], params[1], params[2], 0.0),
+                    qubits[0],
+                    vec![],
+                )),
+            ]);
+        }
+        "u2" if dialect == Dialect::Qasm3 => {
+            return Some(vec![
+                Element::GlobalPhase(-(params[0] + params[1] + PI) / 2.0, vec![]),
+                Element::Instruction(InstructionIR::Unitary(
+                    u_matrix(PI / 2.0, params[0], params[1], 0.0),
+                    qubits[0],
+                    vec![],
+                )),
+            ]);
+        }
+        "x" => InstructionIR::PauliX(qubits[0], vec![]),
+        "y" => InstructionIR::PauliY(qubits[0], vec![]),
+        "z" => InstructionIR::PauliZ(qubits[0], vec![]),
+        "h" => InstructionIR::Hadamard(qubits[0], vec![]),
+        "s" => InstructionIR::S(qubits[0], vec![]),
+        "sdg" => InstructionIR::Sdg(qubits[0], vec![]),
+        "t" => InstructionIR::T(qubits[0], vec![]),
+        "tdg" => InstructionIR::Tdg(qubits[0], vec![]),
+        "id" => InstructionIR::Id(qubits[0], vec![]),
+        "sx" => InstructionIR::Unitary(sx_matrix(), qubits[0], vec![]),
+        "p" | "phase" | "u1" => InstructionIR::Phase(params[0], qubits[0], vec![]),
+        "rx" => InstructionIR::Rx(params[0], qubits[0], vec![]),
+        "ry" => InstructionIR::Ry(params[0], qubits[0], vec![]),
+        "rz" => InstructionIR::Rz(params[0], qubits[0], vec![]),
+        "u2" => InstructionIR::Unitary(
+            u_matrix(PI / 2.0, params[0], params[1], 0.0),
+            qubits[0],
+            vec![],
+        ),
+        "U" | "u3" => InstructionIR::Unitary(
+            u_matrix(params[0], params[1], params[2], 0.0),
+            qubits[0],
+            vec![],
+        ),
+        "cx" | "CX" => InstructionIR::PauliX(qubits[1], vec![qubits[0]]),
+        "cy" => InstructionIR::PauliY(qubits[1], vec![qubits[0]]),
+        "cz" => InstructionIR::PauliZ(qubits[1], vec![qubits[0]]),
+        "ch" => InstructionIR::Hadamard(qubits[1], vec![qubits[0]]),
+        "swap" => InstructionIR::Swap(qubits[0], qubits[1], vec![]),
+        "cp" | "cphase" | "cu1" => InstructionIR::Phase(params[0], qubits[1], vec![qubits[0]]),
+        "crx" => InstructionIR::Rx(params[0], qubits[1], vec![qubits[0]]),
+        "cry" => InstructionIR::Ry(params[0], qubits[1], vec![qubits[0]]),
+        "crz" => InstructionIR::Rz(params[0], qubits[1], vec![qubits[0]]),
+        "cu3" => InstructionIR::Unitary(
+            u_matrix(params[0], params[1], params[2], 0.0),
+            qubits[1],
+            vec![qubits[0]],
+        ),
+        "cu" => InstructionIR::Unitary(
+            u_matrix(params[0], params[1], params[2], params[3]),
+            qubits[1],
+            vec![qubits[0]],
+        ),
+        "ccx" => InstructionIR::PauliX(qubits[2], vec![qubits[0], qubits[1]]),
+        "cswap" => InstructionIR::Swap(qubits[1], qubits[2], vec![qubits[0]]),
+        _ => return None,
+    };
+    Some(vec![Element::Instruction(instruction)])
+}
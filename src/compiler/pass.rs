@@ -0,0 +1,134 @@
+use crate::{compiler::ir::CircuitIR, errors::CompilerError};
+use dyn_clone::DynClone;
+use std::time::{Duration, Instant};
+
+/// Trait for transformations over the instruction list of a `CircuitIR`
+///
+/// Passes are run in order by a `PassManager`. A pass may remove, insert, reorder or replace
+/// instructions, but must leave the circuit equivalent to the original, up to a global phase.
+pub trait Pass: DynClone + Send + Sync + 'static {
+    /// Returns the name of the pass, used to label its statistics
+    fn name(&self) -> &str;
+
+    /// Runs the pass, rewriting the circuit in place
+    ///
+    /// # Arguments
+    ///
+    /// * `circuit` - The circuit to rewrite.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), CompilerError>` - An error if the circuit cannot be rewritten by this pass.
+    fn run(&self, circuit: &mut CircuitIR) -> Result<(), CompilerError>;
+}
+
+dyn_clone::clone_trait_object!(Pass);
+
+/// Statistics collected while running a single pass
+///
+/// # Fields
+///
+/// * `name` - The name of the pass.
+/// * `instructions_before` / `instructions_after` - The number of instructions before and after the pass.
+/// * `depth_before` / `depth_after` - The depth of the circuit before and after the pass.
+/// * `duration` - The time taken by the pass.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PassStatistics {
+    /// The name of the pass.
+    pub name: String,
+    /// The number of instructions before the pass.
+    pub instructions_before: usize,
+    /// The number of instructions after the pass.
+    pub instructions_after: usize,
+    /// The depth of the circuit before the pass.
+    pub depth_before: usize,
+    /// The depth of the circuit after the pass.
+    pub depth_after: usize,
+    /// The time taken by the pass.
+    pub duration: Duration,
+}
+
+impl PassStatistics {
+    /// Returns the number of instructions removed by the pass
+    ///
+    /// The result is negative if the pass added instructions.
+    pub fn instructions_removed(&self) -> isize {
+        self.instructions_before as isize - self.instructions_after as isize
+    }
+
+    /// Returns the reduction in depth achieved by the pass
+    ///
+    /// The result is negative if the pass increased the depth.
+    pub fn depth_reduction(&self) -> isize {
+        self.depth_before as isize - self.depth_after as isize
+    }
+}
+
+/// Runs an ordered list of passes over a `CircuitIR`
+///
+/// Passes can be built-in or user-supplied implementations of `Pass`. Running the manager returns
+/// one `PassStatistics` entry per pass.
+#[derive(Clone, Default)]
+pub struct PassManager {
+    /// The passes to run, in order
+    passes: Vec<Box<dyn Pass>>,
+}
+
+impl PassManager {
+    /// Creates a new pass manager without any passes
+    pub fn new() -> Self {
+        PassManager { passes: Vec::new() }
+    }
+
+    /// Appends a pass to the end of the pipeline
+    ///
+    /// # Arguments
+    ///
+    /// * `pass` - The pass to append.
+    ///
+    /// # Returns
+    ///
+    /// * `&mut Self` - A mutable reference to the pass manager.
+    pub fn add_pass(&mut self, pass: Box<dyn Pass>) -> &mut Self {
+        self.passes.push(pass);
+        self
+    }
+
+    /// Returns the names of the passes in the pipeline, in order
+    pub fn pass_names(&self) -> Vec<&str> {
+        self.passes.iter().map(|pass| pass.name()).collect()
+    }
+
+    /// Runs all passes in order over the circuit
+    ///
+    /// # Arguments
+    ///
+    /// * `circuit` - The circuit to rewrite in place.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<PassStatistics>, CompilerError>` - The statistics of each pass, in order, or the
+    ///   first error returned by a pass. The circuit is left as rewritten by the preceding passes.
+    pub fn run(&self, circuit: &mut CircuitIR) -> Result<Vec<PassStatistics>, CompilerError> {
+        let mut statistics: Vec<PassStatistics> = Vec::with_capacity(self.passes.len());
+
+        for pass in &self.passes {
+            let instructions_before: usize = circuit.instructions.len();
+            let depth_before: usize = circuit.depth();
+            let start: Instant = Instant::now();
+
+            pass.run(circuit)?;
+
+            statistics.push(PassStatistics {
+                name: pass.name().to_string(),
+                instructions_before,
+                instructions_after: circuit.instructions.len(),
+                depth_before,
+                depth_after: circuit.depth(),
+                duration: start.elapsed(),
+            });
+        }
+
+        Ok(statistics)
+    }
+}
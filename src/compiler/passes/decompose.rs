@@ -0,0 +1,513 @@
+use crate::{
+    compiler::{
+        ir::{CircuitIR, InstructionIR},
+        linalg::{adjoint, instruction_matrix, matrix_power, multiply, sx_matrix, u_matrix},
+        pass::Pass,
+        passes::PASS_TOLERANCE,
+        qasm::unitary_to_u_angles,
+    },
+    errors::CompilerError,
+};
+use num_complex::Complex;
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+/// A native gate set that `DecomposeControlled` lowers circuits into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BasisGates {
+    /// CNOT plus any uncontrolled single-qubit instruction, which is left unchanged
+    #[default]
+    CxSingleQubit,
+    /// CNOT, `Rz`, `SX` and `X`
+    ///
+    /// `SX` is represented as a `Unitary` instruction holding the matrix of the `sx` gate, which is
+    /// what the OpenQASM parser produces for `sx` and what the OpenQASM 3.0 emitter writes back as `sx`.
+    CxRzSxX,
+    /// CZ plus arbitrary single-qubit `U3` gates, represented as `Unitary` instructions
+    CzU3,
+}
+
+/// Decomposes controlled and multi-qubit instructions into a native gate set
+///
+/// Controlled X, Y, Z, H, phase, rotation, unitary and SWAP instructions with any number of controls
+/// are rewritten into CNOTs and uncontrolled single-qubit instructions using the constructions of
+/// Barenco et al., "Elementary gates for quantum computation" (1995):
+///
+/// * Singly-controlled gates use the `A·X·B·X·C` decomposition (Lemma 5.1).
+/// * Multi-controlled X gates with enough ancilla qubits use a chain of Toffolis (Lemma 7.2), and
+///   with a single ancilla are split into two halves that borrow each other's qubits (Lemma 7.3).
+/// * All other multi-controlled gates are reduced one control at a time using the square root of
+///   the target gate (Lemma 7.5), borrowing the target qubit as an ancilla for the inner
+///   multi-controlled X gates. This needs no ancilla qubits and uses O(k²) gates for k controls.
+///
+/// Ancilla qubits are borrowed: they may be in any state, and are returned to that state.
+///
+/// The single-qubit instructions are then translated into the chosen `BasisGates`. For `CxRzSxX` and
+/// `CzU3`, runs of single-qubit instructions on the same qubit are fused before being synthesised,
+/// and the result is only equal to the original circuit up to a global phase. Measurements and raw
+/// QASM strings are left unchanged, and classically controlled instructions are decomposed with their
+/// condition kept on every resulting instruction. Instructions with unbound parameters are only left
+/// unchanged in the `CxSingleQubit` basis if they are uncontrolled, and are rejected otherwise.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DecomposeControlled {
+    basis: BasisGates,
+    ancillas: Vec<usize>,
+}
+
+impl DecomposeControlled {
+    /// Creates a decomposition pass into the given basis that does not use ancilla qubits
+    ///
+    /// # Arguments
+    ///
+    /// * `basis` - The gate set to decompose into.
+    pub fn new(basis: BasisGates) -> Self {
+        DecomposeControlled {
+            basis,
+            ancillas: Vec::new(),
+        }
+    }
+
+    /// Creates a decomposition pass into the given basis that may borrow the given ancilla qubits
+    ///
+    /// An ancilla is only borrowed by instructions that do not act on it, so qubits used elsewhere in
+    /// the circuit can also be listed.
+    ///
+    /// # Arguments
+    ///
+    /// * `basis` - The gate set to decompose into.
+    /// * `ancillas` - The qubits that may be borrowed as ancillas.
+    pub fn with_ancillas(basis: BasisGates, ancillas: Vec<usize>) -> Self {
+        DecomposeControlled { basis, ancillas }
+    }
+
+    /// Returns the gate set the pass decomposes into
+    pub fn basis(&self) -> BasisGates {
+        self.basis
+    }
+
+    /// Returns the qubits the pass may borrow as ancillas
+    pub fn ancillas(&self) -> &[usize] {
+        &self.ancillas
+    }
+}
+
+impl Pass for DecomposeControlled {
+    fn name(&self) -> &str {
+        "decompose-controlled"
+    }
+
+    fn run(&self, circuit: &mut CircuitIR) -> Result<(), CompilerError> {
+        if let Some(&ancilla) = self.ancillas.iter().find(|&&qubit| qubit >= circuit.num_qubits) {
+            return Err(CompilerError::InvalidOperands(
+                format!(
+                    "ancilla qubit {} out of range for {} qubits",
+                    ancilla, circuit.num_qubits
+                ),
+                self.name().to_string(),
+            ));
+        }
+
+        if let Some(instruction) = circuit
+            .instructions
+            .iter()
+            .find(|instruction| has_unlowerable_angle(instruction, self.basis))
+        {
+            return Err(CompilerError::UnsupportedOperator(format!(
+                "{:?} has unbound parameters and cannot be decomposed into {:?}",
+                instruction, self.basis
+            )));
+        }
+
+        let mut lowered: Vec<InstructionIR> = Vec::with_capacity(circuit.instructions.len());
+        for instruction in &circuit.instructions {
+            decompose(instruction, &self.ancillas, &mut lowered);
+        }
+
+        circuit.instructions = match self.basis {
+            BasisGates::CxSingleQubit => lowered,
+            BasisGates::CxRzSxX | BasisGates::CzU3 => {
+                translate(&lowered, self.basis, circuit.num_qubits)
+            }
+        };
+        Ok(())
+    }
+}
+
+/// Returns whether an instruction has an unbound angle that cannot be lowered into the basis
+///
+/// Symbolic angles cannot be multiplied into matrices, so such instructions can only be kept as they
+/// are, which is only valid if they are uncontrolled and the basis is `CxSingleQubit`.
+fn has_unlowerable_angle(instruction: &InstructionIR, basis: BasisGates) -> bool {
+    match instruction {
+        InstructionIR::Parameterised(_, _, _, controls) => {
+            !controls.is_empty() || basis != BasisGates::CxSingleQubit
+        }
+        InstructionIR::Conditional(_, inner) => has_unlowerable_angle(inner, basis),
+        _ => false,
+    }
+}
+
+/// Lowers an instruction into CNOTs and uncontrolled single-qubit instructions
+fn decompose(instruction: &InstructionIR, ancillas: &[usize], out: &mut Vec<InstructionIR>) {
+    match instruction {
+        InstructionIR::Swap(a, b, controls) => {
+            // SWAP = CX(b → a) · CX(a → b) · CX(b → a), where only the middle CNOT needs the controls
+            out.push(cx(*a, *b));
+            let mut swap_controls: Vec<usize> = controls.clone();
+            swap_controls.push(*a);
+            multi_controlled_x(&swap_controls, *b, ancillas, out);
+            out.push(cx(*a, *b));
+        }
+        InstructionIR::Measurement(_, _)
+        | InstructionIR::MeasurementInto(_, _, _)
+        | InstructionIR::RawQASM(_) => out.push(instruction.clone()),
+        InstructionIR::Conditional(condition, inner) => {
+            let mut body: Vec<InstructionIR> = Vec::new();
+            decompose(inner, ancillas, &mut body);
+            out.extend(body.into_iter().map(|instruction| {
+                InstructionIR::Conditional(condition.clone(), Box::new(instruction))
+            }));
+        }
+        _ if instruction.controls().is_empty() => out.push(instruction.clone()),
+        // A controlled identity is still the identity
+        InstructionIR::Id(_, _) => {}
+        InstructionIR::PauliX(target, controls) => {
+            multi_controlled_x(controls, *target, ancillas, out)
+        }
+        InstructionIR::PauliY(target, controls) => {
+            // Y = S · X · Sdg
+            out.push(InstructionIR::Sdg(*target, vec![]));
+            multi_controlled_x(controls, *target, ancillas, out);
+            out.push(InstructionIR::S(*target, vec![]));
+        }
+        InstructionIR::PauliZ(target, controls) => {
+            // Z = H · X · H
+            out.push(InstructionIR::Hadamard(*target, vec![]));
+            multi_controlled_x(controls, *target, ancillas, out);
+            out.push(InstructionIR::Hadamard(*target, vec![]));
+        }
+        _ => {
+            let target: usize = instruction.targets()[0];
+            let matrix: [[Complex<f64>; 2]; 2] =
+                instruction_matrix(instruction).expect("single-qubit instructions have a matrix");
+            multi_controlled_unitary(&matrix, instruction.controls(), target, ancillas, out);
+        }
+    }
+}
+
+/// Returns a CNOT from `control` onto `target`
+fn cx(target: usize, control: usize) -> InstructionIR {
+    InstructionIR::PauliX(target, vec![control])
+}
+
+/// Returns the ancillas that an instruction on the given qubits can borrow
+fn free_ancillas(ancillas: &[usize], controls: &[usize], target: usize) -> Vec<usize> {
+    ancillas
+        .iter()
+        .copied()
+        .filter(|qubit| *qubit != target && !controls.contains(qubit))
+        .collect()
+}
+
+/// Lowers an X gate with any number of controls
+fn multi_controlled_x(
+    controls: &[usize],
+    target: usize,
+    ancillas: &[usize],
+    out: &mut Vec<InstructionIR>,
+) {
+    let num_controls: usize = controls.len();
+    let free: Vec<usize> = free_ancillas(ancillas, controls, target);
+    match num_controls {
+        0 => out.push(InstructionIR::PauliX(target, vec![])),
+        1 => out.push(cx(target, controls[0])),
+        2 => toffoli(controls[0], controls[1], target, out),
+        _ if free.len() >= num_controls - 2 => {
+            toffoli_chain(controls, target, &free[..num_controls - 2], out)
+        }
+        _ if !free.is_empty() => {
+            // Lemma 7.3: toggle the target on (second half AND ancilla), then toggle the ancilla on the
+            // first half, and repeat both to restore the ancilla. Each half borrows the other's qubits.
+            let ancilla: usize = free[0];
+            let (first, second) = controls.split_at(num_controls.div_ceil(2));
+            let mut second_with_ancilla: Vec<usize> = second.to_vec();
+            second_with_ancilla.push(ancilla);
+            let mut first_ancillas: Vec<usize> = second.to_vec();
+            first_ancillas.push(target);
+            first_ancillas.extend(&free[1..]);
+            let mut second_ancillas: Vec<usize> = first.to_vec();
+            second_ancillas.extend(&free[1..]);
+
+            for _ in 0..2 {
+                multi_controlled_x(&second_with_ancilla, target, &second_ancillas, out);
+                multi_controlled_x(first, ancilla, &first_ancillas, out);
+            }
+        }
+        _ => {
+            let x: [[Complex<f64>; 2]; 2] = instruction_matrix(&InstructionIR::PauliX(target, vec![]))
+                .expect("Pauli-X has a matrix");
+            multi_controlled_unitary(&x, controls, target, ancillas, out);
+        }
+    }
+}
+
+/// Lowers a Toffoli gate into 6 CNOTs and T, Tdg and H gates
+fn toffoli(control1: usize, control2: usize, target: usize, out: &mut Vec<InstructionIR>) {
+    out.extend([
+        InstructionIR::Hadamard(target, vec![]),
+        cx(target, control2),
+        InstructionIR::Tdg(target, vec![]),
+        cx(target, control1),
+        InstructionIR::T(target, vec![]),
+        cx(target, control2),
+        InstructionIR::Tdg(target, vec![]),
+        cx(target, control1),
+        InstructionIR::T(control2, vec![]),
+        InstructionIR::T(target, vec![]),
+        InstructionIR::Hadamard(target, vec![]),
+        cx(control2, control1),
+        InstructionIR::T(control1, vec![]),
+        InstructionIR::Tdg(control2, vec![]),
+        cx(control2, control1),
+    ]);
+}
+
+/// Lowers an X gate with k ≥ 3 controls into 4(k - 2) Toffolis using k - 2 borrowed ancillas
+fn toffoli_chain(
+    controls: &[usize],
+    target: usize,
+    ancillas: &[usize],
+    out: &mut Vec<InstructionIR>,
+) {
+    let num_controls: usize = controls.len();
+    // Toffolis from the target down the chain: each ancilla accumulates one more control
+    let mut chain: Vec<(usize, usize, usize)> =
+        vec![(controls[num_controls - 1], ancillas[num_controls - 3], target)];
+    for j in (2..num_controls - 1).rev() {
+        chain.push((controls[j], ancillas[j - 2], ancillas[j - 1]));
+    }
+    let bottom: (usize, usize, usize) = (controls[0], controls[1], ancillas[0]);
+
+    for start in 0..2 {
+        let sequence = chain[start..]
+            .iter()
+            .chain(std::iter::once(&bottom))
+            .chain(chain[start..].iter().rev());
+        for &(control1, control2, toffoli_target) in sequence {
+            toffoli(control1, control2, toffoli_target, out);
+        }
+    }
+}
+
+/// Lowers a single-qubit unitary with any number of controls
+fn multi_controlled_unitary(
+    matrix: &[[Complex<f64>; 2]; 2],
+    controls: &[usize],
+    target: usize,
+    ancillas: &[usize],
+    out: &mut Vec<InstructionIR>,
+) {
+    match controls {
+        [] => out.push(InstructionIR::Unitary(*matrix, target, vec![])),
+        [control] => controlled_unitary(matrix, *control, target, out),
+        [rest @ .., last] => {
+            // Lemma 7.5: C^k(U) = C(V) · C^{k-1}(X) · C(V†) · C^{k-1}(X) · C^{k-1}(V) with V² = U,
+            // where the target is free to be borrowed by the inner multi-controlled X gates
+            let root: [[Complex<f64>; 2]; 2] = matrix_power(matrix, 0.5);
+            let mut inner_ancillas: Vec<usize> = ancillas.to_vec();
+            inner_ancillas.push(target);
+
+            controlled_unitary(&root, *last, target, out);
+            multi_controlled_x(rest, *last, &inner_ancillas, out);
+            controlled_unitary(&adjoint(&root), *last, target, out);
+            multi_controlled_x(rest, *last, &inner_ancillas, out);
+
+            let mut root_ancillas: Vec<usize> = ancillas.to_vec();
+            root_ancillas.push(*last);
+            multi_controlled_unitary(&root, rest, target, &root_ancillas, out);
+        }
+    }
+}
+
+/// Lowers a singly-controlled unitary into two CNOTs and single-qubit rotations
+///
+/// With `U = e^{iα} Rz(β) Ry(γ) Rz(δ)`, the controlled gate is `P(α)` on the control followed by
+/// `A·X·B·X·C` on the target, where `A = Rz(β) Ry(γ/2)`, `B = Ry(-γ/2) Rz(-(δ+β)/2)` and
+/// `C = Rz((δ-β)/2)`, so that `A·B·C = I`.
+fn controlled_unitary(
+    matrix: &[[Complex<f64>; 2]; 2],
+    control: usize,
+    target: usize,
+    out: &mut Vec<InstructionIR>,
+) {
+    let (gamma, beta, delta, phase) = unitary_to_u_angles(matrix);
+    // U(θ, φ, λ) = e^{i(φ+λ)/2} Rz(φ) Ry(θ) Rz(λ)
+    let alpha: f64 = phase + (beta + delta) / 2.0;
+
+    push_rotation(InstructionIR::Rz((delta - beta) / 2.0, target, vec![]), out);
+    out.push(cx(target, control));
+    push_rotation(InstructionIR::Rz(-(delta + beta) / 2.0, target, vec![]), out);
+    push_rotation(InstructionIR::Ry(-gamma / 2.0, target, vec![]), out);
+    out.push(cx(target, control));
+    push_rotation(InstructionIR::Ry(gamma / 2.0, target, vec![]), out);
+    push_rotation(InstructionIR::Rz(beta, target, vec![]), out);
+    push_rotation(InstructionIR::Phase(alpha, control, vec![]), out);
+}
+
+/// Pushes an uncontrolled rotation or phase, unless it is the identity up to a global phase
+fn push_rotation(instruction: InstructionIR, out: &mut Vec<InstructionIR>) {
+    let angle: f64 = match &instruction {
+        InstructionIR::Rx(angle, _, _)
+        | InstructionIR::Ry(angle, _, _)
+        | InstructionIR::Rz(angle, _, _)
+        | InstructionIR::Phase(angle, _, _) => *angle,
+        _ => unreachable!("only rotations and phases are pushed"),
+    };
+    if !is_zero_angle(angle) {
+        out.push(instruction);
+    }
+}
+
+/// Translates CNOTs and uncontrolled single-qubit instructions into the `CxRzSxX` or `CzU3` basis
+///
+/// Runs of single-qubit instructions on the same qubit are multiplied together, and each run is
+/// synthesised once it is interrupted by a two-qubit gate, a measurement or a raw QASM string.
+fn translate(
+    instructions: &[InstructionIR],
+    basis: BasisGates,
+    num_qubits: usize,
+) -> Vec<InstructionIR> {
+    let mut pending: Vec<Option<[[Complex<f64>; 2]; 2]>> = vec![None; num_qubits];
+    let mut out: Vec<InstructionIR> = Vec::with_capacity(instructions.len());
+    let hadamard: [[Complex<f64>; 2]; 2] =
+        instruction_matrix(&InstructionIR::Hadamard(0, vec![])).expect("Hadamard has a matrix");
+
+    let accumulate = |pending: &mut Vec<Option<[[Complex<f64>; 2]; 2]>>,
+                          qubit: usize,
+                          matrix: &[[Complex<f64>; 2]; 2]| {
+        pending[qubit] = Some(match &pending[qubit] {
+            Some(previous) => multiply(matrix, previous),
+            None => *matrix,
+        });
+    };
+
+    for instruction in instructions {
+        match instruction {
+            InstructionIR::RawQASM(_) | InstructionIR::Barrier(_) => {
+                for qubit in 0..num_qubits {
+                    flush(&mut pending, qubit, basis, &mut out);
+                }
+                out.push(instruction.clone());
+            }
+            InstructionIR::Conditional(condition, inner) => {
+                // The body is translated on its own, since it cannot be fused with its neighbours
+                for qubit in 0..num_qubits {
+                    flush(&mut pending, qubit, basis, &mut out);
+                }
+                out.extend(
+                    translate(std::slice::from_ref(inner.as_ref()), basis, num_qubits)
+                        .into_iter()
+                        .map(|instruction| {
+                            InstructionIR::Conditional(condition.clone(), Box::new(instruction))
+                        }),
+                );
+            }
+            InstructionIR::PauliX(target, controls) if controls.len() == 1 => {
+                let control: usize = controls[0];
+                if basis == BasisGates::CzU3 {
+                    // CX = (I ⊗ H) · CZ · (I ⊗ H), with the Hadamards fused into the neighbouring runs
+                    accumulate(&mut pending, *target, &hadamard);
+                    flush(&mut pending, control, basis, &mut out);
+                    flush(&mut pending, *target, basis, &mut out);
+                    out.push(InstructionIR::PauliZ(*target, vec![control]));
+                    accumulate(&mut pending, *target, &hadamard);
+                } else {
+                    flush(&mut pending, control, basis, &mut out);
+                    flush(&mut pending, *target, basis, &mut out);
+                    out.push(instruction.clone());
+                }
+            }
+            _ if instruction.controls().is_empty() && instruction.targets().len() == 1 => {
+                match instruction_matrix(instruction) {
+                    Some(matrix) => accumulate(&mut pending, instruction.targets()[0], &matrix),
+                    None => {
+                        // Measurements end the run on their qubit
+                        flush(&mut pending, instruction.targets()[0], basis, &mut out);
+                        out.push(instruction.clone());
+                    }
+                }
+            }
+            _ => unreachable!("instructions are lowered before being translated"),
+        }
+    }
+
+    for qubit in 0..num_qubits {
+        flush(&mut pending, qubit, basis, &mut out);
+    }
+    out
+}
+
+/// Synthesises the pending single-qubit run on a qubit, if there is one
+fn flush(
+    pending: &mut [Option<[[Complex<f64>; 2]; 2]>],
+    qubit: usize,
+    basis: BasisGates,
+    out: &mut Vec<InstructionIR>,
+) {
+    if let Some(matrix) = pending[qubit].take() {
+        synthesise(&matrix, qubit, basis, out);
+    }
+}
+
+/// Synthesises a single-qubit unitary in the given basis, up to a global phase
+fn synthesise(
+    matrix: &[[Complex<f64>; 2]; 2],
+    qubit: usize,
+    basis: BasisGates,
+    out: &mut Vec<InstructionIR>,
+) {
+    let (theta, phi, lambda, _) = unitary_to_u_angles(matrix);
+    let is_close = |a: f64, b: f64| (a - b).abs() < PASS_TOLERANCE;
+
+    if basis == BasisGates::CzU3 {
+        if !(is_close(theta, 0.0) && is_zero_angle(phi + lambda)) {
+            out.push(InstructionIR::Unitary(u_matrix(theta, phi, lambda, 0.0), qubit, vec![]));
+        }
+        return;
+    }
+
+    let rz = |angle: f64, out: &mut Vec<InstructionIR>| {
+        push_rotation(InstructionIR::Rz(reduce_angle(angle), qubit, vec![]), out)
+    };
+    let sx = || InstructionIR::Unitary(sx_matrix(), qubit, vec![]);
+    if is_close(theta, 0.0) {
+        rz(phi + lambda, out);
+    } else if is_close(theta, FRAC_PI_2) {
+        // U(π/2, φ, λ) ∝ Rz(φ + π/2) · SX · Rz(λ - π/2)
+        rz(lambda - FRAC_PI_2, out);
+        out.push(sx());
+        rz(phi + FRAC_PI_2, out);
+    } else if is_close(theta, PI) {
+        // U(π, φ, λ) ∝ Rz(φ - λ - π) · X
+        out.push(InstructionIR::PauliX(qubit, vec![]));
+        rz(phi - lambda - PI, out);
+    } else {
+        // U(θ, φ, λ) ∝ Rz(φ + π) · SX · Rz(θ + π) · SX · Rz(λ)
+        rz(lambda, out);
+        out.push(sx());
+        rz(theta + PI, out);
+        out.push(sx());
+        rz(phi + PI, out);
+    }
+}
+
+/// Returns whether an angle is a multiple of 2π
+fn is_zero_angle(angle: f64) -> bool {
+    let remainder: f64 = angle.rem_euclid(TAU);
+    remainder < PASS_TOLERANCE || TAU - remainder < PASS_TOLERANCE
+}
+
+/// Reduces an angle to (-π, π]
+fn reduce_angle(angle: f64) -> f64 {
+    let angle: f64 = angle.rem_euclid(TAU);
+    if angle > PI { angle - TAU } else { angle }
+}
@@ -0,0 +1,132 @@
+use crate::{
+    compiler::{
+        euler::{EulerBasis, EulerDecomposition},
+        ir::{CircuitIR, InstructionIR},
+        linalg::{instruction_matrix, multiply},
+        pass::Pass,
+        passes::PASS_TOLERANCE,
+    },
+    errors::CompilerError,
+};
+use num_complex::Complex;
+
+/// Fuses runs of single-qubit instructions on the same qubit
+///
+/// A run is a sequence of uncontrolled single-qubit instructions on one qubit, with no other
+/// instruction on that qubit in between. Controlled instructions, swaps and measurements end the runs
+/// on the qubits they touch, and raw QASM instructions end every run.
+///
+/// Without a basis, each run of two or more instructions is replaced by a single `Unitary` holding the
+/// exact product, or removed if the product is the identity up to a global phase. With a basis, each
+/// run is replaced by the minimal Euler rotation sequence in that basis whenever this is no longer
+/// than the run. Euler sequences drop the global phase of the run.
+///
+/// # Fields
+///
+/// * `basis` - The Euler basis to rewrite runs into, or `None` to fuse runs into a `Unitary`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FuseSingleQubitGates {
+    basis: Option<EulerBasis>,
+}
+
+impl FuseSingleQubitGates {
+    /// Creates a pass that fuses each run into a single `Unitary`
+    pub fn new() -> Self {
+        FuseSingleQubitGates { basis: None }
+    }
+
+    /// Creates a pass that rewrites each run into the minimal rotation sequence in an Euler basis
+    ///
+    /// # Arguments
+    ///
+    /// * `basis` - The Euler basis to rewrite runs into.
+    pub fn with_basis(basis: EulerBasis) -> Self {
+        FuseSingleQubitGates { basis: Some(basis) }
+    }
+
+    /// Returns the Euler basis runs are rewritten into, if any
+    pub fn basis(&self) -> Option<EulerBasis> {
+        self.basis
+    }
+
+    /// Replaces the pending run on a qubit, if any, with its fused form
+    fn flush(&self, runs: &mut [Vec<InstructionIR>], qubit: usize, out: &mut Vec<InstructionIR>) {
+        let run: Vec<InstructionIR> = std::mem::take(&mut runs[qubit]);
+        if run.is_empty() || (run.len() < 2 && self.basis.is_none()) {
+            out.extend(run);
+            return;
+        }
+
+        let product: [[Complex<f64>; 2]; 2] = run
+            .iter()
+            .map(|instruction| instruction_matrix(instruction).expect("runs only hold unitaries"))
+            .reduce(|product, matrix| multiply(&matrix, &product))
+            .expect("runs are not empty");
+
+        match self.basis {
+            None if is_identity_up_to_phase(&product) => {}
+            None => out.push(InstructionIR::Unitary(product, qubit, vec![])),
+            Some(basis) => {
+                let rotations: Vec<InstructionIR> =
+                    EulerDecomposition::new(&product, basis).to_instructions(qubit);
+                if rotations.len() <= run.len() {
+                    out.extend(rotations);
+                } else {
+                    out.extend(run);
+                }
+            }
+        }
+    }
+}
+
+impl Pass for FuseSingleQubitGates {
+    fn name(&self) -> &str {
+        "fuse-single-qubit-gates"
+    }
+
+    fn run(&self, circuit: &mut CircuitIR) -> Result<(), CompilerError> {
+        let mut runs: Vec<Vec<InstructionIR>> = vec![Vec::new(); circuit.num_qubits];
+        let mut out: Vec<InstructionIR> = Vec::with_capacity(circuit.instructions.len());
+
+        for instruction in &circuit.instructions {
+            if instruction.is_barrier() {
+                for qubit in 0..runs.len() {
+                    self.flush(&mut runs, qubit, &mut out);
+                }
+                out.push(instruction.clone());
+                continue;
+            }
+
+            let qubits: Vec<usize> = instruction.qubits();
+            if let Some(&qubit) = qubits.iter().find(|&&qubit| qubit >= runs.len()) {
+                return Err(CompilerError::InvalidOperands(
+                    format!("qubit {} on {} qubits", qubit, runs.len()),
+                    self.name().to_string(),
+                ));
+            }
+
+            let is_single_qubit: bool = instruction.controls().is_empty() && qubits.len() == 1;
+            if is_single_qubit && instruction_matrix(instruction).is_some() {
+                runs[qubits[0]].push(instruction.clone());
+            } else {
+                for &qubit in &qubits {
+                    self.flush(&mut runs, qubit, &mut out);
+                }
+                out.push(instruction.clone());
+            }
+        }
+
+        for qubit in 0..runs.len() {
+            self.flush(&mut runs, qubit, &mut out);
+        }
+        circuit.instructions = out;
+        Ok(())
+    }
+}
+
+/// Returns whether a 2×2 matrix is a multiple of the identity
+fn is_identity_up_to_phase(matrix: &[[Complex<f64>; 2]; 2]) -> bool {
+    matrix[0][1].norm() < PASS_TOLERANCE
+        && matrix[1][0].norm() < PASS_TOLERANCE
+        && (matrix[0][0] - matrix[1][1]).norm() < PASS_TOLERANCE
+}
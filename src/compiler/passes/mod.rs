@@ -0,0 +1,97 @@
+pub mod decompose;
+pub mod fusion;
+pub mod peephole;
+
+// Re-export the built-in passes for easier use
+pub use decompose::{BasisGates, DecomposeControlled};
+pub use fusion::FuseSingleQubitGates;
+pub use peephole::{CancelInverses, MergeRotations, PeepholeOptimiser, RemoveIdentities};
+
+use crate::{compiler::ir::InstructionIR, components::operator::ParameterisedKind};
+use num_complex::Complex;
+
+/// Tolerance used when comparing angles and matrix entries in the built-in passes
+pub(crate) const PASS_TOLERANCE: f64 = 1e-10;
+
+/// The Pauli bases in which an instruction is diagonal on a single qubit
+///
+/// An instruction that is diagonal in the same basis as another on every qubit they share commutes
+/// with it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct QubitAction {
+    /// The instruction commutes with Pauli-Z on the qubit
+    z: bool,
+    /// The instruction commutes with Pauli-X on the qubit
+    x: bool,
+}
+
+impl QubitAction {
+    const Z: QubitAction = QubitAction { z: true, x: false };
+    const X: QubitAction = QubitAction { z: false, x: true };
+    const ANY: QubitAction = QubitAction { z: true, x: true };
+    const NONE: QubitAction = QubitAction { z: false, x: false };
+}
+
+/// Returns the action of an instruction on its target qubits
+///
+/// Control qubits are always acted on diagonally in the Z basis.
+fn target_action(instruction: &InstructionIR) -> QubitAction {
+    match instruction {
+        InstructionIR::PauliZ(_, _)
+        | InstructionIR::S(_, _)
+        | InstructionIR::Sdg(_, _)
+        | InstructionIR::T(_, _)
+        | InstructionIR::Tdg(_, _)
+        | InstructionIR::Phase(_, _, _)
+        | InstructionIR::Rz(_, _, _)
+        | InstructionIR::Parameterised(ParameterisedKind::RotateZ | ParameterisedKind::PhaseShift, _, _, _) => {
+            QubitAction::Z
+        }
+        InstructionIR::PauliX(_, _)
+        | InstructionIR::Rx(_, _, _)
+        | InstructionIR::Parameterised(ParameterisedKind::RotateX, _, _, _) => QubitAction::X,
+        InstructionIR::Id(_, _) => QubitAction::ANY,
+        InstructionIR::Unitary(matrix, _, _) if is_diagonal(matrix) => QubitAction::Z,
+        _ => QubitAction::NONE,
+    }
+}
+
+/// Returns the action of an instruction on a qubit, or `None` if it does not act on the qubit
+fn action_on(instruction: &InstructionIR, qubit: usize) -> Option<QubitAction> {
+    if instruction.controls().contains(&qubit) {
+        Some(QubitAction::Z)
+    } else if instruction.targets().contains(&qubit) {
+        Some(target_action(instruction))
+    } else {
+        None
+    }
+}
+
+/// Returns whether two instructions can be reordered without changing the circuit
+///
+/// This is a conservative check: instructions on disjoint qubits commute, as do instructions that are
+/// diagonal in the same Pauli basis on every qubit they share. Measurements only commute with
+/// instructions on other qubits, and barriers such as raw QASM or classically controlled
+/// instructions never commute.
+pub(crate) fn commutes(a: &InstructionIR, b: &InstructionIR) -> bool {
+    if a.is_barrier() || b.is_barrier() {
+        return false;
+    }
+    a.qubits().iter().all(|&qubit| {
+        match (action_on(a, qubit), action_on(b, qubit)) {
+            (Some(action_a), Some(action_b)) => {
+                (action_a.z && action_b.z) || (action_a.x && action_b.x)
+            }
+            _ => true,
+        }
+    })
+}
+
+/// Returns whether two control lists contain the same qubits, in any order
+pub(crate) fn same_controls(a: &[usize], b: &[usize]) -> bool {
+    a.len() == b.len() && a.iter().all(|qubit| b.contains(qubit))
+}
+
+fn is_diagonal(matrix: &[[Complex<f64>; 2]; 2]) -> bool {
+    matrix[0][1].norm() < PASS_TOLERANCE && matrix[1][0].norm() < PASS_TOLERANCE
+}
@@ -0,0 +1,285 @@
+use crate::{
+    compiler::{
+        ir::{CircuitIR, InstructionIR},
+        pass::Pass,
+        passes::{PASS_TOLERANCE, commutes, same_controls},
+    },
+    errors::CompilerError,
+};
+use num_complex::Complex;
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI, TAU};
+
+/// Removes instructions that act as the identity
+///
+/// This removes `Id` instructions (with or without controls), phase shifts by a multiple of 2π, and
+/// rotations and unitaries that are the identity up to a global phase. Controlled rotations are only
+/// removed for multiples of 4π, since a controlled rotation by 2π applies a relative phase of -1.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RemoveIdentities;
+
+/// Cancels pairs of instructions that are inverses of each other
+///
+/// This covers self-inverse gates (`H H`, `X X`, `CX CX`, `SWAP SWAP`, ...) and adjoint pairs such as
+/// `S Sdg` and `T Tdg`, including their controlled forms. Instructions on disjoint qubits, or that
+/// commute with the first instruction of the pair, do not block the cancellation.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CancelInverses;
+
+/// Merges consecutive rotations and phases about the same axis
+///
+/// `Rx`, `Ry` and `Rz` rotations on the same target with the same controls are merged by adding their
+/// angles, as are phase gates (`Z`, `S`, `Sdg`, `T`, `Tdg` and `P`). Merged phases that correspond to a
+/// named gate are emitted as that gate, and merged instructions that come out as the identity are
+/// dropped. Instructions that commute with the first instruction do not block the merge.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MergeRotations;
+
+/// Runs identity removal, inverse cancellation and rotation merging until the circuit stops changing
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PeepholeOptimiser;
+
+impl Pass for RemoveIdentities {
+    fn name(&self) -> &str {
+        "remove-identities"
+    }
+
+    fn run(&self, circuit: &mut CircuitIR) -> Result<(), CompilerError> {
+        remove_identities(&mut circuit.instructions);
+        Ok(())
+    }
+}
+
+impl Pass for CancelInverses {
+    fn name(&self) -> &str {
+        "cancel-inverses"
+    }
+
+    fn run(&self, circuit: &mut CircuitIR) -> Result<(), CompilerError> {
+        while combine_with_lookahead(&mut circuit.instructions, cancel) {}
+        Ok(())
+    }
+}
+
+impl Pass for MergeRotations {
+    fn name(&self) -> &str {
+        "merge-rotations"
+    }
+
+    fn run(&self, circuit: &mut CircuitIR) -> Result<(), CompilerError> {
+        while combine_with_lookahead(&mut circuit.instructions, merge) {}
+        Ok(())
+    }
+}
+
+impl Pass for PeepholeOptimiser {
+    fn name(&self) -> &str {
+        "peephole"
+    }
+
+    fn run(&self, circuit: &mut CircuitIR) -> Result<(), CompilerError> {
+        loop {
+            let removed: bool = remove_identities(&mut circuit.instructions);
+            let cancelled: bool = combine_with_lookahead(&mut circuit.instructions, cancel);
+            let merged: bool = combine_with_lookahead(&mut circuit.instructions, merge);
+            if !(removed || cancelled || merged) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Removes all identity instructions, returning whether any were removed
+fn remove_identities(instructions: &mut Vec<InstructionIR>) -> bool {
+    let length: usize = instructions.len();
+    instructions.retain(|instruction| !is_identity(instruction));
+    instructions.len() != length
+}
+
+/// Combines each instruction with the first later instruction it can be combined with
+///
+/// The search looks past instructions that commute with the current one, and stops at the first
+/// instruction that does not. `combine` returns `None` if the pair cannot be combined, `Some(None)` if
+/// the pair cancels, and `Some(Some(instruction))` if the pair is replaced by a single instruction,
+/// which takes the place of the later one.
+///
+/// # Returns
+/// Whether any instructions were combined.
+fn combine_with_lookahead(
+    instructions: &mut Vec<InstructionIR>,
+    combine: fn(&InstructionIR, &InstructionIR) -> Option<Option<InstructionIR>>,
+) -> bool {
+    let mut slots: Vec<Option<InstructionIR>> = instructions.drain(..).map(Some).collect();
+    let mut changed: bool = false;
+
+    for i in 0..slots.len() {
+        let current: InstructionIR = match &slots[i] {
+            Some(instruction) => instruction.clone(),
+            None => continue,
+        };
+        for j in i + 1..slots.len() {
+            let next: &InstructionIR = match &slots[j] {
+                Some(instruction) => instruction,
+                None => continue,
+            };
+            if let Some(combined) = combine(&current, next) {
+                slots[i] = None;
+                slots[j] = combined;
+                changed = true;
+                break;
+            }
+            if !commutes(&current, next) {
+                break;
+            }
+        }
+    }
+
+    *instructions = slots.into_iter().flatten().collect();
+    changed
+}
+
+/// Returns `Some(None)` if the two instructions are inverses of each other
+fn cancel(a: &InstructionIR, b: &InstructionIR) -> Option<Option<InstructionIR>> {
+    let inverse: InstructionIR = a.inverse()?;
+    let is_inverse: bool = match (&inverse, b) {
+        (InstructionIR::Swap(a1, a2, a_controls), InstructionIR::Swap(b1, b2, b_controls)) => {
+            ((a1, a2) == (b1, b2) || (a1, a2) == (b2, b1)) && same_controls(a_controls, b_controls)
+        }
+        // Barriers are their own inverse, but must be kept
+        (InstructionIR::Barrier(_), _) => false,
+        // Rotations and phases are left to `MergeRotations`, which also handles partial cancellation
+        (InstructionIR::Unitary(_, _, _), _)
+        | (InstructionIR::Phase(_, _, _), _)
+        | (InstructionIR::Rx(_, _, _), _)
+        | (InstructionIR::Ry(_, _, _), _)
+        | (InstructionIR::Rz(_, _, _), _)
+        | (InstructionIR::Parameterised(_, _, _, _), _) => false,
+        _ => {
+            std::mem::discriminant(&inverse) == std::mem::discriminant(b)
+                && inverse.targets() == b.targets()
+                && same_controls(inverse.controls(), b.controls())
+        }
+    };
+    if is_inverse { Some(None) } else { None }
+}
+
+/// Merges two rotations about the same axis, or two phase gates, on the same qubits
+fn merge(a: &InstructionIR, b: &InstructionIR) -> Option<Option<InstructionIR>> {
+    if a.targets().len() != 1
+        || a.targets() != b.targets()
+        || !same_controls(a.controls(), b.controls())
+    {
+        return None;
+    }
+    let target: usize = a.targets()[0];
+    let controls: Vec<usize> = a.controls().to_vec();
+
+    match (a, b) {
+        (InstructionIR::Rx(angle_a, _, _), InstructionIR::Rx(angle_b, _, _)) => Some(
+            canonical_rotation(angle_a + angle_b, &controls)
+                .map(|angle| InstructionIR::Rx(angle, target, controls.clone())),
+        ),
+        (InstructionIR::Ry(angle_a, _, _), InstructionIR::Ry(angle_b, _, _)) => Some(
+            canonical_rotation(angle_a + angle_b, &controls)
+                .map(|angle| InstructionIR::Ry(angle, target, controls.clone())),
+        ),
+        (InstructionIR::Rz(angle_a, _, _), InstructionIR::Rz(angle_b, _, _)) => Some(
+            canonical_rotation(angle_a + angle_b, &controls)
+                .map(|angle| InstructionIR::Rz(angle, target, controls.clone())),
+        ),
+        _ => {
+            let angle: f64 = phase_angle(a)? + phase_angle(b)?;
+            Some(canonical_phase(angle, target, controls))
+        }
+    }
+}
+
+/// Returns the angle of a phase gate, i.e. θ such that the gate is diag(1, e^{iθ})
+fn phase_angle(instruction: &InstructionIR) -> Option<f64> {
+    match instruction {
+        InstructionIR::PauliZ(_, _) => Some(PI),
+        InstructionIR::S(_, _) => Some(FRAC_PI_2),
+        InstructionIR::Sdg(_, _) => Some(-FRAC_PI_2),
+        InstructionIR::T(_, _) => Some(FRAC_PI_4),
+        InstructionIR::Tdg(_, _) => Some(-FRAC_PI_4),
+        InstructionIR::Phase(angle, _, _) => Some(*angle),
+        _ => None,
+    }
+}
+
+/// Returns the phase gate for an angle, using a named gate where possible
+///
+/// Returns `None` if the angle is a multiple of 2π.
+fn canonical_phase(angle: f64, target: usize, controls: Vec<usize>) -> Option<InstructionIR> {
+    // Reduce the angle to (-π, π]
+    let mut angle: f64 = angle.rem_euclid(TAU);
+    if angle > PI + PASS_TOLERANCE {
+        angle -= TAU;
+    }
+    let is_close = |value: f64| (angle - value).abs() < PASS_TOLERANCE;
+
+    if is_close(0.0) || is_close(TAU) {
+        None
+    } else if is_close(PI) || is_close(-PI) {
+        Some(InstructionIR::PauliZ(target, controls))
+    } else if is_close(FRAC_PI_2) {
+        Some(InstructionIR::S(target, controls))
+    } else if is_close(-FRAC_PI_2) {
+        Some(InstructionIR::Sdg(target, controls))
+    } else if is_close(FRAC_PI_4) {
+        Some(InstructionIR::T(target, controls))
+    } else if is_close(-FRAC_PI_4) {
+        Some(InstructionIR::Tdg(target, controls))
+    } else {
+        Some(InstructionIR::Phase(angle, target, controls))
+    }
+}
+
+/// Reduces a rotation angle to (-2π, 2π], returning `None` if the rotation is the identity
+///
+/// Uncontrolled rotations by ±2π are the identity up to a global phase and are also dropped.
+fn canonical_rotation(angle: f64, controls: &[usize]) -> Option<f64> {
+    let period: f64 = 2.0 * TAU;
+    let mut angle: f64 = angle.rem_euclid(period);
+    if angle > TAU + PASS_TOLERANCE {
+        angle -= period;
+    }
+    if is_rotation_identity(angle, controls) {
+        None
+    } else {
+        Some(angle)
+    }
+}
+
+/// Returns whether a rotation by `angle` with the given controls is the identity up to a global phase
+fn is_rotation_identity(angle: f64, controls: &[usize]) -> bool {
+    let period: f64 = if controls.is_empty() { TAU } else { 2.0 * TAU };
+    let remainder: f64 = angle.rem_euclid(period);
+    remainder < PASS_TOLERANCE || period - remainder < PASS_TOLERANCE
+}
+
+/// Returns whether an instruction is the identity, up to a global phase if it is uncontrolled
+fn is_identity(instruction: &InstructionIR) -> bool {
+    match instruction {
+        InstructionIR::Id(_, _) => true,
+        InstructionIR::Phase(angle, _, _) => {
+            let remainder: f64 = angle.rem_euclid(TAU);
+            remainder < PASS_TOLERANCE || TAU - remainder < PASS_TOLERANCE
+        }
+        InstructionIR::Rx(angle, _, controls)
+        | InstructionIR::Ry(angle, _, controls)
+        | InstructionIR::Rz(angle, _, controls) => is_rotation_identity(*angle, controls),
+        InstructionIR::Unitary(matrix, _, controls) => {
+            // Uncontrolled unitaries only need to be proportional to the identity
+            let scale: Complex<f64> = if controls.is_empty() {
+                matrix[0][0]
+            } else {
+                Complex::new(1.0, 0.0)
+            };
+            matrix[0][1].norm() < PASS_TOLERANCE
+                && matrix[1][0].norm() < PASS_TOLERANCE
+                && (matrix[0][0] - scale).norm() < PASS_TOLERANCE
+                && (matrix[1][1] - scale).norm() < PASS_TOLERANCE
+        }
+        _ => false,
+    }
+}
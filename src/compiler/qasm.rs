@@ -0,0 +1,438 @@
+use crate::{
+    compiler::{
+        ir::{CircuitIR, InstructionIR},
+        linalg::is_sx,
+        parser::is_valid_identifier,
+    },
+    components::{classical::ClassicalCondition, measurement::MeasurementBasis, operator::ParameterisedKind},
+    errors::CompilerError,
+};
+use num_complex::Complex;
+
+/// Name of the quantum register declared in emitted OpenQASM programs
+pub(crate) const QUBIT_REGISTER: &str = "q";
+
+/// Name of the classical register declared in emitted OpenQASM programs
+pub(crate) const BIT_REGISTER: &str = "c";
+
+/// Tolerance used when extracting Euler angles from a unitary matrix
+pub(crate) const ANGLE_TOLERANCE: f64 = 1e-12;
+
+impl CircuitIR {
+    /// Emits the circuit as an OpenQASM 3.0 program
+    ///
+    /// The program declares a single qubit register `q` of size `num_qubits`, and a classical
+    /// register `c` of size `num_bits` if the circuit contains any measurements or classically
+    /// controlled instructions. The outcome of a plain measurement on qubit `i` is stored in `c[i]`,
+    /// if the register has such a bit. Classically controlled instructions are emitted as
+    /// `if (c == v)` statements, or as comparisons of single bits of `c`. Unbound parameters are
+    /// declared as `input float[64]` variables.
+    ///
+    /// # Returns
+    /// The OpenQASM 3.0 source, or a `CompilerError` if an instruction has invalid operands or a
+    /// parameter name is not an identifier that the program can declare.
+    pub fn to_qasm(&self) -> Result<String, CompilerError> {
+        let mut qasm: String = String::from("OPENQASM 3.0;\ninclude \"stdgates.inc\";\n\n");
+        for parameter in &self.parameters {
+            if !is_valid_identifier(parameter) || parameter == QUBIT_REGISTER || parameter == BIT_REGISTER {
+                return Err(CompilerError::UnsupportedOperator(format!(
+                    "parameter name '{}' is not a valid OpenQASM 3.0 identifier",
+                    parameter
+                )));
+            }
+            qasm.push_str(&format!("input float[64] {};\n", parameter));
+        }
+        qasm.push_str(&format!("qubit[{}] {};\n", self.num_qubits, QUBIT_REGISTER));
+
+        if self.has_classical_bits() {
+            qasm.push_str(&format!("bit[{}] {};\n", self.num_bits, BIT_REGISTER));
+        }
+        qasm.push('\n');
+
+        for instruction in &self.instructions {
+            for line in instruction_to_qasm(instruction, self.num_qubits, self.num_bits)? {
+                qasm.push_str(&line);
+                qasm.push('\n');
+            }
+        }
+
+        Ok(qasm)
+    }
+
+    /// Returns whether the emitted program needs a classical register, which is the case if the
+    /// circuit has classical bits and any measurements or classically controlled instructions
+    pub(crate) fn has_classical_bits(&self) -> bool {
+        self.num_bits > 0
+            && self.instructions.iter().any(|instruction| {
+                matches!(
+                    instruction,
+                    InstructionIR::Measurement(_, _)
+                        | InstructionIR::MeasurementInto(_, _, _)
+                        | InstructionIR::Conditional(_, _)
+                )
+            })
+    }
+}
+
+/// Converts a single IR instruction into one or more OpenQASM 3.0 statements
+fn instruction_to_qasm(
+    instruction: &InstructionIR,
+    num_qubits: usize,
+    num_bits: usize,
+) -> Result<Vec<String>, CompilerError> {
+    let lines: Vec<String> = match instruction {
+        InstructionIR::Hadamard(target, controls) => {
+            vec![gate_statement("h", &[], &[*target], controls, num_qubits)?]
+        }
+        InstructionIR::PauliX(target, controls) => {
+            vec![gate_statement("x", &[], &[*target], controls, num_qubits)?]
+        }
+        InstructionIR::PauliY(target, controls) => {
+            vec![gate_statement("y", &[], &[*target], controls, num_qubits)?]
+        }
+        InstructionIR::PauliZ(target, controls) => {
+            vec![gate_statement("z", &[], &[*target], controls, num_qubits)?]
+        }
+        InstructionIR::S(target, controls) => {
+            vec![gate_statement("s", &[], &[*target], controls, num_qubits)?]
+        }
+        InstructionIR::T(target, controls) => {
+            vec![gate_statement("t", &[], &[*target], controls, num_qubits)?]
+        }
+        InstructionIR::Sdg(target, controls) => {
+            vec![gate_statement("sdg", &[], &[*target], controls, num_qubits)?]
+        }
+        InstructionIR::Tdg(target, controls) => {
+            vec![gate_statement("tdg", &[], &[*target], controls, num_qubits)?]
+        }
+        InstructionIR::Phase(angle, target, controls) => {
+            vec![gate_statement("p", &[*angle], &[*target], controls, num_qubits)?]
+        }
+        InstructionIR::Rx(angle, target, controls) => {
+            vec![gate_statement("rx", &[*angle], &[*target], controls, num_qubits)?]
+        }
+        InstructionIR::Ry(angle, target, controls) => {
+            vec![gate_statement("ry", &[*angle], &[*target], controls, num_qubits)?]
+        }
+        InstructionIR::Rz(angle, target, controls) => {
+            vec![gate_statement("rz", &[*angle], &[*target], controls, num_qubits)?]
+        }
+        InstructionIR::Id(target, controls) => {
+            vec![gate_statement("id", &[], &[*target], controls, num_qubits)?]
+        }
+        InstructionIR::Swap(target1, target2, controls) => {
+            vec![gate_statement("swap", &[], &[*target1, *target2], controls, num_qubits)?]
+        }
+        InstructionIR::Unitary(matrix, target, controls) if is_sx(matrix) => {
+            vec![gate_statement("sx", &[], &[*target], controls, num_qubits)?]
+        }
+        InstructionIR::Unitary(matrix, target, controls) => {
+            let (theta, phi, lambda, global_phase) = unitary_to_u_angles(matrix);
+            let mut lines: Vec<String> = vec![gate_statement(
+                "U",
+                &[theta, phi, lambda],
+                &[*target],
+                controls,
+                num_qubits,
+            )?];
+            // The global phase is only observable when the gate is controlled
+            if !controls.is_empty() && global_phase.abs() > ANGLE_TOLERANCE {
+                lines.push(gphase_statement(global_phase, controls, num_qubits)?);
+            }
+            lines
+        }
+        InstructionIR::Parameterised(kind, angle, target, controls) => {
+            let name: &str = parameterised_gate_name(*kind);
+            validate_operands(name, &[*target], controls, num_qubits)?;
+            let operands: Vec<String> = controls
+                .iter()
+                .chain(std::iter::once(target))
+                .map(|&qubit| qubit_operand(qubit))
+                .collect();
+            vec![format!(
+                "{}{}({}) {};",
+                control_modifier(controls.len()),
+                name,
+                angle,
+                operands.join(", ")
+            )]
+        }
+        InstructionIR::Measurement(target, basis) => {
+            // Outcomes without a bit of the same index are discarded
+            let bit: Option<usize> = Some(*target).filter(|&bit| bit < num_bits);
+            measurement_statements(*target, bit, basis, num_qubits)?
+        }
+        InstructionIR::MeasurementInto(target, bit, basis) => {
+            validate_bits("measure", &[*bit], num_bits)?;
+            measurement_statements(*target, Some(*bit), basis, num_qubits)?
+        }
+        InstructionIR::Reset(target) => {
+            validate_operands("reset", &[*target], &[], num_qubits)?;
+            vec![format!("reset {};", qubit_operand(*target))]
+        }
+        InstructionIR::Barrier(targets) => vec![barrier_statement(targets, num_qubits)?],
+        InstructionIR::Conditional(condition, instruction) => {
+            let body: Vec<String> = instruction_to_qasm(instruction, num_qubits, num_bits)?;
+            match condition_expression(condition, num_bits)? {
+                // An empty condition always holds
+                None => body,
+                Some(expression) if body.len() == 1 => {
+                    vec![format!("if ({}) {}", expression, body[0])]
+                }
+                Some(expression) => {
+                    let mut lines: Vec<String> = vec![format!("if ({}) {{", expression)];
+                    lines.extend(body.iter().map(|line| format!("    {}", line)));
+                    lines.push("}".to_string());
+                    lines
+                }
+            }
+        }
+        InstructionIR::RawQASM(raw) => vec![raw.clone()],
+    };
+    Ok(lines)
+}
+
+/// Builds a barrier statement, e.g. `barrier q[0], q[2];`, which has the same syntax in OpenQASM 2.0
+///
+/// Barriers on every qubit cover the whole register, as in `barrier q;`.
+pub(crate) fn barrier_statement(targets: &[usize], num_qubits: usize) -> Result<String, CompilerError> {
+    if targets.is_empty() {
+        return Ok(format!("barrier {};", QUBIT_REGISTER));
+    }
+    validate_operands("barrier", targets, &[], num_qubits)?;
+    let operands: Vec<String> = targets.iter().map(|&target| qubit_operand(target)).collect();
+    Ok(format!("barrier {};", operands.join(", ")))
+}
+
+/// Builds the OpenQASM 3.0 expression of a classical condition
+///
+/// Conditions on the whole register are emitted as `c == v`, and other conditions as a conjunction
+/// of single bit comparisons such as `c[0] == 1 && c[2] == 0`.
+///
+/// # Returns
+/// The expression, `None` for a condition on no bits, or a `CompilerError` if a bit is out of range
+/// or the value does not fit into the bits.
+fn condition_expression(
+    condition: &ClassicalCondition,
+    num_bits: usize,
+) -> Result<Option<String>, CompilerError> {
+    validate_bits("if", &condition.bits, num_bits)?;
+    if !condition.is_valid() {
+        return Err(CompilerError::InvalidOperands(
+            format!("value {} for {} bits", condition.value, condition.bits.len()),
+            "if".to_string(),
+        ));
+    }
+
+    if condition.bits.is_empty() {
+        return Ok(None);
+    }
+    if condition.bits.len() == num_bits && condition.bits.iter().enumerate().all(|(i, &bit)| i == bit) {
+        return Ok(Some(format!("{} == {}", BIT_REGISTER, condition.value)));
+    }
+    let comparisons: Vec<String> = condition
+        .bits
+        .iter()
+        .enumerate()
+        .map(|(k, &bit)| format!("{}[{}] == {}", BIT_REGISTER, bit, (condition.value >> k) & 1))
+        .collect();
+    Ok(Some(comparisons.join(" && ")))
+}
+
+/// Checks that all classical bit operands are in range
+pub(crate) fn validate_bits(name: &str, bits: &[usize], num_bits: usize) -> Result<(), CompilerError> {
+    match bits.iter().find(|&&bit| bit >= num_bits) {
+        Some(bit) => Err(CompilerError::InvalidOperands(
+            format!("bit {} out of range for {} bits", bit, num_bits),
+            name.to_string(),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Builds a (possibly controlled) gate statement, e.g. `ctrl(2) @ rx(0.5) q[0], q[1], q[2];`
+///
+/// Control qubits are listed before the target qubits, as required by the `ctrl @` modifier.
+fn gate_statement(
+    name: &str,
+    params: &[f64],
+    targets: &[usize],
+    controls: &[usize],
+    num_qubits: usize,
+) -> Result<String, CompilerError> {
+    validate_operands(name, targets, controls, num_qubits)?;
+
+    let mut statement: String = control_modifier(controls.len());
+    statement.push_str(name);
+
+    if !params.is_empty() {
+        let params: Vec<String> = params
+            .iter()
+            .map(|&param| format_angle(name, param))
+            .collect::<Result<Vec<String>, CompilerError>>()?;
+        statement.push_str(&format!("({})", params.join(", ")));
+    }
+
+    let operands: Vec<String> = controls
+        .iter()
+        .chain(targets.iter())
+        .map(|&qubit| qubit_operand(qubit))
+        .collect();
+    statement.push_str(&format!(" {};", operands.join(", ")));
+
+    Ok(statement)
+}
+
+/// Builds a controlled global phase statement, which applies the phase only to the subspace where
+/// all control qubits are in the |1> state.
+fn gphase_statement(
+    angle: f64,
+    controls: &[usize],
+    num_qubits: usize,
+) -> Result<String, CompilerError> {
+    validate_operands("gphase", &[], controls, num_qubits)?;
+
+    let operands: Vec<String> = controls.iter().map(|&qubit| qubit_operand(qubit)).collect();
+    Ok(format!(
+        "{}gphase({}) {};",
+        control_modifier(controls.len()),
+        format_angle("gphase", angle)?,
+        operands.join(", ")
+    ))
+}
+
+/// Builds the statements for a measurement in the given basis, storing the outcome in `bit` if given.
+///
+/// Non-computational bases are emitted as a basis change, a computational measurement, and the
+/// inverse basis change, matching the post-measurement state produced by `State::measure`.
+fn measurement_statements(
+    target: usize,
+    bit: Option<usize>,
+    basis: &MeasurementBasis,
+    num_qubits: usize,
+) -> Result<Vec<String>, CompilerError> {
+    validate_operands("measure", &[target], &[], num_qubits)?;
+
+    let measure: String = match bit {
+        Some(bit) => format!("{}[{}] = measure {};", BIT_REGISTER, bit, qubit_operand(target)),
+        None => format!("measure {};", qubit_operand(target)),
+    };
+
+    let lines: Vec<String> = match basis {
+        MeasurementBasis::Computational => vec![measure],
+        MeasurementBasis::X => vec![
+            gate_statement("h", &[], &[target], &[], num_qubits)?,
+            measure,
+            gate_statement("h", &[], &[target], &[], num_qubits)?,
+        ],
+        MeasurementBasis::Y => vec![
+            gate_statement("sdg", &[], &[target], &[], num_qubits)?,
+            gate_statement("h", &[], &[target], &[], num_qubits)?,
+            measure,
+            gate_statement("h", &[], &[target], &[], num_qubits)?,
+            gate_statement("s", &[], &[target], &[], num_qubits)?,
+        ],
+        MeasurementBasis::Custom(matrix) => {
+            let adjoint: [[Complex<f64>; 2]; 2] = [
+                [matrix[0][0].conj(), matrix[1][0].conj()],
+                [matrix[0][1].conj(), matrix[1][1].conj()],
+            ];
+            let (theta, phi, lambda, _) = unitary_to_u_angles(matrix);
+            let (theta_dg, phi_dg, lambda_dg, _) = unitary_to_u_angles(&adjoint);
+            vec![
+                gate_statement("U", &[theta, phi, lambda], &[target], &[], num_qubits)?,
+                measure,
+                gate_statement("U", &[theta_dg, phi_dg, lambda_dg], &[target], &[], num_qubits)?,
+            ]
+        }
+    };
+    Ok(lines)
+}
+
+/// Returns the `ctrl @` modifier prefix for the given number of control qubits
+fn control_modifier(num_controls: usize) -> String {
+    match num_controls {
+        0 => String::new(),
+        1 => "ctrl @ ".to_string(),
+        n => format!("ctrl({}) @ ", n),
+    }
+}
+
+/// Returns the name of the standard library gate for a kind of parameterised operator
+fn parameterised_gate_name(kind: ParameterisedKind) -> &'static str {
+    match kind {
+        ParameterisedKind::RotateX => "rx",
+        ParameterisedKind::RotateY => "ry",
+        ParameterisedKind::RotateZ => "rz",
+        ParameterisedKind::PhaseShift => "p",
+    }
+}
+
+/// Returns the operand for a qubit in the quantum register, e.g. `q[3]`
+pub(crate) fn qubit_operand(qubit: usize) -> String {
+    format!("{}[{}]", QUBIT_REGISTER, qubit)
+}
+
+/// Formats an angle with enough precision to be read back exactly
+pub(crate) fn format_angle(name: &str, angle: f64) -> Result<String, CompilerError> {
+    if !angle.is_finite() {
+        return Err(CompilerError::InvalidOperands(
+            format!("non-finite angle {}", angle),
+            name.to_string(),
+        ));
+    }
+    Ok(format!("{:?}", angle))
+}
+
+/// Checks that all operands are in range and that no qubit is used twice in the same statement
+pub(crate) fn validate_operands(
+    name: &str,
+    targets: &[usize],
+    controls: &[usize],
+    num_qubits: usize,
+) -> Result<(), CompilerError> {
+    let operands: Vec<usize> = controls.iter().chain(targets.iter()).copied().collect();
+    for (i, &qubit) in operands.iter().enumerate() {
+        if qubit >= num_qubits {
+            return Err(CompilerError::InvalidOperands(
+                format!("qubit {} out of range for {} qubits", qubit, num_qubits),
+                name.to_string(),
+            ));
+        }
+        if operands[..i].contains(&qubit) {
+            return Err(CompilerError::InvalidOperands(
+                format!("qubit {} used more than once", qubit),
+                name.to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Decomposes a 2×2 unitary into the parameters of the OpenQASM `U` gate
+///
+/// Finds `(θ, φ, λ, α)` such that `matrix = e^{iα} U(θ, φ, λ)`, where
+/// `U(θ, φ, λ) = [[cos(θ/2), -e^{iλ} sin(θ/2)], [e^{iφ} sin(θ/2), e^{i(φ+λ)} cos(θ/2)]]`.
+pub(crate) fn unitary_to_u_angles(matrix: &[[Complex<f64>; 2]; 2]) -> (f64, f64, f64, f64) {
+    let cos_half: f64 = matrix[0][0].norm();
+    let sin_half: f64 = matrix[1][0].norm();
+    let theta: f64 = 2.0 * sin_half.atan2(cos_half);
+
+    if cos_half > ANGLE_TOLERANCE {
+        let global_phase: f64 = matrix[0][0].arg();
+        if sin_half > ANGLE_TOLERANCE {
+            let phi: f64 = matrix[1][0].arg() - global_phase;
+            let lambda: f64 = (-matrix[0][1]).arg() - global_phase;
+            (theta, phi, lambda, global_phase)
+        } else {
+            // Diagonal matrix: only the sum φ + λ is defined
+            let lambda: f64 = matrix[1][1].arg() - global_phase;
+            (theta, 0.0, lambda, global_phase)
+        }
+    } else {
+        // Anti-diagonal matrix: absorb the phase of the lower-left entry into φ
+        let phi: f64 = matrix[1][0].arg();
+        let lambda: f64 = (-matrix[0][1]).arg();
+        (theta, phi, lambda, 0.0)
+    }
+}
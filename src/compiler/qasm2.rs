@@ -0,0 +1,251 @@
+use crate::{
+    compiler::{
+        ir::{CircuitIR, InstructionIR},
+        qasm::{
+            ANGLE_TOLERANCE, BIT_REGISTER, QUBIT_REGISTER, barrier_statement, format_angle, qubit_operand,
+            unitary_to_u_angles, validate_bits, validate_operands,
+        },
+    },
+    components::measurement::MeasurementBasis,
+    errors::CompilerError,
+};
+use std::f64::consts::PI;
+
+impl CircuitIR {
+    /// Emits the circuit as an OpenQASM 2.0 program using the gates of `qelib1.inc`
+    ///
+    /// The program declares a quantum register `q` of size `num_qubits`, and a classical register
+    /// `c` of size `num_bits` if the circuit contains any measurements or classically controlled
+    /// instructions. The outcome of a plain measurement on qubit `i` is stored in `c[i]`.
+    /// Classically controlled instructions are emitted as `if(c==v)` statements, so their
+    /// conditions must compare the whole register.
+    ///
+    /// # Returns
+    /// The OpenQASM 2.0 source, or a `CompilerError` if an instruction has invalid operands or has
+    /// no OpenQASM 2.0 spelling, including unbound parameters.
+    pub fn to_qasm2(&self) -> Result<String, CompilerError> {
+        if let Some(parameter) = self.parameters.first() {
+            return Err(CompilerError::UnsupportedOperator(format!(
+                "OpenQASM 2.0 has no input parameters, but {} is unbound",
+                parameter
+            )));
+        }
+
+        let mut qasm: String = String::from("OPENQASM 2.0;\ninclude \"qelib1.inc\";\n\n");
+        qasm.push_str(&format!("qreg {}[{}];\n", QUBIT_REGISTER, self.num_qubits));
+
+        if self.has_classical_bits() {
+            qasm.push_str(&format!("creg {}[{}];\n", BIT_REGISTER, self.num_bits));
+        }
+        qasm.push('\n');
+
+        for instruction in &self.instructions {
+            for line in instruction_to_qasm2(instruction, self.num_qubits, self.num_bits)? {
+                qasm.push_str(&line);
+                qasm.push('\n');
+            }
+        }
+
+        Ok(qasm)
+    }
+}
+
+/// Converts a single IR instruction into one or more OpenQASM 2.0 statements
+///
+/// Controlled gates are lowered to the controlled gates of `qelib1.inc` (`cx`, `cy`, `cz`, `ch`,
+/// `ccx`, `cu1`, `crx`, `cry`, `crz`, `cu3` and `cswap`).
+fn instruction_to_qasm2(
+    instruction: &InstructionIR,
+    num_qubits: usize,
+    num_bits: usize,
+) -> Result<Vec<String>, CompilerError> {
+    let statement = |name: &str, params: &[f64], targets: &[usize], controls: &[usize]| {
+        gate_statement(name, params, targets, controls, num_qubits)
+    };
+
+    let lines: Vec<String> = match instruction {
+        InstructionIR::Hadamard(target, controls) => match controls.len() {
+            0 => vec![statement("h", &[], &[*target], controls)?],
+            1 => vec![statement("ch", &[], &[*target], controls)?],
+            _ => return Err(too_many_controls("h", controls.len())),
+        },
+        InstructionIR::PauliX(target, controls) => match controls.len() {
+            0 => vec![statement("x", &[], &[*target], controls)?],
+            1 => vec![statement("cx", &[], &[*target], controls)?],
+            2 => vec![statement("ccx", &[], &[*target], controls)?],
+            _ => return Err(too_many_controls("x", controls.len())),
+        },
+        InstructionIR::PauliY(target, controls) => match controls.len() {
+            0 => vec![statement("y", &[], &[*target], controls)?],
+            1 => vec![statement("cy", &[], &[*target], controls)?],
+            _ => return Err(too_many_controls("y", controls.len())),
+        },
+        InstructionIR::PauliZ(target, controls) => match controls.len() {
+            0 => vec![statement("z", &[], &[*target], controls)?],
+            1 => vec![statement("cz", &[], &[*target], controls)?],
+            _ => return Err(too_many_controls("z", controls.len())),
+        },
+        InstructionIR::S(target, controls) => {
+            phase_statements("s", PI / 2.0, *target, controls, num_qubits)?
+        }
+        InstructionIR::T(target, controls) => {
+            phase_statements("t", PI / 4.0, *target, controls, num_qubits)?
+        }
+        InstructionIR::Sdg(target, controls) => {
+            phase_statements("sdg", -PI / 2.0, *target, controls, num_qubits)?
+        }
+        InstructionIR::Tdg(target, controls) => {
+            phase_statements("tdg", -PI / 4.0, *target, controls, num_qubits)?
+        }
+        InstructionIR::Phase(angle, target, controls) => match controls.len() {
+            0 => vec![statement("u1", &[*angle], &[*target], controls)?],
+            1 => vec![statement("cu1", &[*angle], &[*target], controls)?],
+            _ => return Err(too_many_controls("u1", controls.len())),
+        },
+        InstructionIR::Rx(angle, target, controls) => match controls.len() {
+            0 => vec![statement("rx", &[*angle], &[*target], controls)?],
+            1 => vec![statement("crx", &[*angle], &[*target], controls)?],
+            _ => return Err(too_many_controls("rx", controls.len())),
+        },
+        InstructionIR::Ry(angle, target, controls) => match controls.len() {
+            0 => vec![statement("ry", &[*angle], &[*target], controls)?],
+            1 => vec![statement("cry", &[*angle], &[*target], controls)?],
+            _ => return Err(too_many_controls("ry", controls.len())),
+        },
+        InstructionIR::Rz(angle, target, controls) => match controls.len() {
+            0 => vec![statement("rz", &[*angle], &[*target], controls)?],
+            1 => vec![statement("crz", &[*angle], &[*target], controls)?],
+            _ => return Err(too_many_controls("rz", controls.len())),
+        },
+        // A controlled identity is still the identity
+        InstructionIR::Id(target, controls) => {
+            validate_operands("id", &[*target], controls, num_qubits)?;
+            vec![statement("id", &[], &[*target], &[])?]
+        }
+        InstructionIR::Swap(target1, target2, controls) => match controls.len() {
+            0 => vec![statement("swap", &[], &[*target1, *target2], controls)?],
+            1 => vec![statement("cswap", &[], &[*target1, *target2], controls)?],
+            _ => return Err(too_many_controls("swap", controls.len())),
+        },
+        InstructionIR::Unitary(matrix, target, controls) => {
+            let (theta, phi, lambda, global_phase) = unitary_to_u_angles(matrix);
+            match controls.len() {
+                0 => vec![statement("u3", &[theta, phi, lambda], &[*target], controls)?],
+                1 => {
+                    let mut lines: Vec<String> =
+                        vec![statement("cu3", &[theta, phi, lambda], &[*target], controls)?];
+                    // A controlled global phase is a phase shift on the control qubit
+                    if global_phase.abs() > ANGLE_TOLERANCE {
+                        lines.push(statement("u1", &[global_phase], &[controls[0]], &[])?);
+                    }
+                    lines
+                }
+                _ => return Err(too_many_controls("u3", controls.len())),
+            }
+        }
+        InstructionIR::Measurement(target, basis) => {
+            vec![measure_statement(*target, *target, basis, num_qubits, num_bits)?]
+        }
+        InstructionIR::MeasurementInto(target, bit, basis) => {
+            vec![measure_statement(*target, *bit, basis, num_qubits, num_bits)?]
+        }
+        InstructionIR::Parameterised(_, angle, _, _) => {
+            return Err(CompilerError::UnsupportedOperator(format!(
+                "OpenQASM 2.0 has no input parameters, but the angle {} is unbound",
+                angle
+            )));
+        }
+        InstructionIR::Reset(target) => {
+            validate_operands("reset", &[*target], &[], num_qubits)?;
+            vec![format!("reset {};", qubit_operand(*target))]
+        }
+        InstructionIR::Barrier(targets) => vec![barrier_statement(targets, num_qubits)?],
+        InstructionIR::Conditional(condition, instruction) => {
+            let is_register: bool = condition.bits.len() == num_bits
+                && condition.bits.iter().enumerate().all(|(i, &bit)| i == bit);
+            if !is_register || !condition.is_valid() {
+                return Err(CompilerError::UnsupportedOperator(format!(
+                    "OpenQASM 2.0 conditions must compare the whole classical register, found {:?}",
+                    condition
+                )));
+            }
+            // Every statement of the body is conditioned separately, since `if` has no blocks
+            instruction_to_qasm2(instruction, num_qubits, num_bits)?
+                .into_iter()
+                .map(|line| format!("if({}=={}) {}", BIT_REGISTER, condition.value, line))
+                .collect()
+        }
+        InstructionIR::RawQASM(raw) => vec![raw.clone()],
+    };
+    Ok(lines)
+}
+
+/// Builds a computational basis measurement statement, e.g. `measure q[0] -> c[1];`
+fn measure_statement(
+    target: usize,
+    bit: usize,
+    basis: &MeasurementBasis,
+    num_qubits: usize,
+    num_bits: usize,
+) -> Result<String, CompilerError> {
+    if *basis != MeasurementBasis::Computational {
+        return Err(CompilerError::UnsupportedOperator(format!(
+            "OpenQASM 2.0 only supports measurements in the computational basis, found {:?} basis on qubit {}",
+            basis, target
+        )));
+    }
+    validate_operands("measure", &[target], &[], num_qubits)?;
+    validate_bits("measure", &[bit], num_bits)?;
+    Ok(format!("measure {} -> {}[{}];", qubit_operand(target), BIT_REGISTER, bit))
+}
+
+/// Builds the statements for an S, T or adjoint gate, lowering the controlled form to `cu1`
+fn phase_statements(
+    name: &str,
+    angle: f64,
+    target: usize,
+    controls: &[usize],
+    num_qubits: usize,
+) -> Result<Vec<String>, CompilerError> {
+    match controls.len() {
+        0 => Ok(vec![gate_statement(name, &[], &[target], controls, num_qubits)?]),
+        1 => Ok(vec![gate_statement("cu1", &[angle], &[target], controls, num_qubits)?]),
+        _ => Err(too_many_controls(name, controls.len())),
+    }
+}
+
+/// Builds a gate statement, e.g. `crz(0.5) q[0], q[1];`, with the control qubits listed first
+fn gate_statement(
+    name: &str,
+    params: &[f64],
+    targets: &[usize],
+    controls: &[usize],
+    num_qubits: usize,
+) -> Result<String, CompilerError> {
+    validate_operands(name, targets, controls, num_qubits)?;
+
+    let mut statement: String = name.to_string();
+    if !params.is_empty() {
+        let params: Vec<String> = params
+            .iter()
+            .map(|&param| format_angle(name, param))
+            .collect::<Result<Vec<String>, CompilerError>>()?;
+        statement.push_str(&format!("({})", params.join(", ")));
+    }
+
+    let operands: Vec<String> = controls
+        .iter()
+        .chain(targets.iter())
+        .map(|&qubit| qubit_operand(qubit))
+        .collect();
+    statement.push_str(&format!(" {};", operands.join(", ")));
+
+    Ok(statement)
+}
+
+fn too_many_controls(name: &str, num_controls: usize) -> CompilerError {
+    CompilerError::UnsupportedOperator(format!(
+        "'{}' with {} control qubits has no OpenQASM 2.0 equivalent in qelib1.inc",
+        name, num_controls
+    ))
+}
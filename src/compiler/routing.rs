@@ -0,0 +1,584 @@
+use crate::{
+    circuit::Circuit,
+    compiler::{
+        coupling_map::CouplingMap,
+        ir::{CircuitIR, InstructionIR},
+    },
+    components::state::State,
+    errors::{CompilerError, Error},
+};
+use num_complex::Complex;
+use std::collections::HashMap;
+
+/// Default number of forward-backward iterations used by `SabreRouter::layout`
+const DEFAULT_LAYOUT_ITERATIONS: usize = 3;
+
+/// Maximum number of upcoming two-qubit instructions considered by the lookahead heuristic
+const EXTENDED_SET_SIZE: usize = 20;
+
+/// Weight of the upcoming instructions relative to the front layer in the routing heuristic
+const EXTENDED_SET_WEIGHT: f64 = 0.5;
+
+/// Amount by which the decay factor of a qubit grows each time it is swapped
+const DECAY_INCREMENT: f64 = 0.001;
+
+/// Number of consecutive swaps after which the decay factors are reset
+const DECAY_RESET_INTERVAL: usize = 5;
+
+/// Tolerance on the norm of a state read back by `RoutedCircuit::to_logical_state`
+const IDLE_QUBIT_TOLERANCE: f64 = 1e-8;
+
+/// An assignment of logical qubits to distinct physical qubits
+///
+/// # Fields
+///
+/// * `physical` - The physical qubit holding each logical qubit, indexed by logical qubit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    physical: Vec<usize>,
+}
+
+impl Layout {
+    /// Creates a layout from the physical qubit assigned to each logical qubit
+    ///
+    /// # Arguments
+    ///
+    /// * `physical` - The physical qubit holding each logical qubit, indexed by logical qubit.
+    ///
+    /// # Returns
+    /// The layout, or `CompilerError::InvalidOperands` if two logical qubits share a physical qubit.
+    pub fn new(physical: Vec<usize>) -> Result<Self, CompilerError> {
+        for (i, qubit) in physical.iter().enumerate() {
+            if physical[..i].contains(qubit) {
+                return Err(CompilerError::InvalidOperands(
+                    format!("physical qubit {} assigned more than once", qubit),
+                    "layout".to_string(),
+                ));
+            }
+        }
+        Ok(Layout { physical })
+    }
+
+    /// Creates the layout that places logical qubit `i` on physical qubit `i`
+    ///
+    /// # Arguments
+    ///
+    /// * `num_qubits` - The number of logical qubits.
+    pub fn trivial(num_qubits: usize) -> Self {
+        Layout {
+            physical: (0..num_qubits).collect(),
+        }
+    }
+
+    /// Returns the number of logical qubits in the layout
+    pub fn num_qubits(&self) -> usize {
+        self.physical.len()
+    }
+
+    /// Returns the physical qubit holding a logical qubit
+    ///
+    /// # Panics
+    /// Panics if `logical` is out of range.
+    pub fn physical(&self, logical: usize) -> usize {
+        self.physical[logical]
+    }
+
+    /// Returns the logical qubit held by a physical qubit, or `None` if it holds no logical qubit
+    pub fn logical(&self, physical: usize) -> Option<usize> {
+        self.physical.iter().position(|&qubit| qubit == physical)
+    }
+
+    /// Returns the physical qubit holding each logical qubit, indexed by logical qubit
+    pub fn as_slice(&self) -> &[usize] {
+        &self.physical
+    }
+
+    /// Extends the layout to a permutation of all physical qubits
+    ///
+    /// Physical qubits that hold no logical qubit are given to the extra virtual qubits in ascending
+    /// order.
+    fn to_permutation(&self, num_physical: usize) -> Vec<usize> {
+        let mut permutation: Vec<usize> = self.physical.clone();
+        permutation.extend((0..num_physical).filter(|qubit| !self.physical.contains(qubit)));
+        permutation
+    }
+}
+
+/// A circuit that has been routed onto a device, with the layouts needed to interpret its results
+///
+/// # Fields
+///
+/// * `circuit` - The routed circuit, acting on the physical qubits of the device.
+/// * `initial_layout` - The physical qubit holding each logical qubit at the start of the circuit.
+/// * `final_layout` - The physical qubit holding each logical qubit at the end of the circuit.
+/// * `num_swaps` - The number of SWAP instructions inserted by the router.
+#[derive(Debug)]
+pub struct RoutedCircuit {
+    /// The routed circuit, acting on the physical qubits of the device.
+    pub circuit: Circuit,
+    /// The physical qubit holding each logical qubit at the start of the circuit.
+    pub initial_layout: Layout,
+    /// The physical qubit holding each logical qubit at the end of the circuit.
+    pub final_layout: Layout,
+    /// The number of SWAP instructions inserted by the router.
+    pub num_swaps: usize,
+}
+
+impl RoutedCircuit {
+    /// Places a state of the logical qubits onto the physical qubits, using the initial layout
+    ///
+    /// Physical qubits that hold no logical qubit are initialised to |0⟩.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state of the logical qubits.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<State, Error>` - The state of the physical qubits, to be passed to the routed circuit.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the number of qubits of the state differs from the number of logical qubits.
+    pub fn to_physical_state(&self, state: &State) -> Result<State, Error> {
+        if state.num_qubits != self.initial_layout.num_qubits() {
+            return Err(Error::InvalidNumberOfQubits(state.num_qubits));
+        }
+        let mut state_vector: Vec<Complex<f64>> =
+            vec![Complex::new(0.0, 0.0); 1 << self.circuit.num_qubits];
+        for (index, amplitude) in state.state_vector.iter().enumerate() {
+            state_vector[physical_index(index, &self.initial_layout)] = *amplitude;
+        }
+        Ok(State {
+            state_vector,
+            num_qubits: self.circuit.num_qubits,
+        })
+    }
+
+    /// Reads the state of the logical qubits back from the physical qubits, using the final layout
+    ///
+    /// This undoes the permutation applied by the inserted SWAPs. Physical qubits that hold no logical
+    /// qubit are assumed to be in |0⟩, as they are when the input was built by `to_physical_state`.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state of the physical qubits after running the routed circuit.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<State, Error>` - The state of the logical qubits.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the number of qubits of the state differs from the number of physical qubits.
+    /// * Returns an error if the physical qubits that hold no logical qubit are not in |0⟩.
+    pub fn to_logical_state(&self, state: &State) -> Result<State, Error> {
+        if state.num_qubits != self.circuit.num_qubits {
+            return Err(Error::InvalidNumberOfQubits(state.num_qubits));
+        }
+        let state_vector: Vec<Complex<f64>> = (0..1 << self.final_layout.num_qubits())
+            .map(|index| state.state_vector[physical_index(index, &self.final_layout)])
+            .collect();
+
+        // Any weight outside the subspace where the idle qubits are |0⟩ is lost by the permutation
+        let norm: f64 = state_vector.iter().map(|amplitude| amplitude.norm_sqr()).sum();
+        if (norm - 1.0).abs() > IDLE_QUBIT_TOLERANCE {
+            return Err(Error::StateVectorNotNormalised);
+        }
+        Ok(State {
+            state_vector,
+            num_qubits: self.final_layout.num_qubits(),
+        })
+    }
+}
+
+/// Maps a basis state index over the logical qubits to the index over the physical qubits
+fn physical_index(index: usize, layout: &Layout) -> usize {
+    layout
+        .as_slice()
+        .iter()
+        .enumerate()
+        .filter(|(logical, _)| index >> logical & 1 == 1)
+        .fold(0, |physical_index, (_, &physical)| physical_index | 1 << physical)
+}
+
+/// Lays out and routes circuits onto a device using the SABRE heuristic
+///
+/// Routing walks the circuit's dependency graph, applying every instruction whose qubits are
+/// adjacent on the device. When no instruction in the front layer can be applied, it inserts the
+/// SWAP on an edge next to a front-layer qubit that most reduces the distance between the qubits of
+/// the front layer, with a smaller weight given to the next few two-qubit instructions and a decay
+/// penalty that discourages repeatedly swapping the same qubits. The layout is found by routing the
+/// circuit forwards and backwards, each time starting from the final layout of the previous run.
+///
+/// See Li, Ding and Xie, "Tackling the Qubit Mapping Problem for NISQ-Era Quantum Devices" (2019).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SabreRouter {
+    coupling_map: CouplingMap,
+    layout_iterations: usize,
+}
+
+impl SabreRouter {
+    /// Creates a router for the given device
+    ///
+    /// # Arguments
+    ///
+    /// * `coupling_map` - The connectivity of the device.
+    pub fn new(coupling_map: CouplingMap) -> Self {
+        SabreRouter::with_layout_iterations(coupling_map, DEFAULT_LAYOUT_ITERATIONS)
+    }
+
+    /// Creates a router that runs the given number of forward-backward iterations to find a layout
+    ///
+    /// # Arguments
+    ///
+    /// * `coupling_map` - The connectivity of the device.
+    /// * `layout_iterations` - The number of forward-backward routing runs used by `layout`. With
+    ///   zero iterations, `layout` returns the trivial layout.
+    pub fn with_layout_iterations(coupling_map: CouplingMap, layout_iterations: usize) -> Self {
+        SabreRouter {
+            coupling_map,
+            layout_iterations,
+        }
+    }
+
+    /// Returns the connectivity of the device
+    pub fn coupling_map(&self) -> &CouplingMap {
+        &self.coupling_map
+    }
+
+    /// Chooses an initial layout for a circuit
+    ///
+    /// # Returns
+    /// The layout, or a `CompilerError` if the circuit cannot be routed onto the device (see `route`).
+    pub fn layout(&self, circuit: &CircuitIR) -> Result<Layout, CompilerError> {
+        self.validate(circuit)?;
+        let reversed: Vec<InstructionIR> = circuit.instructions.iter().rev().cloned().collect();
+        let num_physical: usize = self.coupling_map.num_qubits();
+
+        let mut permutation: Vec<usize> =
+            Layout::trivial(circuit.num_qubits).to_permutation(num_physical);
+        for _ in 0..self.layout_iterations {
+            let (_, forward, _) = self.sabre(&circuit.instructions, permutation);
+            let (_, backward, _) = self.sabre(&reversed, forward);
+            permutation = backward;
+        }
+        permutation.truncate(circuit.num_qubits);
+        Ok(Layout {
+            physical: permutation,
+        })
+    }
+
+    /// Routes a circuit onto the device, starting from the given layout
+    ///
+    /// # Arguments
+    ///
+    /// * `circuit` - The circuit to route, over logical qubits.
+    /// * `layout` - The initial physical qubit of each logical qubit.
+    ///
+    /// # Returns
+    /// The routed circuit over the physical qubits of the device, in which every two-qubit instruction
+    /// acts on adjacent qubits, together with the final layout and the number of inserted SWAPs.
+    /// Measurements keep writing the classical bits of the input circuit.
+    ///
+    /// # Errors
+    ///
+    /// * `CompilerError::InvalidOperands` if the circuit has more qubits than the device, the layout does
+    ///   not match the circuit, or the coupling map is not connected.
+    /// * `CompilerError::UnsupportedOperator` if the circuit contains an instruction on more than two
+    ///   qubits other than a barrier, a raw QASM string or a classically controlled instruction.
+    pub fn route(
+        &self,
+        circuit: &CircuitIR,
+        layout: &Layout,
+    ) -> Result<(CircuitIR, Layout, usize), CompilerError> {
+        self.validate(circuit)?;
+        let num_physical: usize = self.coupling_map.num_qubits();
+        if layout.num_qubits() != circuit.num_qubits
+            || layout.as_slice().iter().any(|&qubit| qubit >= num_physical)
+        {
+            return Err(CompilerError::InvalidOperands(
+                format!(
+                    "layout {:?} for {} logical and {} physical qubits",
+                    layout.as_slice(),
+                    circuit.num_qubits,
+                    num_physical
+                ),
+                "router".to_string(),
+            ));
+        }
+
+        let (instructions, mut permutation, num_swaps) =
+            self.sabre(&circuit.instructions, layout.to_permutation(num_physical));
+        permutation.truncate(circuit.num_qubits);
+        let routed: CircuitIR = CircuitIR {
+            num_qubits: num_physical,
+            num_bits: circuit.num_bits,
+            instructions,
+            parameters: circuit.parameters.clone(),
+        };
+        Ok((
+            routed,
+            Layout {
+                physical: permutation,
+            },
+            num_swaps,
+        ))
+    }
+
+    /// Checks that a circuit can be routed onto the device
+    fn validate(&self, circuit: &CircuitIR) -> Result<(), CompilerError> {
+        if circuit.num_qubits > self.coupling_map.num_qubits() {
+            return Err(CompilerError::InvalidOperands(
+                format!(
+                    "{} logical qubits on a device with {} qubits",
+                    circuit.num_qubits,
+                    self.coupling_map.num_qubits()
+                ),
+                "router".to_string(),
+            ));
+        }
+        let mut has_two_qubit_instructions: bool = false;
+        for instruction in &circuit.instructions {
+            if matches!(instruction, InstructionIR::RawQASM(_)) {
+                return Err(CompilerError::UnsupportedOperator(
+                    "Raw QASM cannot be routed".to_string(),
+                ));
+            }
+            // The routed order keeps the writes of each classical bit in order, but not their reads
+            if matches!(instruction, InstructionIR::Conditional(_, _)) {
+                return Err(CompilerError::UnsupportedOperator(
+                    "Classically controlled instructions cannot be routed".to_string(),
+                ));
+            }
+            match instruction.qubits().len() {
+                // Barriers only order the instructions on their qubits
+                _ if matches!(instruction, InstructionIR::Barrier(_)) => {}
+                0 | 1 => {}
+                2 => has_two_qubit_instructions = true,
+                _ => {
+                    return Err(CompilerError::UnsupportedOperator(format!(
+                        "{:?} acts on more than two qubits and must be decomposed before routing",
+                        instruction
+                    )));
+                }
+            }
+        }
+        if has_two_qubit_instructions && !self.coupling_map.is_connected() {
+            return Err(CompilerError::InvalidOperands(
+                "coupling map is not connected".to_string(),
+                "router".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Runs the SABRE routing heuristic over a validated list of instructions
+    ///
+    /// `permutation` maps every virtual qubit to a physical qubit, where the virtual qubits beyond the
+    /// circuit's qubits are idle.
+    ///
+    /// # Returns
+    /// The routed instructions over physical qubits, the final permutation and the number of SWAPs.
+    fn sabre(
+        &self,
+        instructions: &[InstructionIR],
+        mut permutation: Vec<usize>,
+    ) -> (Vec<InstructionIR>, Vec<usize>, usize) {
+        let num_physical: usize = self.coupling_map.num_qubits();
+        let distances: &[Vec<usize>] = self.coupling_map.distance_matrix();
+        let mut inverse: Vec<usize> = vec![0; num_physical];
+        for (virtual_qubit, &physical) in permutation.iter().enumerate() {
+            inverse[physical] = virtual_qubit;
+        }
+
+        // Dependency graph: each instruction depends on the previous instruction on each of its qubits,
+        // and on the previous instruction that wrote each of the bits it writes, so that the last
+        // measurement into a bit still determines its value
+        let mut successors: Vec<Vec<usize>> = vec![Vec::new(); instructions.len()];
+        let mut remaining_predecessors: Vec<usize> = vec![0; instructions.len()];
+        let mut last: Vec<Option<usize>> = vec![None; num_physical];
+        let mut last_write: HashMap<usize, usize> = HashMap::new();
+        for (i, instruction) in instructions.iter().enumerate() {
+            let qubits: Vec<usize> = match instruction {
+                InstructionIR::Barrier(targets) if targets.is_empty() => (0..num_physical).collect(),
+                _ => instruction.qubits(),
+            };
+            let previous_on_qubits = qubits.iter().filter_map(|&qubit| last[qubit].replace(i));
+            let previous_on_bits: Vec<usize> = instruction
+                .written_bits()
+                .into_iter()
+                .filter_map(|bit| last_write.insert(bit, i))
+                .collect();
+            for previous in previous_on_qubits.chain(previous_on_bits) {
+                if !successors[previous].contains(&i) {
+                    successors[previous].push(i);
+                    remaining_predecessors[i] += 1;
+                }
+            }
+        }
+
+        let mut front: Vec<usize> = (0..instructions.len())
+            .filter(|&i| remaining_predecessors[i] == 0)
+            .collect();
+        let mut routed: Vec<InstructionIR> = Vec::with_capacity(instructions.len());
+        let mut decay: Vec<f64> = vec![1.0; num_physical];
+        let mut num_swaps: usize = 0;
+        let mut swaps_since_progress: usize = 0;
+
+        while !front.is_empty() {
+            // Apply every front-layer instruction whose qubits are adjacent
+            let (executable, blocked): (Vec<usize>, Vec<usize>) =
+                front.iter().partition(|&&i| match interacting_pair(&instructions[i]) {
+                    Some((a, b)) => self.coupling_map.are_adjacent(permutation[a], permutation[b]),
+                    None => true,
+                });
+            if !executable.is_empty() {
+                front = blocked;
+                for i in executable {
+                    routed.push(match &instructions[i] {
+                        // A measurement writes the bit of its logical qubit, which routing must not move
+                        InstructionIR::Measurement(target, basis) => {
+                            InstructionIR::MeasurementInto(permutation[*target], *target, *basis)
+                        }
+                        instruction => instruction.map_qubits(|qubit| permutation[qubit]),
+                    });
+                    for &next in &successors[i] {
+                        remaining_predecessors[next] -= 1;
+                        if remaining_predecessors[next] == 0 {
+                            front.push(next);
+                        }
+                    }
+                }
+                decay.fill(1.0);
+                swaps_since_progress = 0;
+                continue;
+            }
+
+            let pairs = |indices: &[usize]| -> Vec<(usize, usize)> {
+                indices
+                    .iter()
+                    .map(|&i| {
+                        interacting_pair(&instructions[i]).expect("blocked instructions act on two qubits")
+                    })
+                    .collect()
+            };
+            let front_pairs: Vec<(usize, usize)> = pairs(&front);
+
+            // If the heuristic stops making progress, move the first blocked pair together directly
+            if swaps_since_progress > 10 * num_physical {
+                let (a, b) = front_pairs[0];
+                let (mut source, target) = (permutation[a], permutation[b]);
+                while distances[source][target] > 1 {
+                    let next: usize = *self
+                        .coupling_map
+                        .neighbours(source)
+                        .iter()
+                        .find(|&&next| distances[next][target] < distances[source][target])
+                        .expect("the coupling map is connected");
+                    apply_swap(source, next, &mut permutation, &mut inverse, &mut routed);
+                    num_swaps += 1;
+                    source = next;
+                }
+                continue;
+            }
+
+            let extended_pairs: Vec<(usize, usize)> =
+                pairs(&extended_set(instructions, &front, &successors, &remaining_predecessors));
+
+            // Candidate SWAPs are the edges touching a qubit of a blocked front-layer instruction
+            let mut candidates: Vec<(usize, usize)> = Vec::new();
+            for &(a, b) in &front_pairs {
+                for physical in [permutation[a], permutation[b]] {
+                    for &neighbour in self.coupling_map.neighbours(physical) {
+                        let edge: (usize, usize) = (physical.min(neighbour), physical.max(neighbour));
+                        if !candidates.contains(&edge) {
+                            candidates.push(edge);
+                        }
+                    }
+                }
+            }
+
+            let cost = |pairs: &[(usize, usize)], permutation: &[usize]| -> f64 {
+                let total: usize = pairs
+                    .iter()
+                    .map(|&(a, b)| distances[permutation[a]][permutation[b]])
+                    .sum();
+                total as f64 / pairs.len().max(1) as f64
+            };
+            let mut best: Option<((usize, usize), f64)> = None;
+            for &(p1, p2) in &candidates {
+                let mut trial: Vec<usize> = permutation.clone();
+                trial.swap(inverse[p1], inverse[p2]);
+                let score: f64 = decay[p1].max(decay[p2])
+                    * (cost(&front_pairs, &trial)
+                        + EXTENDED_SET_WEIGHT * cost(&extended_pairs, &trial));
+                if best.is_none_or(|(_, best_score)| score < best_score) {
+                    best = Some(((p1, p2), score));
+                }
+            }
+
+            let ((p1, p2), _) = best.expect("blocked instructions have candidate swaps");
+            apply_swap(p1, p2, &mut permutation, &mut inverse, &mut routed);
+            num_swaps += 1;
+            swaps_since_progress += 1;
+            decay[p1] += DECAY_INCREMENT;
+            decay[p2] += DECAY_INCREMENT;
+            if swaps_since_progress.is_multiple_of(DECAY_RESET_INTERVAL) {
+                decay.fill(1.0);
+            }
+        }
+
+        (routed, permutation, num_swaps)
+    }
+}
+
+/// Returns the next two-qubit instructions after the front layer, in dependency order
+fn extended_set(
+    instructions: &[InstructionIR],
+    front: &[usize],
+    successors: &[Vec<usize>],
+    remaining_predecessors: &[usize],
+) -> Vec<usize> {
+    let mut predecessors: Vec<usize> = remaining_predecessors.to_vec();
+    let mut queue: Vec<usize> = front.to_vec();
+    let mut extended: Vec<usize> = Vec::new();
+    let mut position: usize = 0;
+    while position < queue.len() && extended.len() < EXTENDED_SET_SIZE {
+        let i: usize = queue[position];
+        position += 1;
+        for &next in &successors[i] {
+            predecessors[next] -= 1;
+            if predecessors[next] == 0 {
+                queue.push(next);
+                if interacting_pair(&instructions[next]).is_some() {
+                    extended.push(next);
+                }
+            }
+        }
+    }
+    extended
+}
+
+/// Returns the two qubits of an instruction that must be adjacent on the device, if there are two
+///
+/// Barriers never need their qubits to be adjacent.
+fn interacting_pair(instruction: &InstructionIR) -> Option<(usize, usize)> {
+    match instruction.qubits()[..] {
+        [a, b] if !matches!(instruction, InstructionIR::Barrier(_)) => Some((a, b)),
+        _ => None,
+    }
+}
+
+/// Emits a SWAP between two physical qubits and updates the permutation
+fn apply_swap(
+    p1: usize,
+    p2: usize,
+    permutation: &mut [usize],
+    inverse: &mut [usize],
+    routed: &mut Vec<InstructionIR>,
+) {
+    routed.push(InstructionIR::Swap(p1, p2, vec![]));
+    permutation.swap(inverse[p1], inverse[p2]);
+    inverse.swap(p1, p2);
+}
@@ -0,0 +1,481 @@
+use crate::{
+    components::{
+        operator::{Operator, Pauli, UnitaryN},
+        state::State,
+    },
+    errors::Error,
+};
+use num_complex::Complex;
+use rand::Rng;
+
+/// A square matrix stored as a vector of rows.
+type Matrix = Vec<Vec<Complex<f64>>>;
+
+// Helper function to get the 2×2 matrix of a single-qubit operator from its rows
+fn matrix2(rows: [[f64; 2]; 2]) -> Matrix {
+    rows.iter()
+        .map(|row| row.iter().map(|&element| Complex::new(element, 0.0)).collect())
+        .collect()
+}
+
+// Helper function to get the matrix of a single-qubit Pauli operator, or the identity
+fn pauli_matrix(pauli: Option<Pauli>) -> Matrix {
+    match pauli {
+        Some(pauli) => pauli.matrix().expect("Pauli operators have a known matrix"),
+        None => matrix2([[1.0, 0.0], [0.0, 1.0]]),
+    }
+}
+
+// Helper function to get the matrix of a tensor product of single-qubit matrices, where factors[j] acts on bit j of
+// the row and column indices
+fn tensor_product(factors: &[Matrix]) -> Matrix {
+    let dim: usize = 1 << factors.len();
+    (0..dim)
+        .map(|r| {
+            (0..dim)
+                .map(|c| {
+                    factors
+                        .iter()
+                        .enumerate()
+                        .map(|(j, factor)| factor[(r >> j) & 1][(c >> j) & 1])
+                        .product()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// Helper function to multiply two square matrices of the same dimension
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    let dim: usize = a.len();
+    (0..dim)
+        .map(|r| (0..dim).map(|c| (0..dim).map(|k| a[r][k] * b[k][c]).sum()).collect())
+        .collect()
+}
+
+// Helper function to check that a probability lies in [0, 1]
+fn validate_probability(probability: f64) -> Result<(), Error> {
+    if !(0.0..=1.0).contains(&probability) {
+        return Err(Error::InvalidProbability(probability));
+    }
+    Ok(())
+}
+
+/// A quantum channel ρ → Σ_i K_i ρ K_i† defined by its Kraus operators K_i, such as a noise process.
+///
+/// The Kraus operators must satisfy the completeness relation Σ_i K_i† K_i = I, so that the channel preserves the
+/// trace. A channel is applied exactly to a `DensityMatrix` with `DensityMatrix::apply_channel`, and stochastically to
+/// a `State` with `Channel::apply`, which picks a single Kraus operator K_i with probability ‖K_i |ψ⟩‖². Averaging the
+/// states of many such trajectories gives the same density matrix as the exact application.
+///
+/// Bit j of the row and column indices of each Kraus operator corresponds to the j-th target qubit, as for
+/// `UnitaryN`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Channel {
+    /// The 2^k×2^k Kraus operators, as vectors of rows.
+    kraus_operators: Vec<Matrix>,
+    /// The number of qubits k the channel acts on.
+    num_qubits: usize,
+}
+
+impl Channel {
+    /// Creates a new channel from its Kraus operators.
+    ///
+    /// Kraus operators whose elements are all zero do not contribute to the channel and are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `kraus_operators` - The 2^k×2^k Kraus operators as vectors of rows, with k at least 1.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Channel, Error>` - The channel, or an error if the Kraus operators are invalid.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if there are no Kraus operators.
+    /// * Returns an error if a Kraus operator is not square, or the dimensions differ or are not a power of two greater
+    ///   than 1.
+    /// * Returns an error if the Kraus operators do not satisfy Σ_i K_i† K_i = I.
+    pub fn new(kraus_operators: Vec<Vec<Vec<Complex<f64>>>>) -> Result<Self, Error> {
+        let Some(first) = kraus_operators.first() else {
+            return Err(Error::InvalidNumberOfInputs(0, 1));
+        };
+        let dim: usize = first.len();
+        for kraus in &kraus_operators {
+            if kraus.len() != dim {
+                return Err(Error::InvalidMatrixDimensions(dim, kraus.len()));
+            }
+            if let Some(row) = kraus.iter().find(|row| row.len() != dim) {
+                return Err(Error::InvalidMatrixDimensions(dim, row.len()));
+            }
+        }
+        if dim < 2 || !dim.is_power_of_two() {
+            return Err(Error::InvalidMatrixDimensions(dim, dim));
+        }
+
+        // Σ_i K_i† K_i = I, with the tolerance scaled by the dimension and the number of Kraus operators
+        let tol: f64 = f64::EPSILON * 4.0 * (dim * kraus_operators.len()) as f64;
+        for i in 0..dim {
+            for j in i..dim {
+                let sum: Complex<f64> = kraus_operators
+                    .iter()
+                    .flat_map(|kraus| kraus.iter().map(move |row| row[i].conj() * row[j]))
+                    .sum();
+                let expected: f64 = if i == j { 1.0 } else { 0.0 };
+                if (sum - expected).norm() > tol {
+                    return Err(Error::IncompleteKrausOperators);
+                }
+            }
+        }
+
+        let zero: Complex<f64> = Complex::new(0.0, 0.0);
+        Ok(Channel {
+            kraus_operators: kraus_operators
+                .into_iter()
+                .filter(|kraus| kraus.iter().flatten().any(|&element| element != zero))
+                .collect(),
+            num_qubits: dim.trailing_zeros() as usize,
+        })
+    }
+
+    // Helper function to create a channel that applies each unitary with the given probability
+    fn _mixture(unitaries: Vec<(f64, Matrix)>) -> Result<Self, Error> {
+        Channel::new(
+            unitaries
+                .into_iter()
+                .map(|(probability, unitary)| {
+                    let scale: f64 = probability.sqrt();
+                    unitary
+                        .into_iter()
+                        .map(|row| row.into_iter().map(|element| element * scale).collect())
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    /// Creates a depolarising channel ρ → (1 - p) ρ + p I / 2^n on n qubits.
+    ///
+    /// The channel applies each of the 4^n - 1 non-identity Pauli strings with probability p / 4^n.
+    ///
+    /// # Arguments
+    ///
+    /// * `probability` - The depolarising probability p, between 0 and 1.
+    /// * `num_qubits` - The number of qubits n the channel acts on.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Channel, Error>` - The depolarising channel.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the probability is not between 0 and 1.
+    /// * Returns an error if the number of qubits is 0.
+    pub fn depolarising(probability: f64, num_qubits: usize) -> Result<Self, Error> {
+        validate_probability(probability)?;
+        if num_qubits == 0 {
+            return Err(Error::InvalidNumberOfQubits(0));
+        }
+
+        // Digit j in base 4 of the index of a Pauli string selects I, X, Y or Z on qubit j
+        let paulis: [Option<Pauli>; 4] = [None, Some(Pauli::X), Some(Pauli::Y), Some(Pauli::Z)];
+        let num_strings: usize = 1 << (2 * num_qubits);
+        let weight: f64 = probability / num_strings as f64;
+        Channel::_mixture(
+            (0..num_strings)
+                .map(|string| {
+                    let factors: Vec<Matrix> =
+                        (0..num_qubits).map(|j| pauli_matrix(paulis[(string >> (2 * j)) & 3])).collect();
+                    let probability: f64 = if string == 0 { 1.0 - probability + weight } else { weight };
+                    (probability, tensor_product(&factors))
+                })
+                .collect(),
+        )
+    }
+
+    /// Creates a bit-flip channel, which applies X with probability p.
+    ///
+    /// # Arguments
+    ///
+    /// * `probability` - The probability p of a bit flip, between 0 and 1.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Channel, Error>` - The bit-flip channel.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the probability is not between 0 and 1.
+    pub fn bit_flip(probability: f64) -> Result<Self, Error> {
+        Channel::pauli(probability, 0.0, 0.0)
+    }
+
+    /// Creates a phase-flip channel, which applies Z with probability p.
+    ///
+    /// # Arguments
+    ///
+    /// * `probability` - The probability p of a phase flip, between 0 and 1.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Channel, Error>` - The phase-flip channel.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the probability is not between 0 and 1.
+    pub fn phase_flip(probability: f64) -> Result<Self, Error> {
+        Channel::pauli(0.0, 0.0, probability)
+    }
+
+    /// Creates a single-qubit Pauli channel, which applies X, Y or Z with the given probabilities.
+    ///
+    /// # Arguments
+    ///
+    /// * `p_x` - The probability of applying X.
+    /// * `p_y` - The probability of applying Y.
+    /// * `p_z` - The probability of applying Z.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Channel, Error>` - The Pauli channel.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if a probability is not between 0 and 1, or the probabilities sum to more than 1.
+    pub fn pauli(p_x: f64, p_y: f64, p_z: f64) -> Result<Self, Error> {
+        validate_probability(p_x)?;
+        validate_probability(p_y)?;
+        validate_probability(p_z)?;
+        let p_identity: f64 = 1.0 - (p_x + p_y + p_z);
+        if p_identity < -f64::EPSILON {
+            return Err(Error::InvalidProbability(p_x + p_y + p_z));
+        }
+        Channel::_mixture(vec![
+            (p_identity.max(0.0), pauli_matrix(None)),
+            (p_x, pauli_matrix(Some(Pauli::X))),
+            (p_y, pauli_matrix(Some(Pauli::Y))),
+            (p_z, pauli_matrix(Some(Pauli::Z))),
+        ])
+    }
+
+    /// Creates an amplitude damping channel, which models the decay of |1⟩ to |0⟩ with probability γ.
+    ///
+    /// The Kraus operators are K_0 = [[1, 0], [0, √(1 - γ)]] and K_1 = [[0, √γ], [0, 0]].
+    ///
+    /// # Arguments
+    ///
+    /// * `gamma` - The decay probability γ, between 0 and 1.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Channel, Error>` - The amplitude damping channel.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if γ is not between 0 and 1.
+    pub fn amplitude_damping(gamma: f64) -> Result<Self, Error> {
+        Channel::generalised_amplitude_damping(gamma, 0.0)
+    }
+
+    /// Creates a phase damping channel, which scales the coherences between |0⟩ and |1⟩ by √(1 - λ) without
+    /// changing the populations.
+    ///
+    /// The Kraus operators are K_0 = [[1, 0], [0, √(1 - λ)]] and K_1 = [[0, 0], [0, √λ]].
+    ///
+    /// # Arguments
+    ///
+    /// * `lambda` - The damping probability λ, between 0 and 1.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Channel, Error>` - The phase damping channel.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if λ is not between 0 and 1.
+    pub fn phase_damping(lambda: f64) -> Result<Self, Error> {
+        validate_probability(lambda)?;
+        Channel::new(vec![
+            matrix2([[1.0, 0.0], [0.0, (1.0 - lambda).sqrt()]]),
+            matrix2([[0.0, 0.0], [0.0, lambda.sqrt()]]),
+        ])
+    }
+
+    /// Creates a generalised amplitude damping channel, which models the relaxation of a qubit towards a thermal
+    /// state with population p_1 in |1⟩.
+    ///
+    /// With probability 1 - p_1 the channel is amplitude damping towards |0⟩, and with probability p_1 it is
+    /// amplitude damping towards |1⟩. With p_1 = 0 this is the amplitude damping channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `gamma` - The relaxation probability γ, between 0 and 1.
+    /// * `excited_population` - The population p_1 of |1⟩ in the thermal state, between 0 and 1.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Channel, Error>` - The generalised amplitude damping channel.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if γ or p_1 is not between 0 and 1.
+    pub fn generalised_amplitude_damping(gamma: f64, excited_population: f64) -> Result<Self, Error> {
+        validate_probability(gamma)?;
+        validate_probability(excited_population)?;
+        let decay: f64 = (1.0 - excited_population).sqrt();
+        let excite: f64 = excited_population.sqrt();
+        let keep: f64 = (1.0 - gamma).sqrt();
+        let jump: f64 = gamma.sqrt();
+        Channel::new(vec![
+            matrix2([[decay, 0.0], [0.0, decay * keep]]),
+            matrix2([[0.0, decay * jump], [0.0, 0.0]]),
+            matrix2([[excite * keep, 0.0], [0.0, excite]]),
+            matrix2([[0.0, 0.0], [excite * jump, 0.0]]),
+        ])
+    }
+
+    /// Creates a thermal relaxation channel for a qubit with relaxation time T1 and dephasing time T2, during a gate
+    /// of duration t.
+    ///
+    /// The populations relax towards the thermal state with probability γ = 1 - exp(-t / T1), and the coherences
+    /// decay by exp(-t / T2). This is generalised amplitude damping followed by the phase damping that makes up the
+    /// remaining dephasing, which requires T2 ≤ 2 T1.
+    ///
+    /// # Arguments
+    ///
+    /// * `t1` - The relaxation time T1.
+    /// * `t2` - The dephasing time T2, at most 2 T1.
+    /// * `gate_time` - The duration t of the gate, in the same units as T1 and T2.
+    /// * `excited_population` - The population of |1⟩ in the thermal state, which is 0 at zero temperature.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Channel, Error>` - The thermal relaxation channel.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if T1 or T2 is not positive, T2 is greater than 2 T1, or the gate time is negative.
+    /// * Returns an error if the excited population is not between 0 and 1.
+    pub fn thermal_relaxation(t1: f64, t2: f64, gate_time: f64, excited_population: f64) -> Result<Self, Error> {
+        if !(t1 > 0.0 && t2 > 0.0 && t2 <= 2.0 * t1 && gate_time >= 0.0) {
+            return Err(Error::InvalidRelaxationTimes(t1, t2, gate_time));
+        }
+        let gamma: f64 = 1.0 - (-gate_time / t1).exp();
+        // Amplitude damping already scales the coherences by exp(-t / 2 T1)
+        let lambda: f64 = 1.0 - (gate_time / t1 - 2.0 * gate_time / t2).exp();
+        Channel::generalised_amplitude_damping(gamma, excited_population)?
+            .compose(&Channel::phase_damping(lambda.clamp(0.0, 1.0))?)
+    }
+
+    /// Composes two channels on the same qubits, applying this channel first and then the other.
+    ///
+    /// # Arguments
+    ///
+    /// * `next` - The channel to apply after this one.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Channel, Error>` - The channel with Kraus operators N_j K_i for each pair of Kraus operators.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the channels act on different numbers of qubits.
+    pub fn compose(&self, next: &Channel) -> Result<Self, Error> {
+        if next.num_qubits != self.num_qubits {
+            return Err(Error::InvalidNumberOfQubits(next.num_qubits));
+        }
+        Channel::new(
+            next.kraus_operators
+                .iter()
+                .flat_map(|outer| self.kraus_operators.iter().map(move |inner| multiply(outer, inner)))
+                .collect(),
+        )
+    }
+
+    /// Gets the number of qubits the channel acts on.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The number of qubits k.
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    /// Gets the Kraus operators of the channel.
+    ///
+    /// # Returns
+    ///
+    /// * `&[Vec<Vec<Complex<f64>>>]` - The 2^k×2^k Kraus operators, as vectors of rows.
+    pub fn kraus_operators(&self) -> &[Vec<Vec<Complex<f64>>>] {
+        &self.kraus_operators
+    }
+
+    /// Applies the channel to the target qubits of a pure state by sampling a single quantum trajectory.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state to apply the channel to.
+    /// * `target_qubits` - The qubits the channel acts on, one per qubit of the channel.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<State, Error>` - The normalised state K_i |ψ⟩ / ‖K_i |ψ⟩‖ for the sampled Kraus operator K_i.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the number of target qubits does not match the channel.
+    /// * Returns an error if a target qubit index is invalid, or the target qubits are not different.
+    pub fn apply(&self, state: &State, target_qubits: &[usize]) -> Result<State, Error> {
+        self.apply_with_rng(state, target_qubits, &mut rand::rng())
+    }
+
+    /// Applies the channel to the target qubits of a pure state by sampling a single quantum trajectory, using the
+    /// given random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state to apply the channel to.
+    /// * `target_qubits` - The qubits the channel acts on, one per qubit of the channel.
+    /// * `rng` - The random number generator used to choose the Kraus operator.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<State, Error>` - The normalised state K_i |ψ⟩ / ‖K_i |ψ⟩‖ for the sampled Kraus operator K_i.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the number of target qubits does not match the channel.
+    /// * Returns an error if a target qubit index is invalid, or the target qubits are not different.
+    pub fn apply_with_rng(&self, state: &State, target_qubits: &[usize], rng: &mut impl Rng) -> Result<State, Error> {
+        if target_qubits.len() != self.num_qubits {
+            return Err(Error::InvalidNumberOfQubits(target_qubits.len()));
+        }
+
+        // Each Kraus operator is applied in turn until the cumulative probability passes the random threshold, so a
+        // channel dominated by its first operator usually needs a single application
+        let threshold: f64 = rng.random();
+        let mut cumulative: f64 = 0.0;
+        let mut fallback: Option<(f64, State)> = None;
+        for kraus in &self.kraus_operators {
+            let operator: UnitaryN = UnitaryN {
+                matrix: kraus.clone(),
+                num_qubits: self.num_qubits,
+            };
+            let branch: State = operator.apply(state, target_qubits, &[])?;
+            let probability: f64 = branch.state_vector.iter().map(|amplitude| amplitude.norm_sqr()).sum();
+            if probability <= 0.0 {
+                continue;
+            }
+            cumulative += probability;
+            if threshold < cumulative {
+                return Ok(branch * (1.0 / probability.sqrt()));
+            }
+            fallback = Some((probability, branch));
+        }
+
+        // Rounding can leave the cumulative probability just below the threshold
+        let (probability, branch) = fallback.expect("a complete set of Kraus operators has a non-zero branch");
+        Ok(branch * (1.0 / probability.sqrt()))
+    }
+}
@@ -0,0 +1,241 @@
+use crate::{
+    compiler::{
+        compilable::{Compilable, CompilableGate},
+        ir::InstructionIR,
+    },
+    components::measurement::MeasurementResult,
+};
+use std::fmt;
+
+/// A register of classical bits, holding the outcomes of measurements made while executing a circuit.
+///
+/// # Fields
+///
+/// * `bits` - The values of the bits, each 0 or 1. Bit 0 is the least significant bit of the register value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClassicalRegister {
+    /// The values of the bits, each 0 or 1.
+    bits: Vec<u8>,
+}
+
+impl ClassicalRegister {
+    /// Creates a new register with all bits set to 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_bits` - The number of bits in the register.
+    ///
+    /// # Returns
+    ///
+    /// * `ClassicalRegister` - A new instance of the ClassicalRegister struct.
+    pub fn new(num_bits: usize) -> Self {
+        ClassicalRegister {
+            bits: vec![0; num_bits],
+        }
+    }
+
+    /// Gets the number of bits in the register.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The number of bits in the register.
+    pub fn num_bits(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Gets the values of the bits of the register.
+    ///
+    /// # Returns
+    ///
+    /// * `&[u8]` - The values of the bits, each 0 or 1, starting with bit 0.
+    pub fn bits(&self) -> &[u8] {
+        &self.bits
+    }
+
+    /// Gets the value of a single bit.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the bit.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<u8>` - The value of the bit, or `None` if the index is out of range.
+    pub fn get(&self, index: usize) -> Option<u8> {
+        self.bits.get(index).copied()
+    }
+
+    /// Sets the value of a single bit. Indices outside the register are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the bit.
+    /// * `value` - The new value of the bit, where any non-zero value sets the bit to 1.
+    pub fn set(&mut self, index: usize, value: u8) {
+        if let Some(bit) = self.bits.get_mut(index) {
+            *bit = u8::from(value != 0);
+        }
+    }
+
+    /// Gets the integer value of a sequence of bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `indices` - The indices of the bits, starting with the least significant bit. Bits outside the register
+    ///   are read as 0.
+    ///
+    /// # Returns
+    ///
+    /// * `u64` - The value of the bits, where the bit at `indices[k]` contributes `2^k`.
+    pub fn value_of(&self, indices: &[usize]) -> u64 {
+        indices
+            .iter()
+            .enumerate()
+            .take(u64::BITS as usize)
+            .filter(|&(_, &index)| self.get(index) == Some(1))
+            .fold(0, |value, (k, _)| value | (1 << k))
+    }
+
+    /// Gets the integer value of the whole register.
+    ///
+    /// # Returns
+    ///
+    /// * `u64` - The value of the register, where bit `k` contributes `2^k`. Only the lowest 64 bits are included.
+    pub fn value(&self) -> u64 {
+        let indices: Vec<usize> = (0..self.bits.len()).collect();
+        self.value_of(&indices)
+    }
+
+    /// Records the outcomes of a measurement into the given bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The result of the measurement.
+    /// * `bits` - The bit in which to store the outcome of each measured qubit, in the order of
+    ///   `result.indices`.
+    pub(crate) fn record(&mut self, result: &MeasurementResult, bits: &[usize]) {
+        for (&bit, &outcome) in bits.iter().zip(&result.outcomes) {
+            self.set(bit, outcome);
+        }
+    }
+}
+
+impl fmt::Display for ClassicalRegister {
+    /// Formats the register as a bit string with the most significant bit first, as in OpenQASM.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for bit in self.bits.iter().rev() {
+            write!(f, "{}", bit)?;
+        }
+        Ok(())
+    }
+}
+
+/// A condition on classical bits, comparing them to an integer as in OpenQASM's `if (c == v)`.
+///
+/// # Fields
+///
+/// * `bits` - The indices of the compared bits, starting with the least significant bit.
+/// * `value` - The value the bits must have for the condition to hold.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClassicalCondition {
+    /// The indices of the compared bits, starting with the least significant bit.
+    pub bits: Vec<usize>,
+    /// The value the bits must have for the condition to hold.
+    pub value: u64,
+}
+
+impl ClassicalCondition {
+    /// Creates a new condition that holds when the given bits have the given value.
+    ///
+    /// # Arguments
+    ///
+    /// * `bits` - The indices of the compared bits, starting with the least significant bit.
+    /// * `value` - The value the bits must have, where the bit at `bits[k]` contributes `2^k`.
+    ///
+    /// # Returns
+    ///
+    /// * `ClassicalCondition` - A new instance of the ClassicalCondition struct.
+    pub fn new(bits: Vec<usize>, value: u64) -> Self {
+        ClassicalCondition { bits, value }
+    }
+
+    /// Creates a new condition on a single bit.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit` - The index of the bit.
+    /// * `value` - The value the bit must have.
+    ///
+    /// # Returns
+    ///
+    /// * `ClassicalCondition` - A new instance of the ClassicalCondition struct.
+    pub fn bit(bit: usize, value: bool) -> Self {
+        ClassicalCondition {
+            bits: vec![bit],
+            value: u64::from(value),
+        }
+    }
+
+    /// Creates a new condition on the first `num_bits` bits, as in `if (c == value)` for a register `c`.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_bits` - The number of bits of the register.
+    /// * `value` - The value the register must have.
+    ///
+    /// # Returns
+    ///
+    /// * `ClassicalCondition` - A new instance of the ClassicalCondition struct.
+    pub fn register(num_bits: usize, value: u64) -> Self {
+        ClassicalCondition {
+            bits: (0..num_bits).collect(),
+            value,
+        }
+    }
+
+    /// Checks whether the value of the condition can be represented by its bits.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the condition has at most 64 bits and its value fits into them.
+    pub fn is_valid(&self) -> bool {
+        self.bits.len() <= u64::BITS as usize
+            && (self.bits.len() == u64::BITS as usize || self.value >> self.bits.len() == 0)
+    }
+
+    /// Checks whether the condition holds for the given register.
+    ///
+    /// # Arguments
+    ///
+    /// * `register` - The classical register to check.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the bits of the register have the value of the condition.
+    pub fn is_satisfied(&self, register: &ClassicalRegister) -> bool {
+        self.is_valid() && register.value_of(&self.bits) == self.value
+    }
+}
+
+/// Represents a classically controlled gate in a quantum circuit.
+///
+/// This is an internal struct strictly used for the IR representation of a classically controlled gate.
+#[derive(Clone)]
+pub(crate) struct ConditionalOperation {
+    /// The condition on the classical bits.
+    pub condition: ClassicalCondition,
+    /// The gate to apply if the condition holds.
+    pub gate: CompilableGate,
+}
+
+impl Compilable for ConditionalOperation {
+    fn to_ir(&self, _targets: Vec<usize>, _controls: Vec<usize>) -> Vec<InstructionIR> {
+        // The qubits are those of the wrapped gate
+        self.gate
+            .operator
+            .to_ir(self.gate.targets.clone(), self.gate.controls.clone())
+            .into_iter()
+            .map(|instruction| InstructionIR::Conditional(self.condition.clone(), Box::new(instruction)))
+            .collect()
+    }
+}
@@ -0,0 +1,251 @@
+use crate::{
+    components::{classical::ClassicalRegister, operator::Pauli, pauli_string::SumOp},
+    errors::Error,
+};
+use num_complex::Complex;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A histogram of the values of a classical register over repeated executions of a circuit.
+///
+/// Bitstrings are written with the most significant bit first, as in OpenQASM, so bit `k` of the register is the
+/// character at position `num_bits - 1 - k`.
+///
+/// # Fields
+///
+/// * `num_bits` - The number of bits in each bitstring.
+/// * `counts` - The number of times each bitstring was observed.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Counts {
+    /// The number of bits in each bitstring.
+    num_bits: usize,
+    /// The number of times each bitstring was observed, sorted by bitstring.
+    counts: BTreeMap<String, usize>,
+}
+
+impl Counts {
+    /// Creates a new empty histogram.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_bits` - The number of bits in each bitstring.
+    ///
+    /// # Returns
+    ///
+    /// * `Counts` - A new instance of the Counts struct.
+    pub fn new(num_bits: usize) -> Self {
+        Counts {
+            num_bits,
+            counts: BTreeMap::new(),
+        }
+    }
+
+    /// Gets the number of bits in each bitstring.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The number of bits in each bitstring.
+    pub fn num_bits(&self) -> usize {
+        self.num_bits
+    }
+
+    /// Gets the total number of shots in the histogram.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The sum of the counts of all bitstrings.
+    pub fn shots(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Gets the number of times a bitstring was observed.
+    ///
+    /// # Arguments
+    ///
+    /// * `bitstring` - The bitstring, with the most significant bit first.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The count of the bitstring, or 0 if it was never observed.
+    pub fn get(&self, bitstring: &str) -> usize {
+        self.counts.get(bitstring).copied().unwrap_or(0)
+    }
+
+    /// Gets the number of distinct bitstrings that were observed.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The number of distinct bitstrings.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Checks whether no shots have been recorded.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the histogram is empty.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Iterates over the observed bitstrings and their counts, sorted by bitstring.
+    ///
+    /// # Returns
+    ///
+    /// * `impl Iterator<Item = (&str, usize)>` - The bitstrings and their counts.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.counts.iter().map(|(bitstring, &count)| (bitstring.as_str(), count))
+    }
+
+    /// Adds observations of a bitstring to the histogram.
+    ///
+    /// # Arguments
+    ///
+    /// * `bitstring` - The bitstring, with the most significant bit first.
+    /// * `count` - The number of observations to add.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the bitstring does not have `num_bits` characters that are all `0` or `1`.
+    pub fn add(&mut self, bitstring: &str, count: usize) -> Result<(), Error> {
+        if bitstring.len() != self.num_bits || !bitstring.chars().all(|c| c == '0' || c == '1') {
+            return Err(Error::InvalidBitString(bitstring.to_string(), self.num_bits));
+        }
+        if count > 0 {
+            *self.counts.entry(bitstring.to_string()).or_insert(0) += count;
+        }
+        Ok(())
+    }
+
+    /// Records observations of the value of a classical register.
+    ///
+    /// # Arguments
+    ///
+    /// * `register` - The register, which must have `num_bits` bits.
+    /// * `count` - The number of observations to add.
+    pub(crate) fn record(&mut self, register: &ClassicalRegister, count: usize) {
+        *self.counts.entry(register.to_string()).or_insert(0) += count;
+    }
+
+    /// Gets the most frequently observed bitstring.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<(&str, usize)>` - The bitstring with the highest count and its count, or `None` if the histogram is
+    ///   empty. Ties are broken in favour of the smallest bitstring.
+    pub fn most_frequent(&self) -> Option<(&str, usize)> {
+        self.iter().fold(None, |best, (bitstring, count)| match best {
+            Some((_, best_count)) if best_count >= count => best,
+            _ => Some((bitstring, count)),
+        })
+    }
+
+    /// Marginalises the histogram onto a subset of the bits, summing the counts of bitstrings that agree on them.
+    ///
+    /// # Arguments
+    ///
+    /// * `bits` - The indices of the bits to keep. Bit `bits[k]` becomes bit `k` of the marginal bitstrings.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Counts, Error>` - The histogram of the kept bits.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if a bit index is out of range.
+    pub fn marginalise(&self, bits: &[usize]) -> Result<Counts, Error> {
+        if let Some(&bit) = bits.iter().find(|&&bit| bit >= self.num_bits) {
+            return Err(Error::InvalidBitIndex(bit, self.num_bits));
+        }
+
+        let mut marginal: Counts = Counts::new(bits.len());
+        for (bitstring, count) in self.iter() {
+            let characters: &[u8] = bitstring.as_bytes();
+            let key: String = bits
+                .iter()
+                .rev()
+                .map(|&bit| characters[self.num_bits - 1 - bit] as char)
+                .collect();
+            *marginal.counts.entry(key).or_insert(0) += count;
+        }
+        Ok(marginal)
+    }
+
+    /// Converts the counts to relative frequencies.
+    ///
+    /// # Returns
+    ///
+    /// * `BTreeMap<String, f64>` - The fraction of shots in which each observed bitstring occurred.
+    pub fn probabilities(&self) -> BTreeMap<String, f64> {
+        let shots: f64 = self.shots() as f64;
+        self.counts
+            .iter()
+            .map(|(bitstring, &count)| (bitstring.clone(), count as f64 / shots))
+            .collect()
+    }
+
+    /// Estimates the expectation value of an observable that is diagonal in the computational basis.
+    ///
+    /// Each term of the observable must be a product of Z operators, where a Z on qubit `k` is read from bit `k` as
+    /// (-1)^b. This matches the bits written by measurements in the computational basis without explicit bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `observable` - The observable, as a sum of Pauli strings containing only Z operators.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Complex<f64>, Error>` - The mean of the observable over all shots.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the histogram is empty.
+    /// * Returns an error if a term contains an X or Y operator.
+    /// * Returns an error if a term acts on a qubit without a corresponding bit.
+    pub fn expectation(&self, observable: &SumOp) -> Result<Complex<f64>, Error> {
+        if self.is_empty() {
+            return Err(Error::InvalidNumberOfMeasurements(0));
+        }
+
+        // Each term is a coefficient times the parity of the characters of its bits
+        let mut terms: Vec<(Complex<f64>, Vec<usize>)> = Vec::with_capacity(observable.num_terms());
+        for term in &observable.terms {
+            let mut positions: Vec<usize> = Vec::with_capacity(term.ops().len());
+            for (&qubit, op) in term.ops() {
+                if *op != Pauli::Z {
+                    return Err(Error::NonDiagonalObservable(term.to_string()));
+                }
+                if qubit >= self.num_bits {
+                    return Err(Error::InvalidBitIndex(qubit, self.num_bits));
+                }
+                positions.push(self.num_bits - 1 - qubit);
+            }
+            terms.push((term.coefficient(), positions));
+        }
+
+        let mut total: Complex<f64> = Complex::new(0.0, 0.0);
+        for (bitstring, count) in self.iter() {
+            let characters: &[u8] = bitstring.as_bytes();
+            let eigenvalue: Complex<f64> = terms
+                .iter()
+                .map(|(coefficient, positions)| {
+                    let ones: usize = positions.iter().filter(|&&position| characters[position] == b'1').count();
+                    if ones.is_multiple_of(2) { *coefficient } else { -*coefficient }
+                })
+                .sum();
+            total += eigenvalue * count as f64;
+        }
+        Ok(total / self.shots() as f64)
+    }
+}
+
+impl fmt::Display for Counts {
+    /// Formats the histogram with one `bitstring: count` line per observed bitstring.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (bitstring, count) in self.iter() {
+            writeln!(f, "{}: {}", bitstring, count)?;
+        }
+        Ok(())
+    }
+}
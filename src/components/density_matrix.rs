@@ -0,0 +1,871 @@
+use crate::{
+    components::{
+        channel::Channel,
+        gate::Gate,
+        measurement::MeasurementBasis,
+        operator::{Operator, PARALLEL_THRESHOLD_NUM_QUBITS, Pauli},
+        pauli_string::SumOp,
+        state::State,
+    },
+    errors::Error,
+};
+use num_complex::Complex;
+use rand::Rng;
+use rayon::prelude::*;
+use std::ops::{Add, Mul};
+
+/// The tolerance used to check that a matrix is Hermitian and has unit trace.
+const TOLERANCE: f64 = 1e-10;
+
+/// Represents the mixed state of a quantum register as a density matrix.
+///
+/// The density matrix ρ = Σ_i p_i |ψ_i⟩⟨ψ_i| describes an ensemble of pure states, and is needed to model thermal
+/// states, measurements whose outcomes are discarded and decoherence. Its dimension is 2^n × 2^n for n qubits, so it
+/// needs the square of the memory of a `State`.
+///
+/// As for `State`, qubit k is bit k of the row and column indices.
+#[derive(Debug, Clone)]
+pub struct DensityMatrix {
+    /// The elements of the density matrix in row-major order, so the element in row r and column c is at index
+    /// r * 2^n + c.
+    pub matrix: Vec<Complex<f64>>,
+
+    /// The number of qubits in the system.
+    pub num_qubits: usize,
+}
+
+// Helper function to decide whether to process a density matrix in parallel. A density matrix on n qubits has as many
+// elements as a state vector on 2n qubits, which is compared with the threshold of the operator kernels
+pub(crate) fn is_parallel(num_qubits: usize) -> bool {
+    2 * num_qubits >= PARALLEL_THRESHOLD_NUM_QUBITS
+}
+
+// Helper function to update each row of a row-major matrix from its index, in parallel for large matrices
+pub(crate) fn for_each_row<F>(matrix: &mut [Complex<f64>], num_qubits: usize, update: F)
+where
+    F: Fn(usize, &mut [Complex<f64>]) + Send + Sync,
+{
+    let dim: usize = 1 << num_qubits;
+    if is_parallel(num_qubits) {
+        matrix.par_chunks_mut(dim).enumerate().for_each(|(r, row)| update(r, row));
+    } else {
+        matrix.chunks_mut(dim).enumerate().for_each(|(r, row)| update(r, row));
+    }
+}
+
+// Helper function to get the offset of each basis state of the target qubits, where bit j of the index of an offset
+// is the value of the qubit targets[j]
+fn target_offsets(targets: &[usize]) -> Vec<usize> {
+    (0..1usize << targets.len())
+        .map(|sub_index| {
+            targets
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| (sub_index >> j) & 1 == 1)
+                .fold(0, |offset, (_, &target)| offset | (1 << target))
+        })
+        .collect()
+}
+
+// Helper function to get the value of the target qubits in a basis state, where bit j is the value of targets[j]
+fn sub_index(index: usize, targets: &[usize]) -> usize {
+    targets
+        .iter()
+        .enumerate()
+        .fold(0, |sub_index, (j, &target)| sub_index | (((index >> target) & 1) << j))
+}
+
+// Helper function to get the single-qubit unitary that maps the measurement basis onto the computational basis
+fn basis_rotation(basis: MeasurementBasis) -> Option<[[Complex<f64>; 2]; 2]> {
+    let s: f64 = 1.0 / 2.0_f64.sqrt();
+    match basis {
+        MeasurementBasis::Computational => None,
+        MeasurementBasis::X => Some([
+            [Complex::new(s, 0.0), Complex::new(s, 0.0)],
+            [Complex::new(s, 0.0), Complex::new(-s, 0.0)],
+        ]),
+        // H S†, as in `State::measure`
+        MeasurementBasis::Y => Some([
+            [Complex::new(s, 0.0), Complex::new(0.0, -s)],
+            [Complex::new(s, 0.0), Complex::new(0.0, s)],
+        ]),
+        MeasurementBasis::Custom(unitary) => Some(unitary),
+    }
+}
+
+impl DensityMatrix {
+    /// Creates a new density matrix from its elements.
+    ///
+    /// The matrix must be Hermitian with unit trace. Positive semi-definiteness is not checked.
+    ///
+    /// # Arguments
+    ///
+    /// * `matrix` - The density matrix as a vector of rows.
+    ///
+    /// # Returns
+    ///
+    /// * `density_matrix` - A result containing the density matrix if successful, or an error if the matrix is invalid.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the matrix is empty.
+    /// * Returns an error if the matrix is not square with a power-of-two dimension.
+    /// * Returns an error if the matrix is not Hermitian.
+    /// * Returns an error if the trace of the matrix is not 1.
+    pub fn new(matrix: Vec<Vec<Complex<f64>>>) -> Result<Self, Error> {
+        let dim: usize = matrix.len();
+        if dim == 0 {
+            return Err(Error::InvalidNumberOfQubits(0));
+        }
+        if let Some(row) = matrix.iter().find(|row| row.len() != dim) {
+            return Err(Error::InvalidMatrixDimensions(dim, row.len()));
+        }
+        if !dim.is_power_of_two() {
+            return Err(Error::InvalidMatrixDimensions(dim, dim));
+        }
+
+        let hermitian: bool = matrix
+            .iter()
+            .enumerate()
+            .all(|(r, row)| {
+                row.iter().enumerate().skip(r).all(|(c, &entry)| (entry - matrix[c][r].conj()).norm() <= TOLERANCE)
+            });
+        if !hermitian {
+            return Err(Error::NonHermitianMatrix);
+        }
+        let density_matrix: DensityMatrix = DensityMatrix {
+            matrix: matrix.into_iter().flatten().collect(),
+            num_qubits: dim.trailing_zeros() as usize,
+        };
+        if (density_matrix.trace().re - 1.0).abs() > TOLERANCE {
+            return Err(Error::DensityMatrixNotNormalised);
+        }
+        Ok(density_matrix)
+    }
+
+    /// Creates the density matrix |ψ⟩⟨ψ| of a pure state.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The pure state |ψ⟩.
+    ///
+    /// # Returns
+    ///
+    /// * `density_matrix` - The density matrix of the state.
+    pub fn from_state(state: &State) -> Self {
+        let dim: usize = state.state_vector.len();
+        let mut matrix: Vec<Complex<f64>> = vec![Complex::new(0.0, 0.0); dim * dim];
+        for_each_row(&mut matrix, state.num_qubits, |r, row| {
+            let amplitude: Complex<f64> = state.state_vector[r];
+            for (element, other) in row.iter_mut().zip(&state.state_vector) {
+                *element = amplitude * other.conj();
+            }
+        });
+        DensityMatrix {
+            matrix,
+            num_qubits: state.num_qubits,
+        }
+    }
+
+    /// Creates the density matrix Σ_i p_i |ψ_i⟩⟨ψ_i| of an ensemble of pure states.
+    ///
+    /// # Arguments
+    ///
+    /// * `ensemble` - The probability p_i of each pure state |ψ_i⟩.
+    ///
+    /// # Returns
+    ///
+    /// * `density_matrix` - A result containing the density matrix if successful, or an error if the ensemble is invalid.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the ensemble is empty.
+    /// * Returns an error if the states have different numbers of qubits.
+    /// * Returns an error if a probability is negative, or the probabilities do not sum to 1.
+    pub fn from_ensemble(ensemble: &[(f64, State)]) -> Result<Self, Error> {
+        let Some((first_probability, first)) = ensemble.first() else {
+            return Err(Error::InvalidNumberOfInputs(0, 1));
+        };
+        if let Some((_, state)) = ensemble.iter().find(|(_, state)| state.num_qubits != first.num_qubits) {
+            return Err(Error::InvalidNumberOfQubits(state.num_qubits));
+        }
+        if let Some(&(probability, _)) = ensemble.iter().find(|(probability, _)| *probability < 0.0) {
+            return Err(Error::InvalidProbability(probability));
+        }
+        let total: f64 = ensemble.iter().map(|(probability, _)| probability).sum();
+        if (total - 1.0).abs() > TOLERANCE {
+            return Err(Error::DensityMatrixNotNormalised);
+        }
+
+        Ok(ensemble[1..].iter().fold(
+            DensityMatrix::from_state(first) * *first_probability,
+            |sum, (probability, state)| sum + DensityMatrix::from_state(state) * *probability,
+        ))
+    }
+
+    /// Creates a density matrix with the given number of qubits in the pure state |0...0⟩.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_qubits` - The number of qubits in the system.
+    ///
+    /// # Returns
+    ///
+    /// * `density_matrix` - A result containing the density matrix if successful, or an error if the number of qubits
+    ///   is invalid.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the number of qubits is 0.
+    pub fn new_zero(num_qubits: usize) -> Result<Self, Error> {
+        State::new_zero(num_qubits).map(|state| DensityMatrix::from_state(&state))
+    }
+
+    /// Creates the maximally mixed state I / 2^n with the given number of qubits.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_qubits` - The number of qubits in the system.
+    ///
+    /// # Returns
+    ///
+    /// * `density_matrix` - A result containing the density matrix if successful, or an error if the number of qubits
+    ///   is invalid.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the number of qubits is 0.
+    pub fn maximally_mixed(num_qubits: usize) -> Result<Self, Error> {
+        if num_qubits == 0 {
+            return Err(Error::InvalidNumberOfQubits(0));
+        }
+        let dim: usize = 1 << num_qubits;
+        let mut matrix: Vec<Complex<f64>> = vec![Complex::new(0.0, 0.0); dim * dim];
+        for i in 0..dim {
+            matrix[i * dim + i] = Complex::new(1.0 / dim as f64, 0.0);
+        }
+        Ok(DensityMatrix { matrix, num_qubits })
+    }
+
+    /// Returns the number of qubits of the density matrix.
+    ///
+    /// # Returns
+    ///
+    /// * `num_qubits` - The number of qubits of the density matrix.
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    /// Returns the element in the given row and column of the density matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The index of the row.
+    /// * `column` - The index of the column.
+    ///
+    /// # Returns
+    ///
+    /// * `element` - The element ⟨row|ρ|column⟩.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the row or column is out of bounds.
+    pub fn element(&self, row: usize, column: usize) -> Result<Complex<f64>, Error> {
+        let dim: usize = 1 << self.num_qubits;
+        for index in [row, column] {
+            if index >= dim {
+                return Err(Error::InvalidQubitIndex(index, self.num_qubits));
+            }
+        }
+        Ok(self.matrix[row * dim + column])
+    }
+
+    /// Returns the density matrix as a vector of rows.
+    ///
+    /// # Returns
+    ///
+    /// * `matrix` - The 2^n×2^n density matrix.
+    pub fn to_matrix(&self) -> Vec<Vec<Complex<f64>>> {
+        self.matrix.chunks(1 << self.num_qubits).map(|row| row.to_vec()).collect()
+    }
+
+    /// Returns the probability of the basis state at index `n`, the diagonal element ⟨n|ρ|n⟩.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The index of the basis state.
+    ///
+    /// # Returns
+    ///
+    /// * `probability` - The probability of the basis state at index `n`.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if `n` is out of bounds.
+    pub fn probability(&self, n: usize) -> Result<f64, Error> {
+        self.element(n, n).map(|element| element.re)
+    }
+
+    /// Returns the trace of the density matrix, which is 1 for a physical state.
+    ///
+    /// # Returns
+    ///
+    /// * `trace` - The sum of the diagonal elements.
+    pub fn trace(&self) -> Complex<f64> {
+        let dim: usize = 1 << self.num_qubits;
+        if is_parallel(self.num_qubits) {
+            (0..dim).into_par_iter().map(|i| self.matrix[i * dim + i]).sum()
+        } else {
+            (0..dim).map(|i| self.matrix[i * dim + i]).sum()
+        }
+    }
+
+    /// Returns the purity Tr(ρ²) of the density matrix.
+    ///
+    /// The purity is 1 for pure states, and 1 / 2^n for the maximally mixed state.
+    ///
+    /// # Returns
+    ///
+    /// * `purity` - The purity of the density matrix.
+    pub fn purity(&self) -> f64 {
+        // Tr(ρ²) = Σ_rc |ρ_rc|² for a Hermitian matrix
+        if is_parallel(self.num_qubits) {
+            self.matrix.par_iter().map(|element| element.norm_sqr()).sum()
+        } else {
+            self.matrix.iter().map(|element| element.norm_sqr()).sum()
+        }
+    }
+
+    /// Returns the fidelity ⟨ψ|ρ|ψ⟩ of the density matrix with a pure state.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The pure state |ψ⟩.
+    ///
+    /// # Returns
+    ///
+    /// * `fidelity` - The fidelity, which is 1 if the density matrix is |ψ⟩⟨ψ|.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the state has a different number of qubits.
+    pub fn fidelity(&self, state: &State) -> Result<f64, Error> {
+        if state.num_qubits != self.num_qubits {
+            return Err(Error::InvalidNumberOfQubits(state.num_qubits));
+        }
+        let dim: usize = 1 << self.num_qubits;
+        let row_term = |(row, amplitude): (&[Complex<f64>], &Complex<f64>)| {
+            let product: Complex<f64> =
+                row.iter().zip(&state.state_vector).map(|(element, other)| element * other).sum();
+            amplitude.conj() * product
+        };
+        let fidelity: Complex<f64> = if is_parallel(self.num_qubits) {
+            self.matrix.par_chunks(dim).zip(state.state_vector.par_iter()).map(row_term).sum()
+        } else {
+            self.matrix.chunks(dim).zip(&state.state_vector).map(row_term).sum()
+        };
+        Ok(fidelity.re)
+    }
+
+    // Helper function to validate the target and control qubits of an operation
+    fn _validate_qubits(&self, target_qubits: &[usize], control_qubits: &[usize]) -> Result<(), Error> {
+        for &qubit in target_qubits.iter().chain(control_qubits) {
+            if qubit >= self.num_qubits {
+                return Err(Error::InvalidQubitIndex(qubit, self.num_qubits));
+            }
+        }
+        // Duplicate targets are reported as invalid indices, as in `operator.rs`
+        for (i, &target) in target_qubits.iter().enumerate() {
+            if target_qubits[..i].contains(&target) {
+                return Err(Error::InvalidQubitIndex(target, self.num_qubits));
+            }
+        }
+        if let Some(&control) = control_qubits.iter().find(|control| target_qubits.contains(control)) {
+            return Err(Error::OverlappingControlAndTargetQubits(control, control));
+        }
+        Ok(())
+    }
+
+    /// Computes K ρ K† for a matrix K acting on the target qubits, controlled on the control qubits.
+    ///
+    /// The qubits must be valid, and K must be a 2^k×2^k matrix for k target qubits, where bit j of the row and
+    /// column indices corresponds to `target_qubits[j]`. K does not need to be unitary, so this also applies single
+    /// Kraus operators.
+    pub(crate) fn conjugate_by(
+        &self,
+        matrix: &[Vec<Complex<f64>>],
+        target_qubits: &[usize],
+        control_qubits: &[usize],
+    ) -> DensityMatrix {
+        let dim: usize = 1 << self.num_qubits;
+        let offsets: Vec<usize> = target_offsets(target_qubits);
+        let target_mask: usize = offsets[offsets.len() - 1];
+        let control_mask: usize = control_qubits.iter().fold(0, |mask, &control| mask | (1 << control));
+        let zero: Complex<f64> = Complex::new(0.0, 0.0);
+
+        // Left multiplication by K: row r of the result combines the rows of ρ that differ from r on the targets
+        let mut left: Vec<Complex<f64>> = vec![zero; dim * dim];
+        for_each_row(&mut left, self.num_qubits, |r, row| {
+            if r & control_mask != control_mask {
+                row.copy_from_slice(&self.matrix[r * dim..(r + 1) * dim]);
+                return;
+            }
+            let base: usize = r & !target_mask;
+            for (&coefficient, &offset) in matrix[sub_index(r, target_qubits)].iter().zip(&offsets) {
+                if coefficient == zero {
+                    continue;
+                }
+                let source_row: usize = base | offset;
+                let source: &[Complex<f64>] = &self.matrix[source_row * dim..(source_row + 1) * dim];
+                for (element, &other) in row.iter_mut().zip(source) {
+                    *element += coefficient * other;
+                }
+            }
+        });
+
+        // Right multiplication by K†: element c of each row combines the elements that differ from c on the targets
+        let mut result: Vec<Complex<f64>> = vec![zero; dim * dim];
+        for_each_row(&mut result, self.num_qubits, |r, row| {
+            let source: &[Complex<f64>] = &left[r * dim..(r + 1) * dim];
+            for (c, element) in row.iter_mut().enumerate() {
+                if c & control_mask != control_mask {
+                    *element = source[c];
+                    continue;
+                }
+                let base: usize = c & !target_mask;
+                *element = matrix[sub_index(c, target_qubits)]
+                    .iter()
+                    .zip(&offsets)
+                    .map(|(coefficient, &offset)| coefficient.conj() * source[base | offset])
+                    .sum();
+            }
+        });
+
+        DensityMatrix {
+            matrix: result,
+            num_qubits: self.num_qubits,
+        }
+    }
+
+    /// Applies an operator to the target qubits, using the control qubits if required, as ρ → U ρ U†.
+    ///
+    /// The operator is applied through its matrix, so every built-in operator and any custom operator implementing
+    /// `Operator::matrix` is supported.
+    ///
+    /// # Arguments
+    ///
+    /// * `operator` - The operator U to apply.
+    /// * `target_qubits` - The target qubits of the operator, one per qubit of its matrix.
+    /// * `control_qubits` - The control qubits of the operator.
+    ///
+    /// # Returns
+    ///
+    /// * `density_matrix` - The density matrix after applying the operator.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the matrix of the operator is not known.
+    /// * Returns an error if the number of target qubits does not match the matrix of the operator.
+    /// * Returns an error if a qubit index is invalid, or the target and control qubits overlap.
+    pub fn apply_operator(
+        &self,
+        operator: &dyn Operator,
+        target_qubits: &[usize],
+        control_qubits: &[usize],
+    ) -> Result<DensityMatrix, Error> {
+        let matrix: Vec<Vec<Complex<f64>>> = operator.matrix().ok_or(Error::UnknownMatrix)?;
+        if matrix.len() != 1 << target_qubits.len() {
+            return Err(Error::InvalidNumberOfQubits(target_qubits.len()));
+        }
+        self._validate_qubits(target_qubits, control_qubits)?;
+        Ok(self.conjugate_by(&matrix, target_qubits, control_qubits))
+    }
+
+    /// Applies a channel to the target qubits exactly, as ρ → Σ_i K_i ρ K_i†.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The channel to apply.
+    /// * `target_qubits` - The target qubits of the channel, one per qubit of its Kraus operators.
+    ///
+    /// # Returns
+    ///
+    /// * `density_matrix` - The density matrix after applying the channel.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the number of target qubits does not match the channel.
+    /// * Returns an error if a target qubit index is invalid, or the target qubits are not different.
+    pub fn apply_channel(&self, channel: &Channel, target_qubits: &[usize]) -> Result<DensityMatrix, Error> {
+        if target_qubits.len() != channel.num_qubits() {
+            return Err(Error::InvalidNumberOfQubits(target_qubits.len()));
+        }
+        self._validate_qubits(target_qubits, &[])?;
+        Ok(channel
+            .kraus_operators()
+            .iter()
+            .map(|kraus| self.conjugate_by(kraus, target_qubits, &[]))
+            .reduce(|sum, term| sum + term)
+            .expect("a channel has at least one Kraus operator"))
+    }
+
+    /// Applies a gate to the density matrix.
+    ///
+    /// Measurements are non-selective, so the outcomes are discarded and the result is the mixture of all outcomes.
+    /// Resets return the qubits to |0⟩ without measuring them.
+    ///
+    /// # Arguments
+    ///
+    /// * `gate` - The gate to apply.
+    ///
+    /// # Returns
+    ///
+    /// * `density_matrix` - The density matrix after applying the gate.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the gate is classically controlled. Use `Circuit::execute_density_matrix` for circuits
+    ///   with classically controlled gates.
+    /// * Returns an error if the gate cannot be applied to the density matrix.
+    pub fn apply_gate(&self, gate: &Gate) -> Result<DensityMatrix, Error> {
+        match gate {
+            Gate::Operator(operator, target_qubits, control_qubits) => {
+                self.apply_operator(operator.as_ref(), target_qubits, control_qubits)
+            }
+            Gate::Measurement(basis, qubits) | Gate::MeasurementInto(basis, qubits, _) => {
+                self.measure_non_selective(*basis, qubits)
+            }
+            Gate::Reset(qubits) => self.reset(qubits),
+            Gate::Barrier(_) => Ok(self.clone()),
+            Gate::Conditional(_, _) => Err(Error::MissingClassicalRegister),
+        }
+    }
+
+    /// Resets the given qubits to |0⟩, as ρ → Σ_o |0⟩⟨o| ρ |o⟩⟨0| for each qubit.
+    ///
+    /// # Arguments
+    ///
+    /// * `qubits` - The qubits to reset. If `qubits` is empty, all qubits are reset.
+    ///
+    /// # Returns
+    ///
+    /// * `density_matrix` - The density matrix after the reset.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if a qubit index is invalid.
+    pub fn reset(&self, qubits: &[usize]) -> Result<DensityMatrix, Error> {
+        let qubits: Vec<usize> = self._measured_qubits(qubits)?;
+        let zero: Complex<f64> = Complex::new(0.0, 0.0);
+        let one: Complex<f64> = Complex::new(1.0, 0.0);
+        let keep: Vec<Vec<Complex<f64>>> = vec![vec![one, zero], vec![zero, zero]];
+        let lower: Vec<Vec<Complex<f64>>> = vec![vec![zero, one], vec![zero, zero]];
+        Ok(qubits.iter().fold(self.clone(), |density_matrix, &qubit| {
+            density_matrix.conjugate_by(&keep, &[qubit], &[]) + density_matrix.conjugate_by(&lower, &[qubit], &[])
+        }))
+    }
+
+    // Helper function to validate measured qubits, where an empty list measures all qubits
+    fn _measured_qubits(&self, qubits: &[usize]) -> Result<Vec<usize>, Error> {
+        if qubits.is_empty() {
+            return Ok((0..self.num_qubits).collect());
+        }
+        self._validate_qubits(qubits, &[])?;
+        Ok(qubits.to_vec())
+    }
+
+    // Helper function to rotate the measured qubits from the measurement basis onto the computational basis, or back
+    fn _rotate(&self, basis: MeasurementBasis, qubits: &[usize], inverse: bool) -> DensityMatrix {
+        let Some(rotation) = basis_rotation(basis) else {
+            return self.clone();
+        };
+        let matrix: Vec<Vec<Complex<f64>>> = if inverse {
+            (0..2).map(|r| (0..2).map(|c| rotation[c][r].conj()).collect()).collect()
+        } else {
+            rotation.iter().map(|row| row.to_vec()).collect()
+        };
+        qubits
+            .iter()
+            .fold(self.clone(), |density_matrix, &qubit| density_matrix.conjugate_by(&matrix, &[qubit], &[]))
+    }
+
+    /// Projects the given qubits onto a computational basis state, as ρ → P ρ P, without normalising the result.
+    ///
+    /// The trace of the result is the probability of the outcome. The qubits must be valid.
+    pub(crate) fn project(&self, qubits: &[usize], outcome: usize) -> DensityMatrix {
+        let zero: Complex<f64> = Complex::new(0.0, 0.0);
+        let mut matrix: Vec<Complex<f64>> = self.matrix.clone();
+        for_each_row(&mut matrix, self.num_qubits, |r, row| {
+            if sub_index(r, qubits) != outcome {
+                row.fill(zero);
+                return;
+            }
+            for (c, element) in row.iter_mut().enumerate() {
+                if sub_index(c, qubits) != outcome {
+                    *element = zero;
+                }
+            }
+        });
+        DensityMatrix {
+            matrix,
+            num_qubits: self.num_qubits,
+        }
+    }
+
+    /// Projects the given qubits onto each outcome of a measurement, without normalising the results.
+    ///
+    /// # Returns
+    ///
+    /// The unnormalised density matrix of each outcome with a non-zero probability, where bit k of the outcome is the
+    /// value of `qubits[k]`. The qubits must be valid.
+    pub(crate) fn measurement_branches(
+        &self,
+        basis: MeasurementBasis,
+        qubits: &[usize],
+    ) -> Vec<(usize, DensityMatrix)> {
+        let rotated: DensityMatrix = self._rotate(basis, qubits, false);
+        let probabilities: Vec<f64> = rotated._marginal_probabilities(qubits);
+        probabilities
+            .iter()
+            .enumerate()
+            .filter(|&(_, &probability)| probability > 0.0)
+            .map(|(outcome, _)| (outcome, rotated.project(qubits, outcome)._rotate(basis, qubits, true)))
+            .collect()
+    }
+
+    // Helper function to calculate the probability of each outcome of measuring the given qubits in the computational
+    // basis, where bit k of the outcome is the value of qubits[k]
+    fn _marginal_probabilities(&self, qubits: &[usize]) -> Vec<f64> {
+        let dim: usize = 1 << self.num_qubits;
+        let mut probabilities: Vec<f64> = vec![0.0; 1 << qubits.len()];
+        for i in 0..dim {
+            probabilities[sub_index(i, qubits)] += self.matrix[i * dim + i].re;
+        }
+        probabilities
+    }
+
+    /// Returns the probability of each outcome of measuring the given qubits in the specified basis.
+    ///
+    /// # Arguments
+    ///
+    /// * `basis` - The basis to measure in.
+    /// * `qubits` - The qubits to measure. If `qubits` is empty, all qubits are measured.
+    ///
+    /// # Returns
+    ///
+    /// * `probabilities` - The probability of each outcome, where bit k of the outcome is the value of `qubits[k]`.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if a qubit index is invalid.
+    pub fn measurement_probabilities(&self, basis: MeasurementBasis, qubits: &[usize]) -> Result<Vec<f64>, Error> {
+        let qubits: Vec<usize> = self._measured_qubits(qubits)?;
+        Ok(self._rotate(basis, &qubits, false)._marginal_probabilities(&qubits))
+    }
+
+    /// Measures the given qubits without recording the outcome, as ρ → Σ_o P_o ρ P_o.
+    ///
+    /// This removes the coherences between the outcomes, and is the state of an ensemble of measured registers whose
+    /// outcomes are not known.
+    ///
+    /// # Arguments
+    ///
+    /// * `basis` - The basis to measure in.
+    /// * `qubits` - The qubits to measure. If `qubits` is empty, all qubits are measured.
+    ///
+    /// # Returns
+    ///
+    /// * `density_matrix` - The density matrix after the measurement.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if a qubit index is invalid.
+    pub fn measure_non_selective(&self, basis: MeasurementBasis, qubits: &[usize]) -> Result<DensityMatrix, Error> {
+        let qubits: Vec<usize> = self._measured_qubits(qubits)?;
+        let mask: usize = qubits.iter().fold(0, |mask, &qubit| mask | (1 << qubit));
+
+        let mut rotated: DensityMatrix = self._rotate(basis, &qubits, false);
+        for_each_row(&mut rotated.matrix, self.num_qubits, |r, row| {
+            for (c, element) in row.iter_mut().enumerate() {
+                if (r ^ c) & mask != 0 {
+                    *element = Complex::new(0.0, 0.0);
+                }
+            }
+        });
+        Ok(rotated._rotate(basis, &qubits, true))
+    }
+
+    /// Measures the given qubits, and returns the outcomes and the collapsed density matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `basis` - The basis to measure in.
+    /// * `qubits` - The qubits to measure. If `qubits` is empty, all qubits are measured.
+    ///
+    /// # Returns
+    ///
+    /// * `(outcomes, density_matrix)` - The outcome of each measured qubit, and the normalised density matrix after
+    ///   the measurement.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if a qubit index is invalid.
+    pub fn measure(&self, basis: MeasurementBasis, qubits: &[usize]) -> Result<(Vec<u8>, DensityMatrix), Error> {
+        self.measure_with_rng(basis, qubits, &mut rand::rng())
+    }
+
+    /// Measures the given qubits using the given random number generator, and returns the outcomes and the collapsed
+    /// density matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `basis` - The basis to measure in.
+    /// * `qubits` - The qubits to measure. If `qubits` is empty, all qubits are measured.
+    /// * `rng` - The random number generator used to sample the outcome.
+    ///
+    /// # Returns
+    ///
+    /// * `(outcomes, density_matrix)` - The outcome of each measured qubit, and the normalised density matrix after
+    ///   the measurement.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if a qubit index is invalid.
+    pub fn measure_with_rng(
+        &self,
+        basis: MeasurementBasis,
+        qubits: &[usize],
+        rng: &mut impl Rng,
+    ) -> Result<(Vec<u8>, DensityMatrix), Error> {
+        let qubits: Vec<usize> = self._measured_qubits(qubits)?;
+        let rotated: DensityMatrix = self._rotate(basis, &qubits, false);
+        let probabilities: Vec<f64> = rotated._marginal_probabilities(&qubits);
+        let total: f64 = probabilities.iter().sum();
+        if total < f64::EPSILON {
+            return Err(Error::UnknownError);
+        }
+
+        // Sample an outcome, falling back to the last possible one in case of rounding errors
+        let random_value: f64 = rng.random_range(0.0..total);
+        let mut cumulative: f64 = 0.0;
+        let outcome: usize = probabilities
+            .iter()
+            .position(|&probability| {
+                cumulative += probability;
+                random_value < cumulative
+            })
+            .unwrap_or_else(|| probabilities.iter().rposition(|&probability| probability > 0.0).unwrap_or(0));
+
+        let collapsed: DensityMatrix = rotated.project(&qubits, outcome) * (1.0 / probabilities[outcome]);
+        let outcomes: Vec<u8> = (0..qubits.len()).map(|k| ((outcome >> k) & 1) as u8).collect();
+        Ok((outcomes, collapsed._rotate(basis, &qubits, true)))
+    }
+
+    /// Calculates the expectation value Tr(ρH) of a sum of Pauli strings.
+    ///
+    /// # Arguments
+    ///
+    /// * `observable` - The observable H.
+    ///
+    /// # Returns
+    ///
+    /// * `expectation_value` - The expectation value, which is real for a Hermitian observable.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if a Pauli string acts on a qubit outside the density matrix.
+    pub fn expectation_value(&self, observable: &SumOp) -> Result<Complex<f64>, Error> {
+        let dim: usize = 1 << self.num_qubits;
+        let mut total: Complex<f64> = Complex::new(0.0, 0.0);
+
+        for term in &observable.terms {
+            // P|c⟩ = i^(#Y) (-1)^(c·(Y|Z)) |c ⊕ (X|Y)⟩, so Tr(ρP) = Σ_c ρ[c][c ⊕ flip] phase(c)
+            let (mut flip_mask, mut sign_mask, mut num_y): (usize, usize, usize) = (0, 0, 0);
+            for (&qubit, op) in term.ops() {
+                if qubit >= self.num_qubits {
+                    return Err(Error::InvalidQubitIndex(qubit, self.num_qubits));
+                }
+                match op {
+                    Pauli::X => flip_mask |= 1 << qubit,
+                    Pauli::Y => {
+                        flip_mask |= 1 << qubit;
+                        sign_mask |= 1 << qubit;
+                        num_y += 1;
+                    }
+                    Pauli::Z => sign_mask |= 1 << qubit,
+                }
+            }
+
+            let signed_element = |c: usize| {
+                let element: Complex<f64> = self.matrix[c * dim + (c ^ flip_mask)];
+                if (c & sign_mask).count_ones().is_multiple_of(2) { element } else { -element }
+            };
+            let trace: Complex<f64> = if is_parallel(self.num_qubits) {
+                (0..dim).into_par_iter().map(signed_element).sum()
+            } else {
+                (0..dim).map(signed_element).sum()
+            };
+            total += term.coefficient() * Complex::new(0.0, 1.0).powu(num_y as u32) * trace;
+        }
+        Ok(total)
+    }
+}
+
+impl From<&State> for DensityMatrix {
+    fn from(state: &State) -> Self {
+        DensityMatrix::from_state(state)
+    }
+}
+
+impl PartialEq for DensityMatrix {
+    fn eq(&self, other: &Self) -> bool {
+        // Check if each element is approximately equal within epsilon, as for `State`
+        self.num_qubits == other.num_qubits
+            && self.matrix.iter().zip(&other.matrix).all(|(a, b)| {
+                (a.re - b.re).abs() <= f32::EPSILON.into() && (a.im - b.im).abs() <= f32::EPSILON.into()
+            })
+    }
+}
+
+// Implement multiplication by f64
+impl Mul<f64> for DensityMatrix {
+    type Output = Self;
+
+    /// Multiplies each element of the density matrix by a real scalar.
+    /// Note: This operation typically results in an unnormalised density matrix.
+    fn mul(self, rhs: f64) -> Self::Output {
+        DensityMatrix {
+            matrix: if is_parallel(self.num_qubits) {
+                self.matrix.into_par_iter().map(|element| element * rhs).collect()
+            } else {
+                self.matrix.into_iter().map(|element| element * rhs).collect()
+            },
+            num_qubits: self.num_qubits,
+        }
+    }
+}
+
+// Implement addition of density matrices
+impl Add<DensityMatrix> for DensityMatrix {
+    type Output = Self;
+
+    /// Adds two density matrices element-wise, as for mixing them with weights that sum to 1.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the density matrices have different numbers of qubits.
+    fn add(self, rhs: DensityMatrix) -> Self::Output {
+        assert_eq!(
+            self.num_qubits, rhs.num_qubits,
+            "Cannot add density matrices with different numbers of qubits"
+        );
+        DensityMatrix {
+            matrix: if is_parallel(self.num_qubits) {
+                self.matrix.into_par_iter().zip(rhs.matrix).map(|(a, b)| a + b).collect()
+            } else {
+                self.matrix.into_iter().zip(rhs.matrix).map(|(a, b)| a + b).collect()
+            },
+            num_qubits: self.num_qubits,
+        }
+    }
+}
@@ -1,16 +1,20 @@
 use crate::{
     components::{
-        measurement::MeasurementBasis,
+        classical::{ClassicalCondition, ClassicalRegister},
+        measurement::{MeasurementBasis, MeasurementResult},
         operator::{
-            CNOT, Hadamard, Identity, Operator, Pauli, PhaseS, PhaseSdag, PhaseShift, PhaseT,
-            PhaseTdag, RotateX, RotateY, RotateZ, SWAP, Toffoli, Unitary2
+            CNOT, Hadamard, Identity, Operator, ParameterisedKind, Pauli, PhaseS, PhaseSdag,
+            PhaseT, PhaseTdag, SWAP, Toffoli, Unitary2, Unitary4, UnitaryN
         },
+        parameter::{Parameter, ParameterExpression},
         state::State,
     },
     errors::Error,
 };
 
 use num_complex::Complex;
+use rand::Rng;
+use std::collections::HashMap;
 
 /// Represents a quantum gate as part of a quantum circuit.
 #[derive(Debug, Clone)]
@@ -28,11 +32,49 @@ pub enum Gate {
 
     /// Represents a measurement gate.
     ///
+    /// When executed as part of a circuit, the outcome for qubit `i` is recorded in classical bit `i`, if the
+    /// circuit has such a bit.
+    ///
     /// # Fields
     ///
     /// * `MeasurementBasis` - The basis of measurement (e.g., computational basis).
     /// * `indices` - The indices of the measured qubits.
     Measurement(MeasurementBasis, Vec<usize>),
+
+    /// Represents a measurement gate whose outcomes are recorded in the given classical bits.
+    ///
+    /// # Fields
+    ///
+    /// * `MeasurementBasis` - The basis of measurement (e.g., computational basis).
+    /// * `indices` - The indices of the measured qubits.
+    /// * `bits` - The indices of the classical bits in which the outcomes are recorded, one per measured qubit.
+    MeasurementInto(MeasurementBasis, Vec<usize>, Vec<usize>),
+
+    /// Represents a gate that is only applied if a condition on the classical bits holds.
+    ///
+    /// # Fields
+    ///
+    /// * `condition` - The condition on the classical bits.
+    /// * `gate` - The gate to apply if the condition holds.
+    Conditional(ClassicalCondition, Box<Gate>),
+
+    /// Represents a reset of qubits to the |0> state.
+    ///
+    /// The qubits are measured in the computational basis and flipped back to |0> if the outcome is 1. The outcomes
+    /// are not recorded.
+    ///
+    /// # Fields
+    ///
+    /// * `indices` - The indices of the qubits to reset.
+    Reset(Vec<usize>),
+
+    /// Represents a barrier, which has no effect on the state but prevents compiler passes from moving or merging
+    /// gates across it.
+    ///
+    /// # Fields
+    ///
+    /// * `indices` - The indices of the qubits covered by the barrier. If empty, the barrier covers every qubit.
+    Barrier(Vec<usize>),
 }
 
 impl Gate {
@@ -50,6 +92,65 @@ impl Gate {
         Gate::Measurement(basis, qubit_indices)
     }
 
+    /// Creates a new measurement gate that records its outcomes in the specified classical bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `qubit_indices` - The indices of the qubits to be measured.
+    /// * `bit_indices` - The indices of the classical bits in which the outcomes are recorded, one per qubit.
+    /// * `basis` - The basis of measurement (e.g., computational basis).
+    ///
+    /// # Returns
+    ///
+    /// * `Gate` - A new instance of the Gate struct representing a measurement gate.
+    pub fn new_measurement_into(
+        qubit_indices: Vec<usize>,
+        bit_indices: Vec<usize>,
+        basis: MeasurementBasis,
+    ) -> Self {
+        Gate::MeasurementInto(basis, qubit_indices, bit_indices)
+    }
+
+    /// Creates a new gate that is only applied if a condition on the classical bits holds.
+    ///
+    /// # Arguments
+    ///
+    /// * `condition` - The condition on the classical bits.
+    /// * `gate` - The gate to apply if the condition holds.
+    ///
+    /// # Returns
+    ///
+    /// * `Gate` - A new instance of the Gate struct representing a classically controlled gate.
+    pub fn new_conditional(condition: ClassicalCondition, gate: Gate) -> Self {
+        Gate::Conditional(condition, Box::new(gate))
+    }
+
+    /// Creates a new reset gate for the specified qubit indices.
+    ///
+    /// # Arguments
+    ///
+    /// * `qubit_indices` - The indices of the qubits to reset to |0>.
+    ///
+    /// # Returns
+    ///
+    /// * `Gate` - A new instance of the Gate struct representing a reset gate.
+    pub fn new_reset(qubit_indices: Vec<usize>) -> Self {
+        Gate::Reset(qubit_indices)
+    }
+
+    /// Creates a new barrier for the specified qubit indices.
+    ///
+    /// # Arguments
+    ///
+    /// * `qubit_indices` - The indices of the qubits covered by the barrier, or an empty vector for every qubit.
+    ///
+    /// # Returns
+    ///
+    /// * `Gate` - A new instance of the Gate struct representing a barrier.
+    pub fn new_barrier(qubit_indices: Vec<usize>) -> Self {
+        Gate::Barrier(qubit_indices)
+    }
+
     /// Creates a new operator gate for the specified qubit indices.
     ///
     /// # Arguments
@@ -73,6 +174,9 @@ impl Gate {
 
     /// Applies the gate to the given state and returns the new state.
     ///
+    /// Measurement outcomes are discarded. Use `apply_classical` to record them, and to apply classically
+    /// controlled gates.
+    ///
     /// # Arguments
     ///
     /// * `state` - The quantum state to which the gate will be applied.
@@ -80,14 +184,122 @@ impl Gate {
     /// # Returns
     ///
     /// * `State` - The new quantum state after applying the gate.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the gate is classically controlled.
     pub fn apply(&self, state: &State) -> Result<State, Error> {
+        self.apply_with_rng(state, &mut rand::rng())
+    }
+
+    /// Applies the gate to the given state, using the given random number generator for measurements and resets.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The quantum state to which the gate will be applied.
+    /// * `rng` - The random number generator used to sample measurement outcomes.
+    ///
+    /// # Returns
+    ///
+    /// * `State` - The new quantum state after applying the gate.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the gate is classically controlled.
+    pub fn apply_with_rng(&self, state: &State, rng: &mut impl Rng) -> Result<State, Error> {
         match self {
             Gate::Operator(operator, target_indices, control_indices) => {
                 operator.apply(state, target_indices, control_indices.as_slice())
             }
-            Gate::Measurement(basis, indices) => state
-                .measure(*basis, indices.as_slice())
+            Gate::Measurement(basis, indices) | Gate::MeasurementInto(basis, indices, _) => state
+                .measure_with_rng(*basis, indices.as_slice(), rng)
                 .map(|measurementresult| measurementresult.get_new_state().clone()),
+            Gate::Conditional(_, _) => Err(Error::MissingClassicalRegister),
+            Gate::Reset(indices) => {
+                let result: MeasurementResult =
+                    state.measure_with_rng(MeasurementBasis::Computational, indices.as_slice(), rng)?;
+                // Qubits measured as 1 are flipped back to |0>
+                let flipped: Vec<usize> = result
+                    .get_indices()
+                    .iter()
+                    .zip(&result.outcomes)
+                    .filter(|&(_, &outcome)| outcome == 1)
+                    .map(|(&index, _)| index)
+                    .collect();
+                flipped.iter().try_fold(result.new_state, |state, &index| {
+                    Pauli::X.apply(&state, &[index], &[])
+                })
+            }
+            Gate::Barrier(_) => Ok(state.clone()),
+        }
+    }
+
+    /// Checks whether the gate acts unitarily on the state.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` for operator gates and barriers, and `false` for measurements, resets and classically
+    ///   controlled gates.
+    pub fn is_unitary(&self) -> bool {
+        matches!(self, Gate::Operator(_, _, _) | Gate::Barrier(_))
+    }
+
+    /// Applies the gate to the given state, reading and writing the classical bits of a register.
+    ///
+    /// Measurement outcomes are recorded in the register, and classically controlled gates are only applied if
+    /// their condition holds for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The quantum state to which the gate will be applied.
+    /// * `register` - The classical register of the circuit.
+    ///
+    /// # Returns
+    ///
+    /// * `State` - The new quantum state after applying the gate.
+    pub fn apply_classical(&self, state: &State, register: &mut ClassicalRegister) -> Result<State, Error> {
+        self.apply_classical_with_rng(state, register, &mut rand::rng())
+    }
+
+    /// Applies the gate to the given state, reading and writing the classical bits of a register, and using the
+    /// given random number generator for measurements and resets.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The quantum state to which the gate will be applied.
+    /// * `register` - The classical register of the circuit.
+    /// * `rng` - The random number generator used to sample measurement outcomes.
+    ///
+    /// # Returns
+    ///
+    /// * `State` - The new quantum state after applying the gate.
+    pub fn apply_classical_with_rng(
+        &self,
+        state: &State,
+        register: &mut ClassicalRegister,
+        rng: &mut impl Rng,
+    ) -> Result<State, Error> {
+        match self {
+            Gate::Operator(_, _, _) | Gate::Reset(_) | Gate::Barrier(_) => self.apply_with_rng(state, rng),
+            Gate::Measurement(basis, indices) => {
+                let result: MeasurementResult = state.measure_with_rng(*basis, indices.as_slice(), rng)?;
+                // Measurements without indices measure every qubit, so the bits are taken from the result
+                let bits: Vec<usize> = result.get_indices().clone();
+                register.record(&result, &bits);
+                Ok(result.new_state)
+            }
+            Gate::MeasurementInto(basis, indices, bits) => {
+                let result: MeasurementResult = state.measure_with_rng(*basis, indices.as_slice(), rng)?;
+                register.record(&result, bits);
+                Ok(result.new_state)
+            }
+            Gate::Conditional(condition, gate) => {
+                if condition.is_satisfied(register) {
+                    gate.apply_classical_with_rng(state, register, rng)
+                } else {
+                    Ok(state.clone())
+                }
+            }
         }
     }
 
@@ -99,7 +311,11 @@ impl Gate {
     pub fn get_target_qubits(&self) -> &Vec<usize> {
         match self {
             Gate::Operator(_, target_indices, _) => target_indices,
-            Gate::Measurement(_, indices) => indices,
+            Gate::Measurement(_, indices)
+            | Gate::MeasurementInto(_, indices, _)
+            | Gate::Reset(indices)
+            | Gate::Barrier(indices) => indices,
+            Gate::Conditional(_, gate) => gate.get_target_qubits(),
         }
     }
 
@@ -111,7 +327,85 @@ impl Gate {
     pub fn get_control_qubits(&self) -> Option<&Vec<usize>> {
         match self {
             Gate::Operator(_, _, control_indices) => Some(control_indices),
-            Gate::Measurement(_, _) => None,
+            Gate::Measurement(_, _) | Gate::MeasurementInto(_, _, _) | Gate::Reset(_) | Gate::Barrier(_) => None,
+            Gate::Conditional(_, gate) => gate.get_control_qubits(),
+        }
+    }
+
+    /// Returns the indices of the classical bits that the gate reads or writes.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<usize>` - The bits written by a measurement with explicit bits, or read by the condition of a
+    ///   classically controlled gate. Plain measurements write the bits of their qubits, which are not included.
+    pub fn get_classical_bits(&self) -> Vec<usize> {
+        match self {
+            Gate::Operator(_, _, _) | Gate::Measurement(_, _) | Gate::Reset(_) | Gate::Barrier(_) => Vec::new(),
+            Gate::MeasurementInto(_, _, bits) => bits.clone(),
+            Gate::Conditional(condition, gate) => {
+                let mut bits: Vec<usize> = condition.bits.clone();
+                bits.extend(gate.get_classical_bits());
+                bits
+            }
+        }
+    }
+
+    /// Returns the indices of the classical bits that the gate writes.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_qubits` - The number of qubits of the circuit, since measurements without indices measure every qubit.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<usize>` - The bits written by measurements, where plain measurements write the bits of their qubits.
+    pub(crate) fn written_bits(&self, num_qubits: usize) -> Vec<usize> {
+        match self {
+            Gate::Operator(_, _, _) | Gate::Reset(_) | Gate::Barrier(_) => Vec::new(),
+            Gate::Measurement(_, indices) if indices.is_empty() => (0..num_qubits).collect(),
+            Gate::Measurement(_, indices) => indices.clone(),
+            Gate::MeasurementInto(_, _, bits) => bits.clone(),
+            Gate::Conditional(_, gate) => gate.written_bits(num_qubits),
+        }
+    }
+
+    /// Returns the symbolic parameters that the gate depends on.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Parameter>` - The unbound parameters of the gate's operator, which is empty for measurements, resets
+    ///   and barriers.
+    pub fn parameters(&self) -> Vec<Parameter> {
+        match self {
+            Gate::Operator(operator, _, _) => operator.parameters(),
+            Gate::Measurement(_, _) | Gate::MeasurementInto(_, _, _) | Gate::Reset(_) | Gate::Barrier(_) => {
+                Vec::new()
+            }
+            Gate::Conditional(_, gate) => gate.parameters(),
+        }
+    }
+
+    /// Returns a copy of the gate with values substituted for its parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The values of the parameters. Parameters without a value are left unbound.
+    ///
+    /// # Returns
+    ///
+    /// * `Gate` - The gate with the values substituted.
+    pub fn bind(&self, values: &HashMap<Parameter, f64>) -> Gate {
+        match self {
+            Gate::Operator(operator, targets, controls) => match operator.bind(values) {
+                Some(bound) => Gate::Operator(bound, targets.clone(), controls.clone()),
+                None => self.clone(),
+            },
+            Gate::Measurement(_, _) | Gate::MeasurementInto(_, _, _) | Gate::Reset(_) | Gate::Barrier(_) => {
+                self.clone()
+            }
+            Gate::Conditional(condition, gate) => {
+                Gate::Conditional(condition.clone(), Box::new(gate.bind(values)))
+            }
         }
     }
 
@@ -612,8 +906,8 @@ impl Gate {
     /// # Returns
     ///
     /// * `Gate` - A new instance of the Gate struct representing a Phase Shift gate.
-    pub fn p_gate(qubit_index: usize, angle: f64) -> Self {
-        Gate::Operator(Box::new(PhaseShift::new(angle)), vec![qubit_index], vec![])
+    pub fn p_gate(qubit_index: usize, angle: impl Into<ParameterExpression>) -> Self {
+        Gate::Operator(ParameterisedKind::PhaseShift.operator(angle.into()), vec![qubit_index], vec![])
     }
 
     /// Creates new Phase Shift (P) gates for the specified qubit indices and angle.
@@ -626,11 +920,11 @@ impl Gate {
     /// # Returns
     ///
     /// * `Vec<Gate>` - A vector of Gate structs representing Phase Shift gates for each qubit index.
-    pub fn p_multi_gate(qubit_indices: Vec<usize>, angle: f64) -> Vec<Self> {
-        let op_template = PhaseShift::new(angle);
+    pub fn p_multi_gate(qubit_indices: Vec<usize>, angle: impl Into<ParameterExpression>) -> Vec<Self> {
+        let op_template: Box<dyn Operator> = ParameterisedKind::PhaseShift.operator(angle.into());
         qubit_indices
             .into_iter()
-            .map(|qubit_index| Gate::Operator(Box::new(op_template.clone()), vec![qubit_index], vec![]))
+            .map(|qubit_index| Gate::Operator(op_template.clone(), vec![qubit_index], vec![]))
             .collect()
     }
 
@@ -648,13 +942,13 @@ impl Gate {
     pub fn p_controlled_gates(
         target_indices: Vec<usize>,
         control_indices: Vec<usize>,
-        angle: f64,
+        angle: impl Into<ParameterExpression>,
     ) -> Vec<Self> {
-        let op_template = PhaseShift::new(angle);
+        let op_template: Box<dyn Operator> = ParameterisedKind::PhaseShift.operator(angle.into());
         target_indices
             .into_iter()
             .map(|target_index| Gate::Operator(
-                Box::new(op_template.clone()),
+                op_template.clone(),
                 vec![target_index],
                 control_indices.clone(),
             ))
@@ -671,8 +965,8 @@ impl Gate {
     /// # Returns
     ///
     /// * `Gate` - A new instance of the Gate struct representing a RotateX gate.
-    pub fn rx_gate(qubit_index: usize, angle: f64) -> Self {
-        Gate::Operator(Box::new(RotateX::new(angle)), vec![qubit_index], vec![])
+    pub fn rx_gate(qubit_index: usize, angle: impl Into<ParameterExpression>) -> Self {
+        Gate::Operator(ParameterisedKind::RotateX.operator(angle.into()), vec![qubit_index], vec![])
     }
 
     /// Creates new RotateX (RX) gates for the specified qubit indices and angle.
@@ -685,11 +979,11 @@ impl Gate {
     /// # Returns
     ///
     /// * `Vec<Gate>` - A vector of Gate structs representing RotateX gates for each qubit index.
-    pub fn rx_multi_gate(qubit_indices: Vec<usize>, angle: f64) -> Vec<Self> {
-        let op_template = RotateX::new(angle);
+    pub fn rx_multi_gate(qubit_indices: Vec<usize>, angle: impl Into<ParameterExpression>) -> Vec<Self> {
+        let op_template: Box<dyn Operator> = ParameterisedKind::RotateX.operator(angle.into());
         qubit_indices
             .into_iter()
-            .map(|qubit_index| Gate::Operator(Box::new(op_template.clone()), vec![qubit_index], vec![]))
+            .map(|qubit_index| Gate::Operator(op_template.clone(), vec![qubit_index], vec![]))
             .collect()
     }
 
@@ -707,13 +1001,13 @@ impl Gate {
     pub fn rx_controlled_gates(
         target_indices: Vec<usize>,
         control_indices: Vec<usize>,
-        angle: f64,
+        angle: impl Into<ParameterExpression>,
     ) -> Vec<Self> {
-        let op_template = RotateX::new(angle);
+        let op_template: Box<dyn Operator> = ParameterisedKind::RotateX.operator(angle.into());
         target_indices
             .into_iter()
             .map(|target_index| Gate::Operator(
-                Box::new(op_template.clone()),
+                op_template.clone(),
                 vec![target_index],
                 control_indices.clone(),
             ))
@@ -730,8 +1024,8 @@ impl Gate {
     /// # Returns
     ///
     /// * `Gate` - A new instance of the Gate struct representing a RotateY gate.
-    pub fn ry_gate(qubit_index: usize, angle: f64) -> Self {
-        Gate::Operator(Box::new(RotateY::new(angle)), vec![qubit_index], vec![])
+    pub fn ry_gate(qubit_index: usize, angle: impl Into<ParameterExpression>) -> Self {
+        Gate::Operator(ParameterisedKind::RotateY.operator(angle.into()), vec![qubit_index], vec![])
     }
 
     /// Creates new RotateY (RY) gates for the specified qubit indices and angle.
@@ -744,11 +1038,11 @@ impl Gate {
     /// # Returns
     ///
     /// * `Vec<Gate>` - A vector of Gate structs representing RotateY gates for each qubit index.
-    pub fn ry_multi_gate(qubit_indices: Vec<usize>, angle: f64) -> Vec<Self> {
-        let op_template = RotateY::new(angle);
+    pub fn ry_multi_gate(qubit_indices: Vec<usize>, angle: impl Into<ParameterExpression>) -> Vec<Self> {
+        let op_template: Box<dyn Operator> = ParameterisedKind::RotateY.operator(angle.into());
         qubit_indices
             .into_iter()
-            .map(|qubit_index| Gate::Operator(Box::new(op_template.clone()), vec![qubit_index], vec![]))
+            .map(|qubit_index| Gate::Operator(op_template.clone(), vec![qubit_index], vec![]))
             .collect()
     }
 
@@ -766,13 +1060,13 @@ impl Gate {
     pub fn ry_controlled_gates(
         target_indices: Vec<usize>,
         control_indices: Vec<usize>,
-        angle: f64,
+        angle: impl Into<ParameterExpression>,
     ) -> Vec<Self> {
-        let op_template = RotateY::new(angle);
+        let op_template: Box<dyn Operator> = ParameterisedKind::RotateY.operator(angle.into());
         target_indices
             .into_iter()
             .map(|target_index| Gate::Operator(
-                Box::new(op_template.clone()),
+                op_template.clone(),
                 vec![target_index],
                 control_indices.clone(),
             ))
@@ -789,8 +1083,8 @@ impl Gate {
     /// # Returns
     ///
     /// * `Gate` - A new instance of the Gate struct representing a RotateZ gate.
-    pub fn rz_gate(qubit_index: usize, angle: f64) -> Self {
-        Gate::Operator(Box::new(RotateZ::new(angle)), vec![qubit_index], vec![])
+    pub fn rz_gate(qubit_index: usize, angle: impl Into<ParameterExpression>) -> Self {
+        Gate::Operator(ParameterisedKind::RotateZ.operator(angle.into()), vec![qubit_index], vec![])
     }
 
     /// Creates new RotateZ (RZ) gates for the specified qubit indices and angle.
@@ -803,11 +1097,11 @@ impl Gate {
     /// # Returns
     ///
     /// * `Vec<Gate>` - A vector of Gate structs representing RotateZ gates for each qubit index.
-    pub fn rz_multi_gate(qubit_indices: Vec<usize>, angle: f64) -> Vec<Self> {
-        let op_template = RotateZ::new(angle);
+    pub fn rz_multi_gate(qubit_indices: Vec<usize>, angle: impl Into<ParameterExpression>) -> Vec<Self> {
+        let op_template: Box<dyn Operator> = ParameterisedKind::RotateZ.operator(angle.into());
         qubit_indices
             .into_iter()
-            .map(|qubit_index| Gate::Operator(Box::new(op_template.clone()), vec![qubit_index], vec![]))
+            .map(|qubit_index| Gate::Operator(op_template.clone(), vec![qubit_index], vec![]))
             .collect()
     }
 
@@ -825,13 +1119,13 @@ impl Gate {
     pub fn rz_controlled_gates(
         target_indices: Vec<usize>,
         control_indices: Vec<usize>,
-        angle: f64,
+        angle: impl Into<ParameterExpression>,
     ) -> Vec<Self> {
-        let op_template = RotateZ::new(angle);
+        let op_template: Box<dyn Operator> = ParameterisedKind::RotateZ.operator(angle.into());
         target_indices
             .into_iter()
             .map(|target_index| Gate::Operator(
-                Box::new(op_template.clone()),
+                op_template.clone(),
                 vec![target_index],
                 control_indices.clone(),
             ))
@@ -909,6 +1203,99 @@ impl Gate {
 
     // -- MULTI-QUBIT GATES --
 
+    /// Creates a new Unitary4 gate for the specified qubit indices and unitary matrix.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `qubit_1` - The index of the first qubit, corresponding to bit 0 of the matrix index.
+    /// 
+    /// * `qubit_2` - The index of the second qubit, corresponding to bit 1 of the matrix index.
+    /// 
+    /// * `unitary` - The 4×4 unitary matrix to be applied.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Gate` - A new instance of the Gate struct representing a Unitary4 gate.
+    pub fn unitary4_gate(qubit_1: usize, qubit_2: usize, unitary: [[Complex<f64>; 4]; 4]) -> Self {
+        Gate::Operator(
+            Box::new(Unitary4::new(unitary).unwrap()),
+            vec![qubit_1, qubit_2],
+            vec![],
+        )
+    }
+
+    /// Creates a new controlled Unitary4 gate for the specified qubit indices and unitary matrix.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `qubit_1` - The index of the first target qubit, corresponding to bit 0 of the matrix index.
+    /// 
+    /// * `qubit_2` - The index of the second target qubit, corresponding to bit 1 of the matrix index.
+    /// 
+    /// * `control_indices` - The indices of the control qubits.
+    /// 
+    /// * `unitary` - The 4×4 unitary matrix to be applied.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Gate` - A new instance of the Gate struct representing a controlled Unitary4 gate.
+    pub fn unitary4_controlled_gate(
+        qubit_1: usize,
+        qubit_2: usize,
+        control_indices: Vec<usize>,
+        unitary: [[Complex<f64>; 4]; 4],
+    ) -> Self {
+        Gate::Operator(
+            Box::new(Unitary4::new(unitary).unwrap()),
+            vec![qubit_1, qubit_2],
+            control_indices,
+        )
+    }
+
+    /// Creates a new UnitaryN gate for the specified qubit indices and unitary matrix.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `qubit_indices` - The indices of the k target qubits, where the j-th qubit corresponds to bit j of the matrix index.
+    /// 
+    /// * `unitary` - The 2^k×2^k unitary matrix to be applied.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Gate` - A new instance of the Gate struct representing a UnitaryN gate.
+    pub fn unitary_n_gate(qubit_indices: Vec<usize>, unitary: Vec<Vec<Complex<f64>>>) -> Self {
+        Gate::Operator(
+            Box::new(UnitaryN::new(unitary).unwrap()),
+            qubit_indices,
+            vec![],
+        )
+    }
+
+    /// Creates a new controlled UnitaryN gate for the specified qubit indices and unitary matrix.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `target_indices` - The indices of the k target qubits, where the j-th qubit corresponds to bit j of the matrix index.
+    /// 
+    /// * `control_indices` - The indices of the control qubits.
+    /// 
+    /// * `unitary` - The 2^k×2^k unitary matrix to be applied.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Gate` - A new instance of the Gate struct representing a controlled UnitaryN gate.
+    pub fn unitary_n_controlled_gate(
+        target_indices: Vec<usize>,
+        control_indices: Vec<usize>,
+        unitary: Vec<Vec<Complex<f64>>>,
+    ) -> Self {
+        Gate::Operator(
+            Box::new(UnitaryN::new(unitary).unwrap()),
+            target_indices,
+            control_indices,
+        )
+    }
+
     /// Creates a CNOT gate for the specified target and control qubit indices.
     ///
     /// # Arguments
@@ -101,4 +101,25 @@ impl Compilable for MeasurementOperation {
             .map(|&target| InstructionIR::Measurement(target, self.basis))
             .collect()
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Represents a measurement operation whose outcomes are stored in the given classical bits.
+/// 
+/// This is an internal struct strictly used for the IR representation of a measurement operation.
+pub(crate) struct MeasurementIntoOperation {
+    /// The basis of measurement.
+    pub basis: MeasurementBasis,
+    /// The classical bits in which the outcomes are stored, one per target qubit.
+    pub bits: Vec<usize>,
+}
+
+impl Compilable for MeasurementIntoOperation {
+    fn to_ir(&self, targets: Vec<usize>, _controls: Vec<usize>) -> Vec<InstructionIR> {
+        // No controls for measurement operations.
+        targets.iter()
+            .zip(&self.bits)
+            .map(|(&target, &bit)| InstructionIR::MeasurementInto(target, bit, self.basis))
+            .collect()
+    }
+}
@@ -1,8 +1,14 @@
+pub mod channel;
+pub mod classical;
+pub mod counts;
+pub mod density_matrix;
 pub mod gate;
 #[cfg(feature = "gpu")]
 pub mod gpu_context;
 pub mod measurement;
+pub mod noise;
 pub mod operator;
+pub mod parameter;
 pub mod state;
 pub mod pauli_string;
 
@@ -0,0 +1,293 @@
+use crate::{
+    compiler::{ir::InstructionIR, linalg::is_sx},
+    components::{channel::Channel, classical::ClassicalRegister, gate::Gate},
+    errors::Error,
+};
+use rand::Rng;
+use std::collections::HashMap;
+
+/// A readout confusion matrix, where `confusion[actual][recorded]` is the probability of recording `recorded` when
+/// the outcome of the measurement is `actual`.
+pub type ConfusionMatrix = [[f64; 2]; 2];
+
+/// The kinds of gates after which the channels of a `NoiseModel` are applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GateKind {
+    /// Every operator gate.
+    All,
+    /// Operator gates that act on the given number of qubits, counting both target and control qubits.
+    NumQubits(usize),
+    /// Operator gates with the given OpenQASM 3.0 name, prefixed with a `c` per control qubit, such as `"rz"`, `"sx"`,
+    /// `"cx"` for CNOT or `"ccx"` for Toffoli. Other single-qubit unitaries are named `"U"`. Gates that do not compile
+    /// to a single instruction, such as two-qubit unitaries, have no name.
+    Named(String),
+}
+
+impl GateKind {
+    // Helper function to check whether an operator gate on the given number of qubits and with the given name is of
+    // this kind
+    fn matches(&self, name: Option<&str>, num_qubits: usize) -> bool {
+        match self {
+            GateKind::All => true,
+            GateKind::NumQubits(n) => *n == num_qubits,
+            GateKind::Named(kind_name) => name == Some(kind_name.as_str()),
+        }
+    }
+}
+
+// Helper function to get the name of an operator gate from the single instruction it compiles to
+fn gate_name(gate: &Gate) -> Option<String> {
+    let Gate::Operator(operator, targets, controls) = gate else {
+        return None;
+    };
+    let instructions: Vec<InstructionIR> = operator.to_compilable()?.to_ir(targets.clone(), controls.clone());
+    let [instruction] = instructions.as_slice() else {
+        return None;
+    };
+    let name: &str = match instruction {
+        InstructionIR::Hadamard(..) => "h",
+        InstructionIR::PauliX(..) => "x",
+        InstructionIR::PauliY(..) => "y",
+        InstructionIR::PauliZ(..) => "z",
+        InstructionIR::S(..) => "s",
+        InstructionIR::T(..) => "t",
+        InstructionIR::Sdg(..) => "sdg",
+        InstructionIR::Tdg(..) => "tdg",
+        InstructionIR::Phase(..) => "p",
+        InstructionIR::Rx(..) => "rx",
+        InstructionIR::Ry(..) => "ry",
+        InstructionIR::Rz(..) => "rz",
+        InstructionIR::Id(..) => "id",
+        InstructionIR::Swap(..) => "swap",
+        InstructionIR::Unitary(matrix, _, _) if is_sx(matrix) => "sx",
+        InstructionIR::Unitary(..) => "U",
+        _ => return None,
+    };
+    Some(format!("{}{}", "c".repeat(instruction.controls().len()), name))
+}
+
+/// A channel applied after the gates of a kind, either on the qubits of each gate or on fixed qubits.
+#[derive(Debug, Clone)]
+struct GateError {
+    /// The kind of gates the channel follows.
+    kind: GateKind,
+    /// The qubits the channel acts on, or `None` to act on the qubits of each gate.
+    qubits: Option<Vec<usize>>,
+    /// The channel to apply.
+    channel: Channel,
+}
+
+/// A description of the noise of a device, used to execute circuits without adding channels to them.
+///
+/// Gate errors are channels applied after every operator gate of a `GateKind`, such as a depolarising channel after
+/// every two-qubit gate or every `sx` gate, or thermal relaxation on a specific qubit after every gate that acts on it. Readout errors
+/// are confusion matrices that flip the outcomes recorded by measurements, without changing the measured state.
+///
+/// Noise models are used by `Circuit::execute_noisy`, which is exact, and by `Circuit::run_shots_noisy`, which samples
+/// a quantum trajectory per shot.
+///
+/// # Fields
+///
+/// * `gate_errors` - The channels applied after gates, in the order in which they were added.
+/// * `readout_errors` - The confusion matrix of each qubit with a readout error of its own.
+/// * `default_readout_error` - The confusion matrix of every other qubit, if any.
+#[derive(Debug, Clone, Default)]
+pub struct NoiseModel {
+    /// The channels applied after gates, in the order in which they were added.
+    gate_errors: Vec<GateError>,
+    /// The confusion matrix of each qubit with a readout error of its own.
+    readout_errors: HashMap<usize, ConfusionMatrix>,
+    /// The confusion matrix of every other qubit, if any.
+    default_readout_error: Option<ConfusionMatrix>,
+}
+
+impl NoiseModel {
+    /// Creates a new noise model without any errors.
+    ///
+    /// # Returns
+    ///
+    /// * `NoiseModel` - A new instance of the NoiseModel struct.
+    pub fn new() -> Self {
+        NoiseModel::default()
+    }
+
+    /// Adds a channel that is applied after every operator gate of the given kind, on the qubits of the gate.
+    ///
+    /// A single-qubit channel is applied to each qubit of the gate. A channel on k qubits is applied to the qubits of
+    /// gates that act on k qubits, in the order of their target qubits followed by their control qubits, and is
+    /// ignored for other gates of the kind.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of gates the channel follows.
+    /// * `channel` - The channel to apply.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<&mut NoiseModel, Error>` - The noise model, for chaining.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the kind selects gates on 0 qubits.
+    /// * Returns an error if the kind selects gates on k qubits and the channel acts on neither 1 nor k qubits.
+    pub fn add_gate_error(&mut self, kind: GateKind, channel: Channel) -> Result<&mut Self, Error> {
+        if let GateKind::NumQubits(n) = kind {
+            if n == 0 {
+                return Err(Error::InvalidNumberOfQubits(0));
+            }
+            if channel.num_qubits() != 1 && channel.num_qubits() != n {
+                return Err(Error::InvalidNumberOfQubits(channel.num_qubits()));
+            }
+        }
+        self.gate_errors.push(GateError {
+            kind,
+            qubits: None,
+            channel,
+        });
+        Ok(self)
+    }
+
+    /// Adds a channel that is applied to the given qubits after every operator gate of the given kind that acts on all
+    /// of them, for example the thermal relaxation of a specific qubit.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of gates the channel follows.
+    /// * `qubits` - The qubits the channel acts on, one per qubit of the channel.
+    /// * `channel` - The channel to apply.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<&mut NoiseModel, Error>` - The noise model, for chaining.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the number of qubits does not match the channel.
+    pub fn add_qubit_error(&mut self, kind: GateKind, qubits: &[usize], channel: Channel) -> Result<&mut Self, Error> {
+        if qubits.len() != channel.num_qubits() {
+            return Err(Error::InvalidNumberOfQubits(qubits.len()));
+        }
+        self.gate_errors.push(GateError {
+            kind,
+            qubits: Some(qubits.to_vec()),
+            channel,
+        });
+        Ok(self)
+    }
+
+    /// Sets the readout error of the given qubits, replacing any previous readout error.
+    ///
+    /// # Arguments
+    ///
+    /// * `confusion` - The confusion matrix, where `confusion[actual][recorded]` is the probability of recording
+    ///   `recorded` when the outcome is `actual`. For example, `[[0.98, 0.02], [0.05, 0.95]]` records 1 for 2% of the
+    ///   outcomes 0, and 0 for 5% of the outcomes 1.
+    /// * `qubits` - The qubits with this readout error. If empty, the readout error applies to every qubit without
+    ///   a readout error of its own.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<&mut NoiseModel, Error>` - The noise model, for chaining.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if a probability is not between 0 and 1, or a row of the matrix does not sum to 1.
+    pub fn add_readout_error(&mut self, confusion: ConfusionMatrix, qubits: &[usize]) -> Result<&mut Self, Error> {
+        for row in &confusion {
+            if let Some(&probability) = row.iter().find(|probability| !(0.0..=1.0).contains(*probability)) {
+                return Err(Error::InvalidProbability(probability));
+            }
+            if (row[0] + row[1] - 1.0).abs() > 1e-10 {
+                return Err(Error::InvalidProbability(row[0] + row[1]));
+            }
+        }
+        if qubits.is_empty() {
+            self.default_readout_error = Some(confusion);
+        }
+        for &qubit in qubits {
+            self.readout_errors.insert(qubit, confusion);
+        }
+        Ok(self)
+    }
+
+    /// Gets the readout error of a qubit.
+    ///
+    /// # Arguments
+    ///
+    /// * `qubit` - The index of the qubit.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<ConfusionMatrix>` - The confusion matrix of the qubit, or `None` if its readout is ideal.
+    pub fn readout_error(&self, qubit: usize) -> Option<ConfusionMatrix> {
+        self.readout_errors.get(&qubit).copied().or(self.default_readout_error)
+    }
+
+    /// Checks that every qubit of the noise model exists in a circuit with the given number of qubits.
+    pub(crate) fn validate(&self, num_qubits: usize) -> Result<(), Error> {
+        let qubits = self
+            .gate_errors
+            .iter()
+            .filter_map(|error| error.qubits.as_ref())
+            .flatten()
+            .chain(self.readout_errors.keys());
+        for &qubit in qubits {
+            if qubit >= num_qubits {
+                return Err(Error::InvalidQubitIndex(qubit, num_qubits));
+            }
+        }
+        Ok(())
+    }
+
+    /// Gets the channels to apply after a gate, with the qubits each one acts on, in the order in which they were
+    /// added. Only operator gates are followed by channels.
+    pub(crate) fn channels_after(&self, gate: &Gate) -> Vec<(&Channel, Vec<usize>)> {
+        let Gate::Operator(_, targets, controls) = gate else {
+            return Vec::new();
+        };
+        let gate_qubits: Vec<usize> = targets.iter().chain(controls).copied().collect();
+        // Naming a gate compiles it, so it is only done once, and only if a gate error selects gates by name
+        let name: Option<String> = if self.gate_errors.iter().any(|error| matches!(error.kind, GateKind::Named(_))) {
+            gate_name(gate)
+        } else {
+            None
+        };
+
+        let mut channels: Vec<(&Channel, Vec<usize>)> = Vec::new();
+        let errors = self.gate_errors.iter().filter(|error| error.kind.matches(name.as_deref(), gate_qubits.len()));
+        for error in errors {
+            match &error.qubits {
+                Some(qubits) if qubits.iter().all(|qubit| gate_qubits.contains(qubit)) => {
+                    channels.push((&error.channel, qubits.clone()));
+                }
+                Some(_) => {}
+                None if error.channel.num_qubits() == 1 => {
+                    channels.extend(gate_qubits.iter().map(|&qubit| (&error.channel, vec![qubit])));
+                }
+                None if error.channel.num_qubits() == gate_qubits.len() => {
+                    channels.push((&error.channel, gate_qubits.clone()));
+                }
+                None => {}
+            }
+        }
+        channels
+    }
+
+    /// Flips the bits recorded by a measurement according to the readout errors of the measured qubits.
+    ///
+    /// Each pair is a measured qubit and the bit that holds its outcome, which must already be recorded.
+    pub(crate) fn apply_readout_errors(
+        &self,
+        register: &mut ClassicalRegister,
+        measured: &[(usize, usize)],
+        rng: &mut impl Rng,
+    ) {
+        for &(qubit, bit) in measured {
+            if let (Some(confusion), Some(actual)) = (self.readout_error(qubit), register.get(bit))
+                && rng.random::<f64>() < confusion[actual as usize][1 - actual as usize]
+            {
+                register.set(bit, 1 - actual);
+            }
+        }
+    }
+}
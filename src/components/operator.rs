@@ -1,8 +1,17 @@
-use crate::{components::state::State, errors::Error};
+use crate::{
+    components::{
+        parameter::{Parameter, ParameterExpression},
+        state::State,
+    },
+    errors::Error,
+};
 use dyn_clone::DynClone;
 use num_complex::Complex;
 use rayon::prelude::*;
-use std::{collections::HashSet, fmt::Debug};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+};
 #[cfg(feature = "gpu")]
 use crate::components::gpu_context::{GPU_CONTEXT, KernelType};
 #[cfg(feature = "gpu")]
@@ -12,9 +21,13 @@ use std::f64::consts::PI;
 #[cfg(feature = "gpu")]
 use crate::components::gpu_context::GpuKernelArgs;
 use crate::compiler::compilable::Compilable;
+use crate::compiler::euler::{EulerBasis, EulerDecomposition};
+use crate::compiler::ir::InstructionIR;
+use crate::compiler::kak::KakDecomposition;
+use crate::compiler::linalg::instruction_matrix;
 
 /// Threshold for using parallel CPU implementation
-const PARALLEL_THRESHOLD_NUM_QUBITS: usize = 10;
+pub(crate) const PARALLEL_THRESHOLD_NUM_QUBITS: usize = 10;
 
  /// Threshold for using OpenCL (GPU acceleration)
 const OPENCL_THRESHOLD_NUM_QUBITS: usize = 15;
@@ -145,10 +158,97 @@ pub trait Operator: Send + Sync + Debug + DynClone {
         // Default implementation returns None, indicating no compilable representation
         None
     }
+
+    /// Optionally returns the unitary matrix of the operator on its target qubits.
+    ///
+    /// Control qubits are not part of the matrix, including those of operators such as `CNOT` that require them, so
+    /// the matrix of `CNOT` is that of Pauli-X. Bit j of the row and column indices corresponds to the j-th target
+    /// qubit.
+    ///
+    /// If you implement this method, the operator's adjoint and its compilation to OpenQASM are derived from the
+    /// matrix unless you override `adjoint` or `to_compilable`.
+    ///
+    /// # Returns:
+    ///
+    /// * An optional 2^k×2^k unitary matrix, as a vector of rows, or `None` if the matrix is not known.
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        // Default implementation returns None, indicating the matrix is not known
+        None
+    }
+
+    /// Optionally returns the adjoint (inverse) of the operator, acting on the same target and control qubits.
+    ///
+    /// The default implementation returns a `UnitaryN` with the conjugate transpose of `matrix`, if it is known.
+    ///
+    /// # Returns:
+    ///
+    /// * An optional boxed operator that undoes this operator, or `None` if the adjoint is not known.
+    fn adjoint(&self) -> Option<Box<dyn Operator>> {
+        let matrix: Vec<Vec<Complex<f64>>> = self.matrix()?;
+        let dim: usize = matrix.len();
+        let adjoint: Vec<Vec<Complex<f64>>> = (0..dim)
+            .map(|i| (0..dim).map(|j| matrix[j][i].conj()).collect())
+            .collect();
+        UnitaryN::new(adjoint)
+            .ok()
+            .map(|operator| Box::new(operator) as Box<dyn Operator>)
+    }
+
+    /// Returns the symbolic parameters that the operator depends on.
+    ///
+    /// # Returns:
+    ///
+    /// * The unbound parameters of the operator, which is empty for operators with fixed values.
+    fn parameters(&self) -> Vec<Parameter> {
+        Vec::new()
+    }
+
+    /// Optionally returns a copy of the operator with values substituted for some or all of its parameters.
+    ///
+    /// # Arguments:
+    ///
+    /// * `values` - The values of the parameters to substitute. Parameters without a value are left unbound.
+    ///
+    /// # Returns:
+    ///
+    /// * An optional boxed operator with the values substituted, or `None` if the operator has no parameters.
+    fn bind(&self, _values: &HashMap<Parameter, f64>) -> Option<Box<dyn Operator>> {
+        None
+    }
+
+    /// Optionally returns the operator as a `ParameterisedOperator`.
+    ///
+    /// Gradients of circuits are only computed with respect to the parameters of `ParameterisedOperator`s, so other
+    /// operators with parameters can be bound and executed, but not differentiated.
+    ///
+    /// # Returns:
+    ///
+    /// * An optional reference to the operator as a `ParameterisedOperator`.
+    fn to_parameterised(&self) -> Option<&ParameterisedOperator> {
+        None
+    }
+
+    /// Optionally returns the operator without the control qubits that it requires, such as Pauli-X for `CNOT`.
+    ///
+    /// Operators that require control qubits accept an exact number of them, so gates that gain further control
+    /// qubits, for example in `Circuit::controlled`, use this operator instead.
+    ///
+    /// # Returns:
+    ///
+    /// * An optional boxed operator acting on the target qubits alone, or `None` if the operator does not require
+    ///   control qubits.
+    fn uncontrolled(&self) -> Option<Box<dyn Operator>> {
+        None
+    }
 }
 
 dyn_clone::clone_trait_object!(Operator);
 
+/// Helper function to convert the matrix of a single-qubit instruction into the matrix returned by `Operator::matrix`.
+fn single_qubit_matrix(instruction: InstructionIR) -> Option<Vec<Vec<Complex<f64>>>> {
+    instruction_matrix(&instruction).map(|matrix| matrix.iter().map(|row| row.to_vec()).collect())
+}
+
 /// Helper function to check if all control qubits are in the |1> state for a given basis state index.
 fn check_controls(index: usize, control_qubits: &[usize]) -> bool {
     control_qubits
@@ -382,6 +482,14 @@ impl Operator for Hadamard {
     fn to_compilable(&self) -> Option<&dyn Compilable> {
         Some(self)
     }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        single_qubit_matrix(InstructionIR::Hadamard(0, vec![]))
+    }
+
+    fn adjoint(&self) -> Option<Box<dyn Operator>> {
+        Some(Box::new(*self))
+    }
 }
 
 /// Defines the Pauli operators: X, Y, Z.
@@ -558,6 +666,18 @@ impl Operator for Pauli {
     fn to_compilable(&self) -> Option<&dyn Compilable> {
         Some(self) // Manual implementation for enum
     }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        single_qubit_matrix(match self {
+            Pauli::X => InstructionIR::PauliX(0, vec![]),
+            Pauli::Y => InstructionIR::PauliY(0, vec![]),
+            Pauli::Z => InstructionIR::PauliZ(0, vec![]),
+        })
+    }
+
+    fn adjoint(&self) -> Option<Box<dyn Operator>> {
+        Some(Box::new(*self))
+    }
 }
 
 impl std::fmt::Display for Pauli {
@@ -625,6 +745,19 @@ impl Operator for CNOT {
     fn to_compilable(&self) -> Option<&dyn Compilable> {
         Some(self)
     }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        // The control qubit is not part of the matrix
+        single_qubit_matrix(InstructionIR::PauliX(0, vec![]))
+    }
+
+    fn adjoint(&self) -> Option<Box<dyn Operator>> {
+        Some(Box::new(*self))
+    }
+
+    fn uncontrolled(&self) -> Option<Box<dyn Operator>> {
+        Some(Box::new(Pauli::X))
+    }
 }
 
 /// Defines a SWAP operator.
@@ -750,6 +883,20 @@ impl Operator for SWAP {
     fn to_compilable(&self) -> Option<&dyn Compilable> {
         Some(self)
     }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        let (zero, one) = (Complex::new(0.0, 0.0), Complex::new(1.0, 0.0));
+        Some(vec![
+            vec![one, zero, zero, zero],
+            vec![zero, zero, one, zero],
+            vec![zero, one, zero, zero],
+            vec![zero, zero, zero, one],
+        ])
+    }
+
+    fn adjoint(&self) -> Option<Box<dyn Operator>> {
+        Some(Box::new(*self))
+    }
 }
 
 /// Defines a Toffoli operator.
@@ -809,6 +956,19 @@ impl Operator for Toffoli {
     fn to_compilable(&self) -> Option<&dyn Compilable> {
         Some(self)
     }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        // The control qubits are not part of the matrix
+        single_qubit_matrix(InstructionIR::PauliX(0, vec![]))
+    }
+
+    fn adjoint(&self) -> Option<Box<dyn Operator>> {
+        Some(Box::new(*self))
+    }
+
+    fn uncontrolled(&self) -> Option<Box<dyn Operator>> {
+        Some(Box::new(Pauli::X))
+    }
 }
 
 /// Defines an identity operator
@@ -851,6 +1011,14 @@ impl Operator for Identity {
     fn to_compilable(&self) -> Option<&dyn Compilable> {
         Some(self)
     }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        single_qubit_matrix(InstructionIR::Id(0, vec![]))
+    }
+
+    fn adjoint(&self) -> Option<Box<dyn Operator>> {
+        Some(Box::new(*self))
+    }
 }
 
 /// Defines a Phase S operator.
@@ -939,6 +1107,14 @@ impl Operator for PhaseS {
     fn to_compilable(&self) -> Option<&dyn Compilable> {
         Some(self)
     }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        single_qubit_matrix(InstructionIR::S(0, vec![]))
+    }
+
+    fn adjoint(&self) -> Option<Box<dyn Operator>> {
+        Some(Box::new(PhaseSdag))
+    }
 }
 
 /// Defines a Phase T operator.
@@ -1033,6 +1209,14 @@ impl Operator for PhaseT {
     fn to_compilable(&self) -> Option<&dyn Compilable> {
         Some(self)
     }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        single_qubit_matrix(InstructionIR::T(0, vec![]))
+    }
+
+    fn adjoint(&self) -> Option<Box<dyn Operator>> {
+        Some(Box::new(PhaseTdag))
+    }
 }
 
 /// Defines a Phase Sdag operator.
@@ -1121,6 +1305,14 @@ impl Operator for PhaseSdag {
     fn to_compilable(&self) -> Option<&dyn Compilable> {
         Some(self)
     }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        single_qubit_matrix(InstructionIR::Sdg(0, vec![]))
+    }
+
+    fn adjoint(&self) -> Option<Box<dyn Operator>> {
+        Some(Box::new(PhaseS))
+    }
 }
 
 /// Defines a Phase Tdag operator.
@@ -1215,6 +1407,14 @@ impl Operator for PhaseTdag {
     fn to_compilable(&self) -> Option<&dyn Compilable> {
         Some(self)
     }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        single_qubit_matrix(InstructionIR::Tdg(0, vec![]))
+    }
+
+    fn adjoint(&self) -> Option<Box<dyn Operator>> {
+        Some(Box::new(PhaseT))
+    }
 }
 
 /// Defines the phase shift operator
@@ -1327,6 +1527,14 @@ impl Operator for PhaseShift {
     fn to_compilable(&self) -> Option<&dyn Compilable> {
         Some(self)
     }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        single_qubit_matrix(InstructionIR::Phase(self.angle, 0, vec![]))
+    }
+
+    fn adjoint(&self) -> Option<Box<dyn Operator>> {
+        Some(Box::new(PhaseShift::new(-self.angle)))
+    }
 }
 
 /// Defines the rotate-X operator
@@ -1460,6 +1668,14 @@ impl Operator for RotateX {
     fn to_compilable(&self) -> Option<&dyn Compilable> {
         Some(self)
     }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        single_qubit_matrix(InstructionIR::Rx(self.angle, 0, vec![]))
+    }
+
+    fn adjoint(&self) -> Option<Box<dyn Operator>> {
+        Some(Box::new(RotateX::new(-self.angle)))
+    }
 }
 
 /// Defines the rotate-Y operator
@@ -1591,6 +1807,14 @@ impl Operator for RotateY {
     fn to_compilable(&self) -> Option<&dyn Compilable> {
         Some(self)
     }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        single_qubit_matrix(InstructionIR::Ry(self.angle, 0, vec![]))
+    }
+
+    fn adjoint(&self) -> Option<Box<dyn Operator>> {
+        Some(Box::new(RotateY::new(-self.angle)))
+    }
 }
 
 /// Defines the rotate-Z operator
@@ -1713,6 +1937,14 @@ impl Operator for RotateZ {
     fn to_compilable(&self) -> Option<&dyn Compilable> {
         Some(self)
     }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        single_qubit_matrix(InstructionIR::Rz(self.angle, 0, vec![]))
+    }
+
+    fn adjoint(&self) -> Option<Box<dyn Operator>> {
+        Some(Box::new(RotateZ::new(-self.angle)))
+    }
 }
 
 /// An arbitrary 2×2 unitary operator.
@@ -1739,8 +1971,13 @@ impl Unitary2 {
     ///
     /// * `Error::NonUnitaryMatrix` - If the provided matrix is not unitary.
     pub fn new(matrix: [[Complex<f64>; 2]; 2]) -> Result<Self, Error> {
+        Self::with_tolerance(matrix, f64::EPSILON * 2.0)
+    }
+
+    /// Creates a new Unitary2 operator from a matrix that is unitary up to the given tolerance, such as a matrix
+    /// computed by a decomposition, which accumulates rounding errors.
+    pub(crate) fn with_tolerance(matrix: [[Complex<f64>; 2]; 2], tol: f64) -> Result<Self, Error> {
         // Faster 2×2 unitary check: U U_dagger = I (rows are orthonormal)
-        let tol: f64 = f64::EPSILON * 2.0; // Tolerance for floating point comparisons
         let a: Complex<f64> = matrix[0][0]; // U_00
         let b: Complex<f64> = matrix[0][1]; // U_01
         let c: Complex<f64> = matrix[1][0]; // U_10
@@ -1765,6 +2002,19 @@ impl Unitary2 {
 
         Ok(Unitary2 { matrix })
     }
+
+    /// Decomposes the operator into Euler angles in the given basis, with its global phase.
+    ///
+    /// # Arguments:
+    ///
+    /// * `basis` - The rotation axes to decompose into.
+    ///
+    /// # Returns:
+    ///
+    /// * `EulerDecomposition` - The Euler angles and global phase, which reconstruct the matrix exactly.
+    pub fn euler_decomposition(&self, basis: EulerBasis) -> EulerDecomposition {
+        EulerDecomposition::new(&self.matrix, basis)
+    }
 }
 
 impl Operator for Unitary2 {
@@ -1847,4 +2097,471 @@ impl Operator for Unitary2 {
     fn to_compilable(&self) -> Option<&dyn Compilable> {
         Some(self)
     }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        Some(self.matrix.iter().map(|row| row.to_vec()).collect())
+    }
+
+    fn adjoint(&self) -> Option<Box<dyn Operator>> {
+        // The conjugate transpose of a unitary matrix is unitary, so no validation is needed
+        Some(Box::new(Unitary2 {
+            matrix: std::array::from_fn(|i| std::array::from_fn(|j| self.matrix[j][i].conj())),
+        }))
+    }
+}
+
+/// An arbitrary 4×4 unitary operator.
+///
+/// This operator can be applied to two qubits in a quantum state. It is represented by a 4×4 unitary matrix, where bit 0
+/// of the row and column indices corresponds to the first target qubit and bit 1 to the second.
+#[derive(Debug, Clone, Copy)]
+pub struct Unitary4 {
+    /// The 4×4 unitary matrix representing the operator.
+    pub(crate) matrix: [[Complex<f64>; 4]; 4],
+}
+
+impl Unitary4 {
+    /// Creates a new Unitary4 operator with the given 4×4 unitary matrix.
+    ///
+    /// # Arguments:
+    ///
+    /// * `matrix` - A 4×4 unitary matrix represented as a 2D array of complex numbers.
+    ///
+    /// # Returns:
+    ///
+    /// * `Result<Self, Error>` - A result containing the new Unitary4 operator or an error if the matrix is not unitary.
+    ///
+    /// # Errors:
+    ///
+    /// * `Error::NonUnitaryMatrix` - If the provided matrix is not unitary.
+    pub fn new(matrix: [[Complex<f64>; 4]; 4]) -> Result<Self, Error> {
+        // U U_dagger = I (rows are orthonormal), with the tolerance scaled by the dimension
+        let tol: f64 = f64::EPSILON * 16.0;
+        for i in 0..4 {
+            for j in i..4 {
+                let dot: Complex<f64> = (0..4).map(|k| matrix[i][k] * matrix[j][k].conj()).sum();
+                let expected: f64 = if i == j { 1.0 } else { 0.0 };
+                if (dot - expected).norm() > tol {
+                    return Err(Error::NonUnitaryMatrix);
+                }
+            }
+        }
+
+        Ok(Unitary4 { matrix })
+    }
+
+    /// Computes the KAK decomposition of the operator, which synthesises it into at most 3 CNOTs.
+    ///
+    /// # Returns:
+    ///
+    /// * `KakDecomposition` - The local unitaries, interaction coefficients and global phase, which reconstruct the matrix exactly.
+    pub fn kak_decomposition(&self) -> KakDecomposition {
+        KakDecomposition::new(&self.matrix)
+    }
+}
+
+impl Operator for Unitary4 {
+    /// Applies the Unitary4 operator to the given state's target qubits.
+    ///
+    /// # Arguments:
+    ///
+    /// * `state` - The state to apply the operator to.
+    ///
+    /// * `target_qubits` - The target qubits to apply the operator to. This should be two qubits, the first corresponding to bit 0 of the matrix index.
+    ///
+    /// * `control_qubits` - The control qubits for the operator. If not empty, the operator will be applied conditionally based on the control qubits. Otherwise, it will be applied unconditionally.
+    ///
+    /// # Returns:
+    ///
+    /// * The new state after applying the Unitary4 operator.
+    ///
+    /// # Errors:
+    ///
+    /// * `Error::InvalidNumberOfQubits` - If the target qubits are not 2 different qubits.
+    ///
+    /// * `Error::InvalidQubitIndex` - If the target or control qubit indices are invalid for the number of qubits in the state.
+    ///
+    /// * `Error::OverlappingControlAndTargetQubits` - If the control qubit and target qubit indices overlap.
+    fn apply(
+        &self,
+        state: &State,
+        target_qubits: &[usize],
+        control_qubits: &[usize],
+    ) -> Result<State, Error> {
+        // Validation
+        validate_qubits(state, target_qubits, control_qubits, 2)?;
+
+        let (t0, t1) = (target_qubits[0], target_qubits[1]);
+        let nq: usize = state.num_qubits();
+
+        // Apply the 4×4 block on each group of basis states that differ only in the target qubits
+        let dim = 1 << nq;
+        let mut new_state_vec = state.state_vector.clone();
+        let block = |i: usize| -> [(usize, Complex<f64>); 4] {
+            let indices: [usize; 4] = [i, i | (1 << t0), i | (1 << t1), i | (1 << t0) | (1 << t1)];
+            let amplitudes: [Complex<f64>; 4] = indices.map(|index| state.state_vector[index]);
+            std::array::from_fn(|row| {
+                let value: Complex<f64> = (0..4).map(|k| self.matrix[row][k] * amplitudes[k]).sum();
+                (indices[row], value)
+            })
+        };
+        let is_block_start = |i: usize| {
+            (i >> t0) & 1 == 0 && (i >> t1) & 1 == 0 && check_controls(i, control_qubits)
+        };
+
+        if nq >= PARALLEL_THRESHOLD_NUM_QUBITS {
+            // Parallel implementation
+            let updates: Vec<(usize, Complex<f64>)> = (0..dim)
+                .into_par_iter()
+                .filter(|&i| is_block_start(i))
+                .flat_map_iter(block)
+                .collect();
+            for (idx, val) in updates {
+                new_state_vec[idx] = val;
+            }
+        } else {
+            // Sequential implementation
+            for i in (0..dim).filter(|&i| is_block_start(i)) {
+                for (idx, val) in block(i) {
+                    new_state_vec[idx] = val;
+                }
+            }
+        }
+
+        Ok(State {
+            state_vector: new_state_vec,
+            num_qubits: nq,
+        })
+    }
+
+    fn base_qubits(&self) -> usize {
+        2
+    }
+
+    fn to_compilable(&self) -> Option<&dyn Compilable> {
+        Some(self)
+    }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        Some(self.matrix.iter().map(|row| row.to_vec()).collect())
+    }
+
+    fn adjoint(&self) -> Option<Box<dyn Operator>> {
+        Some(Box::new(Unitary4 {
+            matrix: std::array::from_fn(|i| std::array::from_fn(|j| self.matrix[j][i].conj())),
+        }))
+    }
+}
+
+/// An arbitrary 2^k×2^k unitary operator.
+///
+/// This operator can be applied to any ordered set of k qubits in a quantum state. Bit j of the row and column indices of
+/// the matrix corresponds to the j-th target qubit.
+#[derive(Debug, Clone)]
+pub struct UnitaryN {
+    /// The 2^k×2^k unitary matrix representing the operator.
+    pub(crate) matrix: Vec<Vec<Complex<f64>>>,
+    /// The number of qubits k the operator acts on.
+    pub(crate) num_qubits: usize,
+}
+
+impl UnitaryN {
+    /// Creates a new UnitaryN operator with the given 2^k×2^k unitary matrix.
+    ///
+    /// # Arguments:
+    ///
+    /// * `matrix` - A 2^k×2^k unitary matrix represented as a vector of rows of complex numbers, with k at least 1.
+    ///
+    /// # Returns:
+    ///
+    /// * `Result<Self, Error>` - A result containing the new UnitaryN operator or an error if the matrix is not unitary.
+    ///
+    /// # Errors:
+    ///
+    /// * `Error::InvalidMatrixDimensions` - If the matrix is not square, or its dimension is not a power of two greater than 1.
+    ///
+    /// * `Error::NonUnitaryMatrix` - If the provided matrix is not unitary.
+    pub fn new(matrix: Vec<Vec<Complex<f64>>>) -> Result<Self, Error> {
+        let dim: usize = matrix.len();
+        if let Some(row) = matrix.iter().find(|row| row.len() != dim) {
+            return Err(Error::InvalidMatrixDimensions(dim, row.len()));
+        }
+        if dim < 2 || !dim.is_power_of_two() {
+            return Err(Error::InvalidMatrixDimensions(dim, dim));
+        }
+
+        // U U_dagger = I (rows are orthonormal), with the tolerance scaled by the dimension
+        let tol: f64 = f64::EPSILON * 4.0 * dim as f64;
+        for i in 0..dim {
+            for j in i..dim {
+                let dot: Complex<f64> = matrix[i]
+                    .iter()
+                    .zip(&matrix[j])
+                    .map(|(a, b)| a * b.conj())
+                    .sum();
+                let expected: f64 = if i == j { 1.0 } else { 0.0 };
+                if (dot - expected).norm() > tol {
+                    return Err(Error::NonUnitaryMatrix);
+                }
+            }
+        }
+
+        Ok(UnitaryN {
+            num_qubits: dim.trailing_zeros() as usize,
+            matrix,
+        })
+    }
+}
+
+impl Operator for UnitaryN {
+    /// Applies the UnitaryN operator to the given state's target qubits.
+    ///
+    /// # Arguments:
+    ///
+    /// * `state` - The state to apply the operator to.
+    ///
+    /// * `target_qubits` - The target qubits to apply the operator to, in the order of the bits of the matrix index. This should be k different qubits.
+    ///
+    /// * `control_qubits` - The control qubits for the operator. If not empty, the operator will be applied conditionally based on the control qubits. Otherwise, it will be applied unconditionally.
+    ///
+    /// # Returns:
+    ///
+    /// * The new state after applying the UnitaryN operator.
+    ///
+    /// # Errors:
+    ///
+    /// * `Error::InvalidNumberOfQubits` - If the number of target qubits is not k.
+    ///
+    /// * `Error::InvalidQubitIndex` - If the target or control qubit indices are invalid for the number of qubits in the state, or the target qubits are not different.
+    ///
+    /// * `Error::OverlappingControlAndTargetQubits` - If the control qubit and target qubit indices overlap.
+    fn apply(
+        &self,
+        state: &State,
+        target_qubits: &[usize],
+        control_qubits: &[usize],
+    ) -> Result<State, Error> {
+        // Validation
+        validate_qubits(state, target_qubits, control_qubits, self.num_qubits)?;
+
+        let nq: usize = state.num_qubits();
+        let dim: usize = 1 << nq;
+        let block_dim: usize = self.matrix.len();
+        let target_mask: usize = target_qubits.iter().fold(0, |mask, &t| mask | (1 << t));
+
+        // Offsets of the basis states in a block, relative to the state with all target qubits at |0>
+        let offsets: Vec<usize> = (0..block_dim)
+            .map(|local| {
+                target_qubits
+                    .iter()
+                    .enumerate()
+                    .filter(|&(bit, _)| (local >> bit) & 1 == 1)
+                    .fold(0, |offset, (_, &t)| offset | (1 << t))
+            })
+            .collect();
+
+        // Gather the amplitudes of a block, multiply by the matrix and scatter them back
+        let block = |i: usize| -> Vec<(usize, Complex<f64>)> {
+            let amplitudes: Vec<Complex<f64>> =
+                offsets.iter().map(|offset| state.state_vector[i | offset]).collect();
+            self.matrix
+                .iter()
+                .zip(&offsets)
+                .map(|(row, offset)| {
+                    let value: Complex<f64> = row.iter().zip(&amplitudes).map(|(m, a)| m * a).sum();
+                    (i | offset, value)
+                })
+                .collect()
+        };
+        let is_block_start = |i: usize| i & target_mask == 0 && check_controls(i, control_qubits);
+
+        let mut new_state_vec = state.state_vector.clone();
+        if nq >= PARALLEL_THRESHOLD_NUM_QUBITS {
+            // Parallel implementation
+            let updates: Vec<(usize, Complex<f64>)> = (0..dim)
+                .into_par_iter()
+                .filter(|&i| is_block_start(i))
+                .flat_map_iter(block)
+                .collect();
+            for (idx, val) in updates {
+                new_state_vec[idx] = val;
+            }
+        } else {
+            // Sequential implementation
+            for i in (0..dim).filter(|&i| is_block_start(i)) {
+                for (idx, val) in block(i) {
+                    new_state_vec[idx] = val;
+                }
+            }
+        }
+
+        Ok(State {
+            state_vector: new_state_vec,
+            num_qubits: nq,
+        })
+    }
+
+    fn base_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    /// Only one- and two-qubit operators can be compiled, through Euler and KAK decompositions.
+    fn to_compilable(&self) -> Option<&dyn Compilable> {
+        if self.num_qubits <= 2 { Some(self) } else { None }
+    }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        Some(self.matrix.clone())
+    }
+
+    fn adjoint(&self) -> Option<Box<dyn Operator>> {
+        let dim: usize = self.matrix.len();
+        Some(Box::new(UnitaryN {
+            matrix: (0..dim)
+                .map(|i| (0..dim).map(|j| self.matrix[j][i].conj()).collect())
+                .collect(),
+            num_qubits: self.num_qubits,
+        }))
+    }
+}
+
+/// The kinds of single-qubit operators whose angle can be a symbolic `ParameterExpression`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParameterisedKind {
+    /// A rotation around the X axis, as in `RotateX`
+    RotateX,
+    /// A rotation around the Y axis, as in `RotateY`
+    RotateY,
+    /// A rotation around the Z axis, as in `RotateZ`
+    RotateZ,
+    /// A phase shift of the |1> state, as in `PhaseShift`
+    PhaseShift,
+}
+
+impl ParameterisedKind {
+    /// Creates the operator of this kind with a fixed angle.
+    fn with_angle(self, angle: f64) -> Box<dyn Operator> {
+        match self {
+            ParameterisedKind::RotateX => Box::new(RotateX::new(angle)),
+            ParameterisedKind::RotateY => Box::new(RotateY::new(angle)),
+            ParameterisedKind::RotateZ => Box::new(RotateZ::new(angle)),
+            ParameterisedKind::PhaseShift => Box::new(PhaseShift::new(angle)),
+        }
+    }
+
+    /// Creates the operator of this kind for an angle expression.
+    ///
+    /// Constant angles give the plain operator, e.g. `RotateX`, and symbolic ones a `ParameterisedOperator`.
+    pub(crate) fn operator(self, angle: ParameterExpression) -> Box<dyn Operator> {
+        match angle.value() {
+            Some(value) => self.with_angle(value),
+            None => Box::new(ParameterisedOperator { kind: self, angle }),
+        }
+    }
+}
+
+/// A rotation or phase shift operator whose angle depends on symbolic parameters.
+///
+/// The operator can only be applied once values have been bound to all of its parameters, e.g. with `Circuit::bind`,
+/// which replaces it by the corresponding `RotateX`, `RotateY`, `RotateZ` or `PhaseShift` operator. Circuits containing
+/// unbound parameters can still be inverted, and exported to OpenQASM 3.0 with `input float` declarations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterisedOperator {
+    /// The kind of operator.
+    pub(crate) kind: ParameterisedKind,
+    /// The angle of the operator in radians.
+    pub(crate) angle: ParameterExpression,
+}
+
+impl ParameterisedOperator {
+    /// Creates a new ParameterisedOperator with the given kind and angle.
+    ///
+    /// # Arguments:
+    ///
+    /// * `kind` - The kind of operator.
+    ///
+    /// * `angle` - The angle of the operator in radians, as an expression of parameters.
+    pub fn new(kind: ParameterisedKind, angle: impl Into<ParameterExpression>) -> Self {
+        ParameterisedOperator {
+            kind,
+            angle: angle.into(),
+        }
+    }
+
+    /// Gets the kind of the operator.
+    ///
+    /// # Returns:
+    ///
+    /// * `ParameterisedKind` - The kind of operator.
+    pub fn kind(&self) -> ParameterisedKind {
+        self.kind
+    }
+
+    /// Gets the angle of the operator.
+    ///
+    /// # Returns:
+    ///
+    /// * `&ParameterExpression` - The angle of the operator in radians.
+    pub fn angle(&self) -> &ParameterExpression {
+        &self.angle
+    }
+}
+
+impl Operator for ParameterisedOperator {
+    /// Applies the operator to the given state's target qubit, if its angle has a value.
+    ///
+    /// # Arguments:
+    ///
+    /// * `state` - The state to apply the operator to.
+    ///
+    /// * `target_qubits` - The target qubits to apply the operator to. This should be a single qubit.
+    ///
+    /// * `control_qubits` - The control qubits for the operator. If not empty, the operator will be applied conditionally based on the control qubits. Otherwise, it will be applied unconditionally.
+    ///
+    /// # Returns:
+    ///
+    /// * The new state after applying the operator.
+    ///
+    /// # Errors:
+    ///
+    /// * `Error::UnboundParameter` - If the angle depends on a parameter without a value.
+    ///
+    /// * Any error of the corresponding fixed-angle operator.
+    fn apply(
+        &self,
+        state: &State,
+        target_qubits: &[usize],
+        control_qubits: &[usize],
+    ) -> Result<State, Error> {
+        let angle: f64 = self.angle.evaluate(&HashMap::new())?;
+        self.kind.with_angle(angle).apply(state, target_qubits, control_qubits)
+    }
+
+    fn base_qubits(&self) -> usize {
+        1
+    }
+
+    fn to_compilable(&self) -> Option<&dyn Compilable> {
+        Some(self)
+    }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        self.kind.with_angle(self.angle.value()?).matrix()
+    }
+
+    fn adjoint(&self) -> Option<Box<dyn Operator>> {
+        Some(self.kind.operator(-self.angle.clone()))
+    }
+
+    fn parameters(&self) -> Vec<Parameter> {
+        self.angle.parameters()
+    }
+
+    fn bind(&self, values: &HashMap<Parameter, f64>) -> Option<Box<dyn Operator>> {
+        Some(self.kind.operator(self.angle.bind(values)))
+    }
+
+    fn to_parameterised(&self) -> Option<&ParameterisedOperator> {
+        Some(self)
+    }
 }
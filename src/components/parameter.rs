@@ -0,0 +1,317 @@
+use crate::errors::Error;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A named symbolic parameter of a circuit, such as a variational angle.
+///
+/// Parameters are identified by their name, so two parameters with the same name are equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Parameter {
+    /// The name of the parameter.
+    name: String,
+}
+
+impl Parameter {
+    /// Creates a new parameter with the given name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the parameter. To export circuits to OpenQASM, this must be a valid identifier that is
+    ///   not reserved by OpenQASM 3.0 (e.g. `pi`) or by the emitted program (`q` and `c`).
+    ///
+    /// # Returns
+    ///
+    /// * `Parameter` - A new instance of the Parameter struct.
+    pub fn new(name: &str) -> Self {
+        Parameter {
+            name: name.to_string(),
+        }
+    }
+
+    /// Gets the name of the parameter.
+    ///
+    /// # Returns
+    ///
+    /// * `&str` - The name of the parameter.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// A linear combination of parameters plus a constant, e.g. `2θ - φ + 0.5`.
+///
+/// Expressions are built from parameters and `f64` values with the `+`, `-` and `*` operators, and evaluated once
+/// values have been bound to all their parameters.
+///
+/// # Fields
+///
+/// * `terms` - The parameters of the expression with their non-zero coefficients, in order of first appearance.
+/// * `constant` - The constant term of the expression.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ParameterExpression {
+    /// The parameters of the expression with their non-zero coefficients, in order of first appearance.
+    terms: Vec<(Parameter, f64)>,
+    /// The constant term of the expression.
+    constant: f64,
+}
+
+impl ParameterExpression {
+    /// Creates a constant expression with no parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value of the expression.
+    ///
+    /// # Returns
+    ///
+    /// * `ParameterExpression` - A new instance of the ParameterExpression struct.
+    pub fn constant(value: f64) -> Self {
+        ParameterExpression {
+            terms: Vec::new(),
+            constant: value,
+        }
+    }
+
+    /// Gets the parameters of the expression, in order of first appearance.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Parameter>` - The parameters with a non-zero coefficient in the expression.
+    pub fn parameters(&self) -> Vec<Parameter> {
+        self.terms.iter().map(|(parameter, _)| parameter.clone()).collect()
+    }
+
+    /// Gets the coefficient of a parameter in the expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `parameter` - The parameter to look up.
+    ///
+    /// # Returns
+    ///
+    /// * `f64` - The coefficient of the parameter, or zero if the expression does not depend on it.
+    pub fn coefficient(&self, parameter: &Parameter) -> f64 {
+        self.terms
+            .iter()
+            .find(|(term, _)| term == parameter)
+            .map_or(0.0, |(_, coefficient)| *coefficient)
+    }
+
+    /// Gets the value of the expression if it has no parameters.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<f64>` - The value of a constant expression, or `None` if the expression depends on a parameter.
+    pub fn value(&self) -> Option<f64> {
+        if self.terms.is_empty() { Some(self.constant) } else { None }
+    }
+
+    /// Substitutes values for some or all of the parameters of the expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The values of the parameters to substitute. Parameters without a value are left unbound.
+    ///
+    /// # Returns
+    ///
+    /// * `ParameterExpression` - The expression with the bound parameters replaced by their values.
+    pub fn bind(&self, values: &HashMap<Parameter, f64>) -> ParameterExpression {
+        let mut bound: ParameterExpression = ParameterExpression::constant(self.constant);
+        for (parameter, coefficient) in &self.terms {
+            match values.get(parameter) {
+                Some(value) => bound.constant += coefficient * value,
+                None => bound.terms.push((parameter.clone(), *coefficient)),
+            }
+        }
+        bound
+    }
+
+    /// Evaluates the expression with the given parameter values.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The values of the parameters.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<f64, Error>` - The value of the expression.
+    ///
+    /// # Errors
+    ///
+    /// * `Error::UnboundParameter` - If a parameter of the expression has no value.
+    pub fn evaluate(&self, values: &HashMap<Parameter, f64>) -> Result<f64, Error> {
+        let bound: ParameterExpression = self.bind(values);
+        match bound.terms.first() {
+            Some((parameter, _)) => Err(Error::UnboundParameter(parameter.name().to_string())),
+            None => Ok(bound.constant),
+        }
+    }
+
+    /// Checks that the constant term and every coefficient of the expression are finite.
+    pub(crate) fn is_finite(&self) -> bool {
+        self.constant.is_finite() && self.terms.iter().all(|(_, coefficient)| coefficient.is_finite())
+    }
+
+    /// Adds a multiple of a parameter to the expression, merging it with an existing term.
+    fn add_term(&mut self, parameter: Parameter, coefficient: f64) {
+        match self.terms.iter().position(|(term, _)| *term == parameter) {
+            Some(index) => {
+                self.terms[index].1 += coefficient;
+                if self.terms[index].1 == 0.0 {
+                    self.terms.remove(index);
+                }
+            }
+            None if coefficient != 0.0 => self.terms.push((parameter, coefficient)),
+            None => {}
+        }
+    }
+}
+
+impl From<f64> for ParameterExpression {
+    fn from(value: f64) -> Self {
+        ParameterExpression::constant(value)
+    }
+}
+
+impl From<Parameter> for ParameterExpression {
+    fn from(parameter: Parameter) -> Self {
+        ParameterExpression {
+            terms: vec![(parameter, 1.0)],
+            constant: 0.0,
+        }
+    }
+}
+
+impl From<&Parameter> for ParameterExpression {
+    fn from(parameter: &Parameter) -> Self {
+        ParameterExpression::from(parameter.clone())
+    }
+}
+
+impl<T: Into<ParameterExpression>> Add<T> for ParameterExpression {
+    type Output = ParameterExpression;
+
+    fn add(mut self, rhs: T) -> ParameterExpression {
+        let rhs: ParameterExpression = rhs.into();
+        self.constant += rhs.constant;
+        for (parameter, coefficient) in rhs.terms {
+            self.add_term(parameter, coefficient);
+        }
+        self
+    }
+}
+
+impl<T: Into<ParameterExpression>> Sub<T> for ParameterExpression {
+    type Output = ParameterExpression;
+
+    fn sub(self, rhs: T) -> ParameterExpression {
+        let rhs: ParameterExpression = rhs.into();
+        self + -rhs
+    }
+}
+
+impl Mul<f64> for ParameterExpression {
+    type Output = ParameterExpression;
+
+    fn mul(mut self, rhs: f64) -> ParameterExpression {
+        self.constant *= rhs;
+        if rhs == 0.0 {
+            self.terms.clear();
+        }
+        for (_, coefficient) in &mut self.terms {
+            *coefficient *= rhs;
+        }
+        self
+    }
+}
+
+impl Mul<ParameterExpression> for f64 {
+    type Output = ParameterExpression;
+
+    fn mul(self, rhs: ParameterExpression) -> ParameterExpression {
+        rhs * self
+    }
+}
+
+impl Neg for ParameterExpression {
+    type Output = ParameterExpression;
+
+    fn neg(self) -> ParameterExpression {
+        self * -1.0
+    }
+}
+
+impl<T: Into<ParameterExpression>> Add<T> for Parameter {
+    type Output = ParameterExpression;
+
+    fn add(self, rhs: T) -> ParameterExpression {
+        ParameterExpression::from(self) + rhs
+    }
+}
+
+impl<T: Into<ParameterExpression>> Sub<T> for Parameter {
+    type Output = ParameterExpression;
+
+    fn sub(self, rhs: T) -> ParameterExpression {
+        ParameterExpression::from(self) - rhs
+    }
+}
+
+impl Mul<f64> for Parameter {
+    type Output = ParameterExpression;
+
+    fn mul(self, rhs: f64) -> ParameterExpression {
+        ParameterExpression::from(self) * rhs
+    }
+}
+
+impl Mul<Parameter> for f64 {
+    type Output = ParameterExpression;
+
+    fn mul(self, rhs: Parameter) -> ParameterExpression {
+        ParameterExpression::from(rhs) * self
+    }
+}
+
+impl Neg for Parameter {
+    type Output = ParameterExpression;
+
+    fn neg(self) -> ParameterExpression {
+        -ParameterExpression::from(self)
+    }
+}
+
+impl fmt::Display for ParameterExpression {
+    /// Formats the expression as an OpenQASM 3.0 expression, e.g. `2.0*theta - phi + 0.5`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (parameter, coefficient)) in self.terms.iter().enumerate() {
+            let sign: &str = match (index, *coefficient < 0.0) {
+                (0, true) => "-",
+                (0, false) => "",
+                (_, true) => " - ",
+                (_, false) => " + ",
+            };
+            if coefficient.abs() == 1.0 {
+                write!(f, "{}{}", sign, parameter)?;
+            } else {
+                write!(f, "{}{:?}*{}", sign, coefficient.abs(), parameter)?;
+            }
+        }
+        if self.terms.is_empty() {
+            write!(f, "{:?}", self.constant)
+        } else if self.constant < 0.0 {
+            write!(f, " - {:?}", -self.constant)
+        } else if self.constant > 0.0 {
+            write!(f, " + {:?}", self.constant)
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::components::{
+    counts::Counts,
     measurement::{MeasurementBasis, MeasurementResult},
     operator::{
         CNOT, Hadamard, Identity, Operator, Pauli, PhaseS, PhaseSdag, PhaseShift, PhaseT,
@@ -7,7 +8,7 @@ use crate::components::{
 };
 use crate::errors::Error;
 use num_complex::Complex;
-use rand::Rng;
+use rand::{Rng, SeedableRng, rngs::StdRng};
 use rayon::prelude::*;
 use std::ops::{Add, Mul, Sub};
 
@@ -19,6 +20,63 @@ fn calculate_adjoint(matrix: &[[Complex<f64>; 2]; 2]) -> [[Complex<f64>; 2]; 2]
     ]
 }
 
+/// Creates an independent random number generator for one of many parallel streams derived from a seed.
+///
+/// The seed of each stream is mixed with SplitMix64, so streams with neighbouring indices are uncorrelated. Deriving one
+/// stream per shot keeps parallel sampling reproducible, independently of the number of threads.
+pub(crate) fn stream_rng(seed: u64, stream: u64) -> StdRng {
+    fn splitmix64(value: u64) -> u64 {
+        let mut z: u64 = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+    StdRng::seed_from_u64(splitmix64(seed ^ splitmix64(stream)))
+}
+
+/// An alias table for drawing samples from a discrete distribution in constant time, built with Vose's method.
+struct AliasTable {
+    /// The probability of keeping each column instead of taking its alias.
+    keep: Vec<f64>,
+    /// The outcome that fills the rest of each column.
+    alias: Vec<usize>,
+}
+
+impl AliasTable {
+    /// Builds the alias table of a distribution whose weights sum to `total`.
+    fn new(weights: &[f64], total: f64) -> Self {
+        let n: usize = weights.len();
+        let mut keep: Vec<f64> = weights.iter().map(|&weight| weight * n as f64 / total).collect();
+        let mut alias: Vec<usize> = (0..n).collect();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) = (0..n).partition(|&i| keep[i] < 1.0);
+
+        while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+            small.pop();
+            alias[s] = l;
+            keep[l] -= 1.0 - keep[s];
+            if keep[l] < 1.0 {
+                large.pop();
+                small.push(l);
+            }
+        }
+        // Columns left over because of rounding errors are full
+        for i in small.into_iter().chain(large) {
+            keep[i] = 1.0;
+        }
+        AliasTable { keep, alias }
+    }
+
+    /// Draws an outcome from the distribution.
+    fn sample(&self, rng: &mut impl Rng) -> usize {
+        let column: usize = rng.random_range(0..self.keep.len());
+        if rng.random::<f64>() < self.keep[column] {
+            column
+        } else {
+            self.alias[column]
+        }
+    }
+}
+
 #[derive(Clone)]
 /// Represents the state of a quantum register.
 /// 
@@ -267,11 +325,44 @@ impl State {
 
     // ***** MEASUREMENT FUNCTIONS *****
 
+    // Helper function to calculate the probability of each outcome of measuring the given qubits in the computational
+    // basis, where bit k of the outcome is the value of qubits[k]
+    fn _marginal_probabilities(&self, measured_qubits: &[usize]) -> Vec<f64> {
+        let num_outcomes: usize = 1 << measured_qubits.len();
+        self.state_vector
+            .par_iter()
+            .enumerate()
+            .fold(
+                || vec![0.0; num_outcomes], // Thread-local accumulator
+                |mut acc_probs, (idx, amplitude)| {
+                    let mut outcome_val_for_this_state = 0;
+                    // Extract the bits corresponding to measured_qubits to form the outcome value
+                    for (bit_idx, &qubit_pos) in measured_qubits.iter().enumerate() {
+                        if (idx >> qubit_pos) & 1 != 0 {
+                            outcome_val_for_this_state |= 1 << bit_idx;
+                        }
+                    }
+                    acc_probs[outcome_val_for_this_state] += amplitude.norm_sqr();
+                    acc_probs
+                },
+            )
+            .reduce(
+                || vec![0.0; num_outcomes], // Initialiser for combining thread-local results
+                |mut total_probs, local_probs| {
+                    for (total, local) in total_probs.iter_mut().zip(local_probs) {
+                        *total += local;
+                    }
+                    total_probs
+                },
+            )
+    }
+
     fn _measure_computational(
         &self,
         measured_qubits: &[usize],
+        rng: &mut impl Rng,
     ) -> Result<MeasurementResult, Error> {
-        return self.measure(MeasurementBasis::Computational, measured_qubits);
+        self.measure_with_rng(MeasurementBasis::Computational, measured_qubits, rng)
     }
 
     /// Measures the state vector in the specified basis and returns the measurement result.
@@ -294,6 +385,35 @@ impl State {
         &self,
         basis: MeasurementBasis,
         measured_qubits: &[usize],
+    ) -> Result<MeasurementResult, Error> {
+        self.measure_with_rng(basis, measured_qubits, &mut rand::rng())
+    }
+
+    /// Measures the state vector in the specified basis using the given random number generator, and returns the
+    /// measurement result.
+    ///
+    /// Passing a seeded generator, such as `StdRng::seed_from_u64`, makes the outcome reproducible.
+    ///
+    /// # Arguments
+    ///
+    /// * `basis` - The basis to measure in.
+    /// * `indices` - The indices of the qubits to measure. If `indices` is empty, all qubits are measured.
+    /// * `rng` - The random number generator used to sample the outcome.
+    ///
+    /// # Returns
+    ///
+    /// * `result` - A result containing the measurement result if successful, or an error if the measurement fails.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the measurement fails.
+    /// * Returns an error if the number of qubits is invalid.
+    /// * Returns an error if the indices are out of bounds for the state vector.
+    pub fn measure_with_rng(
+        &self,
+        basis: MeasurementBasis,
+        measured_qubits: &[usize],
+        rng: &mut impl Rng,
     ) -> Result<MeasurementResult, Error> {
         // If no indices are provided, measure all qubits
         let all_qubits: Vec<usize> = if measured_qubits.is_empty() {
@@ -321,38 +441,8 @@ impl State {
 
         match basis {
             MeasurementBasis::Computational => {
-                let num_outcomes: usize = 1 << num_measured;
-
                 // Calculate probabilities for each outcome (outcome as a single integer 0..num_outcomes-1)
-                let probabilities: Vec<f64> = self.state_vector
-                    .par_iter()
-                    .enumerate()
-                    .fold(
-                        || vec![0.0; num_outcomes], // Thread-local accumulator
-                        |mut acc_probs, (idx, amplitude)| {
-                            let mut outcome_val_for_this_state = 0;
-                            // Extract the bits corresponding to measured_qubits to form the outcome value
-                            for (bit_idx, &qubit_pos) in actual_measured_qubits.iter().enumerate() {
-                                if (idx >> qubit_pos) & 1 != 0 {
-                                    outcome_val_for_this_state |= 1 << bit_idx;
-                                }
-                            }
-                            // Ensure outcome_val_for_this_state is within bounds (0 to num_outcomes-1)
-                            if outcome_val_for_this_state < num_outcomes {
-                                 acc_probs[outcome_val_for_this_state] += amplitude.norm_sqr();
-                            }
-                            acc_probs
-                        },
-                    )
-                    .reduce(
-                        || vec![0.0; num_outcomes], // Initialiser for combining thread-local results
-                        |mut total_probs, local_probs| {
-                            for i in 0..num_outcomes {
-                                total_probs[i] += local_probs[i];
-                            }
-                            total_probs
-                        },
-                    );
+                let probabilities: Vec<f64> = self._marginal_probabilities(actual_measured_qubits);
 
                 // Normalise probabilities
                 let total_probability: f64 = probabilities.iter().sum();
@@ -365,7 +455,6 @@ impl State {
                     .collect();
 
                 // Sample an outcome based on the probabilities
-                let mut rng = rand::rng();
                 let random_value: f64 = rng.random_range(0.0..1.0);
 
                 let mut cumulative_probability: f64 = 0.0;
@@ -439,7 +528,7 @@ impl State {
                 // Apply Hadamard to measured qubits
                 let transformed_state = self.h_multi(actual_measured_qubits)?;
                 // Measure in computational basis
-                let computational_measurement_result = transformed_state._measure_computational(actual_measured_qubits)?;
+                let computational_measurement_result = transformed_state._measure_computational(actual_measured_qubits, rng)?;
                 // Transform the new state back by applying Hadamard again
                 let final_state = computational_measurement_result.new_state.h_multi(actual_measured_qubits)?;
                 Ok(MeasurementResult {
@@ -454,7 +543,7 @@ impl State {
                 let state_after_sdag = self.s_dag_multi(actual_measured_qubits)?;
                 let transformed_state = state_after_sdag.h_multi(actual_measured_qubits)?;
                 // Measure in computational basis
-                let computational_measurement_result = transformed_state._measure_computational(actual_measured_qubits)?;
+                let computational_measurement_result = transformed_state._measure_computational(actual_measured_qubits, rng)?;
                 // Transform the new state back by applying H then S
                 let state_after_h = computational_measurement_result.new_state.h_multi(actual_measured_qubits)?;
                 let final_state = state_after_h.s_multi(actual_measured_qubits)?;
@@ -470,7 +559,7 @@ impl State {
                 let transformed_state = self.unitary_multi(actual_measured_qubits, u_matrix)?;
 
                 // Measure in computational basis
-                let computational_measurement_result = transformed_state._measure_computational(actual_measured_qubits)?;
+                let computational_measurement_result = transformed_state._measure_computational(actual_measured_qubits, rng)?;
 
                 // Calculate U_dagger (adjoint of u_matrix)
                 let u_dagger_matrix = calculate_adjoint(&u_matrix);
@@ -506,11 +595,49 @@ impl State {
     /// * Returns an error if the number of qubits is invalid.
     /// * Returns an error if the indices are out of bounds for the state vector.
     /// * Returns an error if `n` is 0.
+    ///
+    /// # Notes
+    ///
+    /// Each measurement builds its own post-measurement state. Use `sample` when only the outcomes are needed.
     pub fn measure_n(
         &self,
         basis: MeasurementBasis,
         measured_qubits: &[usize],
         n: usize,
+    ) -> Result<Vec<MeasurementResult>, Error> {
+        self.measure_n_with_rng(basis, measured_qubits, n, &mut rand::rng())
+    }
+
+    /// Measures the state vector `n` times in the specified basis using the given random number generator, and
+    /// returns the measurement results.
+    ///
+    /// A seed is drawn from the generator, and each measurement uses its own stream derived from that seed. The
+    /// measurements run in parallel, but the results only depend on the state of the generator, not on the number of
+    /// threads.
+    ///
+    /// # Arguments
+    ///
+    /// * `basis` - The basis to measure in.
+    /// * `indices` - The indices of the qubits to measure. If `indices` is empty, all qubits are measured.
+    /// * `n` - The number of measurements to perform.
+    /// * `rng` - The random number generator from which the seed of the measurements is drawn.
+    ///
+    /// # Returns
+    ///
+    /// * `results` - A result containing a vector of measurement results if successful, or an error if the measurement fails.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the measurement fails.
+    /// * Returns an error if the number of qubits is invalid.
+    /// * Returns an error if the indices are out of bounds for the state vector.
+    /// * Returns an error if `n` is 0.
+    pub fn measure_n_with_rng(
+        &self,
+        basis: MeasurementBasis,
+        measured_qubits: &[usize],
+        n: usize,
+        rng: &mut impl Rng,
     ) -> Result<Vec<MeasurementResult>, Error> {
         if n == 0 {
             return Err(Error::InvalidNumberOfMeasurements(0));
@@ -535,13 +662,136 @@ impl State {
             }
         }
 
+        let seed: u64 = rng.random();
         let results: Vec<MeasurementResult> = (0..n)
             .into_par_iter()
-            .map(|_| self.measure(basis, actual_measured_qubits))
+            .map(|shot| self.measure_with_rng(basis, actual_measured_qubits, &mut stream_rng(seed, shot as u64)))
             .collect::<Result<Vec<MeasurementResult>, Error>>()?;
         Ok(results)
     }
 
+    /// Samples the outcomes of measuring the state `shots` times in the specified basis, without collapsing it.
+    ///
+    /// The distribution of the measured qubits is computed once, and outcomes are drawn from it with an alias table in
+    /// constant time per shot. Unlike `measure_n`, no post-measurement states are built.
+    ///
+    /// # Arguments
+    ///
+    /// * `basis` - The basis to measure in.
+    /// * `measured_qubits` - The indices of the qubits to measure. If `measured_qubits` is empty, all qubits are measured.
+    /// * `shots` - The number of samples to draw.
+    ///
+    /// # Returns
+    ///
+    /// * `counts` - A histogram of the outcomes, where bit `k` of each bitstring is the outcome of `measured_qubits[k]`.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if `shots` is 0.
+    /// * Returns an error if the number of qubits is invalid.
+    /// * Returns an error if the indices are out of bounds for the state vector.
+    pub fn sample(
+        &self,
+        basis: MeasurementBasis,
+        measured_qubits: &[usize],
+        shots: usize,
+    ) -> Result<Counts, Error> {
+        self.sample_with_rng(basis, measured_qubits, shots, &mut rand::rng())
+    }
+
+    /// Samples the outcomes of measuring the state `shots` times in the specified basis using the given random number
+    /// generator, without collapsing it.
+    ///
+    /// # Arguments
+    ///
+    /// * `basis` - The basis to measure in.
+    /// * `measured_qubits` - The indices of the qubits to measure. If `measured_qubits` is empty, all qubits are measured.
+    /// * `shots` - The number of samples to draw.
+    /// * `rng` - The random number generator used to draw the samples.
+    ///
+    /// # Returns
+    ///
+    /// * `counts` - A histogram of the outcomes, where bit `k` of each bitstring is the outcome of `measured_qubits[k]`.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if `shots` is 0.
+    /// * Returns an error if the number of qubits is invalid.
+    /// * Returns an error if the indices are out of bounds for the state vector.
+    pub fn sample_with_rng(
+        &self,
+        basis: MeasurementBasis,
+        measured_qubits: &[usize],
+        shots: usize,
+        rng: &mut impl Rng,
+    ) -> Result<Counts, Error> {
+        if shots == 0 {
+            return Err(Error::InvalidNumberOfMeasurements(0));
+        }
+
+        // If no indices are provided, measure all qubits
+        let all_indices: Vec<usize> = (0..self.num_qubits).collect();
+        let actual_measured_qubits: &[usize] = if measured_qubits.is_empty() {
+            &all_indices
+        } else {
+            measured_qubits
+        };
+
+        // Check for valid indices
+        let num_measured: usize = actual_measured_qubits.len();
+        if num_measured > self.num_qubits {
+            return Err(Error::InvalidNumberOfQubits(self.num_qubits));
+        }
+        for &index in actual_measured_qubits {
+            if index >= self.num_qubits {
+                return Err(Error::InvalidQubitIndex(index, self.num_qubits));
+            }
+        }
+
+        // Rotate the measurement basis onto the computational basis
+        let rotated: Option<State> = match basis {
+            MeasurementBasis::Computational => None,
+            MeasurementBasis::X => Some(self.h_multi(actual_measured_qubits)?),
+            MeasurementBasis::Y => Some(
+                self.s_dag_multi(actual_measured_qubits)?
+                    .h_multi(actual_measured_qubits)?,
+            ),
+            MeasurementBasis::Custom(u_matrix) => Some(self.unitary_multi(actual_measured_qubits, u_matrix)?),
+        };
+        let frequencies: Vec<usize> = rotated
+            .as_ref()
+            .unwrap_or(self)
+            .sample_frequencies(actual_measured_qubits, shots, rng)?;
+
+        let mut counts: Counts = Counts::new(num_measured);
+        for (outcome, &frequency) in frequencies.iter().enumerate().filter(|&(_, &frequency)| frequency > 0) {
+            counts.add(&format!("{:0width$b}", outcome, width = num_measured), frequency)?;
+        }
+        Ok(counts)
+    }
+
+    // Helper function to sample measurements of valid qubits in the computational basis, returning how often each
+    // outcome occurred, where bit k of the outcome is the value of measured_qubits[k]
+    pub(crate) fn sample_frequencies(
+        &self,
+        measured_qubits: &[usize],
+        shots: usize,
+        rng: &mut impl Rng,
+    ) -> Result<Vec<usize>, Error> {
+        let probabilities: Vec<f64> = self._marginal_probabilities(measured_qubits);
+        let total_probability: f64 = probabilities.iter().sum();
+        if total_probability < f64::EPSILON {
+            return Err(Error::UnknownError);
+        }
+
+        let table: AliasTable = AliasTable::new(&probabilities, total_probability);
+        let mut frequencies: Vec<usize> = vec![0; probabilities.len()];
+        for _ in 0..shots {
+            frequencies[table.sample(rng)] += 1;
+        }
+        Ok(frequencies)
+    }
+
     /// Performs a tensor product of two state vectors and returns the resulting state.
     /// Uses parallel computation if the resulting dimension is large enough.
     ///
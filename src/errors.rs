@@ -35,14 +35,155 @@ pub enum Error {
     #[error("Invalid qubit index: {0} for {1} qubits")]
     InvalidQubitIndex(usize, usize),
 
+    /// A classical bit index is invalid for the number of bits
+    /// 
+    /// # Arguments:
+    /// 
+    /// * `0` - The invalid bit index
+    /// * `1` - The number of bits
+    #[error("Invalid bit index: {0} for {1} bits")]
+    InvalidBitIndex(usize, usize),
+
+    /// A bitstring does not have the expected number of bits, or contains characters other than 0 and 1
+    /// 
+    /// # Arguments:
+    /// 
+    /// * `0` - The invalid bitstring
+    /// * `1` - The expected number of bits
+    #[error("Invalid bitstring: {0:?} for {1} bits")]
+    InvalidBitString(String, usize),
+
+    /// The value of a classical condition does not fit into its bits
+    /// 
+    /// # Arguments:
+    /// 
+    /// * `0` - The value of the condition
+    /// * `1` - The number of bits of the condition
+    #[error("Invalid classical condition: {0} does not fit into {1} bits")]
+    InvalidClassicalCondition(u64, usize),
+
+    /// A classically controlled gate was applied without a classical register to check its condition
+    #[error("Classically controlled gate applied without a classical register")]
+    MissingClassicalRegister,
+
+    /// A gate of a classically controlled body other than the last writes a bit of the condition, which the later
+    /// gates of the body would then check again
+    /// 
+    /// # Arguments:
+    /// 
+    /// * `0` - The bit of the condition that is written
+    #[error("Classically controlled body writes bit {0} of its condition before its last gate")]
+    ConditionalBodyWritesCondition(usize),
+
     /// The state vector is not normalised
     #[error("State vector is not normalised")]
     StateVectorNotNormalised,
 
+    /// The trace of a density matrix is not 1
+    #[error("Density matrix does not have unit trace")]
+    DensityMatrixNotNormalised,
+
+    /// A matrix that must be Hermitian, such as a density matrix, is not
+    #[error("Non-Hermitian matrix")]
+    NonHermitianMatrix,
+
+    /// A probability is outside the interval [0, 1]
+    /// 
+    /// # Arguments:
+    /// 
+    /// * `0` - The invalid probability
+    #[error("Invalid probability: {0}")]
+    InvalidProbability(f64),
+
     /// Input matrix for arbitrary unitary operator was not unitary
     #[error("Non-unitary matrix")]
     NonUnitaryMatrix,
 
+    /// The Kraus operators of a channel do not satisfy the completeness relation Σ K†K = I
+    #[error("Kraus operators are not complete")]
+    IncompleteKrausOperators,
+
+    /// The relaxation times or gate time of a thermal relaxation channel are invalid
+    ///
+    /// # Arguments:
+    ///
+    /// * `0` - The relaxation time T1
+    /// * `1` - The dephasing time T2
+    /// * `2` - The gate time
+    #[error("Invalid relaxation times: T1 = {0}, T2 = {1}, gate time = {2}")]
+    InvalidRelaxationTimes(f64, f64, f64),
+
+    /// An evolution time is negative, not finite or earlier than the previous time
+    ///
+    /// # Arguments:
+    ///
+    /// * `0` - The invalid time
+    #[error("Invalid evolution time: {0}")]
+    InvalidEvolutionTime(f64),
+
+    /// The step size of an integrator is not positive, or an adaptive step became too small
+    ///
+    /// # Arguments:
+    ///
+    /// * `0` - The invalid step size
+    #[error("Invalid time step: {0}")]
+    InvalidTimeStep(f64),
+
+    /// The error tolerance of an adaptive integrator is not positive
+    ///
+    /// # Arguments:
+    ///
+    /// * `0` - The invalid tolerance
+    #[error("Invalid tolerance: {0}")]
+    InvalidTolerance(f64),
+
+    /// Input matrix for arbitrary unitary operator is not square with a power-of-two dimension
+    /// 
+    /// # Arguments:
+    /// 
+    /// * `0` - The number of rows
+    /// * `1` - The number of columns in the first row that differs from the number of rows, or the
+    ///   number of rows if the matrix is square
+    #[error("Invalid matrix dimensions: {0}x{1}")]
+    InvalidMatrixDimensions(usize, usize),
+
+    /// The circuit contains a non-unitary gate, such as a measurement, where only unitary gates
+    /// are allowed
+    #[error("Circuit contains a non-unitary gate")]
+    NonUnitaryCircuit,
+
+    /// An operator does not provide an adjoint, so it cannot be inverted
+    #[error("Operator has no known adjoint")]
+    UnknownAdjoint,
+
+    /// An operator does not provide its matrix, so it cannot be applied to a density matrix
+    #[error("Operator has no known matrix")]
+    UnknownMatrix,
+
+    /// A symbolic parameter has no value
+    /// 
+    /// # Arguments:
+    /// 
+    /// * `0` - The name of the unbound parameter
+    #[error("Unbound parameter: {0}")]
+    UnboundParameter(String),
+
+    /// An operator depends on parameters but cannot be differentiated with respect to them
+    /// 
+    /// # Arguments:
+    /// 
+    /// * `0` - A description of the operator
+    #[error("Operator cannot be differentiated: {0}")]
+    NonDifferentiableOperator(String),
+
+    /// An observable is not diagonal in the computational basis, so it cannot be estimated from measurement counts
+    /// 
+    /// # Arguments:
+    /// 
+    /// * `0` - The term that is not diagonal
+    #[error("Observable is not diagonal in the computational basis: {0}")]
+    NonDiagonalObservable(String),
+
     /// Unexpected number of inputs
     /// 
     /// # Arguments:
@@ -79,6 +220,16 @@ pub enum CompilerError {
 
     #[error("Invalid operands ({0}) for operator {1}")]
     InvalidOperands(String, String),
+
+    /// The OpenQASM source could not be parsed
+    ///
+    /// # Arguments:
+    ///
+    /// * `0` - The line on which the error occurred (1-based)
+    /// * `1` - The column at which the error occurred (1-based)
+    /// * `2` - A description of the error
+    #[error("Parse error at line {0}, column {1}: {2}")]
+    ParseError(usize, usize, String),
 }
 
 #[cfg(feature = "gpu")]
@@ -7,17 +7,30 @@ pub mod algorithms;
 pub mod compiler;
 
 // Re-export important types for easier imports
+pub use crate::components::channel::Channel;
+pub use crate::components::classical::{ClassicalCondition, ClassicalRegister};
+pub use crate::components::counts::Counts;
+pub use crate::components::density_matrix::DensityMatrix;
 pub use crate::components::measurement::{MeasurementBasis, MeasurementResult};
 pub use crate::components::operator::{
     CNOT, Hadamard, Identity, Operator, Pauli, PhaseS, PhaseSdag, PhaseShift, PhaseT, PhaseTdag,
-    RotateX, RotateY, RotateZ, SWAP, Toffoli, Unitary2
+    ParameterisedKind, ParameterisedOperator, RotateX, RotateY, RotateZ, SWAP, Toffoli, Unitary2, Unitary4,
+    UnitaryN
 };
+pub use crate::components::noise::{ConfusionMatrix, GateKind, NoiseModel};
+pub use crate::components::parameter::{Parameter, ParameterExpression};
 pub use crate::components::state::{ChainableState, State};
 pub use crate::components::pauli_string::{PauliString, SumOp};
 pub use crate::circuit::{Circuit, CircuitBuilder};
 pub use crate::subroutine::Subroutine;
 pub use crate::models::{heisenberg, ising};
-pub use crate::algorithms::time_evolution;
+pub use crate::algorithms::{gradient, lindblad, time_evolution, trajectory};
+pub use crate::compiler::ir::{CircuitIR, InstructionIR};
+pub use crate::compiler::pass::{Pass, PassManager, PassStatistics};
+pub use crate::compiler::coupling_map::CouplingMap;
+pub use crate::compiler::euler::{EulerBasis, EulerDecomposition};
+pub use crate::compiler::kak::KakDecomposition;
+pub use crate::compiler::routing::{Layout, RoutedCircuit, SabreRouter};
 
 #[cfg(test)]
 pub mod tests;
@@ -4,6 +4,7 @@ use crate::{
     errors::Error,
     circuit::CircuitBuilder,
 };
+use num_complex::Complex;
 
 /// A subroutine for a quantum circuit.
 ///
@@ -78,6 +79,76 @@ impl Subroutine {
         self.num_qubits
     }
 
+    /// Computes the dense `2^n x 2^n` unitary matrix of the subroutine.
+    ///
+    /// See `Circuit::unitary` for the qubit ordering of the matrix.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Vec<Complex<f64>>>, Error>` - The unitary matrix of the subroutine, as a vector
+    ///   of rows.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the subroutine contains a measurement.
+    /// * Returns an error if the subroutine cannot be executed due to invalid gate operations.
+    pub fn unitary(&self) -> Result<Vec<Vec<Complex<f64>>>, Error> {
+        Circuit::with_gates(self.gates.clone(), self.num_qubits)?.unitary()
+    }
+
+    /// Returns the inverse of the subroutine. See `Circuit::inverse`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Subroutine, Error>` - The inverse subroutine.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the subroutine contains a measurement.
+    /// * Returns an error if the subroutine contains an operator without a known adjoint.
+    pub fn inverse(&self) -> Result<Subroutine, Error> {
+        let circuit: Circuit = Circuit::with_gates(self.gates.clone(), self.num_qubits)?.inverse()?;
+        Ok(Subroutine::with_gates(circuit.gates, circuit.num_qubits))
+    }
+
+    /// Returns the subroutine repeated `k` times. See `Circuit::power`.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - The number of repetitions of the subroutine.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Subroutine, Error>` - The subroutine applied `k` times.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the subroutine contains a measurement.
+    pub fn power(&self, k: usize) -> Result<Subroutine, Error> {
+        let circuit: Circuit = Circuit::with_gates(self.gates.clone(), self.num_qubits)?.power(k)?;
+        Ok(Subroutine::with_gates(circuit.gates, circuit.num_qubits))
+    }
+
+    /// Returns a controlled version of the subroutine. See `Circuit::controlled`.
+    ///
+    /// # Arguments
+    ///
+    /// * `control_qubits` - The control qubits, which must not be a target of any gate.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Subroutine, Error>` - The controlled subroutine.
+    ///
+    /// # Errors
+    ///
+    /// * Returns an error if the subroutine contains a measurement.
+    /// * Returns an error if a control qubit is out of range, repeated, or a target of a gate.
+    pub fn controlled(&self, control_qubits: &[usize]) -> Result<Subroutine, Error> {
+        let circuit: Circuit = Circuit::with_gates(self.gates.clone(), self.num_qubits)?
+            .controlled(control_qubits)?;
+        Ok(Subroutine::with_gates(circuit.gates, circuit.num_qubits))
+    }
+
     // -- COMMON SUBROUTINES --
 
     /// Creates a quantum fourier transform subroutine for the specified qubits.
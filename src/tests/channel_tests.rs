@@ -0,0 +1,167 @@
+use super::helpers::c;
+use crate::{
+    components::{channel::Channel, density_matrix::DensityMatrix, state::State},
+    errors::Error,
+};
+use num_complex::Complex;
+use rand::{SeedableRng, rngs::StdRng};
+
+fn zero() -> DensityMatrix {
+    DensityMatrix::new_zero(1).unwrap()
+}
+
+fn one() -> DensityMatrix {
+    DensityMatrix::from_state(&State::new_basis_n(1, 1).unwrap())
+}
+
+fn plus() -> DensityMatrix {
+    DensityMatrix::from_state(&State::new_plus(1).unwrap())
+}
+
+fn assert_close(actual: Complex<f64>, expected: f64) {
+    assert!((actual - c(expected, 0.0)).norm() < 1e-12, "expected {}, got {}", expected, actual);
+}
+
+#[test]
+fn test_channel_validation() {
+    assert_eq!(Channel::new(vec![]).err(), Some(Error::InvalidNumberOfInputs(0, 1)));
+    assert_eq!(
+        Channel::new(vec![vec![vec![c(1.0, 0.0), c(0.0, 0.0)], vec![c(0.0, 0.0)]]]).err(),
+        Some(Error::InvalidMatrixDimensions(2, 1))
+    );
+    assert_eq!(
+        Channel::new(vec![vec![vec![c(1.0, 0.0); 3]; 3]]).err(),
+        Some(Error::InvalidMatrixDimensions(3, 3))
+    );
+
+    // A single Kraus operator must be unitary, and a scaled identity is not complete
+    let half: f64 = 0.5f64.sqrt();
+    let scaled: Vec<Vec<Complex<f64>>> = vec![vec![c(half, 0.0), c(0.0, 0.0)], vec![c(0.0, 0.0), c(half, 0.0)]];
+    assert_eq!(Channel::new(vec![scaled.clone()]).err(), Some(Error::IncompleteKrausOperators));
+    let channel: Channel = Channel::new(vec![scaled.clone(), scaled]).unwrap();
+    assert_eq!(channel.num_qubits(), 1);
+    assert_eq!(channel.kraus_operators().len(), 2);
+
+    assert_eq!(Channel::bit_flip(1.5).err(), Some(Error::InvalidProbability(1.5)));
+    assert_eq!(Channel::amplitude_damping(-0.1).err(), Some(Error::InvalidProbability(-0.1)));
+    assert_eq!(Channel::pauli(0.5, 0.4, 0.3).err(), Some(Error::InvalidProbability(0.5 + 0.4 + 0.3)));
+    assert_eq!(Channel::depolarising(0.1, 0).err(), Some(Error::InvalidNumberOfQubits(0)));
+    assert_eq!(
+        Channel::thermal_relaxation(10.0, 25.0, 1.0, 0.0).err(),
+        Some(Error::InvalidRelaxationTimes(10.0, 25.0, 1.0))
+    );
+    assert_eq!(
+        Channel::thermal_relaxation(10.0, 5.0, -1.0, 0.0).err(),
+        Some(Error::InvalidRelaxationTimes(10.0, 5.0, -1.0))
+    );
+
+    // Kraus operators that vanish are dropped
+    assert_eq!(Channel::bit_flip(0.0).unwrap().kraus_operators().len(), 1);
+    assert_eq!(Channel::depolarising(0.2, 2).unwrap().kraus_operators().len(), 16);
+}
+
+#[test]
+fn test_channels_on_density_matrices() {
+    let p: f64 = 0.3;
+
+    // Depolarising shrinks the Bloch vector by 1 - p
+    let rho: DensityMatrix = zero().apply_channel(&Channel::depolarising(p, 1).unwrap(), &[0]).unwrap();
+    assert_close(rho.element(0, 0).unwrap(), 1.0 - p / 2.0);
+    let rho: DensityMatrix = DensityMatrix::from_state(&State::new_basis_n(2, 2).unwrap())
+        .apply_channel(&Channel::depolarising(1.0, 2).unwrap(), &[1, 0])
+        .unwrap();
+    assert_eq!(rho, DensityMatrix::maximally_mixed(2).unwrap());
+
+    let rho: DensityMatrix = plus().apply_channel(&Channel::bit_flip(p).unwrap(), &[0]).unwrap();
+    assert_eq!(rho, plus());
+    let rho: DensityMatrix = plus().apply_channel(&Channel::phase_flip(p).unwrap(), &[0]).unwrap();
+    assert_close(rho.element(0, 1).unwrap(), 0.5 * (1.0 - 2.0 * p));
+    let rho: DensityMatrix = zero().apply_channel(&Channel::pauli(0.1, 0.2, 0.3).unwrap(), &[0]).unwrap();
+    assert_close(rho.element(1, 1).unwrap(), 0.3);
+
+    let rho: DensityMatrix = one().apply_channel(&Channel::amplitude_damping(p).unwrap(), &[0]).unwrap();
+    assert_close(rho.element(1, 1).unwrap(), 1.0 - p);
+    let rho: DensityMatrix = plus().apply_channel(&Channel::amplitude_damping(p).unwrap(), &[0]).unwrap();
+    assert_close(rho.element(0, 1).unwrap(), 0.5 * (1.0 - p).sqrt());
+
+    let rho: DensityMatrix = plus().apply_channel(&Channel::phase_damping(p).unwrap(), &[0]).unwrap();
+    assert_close(rho.element(0, 0).unwrap(), 0.5);
+    assert_close(rho.element(0, 1).unwrap(), 0.5 * (1.0 - p).sqrt());
+
+    // Full relaxation reaches the thermal state from any state
+    let channel: Channel = Channel::generalised_amplitude_damping(1.0, 0.2).unwrap();
+    for rho in [zero(), one(), plus()] {
+        let relaxed: DensityMatrix = rho.apply_channel(&channel, &[0]).unwrap();
+        assert_close(relaxed.element(1, 1).unwrap(), 0.2);
+        assert_close(relaxed.element(0, 1).unwrap(), 0.0);
+    }
+
+    // Composition applies the first channel and then the second
+    let composed: Channel = Channel::bit_flip(0.1).unwrap().compose(&Channel::bit_flip(0.2).unwrap()).unwrap();
+    let rho: DensityMatrix = zero().apply_channel(&composed, &[0]).unwrap();
+    assert_close(rho.element(1, 1).unwrap(), 0.1 * 0.8 + 0.9 * 0.2);
+    assert_eq!(
+        composed.compose(&Channel::depolarising(0.1, 2).unwrap()).err(),
+        Some(Error::InvalidNumberOfQubits(2))
+    );
+
+    let rho: DensityMatrix = DensityMatrix::new_zero(2).unwrap();
+    assert_eq!(rho.apply_channel(&composed, &[0, 1]).err(), Some(Error::InvalidNumberOfQubits(2)));
+    assert_eq!(rho.apply_channel(&composed, &[2]).err(), Some(Error::InvalidQubitIndex(2, 2)));
+}
+
+#[test]
+fn test_thermal_relaxation() {
+    let (t1, t2, time): (f64, f64, f64) = (50.0, 30.0, 4.0);
+    let channel: Channel = Channel::thermal_relaxation(t1, t2, time, 0.0).unwrap();
+
+    // Populations decay with T1 and coherences with T2
+    let rho: DensityMatrix = one().apply_channel(&channel, &[0]).unwrap();
+    assert_close(rho.element(1, 1).unwrap(), (-time / t1).exp());
+    let rho: DensityMatrix = plus().apply_channel(&channel, &[0]).unwrap();
+    assert_close(rho.element(0, 0).unwrap(), 1.0 - 0.5 * (-time / t1).exp());
+    assert_close(rho.element(0, 1).unwrap(), 0.5 * (-time / t2).exp());
+
+    // At finite temperature the ground state is partially excited
+    let channel: Channel = Channel::thermal_relaxation(t1, 2.0 * t1, time, 0.1).unwrap();
+    let rho: DensityMatrix = zero().apply_channel(&channel, &[0]).unwrap();
+    assert_close(rho.element(1, 1).unwrap(), 0.1 * (1.0 - (-time / t1).exp()));
+
+    // A zero gate time leaves the state unchanged
+    let channel: Channel = Channel::thermal_relaxation(t1, t2, 0.0, 0.0).unwrap();
+    assert_eq!(plus().apply_channel(&channel, &[0]).unwrap(), plus());
+}
+
+#[test]
+fn test_channel_trajectories_on_states() {
+    let state: State = State::new_plus(2).unwrap();
+    let channel: Channel = Channel::thermal_relaxation(20.0, 15.0, 10.0, 0.0).unwrap();
+    let exact: DensityMatrix = DensityMatrix::from_state(&state).apply_channel(&channel, &[1]).unwrap();
+
+    // The average of the trajectories converges to the exact density matrix
+    let trajectories: usize = 4000;
+    let mut rng: StdRng = StdRng::seed_from_u64(17);
+    let average: DensityMatrix = (0..trajectories)
+        .map(|_| {
+            let trajectory: State = channel.apply_with_rng(&state, &[1], &mut rng).unwrap();
+            let norm: f64 = trajectory.state_vector.iter().map(|amplitude| amplitude.norm_sqr()).sum();
+            assert!((norm - 1.0).abs() < 1e-12);
+            DensityMatrix::from_state(&trajectory) * (1.0 / trajectories as f64)
+        })
+        .reduce(|sum, term| sum + term)
+        .unwrap();
+    for (actual, expected) in average.matrix.iter().zip(&exact.matrix) {
+        assert!((actual - expected).norm() < 0.03);
+    }
+
+    // A seeded generator gives the same trajectory
+    let first: State = channel.apply_with_rng(&state, &[1], &mut StdRng::seed_from_u64(3)).unwrap();
+    let second: State = channel.apply_with_rng(&state, &[1], &mut StdRng::seed_from_u64(3)).unwrap();
+    assert_eq!(first, second);
+
+    // A certain bit flip is deterministic
+    let flipped: State = Channel::bit_flip(1.0).unwrap().apply(&state.x(0).unwrap(), &[0]).unwrap();
+    assert_eq!(flipped, state);
+    assert_eq!(channel.apply(&state, &[0, 1]).err(), Some(Error::InvalidNumberOfQubits(2)));
+    assert_eq!(channel.apply(&state, &[2]).err(), Some(Error::InvalidQubitIndex(2, 2)));
+}
@@ -0,0 +1,306 @@
+use crate::{
+    circuit::{Circuit, CircuitBuilder},
+    compiler::ir::{CircuitIR, InstructionIR},
+    components::{
+        classical::{ClassicalCondition, ClassicalRegister},
+        gate::Gate,
+        measurement::MeasurementBasis,
+        operator::{Operator, Pauli},
+        state::State,
+    },
+    errors::{CompilerError, Error},
+};
+use num_complex::Complex;
+
+/// Teleports qubit 0 onto qubit 2, correcting with classically controlled X and Z gates
+fn teleportation() -> Circuit {
+    CircuitBuilder::with_bits(3, 2)
+        .ry_gate(0, 0.7)
+        .rz_gate(0, 1.3)
+        .h_gate(1)
+        .cnot_gate(2, 1)
+        .cnot_gate(1, 0)
+        .h_gate(0)
+        .measure_into_gate(MeasurementBasis::Computational, vec![0, 1], vec![0, 1])
+        .conditional(ClassicalCondition::bit(1, true), |builder| {
+            builder.x_gate(2);
+        })
+        .conditional(ClassicalCondition::bit(0, true), |builder| {
+            builder.z_gate(2);
+        })
+        .build_final()
+}
+
+#[test]
+fn test_classical_register_values() {
+    let mut register: ClassicalRegister = ClassicalRegister::new(4);
+    assert_eq!(register.value(), 0);
+
+    register.set(0, 1);
+    register.set(2, 5);
+    register.set(7, 1); // Out of range, ignored
+    assert_eq!(register.bits(), &[1, 0, 1, 0]);
+    assert_eq!(register.value(), 5);
+    assert_eq!(register.value_of(&[2, 0]), 3);
+    assert_eq!(register.value_of(&[1, 9]), 0);
+    assert_eq!(register.get(2), Some(1));
+    assert_eq!(register.get(4), None);
+    assert_eq!(register.to_string(), "0101");
+
+    assert!(ClassicalCondition::register(4, 5).is_satisfied(&register));
+    assert!(ClassicalCondition::new(vec![2, 1], 1).is_satisfied(&register));
+    assert!(!ClassicalCondition::bit(1, true).is_satisfied(&register));
+    assert!(!ClassicalCondition::new(vec![0], 2).is_valid());
+}
+
+#[test]
+fn test_teleportation_with_conditional_corrections() {
+    let psi: State = CircuitBuilder::new(1)
+        .ry_gate(0, 0.7)
+        .rz_gate(0, 1.3)
+        .build_final()
+        .execute(&State::new_zero(1).unwrap())
+        .unwrap();
+    let circuit: Circuit = teleportation();
+
+    for _ in 0..20 {
+        let (state, register) = circuit.execute_with_register(&State::new_zero(3).unwrap()).unwrap();
+        assert_eq!(register.num_bits(), 2);
+
+        // The measured qubits are left in the recorded basis state, and qubit 2 holds ψ
+        let offset: usize = register.value() as usize;
+        let mut expected: Vec<Complex<f64>> = vec![Complex::new(0.0, 0.0); 8];
+        expected[offset] = psi.state_vector[0];
+        expected[offset + 4] = psi.state_vector[1];
+        let expected: State = State::new(expected).unwrap();
+        let fidelity: f64 = expected.inner_product(&state).unwrap().norm_sqr();
+        assert!((fidelity - 1.0).abs() < 1e-10, "fidelity {}", fidelity);
+    }
+}
+
+#[test]
+fn test_conditional_gates_skipped_when_condition_fails() {
+    // Qubit 0 is measured as 1, so only the gates conditioned on c[0] == 1 are applied
+    let circuit: Circuit = CircuitBuilder::with_bits(3, 3)
+        .x_gate(0)
+        .measure_gate(MeasurementBasis::Computational, vec![0])
+        .conditional(ClassicalCondition::bit(0, false), |builder| {
+            builder.x_gate(1);
+        })
+        .conditional(ClassicalCondition::register(3, 1), |builder| {
+            builder.x_gate(2).measure_into_gate(MeasurementBasis::Computational, vec![2], vec![1]);
+        })
+        .build_final();
+
+    let (state, register) = circuit.execute_with_register(&State::new_zero(3).unwrap()).unwrap();
+    assert_eq!(state, State::new_basis_n(3, 0b101).unwrap());
+    assert_eq!(register.bits(), &[1, 1, 0]);
+
+    // Plain execution discards the register
+    assert_eq!(circuit.execute(&State::new_zero(3).unwrap()).unwrap(), state);
+}
+
+#[test]
+fn test_classical_validation_errors() {
+    let x: Box<dyn Operator> = Box::new(Pauli::X);
+    let conditional_x = |condition: ClassicalCondition| {
+        Gate::new_conditional(condition, Gate::new_operator(x.clone(), vec![0], vec![]))
+    };
+
+    assert_eq!(
+        Circuit::with_bits(vec![conditional_x(ClassicalCondition::bit(2, true))], 2, 2).err(),
+        Some(Error::InvalidBitIndex(2, 2))
+    );
+    assert_eq!(
+        Circuit::with_bits(vec![conditional_x(ClassicalCondition::new(vec![0, 1], 4))], 2, 2).err(),
+        Some(Error::InvalidClassicalCondition(4, 2))
+    );
+    let measurement: Gate = Gate::new_measurement_into(vec![0, 1], vec![0], MeasurementBasis::Computational);
+    assert_eq!(
+        Circuit::with_bits(vec![measurement], 2, 2).err(),
+        Some(Error::InvalidNumberOfInputs(1, 2))
+    );
+    let measurement: Gate = Gate::new_measurement_into(vec![0], vec![3], MeasurementBasis::Computational);
+    assert_eq!(
+        Circuit::new(2).add_gate(measurement),
+        Err(Error::InvalidBitIndex(3, 2))
+    );
+
+    // Gates cannot check a condition without a register
+    let state: State = State::new_zero(2).unwrap();
+    assert_eq!(
+        conditional_x(ClassicalCondition::bit(0, false)).apply(&state).err(),
+        Some(Error::MissingClassicalRegister)
+    );
+
+    // Classically controlled circuits are not unitary
+    assert_eq!(teleportation().unitary().err(), Some(Error::NonUnitaryCircuit));
+    assert_eq!(teleportation().inverse().err(), Some(Error::NonUnitaryCircuit));
+}
+
+#[test]
+fn test_qasm_classical_control_round_trip() {
+    let circuit: Circuit = CircuitBuilder::with_bits(2, 3)
+        .h_gate(0)
+        .measure_into_gate(MeasurementBasis::Computational, vec![0], vec![2])
+        .measure_gate(MeasurementBasis::Computational, vec![1])
+        .conditional(ClassicalCondition::register(3, 4), |builder| {
+            builder.x_gate(1);
+        })
+        .conditional(ClassicalCondition::new(vec![2, 0], 1), |builder| {
+            builder.h_gate(0).cz_gates(vec![1], vec![0]);
+        })
+        .build_final();
+
+    let qasm: String = circuit.to_qasm().unwrap();
+    let expected: &str = "OPENQASM 3.0;\n\
+                          include \"stdgates.inc\";\n\
+                          \n\
+                          qubit[2] q;\n\
+                          bit[3] c;\n\
+                          \n\
+                          h q[0];\n\
+                          c[2] = measure q[0];\n\
+                          c[1] = measure q[1];\n\
+                          if (c == 4) x q[1];\n\
+                          if (c[2] == 1 && c[0] == 0) h q[0];\n\
+                          if (c[2] == 1 && c[0] == 0) ctrl @ z q[0], q[1];\n";
+    assert_eq!(qasm, expected);
+
+    let parsed: CircuitIR = CircuitIR::try_from(&Circuit::from_qasm(&qasm).unwrap()).unwrap();
+    assert_eq!(parsed, CircuitIR::try_from(&circuit).unwrap());
+    assert_eq!(parsed.num_bits, 3);
+}
+
+#[test]
+fn test_qasm_parse_conditional_blocks() {
+    let source: &str = r#"
+        OPENQASM 3.0;
+        include "stdgates.inc";
+        qubit[2] q;
+        bit[1] m;
+        bit[2] c;
+        m[0] = measure q[0];
+        c = measure q;
+        if (c[1] == 1 && m == 0) {
+            x q[0];
+            c[0] = measure q[0];
+        }
+    "#;
+    let ir: CircuitIR = CircuitIR::try_from(&Circuit::from_qasm(source).unwrap()).unwrap();
+    let condition: ClassicalCondition = ClassicalCondition::new(vec![2, 0], 1);
+    let computational: MeasurementBasis = MeasurementBasis::Computational;
+    assert_eq!(ir.num_bits, 3);
+    assert_eq!(
+        ir.instructions,
+        vec![
+            InstructionIR::Measurement(0, computational),
+            InstructionIR::MeasurementInto(0, 1, computational),
+            InstructionIR::MeasurementInto(1, 2, computational),
+            InstructionIR::Conditional(condition.clone(), Box::new(InstructionIR::PauliX(0, vec![]))),
+            InstructionIR::Conditional(
+                condition,
+                Box::new(InstructionIR::MeasurementInto(0, 1, computational))
+            ),
+        ]
+    );
+
+    // A classically controlled instruction is a barrier for the depth
+    assert_eq!(ir.depth(), 4);
+
+    for (source, position) in [
+        ("qubit q; bit c; if (c == 2) x q;", (1, 26)),
+        ("qubit q; bit c; if (c == 1) qubit r;", (1, 29)),
+        ("qubit q; bit c; if (c == 1) x q; else x q;", (1, 34)),
+    ] {
+        match Circuit::from_qasm(source) {
+            Err(CompilerError::ParseError(line, column, _)) => assert_eq!((line, column), position),
+            other => panic!("expected a parse error for {:?}, got {:?}", source, other.is_ok()),
+        }
+    }
+}
+
+#[test]
+fn test_conditional_bodies_that_overwrite_their_condition() {
+    // Every instruction of the body checks the condition again, so a measurement into a bit of the
+    // condition is only allowed as the last instruction
+    let source: &str = r#"
+        OPENQASM 3.0;
+        include "stdgates.inc";
+        qubit[2] q;
+        bit[1] c;
+        if (c[0] == 0) {
+            x q[0];
+            c[0] = measure q[0];
+            x q[1];
+        }
+    "#;
+    assert!(matches!(Circuit::from_qasm(source), Err(CompilerError::ParseError(8, 13, _))));
+    assert!(matches!(
+        Circuit::from_qasm("qubit[2] q; bit[2] c; if (c == 0) c = measure q;"),
+        Err(CompilerError::ParseError(1, 35, _))
+    ));
+
+    let measured_last: Circuit = Circuit::from_qasm(
+        "qubit[2] q; bit[1] c; if (c[0] == 0) { x q[1]; x q[0]; c[0] = measure q[0]; }",
+    )
+    .unwrap();
+    let (state, register) = measured_last.execute_with_register(&State::new_zero(2).unwrap()).unwrap();
+    assert_eq!(state, State::new_basis_n(2, 3).unwrap());
+    assert_eq!(register.bits(), &[1]);
+}
+
+#[test]
+fn test_builder_conditional_overwriting_its_condition() {
+    let mut builder: CircuitBuilder = CircuitBuilder::with_bits(2, 1);
+    builder.conditional(ClassicalCondition::bit(0, false), |builder| {
+        builder
+            .x_gate(0)
+            .measure_into_gate(MeasurementBasis::Computational, vec![0], vec![0])
+            .x_gate(1);
+    });
+    assert_eq!(builder.build().err(), Some(Error::ConditionalBodyWritesCondition(0)));
+
+    // Writing the condition in the last gate of the body is allowed
+    let mut builder: CircuitBuilder = CircuitBuilder::with_bits(2, 1);
+    builder.conditional(ClassicalCondition::bit(0, false), |builder| {
+        builder.x_gate(0).measure_gate(MeasurementBasis::Computational, vec![0]);
+    });
+    assert!(builder.build().is_ok());
+}
+
+#[test]
+fn test_qasm2_classical_control() {
+    let circuit: Circuit = CircuitBuilder::with_bits(2, 2)
+        .h_gate(0)
+        .measure_into_gate(MeasurementBasis::Computational, vec![0], vec![1])
+        .conditional(ClassicalCondition::register(2, 2), |builder| {
+            builder.x_gate(1).cnot_gate(0, 1);
+        })
+        .build_final();
+
+    let qasm: String = circuit.to_qasm2().unwrap();
+    assert!(qasm.contains("creg c[2];\n"));
+    assert!(qasm.ends_with(
+        "measure q[0] -> c[1];\n\
+         if(c==2) x q[1];\n\
+         if(c==2) cx q[1], q[0];\n"
+    ));
+    let parsed: Circuit = Circuit::from_qasm2(&qasm).unwrap();
+    assert_eq!(
+        CircuitIR::try_from(&parsed).unwrap(),
+        CircuitIR::try_from(&circuit).unwrap()
+    );
+
+    // OpenQASM 2.0 can only compare whole registers
+    let partial: Circuit = CircuitBuilder::with_bits(2, 2)
+        .conditional(ClassicalCondition::bit(0, true), |builder| {
+            builder.x_gate(1);
+        })
+        .build_final();
+    assert!(matches!(partial.to_qasm2(), Err(CompilerError::UnsupportedOperator(_))));
+    assert!(matches!(
+        Circuit::from_qasm2("OPENQASM 2.0;\nqreg q[1];\ncreg c[2];\nif(c[0]==1) x q[0];\n"),
+        Err(CompilerError::ParseError(4, 4, _))
+    ));
+}
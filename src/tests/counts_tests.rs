@@ -0,0 +1,154 @@
+use crate::{
+    circuit::{Circuit, CircuitBuilder},
+    components::{
+        classical::ClassicalCondition,
+        counts::Counts,
+        measurement::MeasurementBasis,
+        operator::Pauli,
+        pauli_string::{PauliString, SumOp},
+        state::State,
+    },
+    errors::Error,
+};
+use num_complex::Complex;
+use std::collections::HashMap;
+
+fn z_string(qubits: &[usize], coefficient: f64) -> PauliString {
+    let ops: HashMap<usize, Pauli> = qubits.iter().map(|&qubit| (qubit, Pauli::Z)).collect();
+    PauliString::with_ops(Complex::new(coefficient, 0.0), ops)
+}
+
+#[test]
+fn test_counts_histogram() {
+    let mut counts: Counts = Counts::new(3);
+    counts.add("001", 5).unwrap();
+    counts.add("110", 3).unwrap();
+    counts.add("011", 2).unwrap();
+    counts.add("001", 1).unwrap();
+    assert_eq!(counts.add("01", 1), Err(Error::InvalidBitString("01".to_string(), 3)));
+    assert_eq!(counts.add("0a1", 1), Err(Error::InvalidBitString("0a1".to_string(), 3)));
+
+    assert_eq!(counts.shots(), 11);
+    assert_eq!(counts.len(), 3);
+    assert_eq!(counts.get("001"), 6);
+    assert_eq!(counts.get("111"), 0);
+    assert_eq!(counts.most_frequent(), Some(("001", 6)));
+    assert_eq!(counts.to_string(), "001: 6\n011: 2\n110: 3\n");
+
+    // Bit 0 is the rightmost character, so bits [0, 2] of "110" are "10" read as bit 2 then bit 0
+    let marginal: Counts = counts.marginalise(&[0, 2]).unwrap();
+    assert_eq!(marginal.num_bits(), 2);
+    assert_eq!(marginal.get("01"), 8);
+    assert_eq!(marginal.get("10"), 3);
+    assert_eq!(counts.marginalise(&[2, 0]).unwrap().get("10"), 8);
+    assert_eq!(counts.marginalise(&[3]), Err(Error::InvalidBitIndex(3, 3)));
+
+    let probabilities = counts.probabilities();
+    assert!((probabilities["110"] - 3.0 / 11.0).abs() < 1e-12);
+    assert!((probabilities.values().sum::<f64>() - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_counts_diagonal_expectation() {
+    let mut counts: Counts = Counts::new(2);
+    counts.add("00", 3).unwrap();
+    counts.add("01", 1).unwrap();
+
+    // <Z_0> = (3 - 1) / 4, <Z_1> = 1 and <Z_0 Z_1> = <Z_0>
+    let observable: SumOp = SumOp::new(vec![
+        z_string(&[0], 1.0),
+        z_string(&[1], 2.0),
+        z_string(&[0, 1], -1.0),
+        z_string(&[], 0.5),
+    ]);
+    let expectation: Complex<f64> = counts.expectation(&observable).unwrap();
+    assert!((expectation - Complex::new(0.5 + 2.0 - 0.5 + 0.5, 0.0)).norm() < 1e-12);
+
+    let x: SumOp = SumOp::new(vec![PauliString::new(Complex::new(1.0, 0.0)).with_op(0, Pauli::X)]);
+    assert!(matches!(counts.expectation(&x), Err(Error::NonDiagonalObservable(_))));
+    assert_eq!(
+        counts.expectation(&SumOp::new(vec![z_string(&[2], 1.0)])),
+        Err(Error::InvalidBitIndex(2, 2))
+    );
+    assert_eq!(Counts::new(2).expectation(&observable), Err(Error::InvalidNumberOfMeasurements(0)));
+}
+
+#[test]
+fn test_run_shots_bell_state() {
+    let circuit: Circuit = CircuitBuilder::new(2)
+        .h_gate(0)
+        .cnot_gate(1, 0)
+        .measure_gate(MeasurementBasis::Computational, vec![0, 1])
+        .build_final();
+    let initial: State = State::new_zero(2).unwrap();
+
+    let counts: Counts = circuit.run_shots(&initial, 2000, Some(7)).unwrap();
+    assert_eq!(counts.shots(), 2000);
+    assert_eq!(counts.get("00") + counts.get("11"), 2000);
+    assert!((counts.get("00") as f64 / 2000.0 - 0.5).abs() < 0.05);
+    assert_eq!(circuit.run_shots(&initial, 2000, Some(7)).unwrap(), counts);
+
+    let zz: SumOp = SumOp::new(vec![z_string(&[0, 1], 1.0)]);
+    assert!((counts.expectation(&zz).unwrap().re - 1.0).abs() < 1e-12);
+
+    assert_eq!(circuit.run_shots(&initial, 0, None), Err(Error::InvalidNumberOfMeasurements(0)));
+    assert_eq!(
+        circuit.run_shots(&State::new_zero(3).unwrap(), 10, None),
+        Err(Error::InvalidNumberOfQubits(3))
+    );
+
+    // Invalid terminal measurements are reported instead of sampled
+    let out_of_range: Circuit = CircuitBuilder::new(2)
+        .h_gate(0)
+        .measure_gate(MeasurementBasis::Computational, vec![5])
+        .build_final();
+    assert_eq!(
+        out_of_range.run_shots(&initial, 10, Some(7)),
+        Err(Error::InvalidQubitIndex(5, 2))
+    );
+}
+
+#[test]
+fn test_run_shots_terminal_measurement_bases_and_bits() {
+    // |+> on qubit 0 and |i+> on qubit 1 are eigenstates of the X and Y measurements
+    let circuit: Circuit = CircuitBuilder::with_bits(3, 4)
+        .h_gate(0)
+        .h_gate(1)
+        .s_gate(1)
+        .x_gate(2)
+        .measure_into_gate(MeasurementBasis::X, vec![0], vec![3])
+        .barrier(vec![])
+        .measure_into_gate(MeasurementBasis::Y, vec![1], vec![1])
+        .measure_into_gate(MeasurementBasis::Computational, vec![2], vec![0])
+        .build_final();
+
+    let counts: Counts = circuit.run_shots(&State::new_zero(3).unwrap(), 100, None).unwrap();
+    assert_eq!(counts.get("0001"), 100);
+
+    // The same circuit without its measurements gives the probabilities of the sampled state
+    let circuit: Circuit = CircuitBuilder::new(1)
+        .ry_gate(0, 2.0 * (0.2f64).sqrt().asin())
+        .measure_gate(MeasurementBasis::Computational, vec![])
+        .build_final();
+    let counts: Counts = circuit.run_shots(&State::new_zero(1).unwrap(), 5000, Some(1)).unwrap();
+    assert!((counts.probabilities()["1"] - 0.2).abs() < 0.03);
+}
+
+#[test]
+fn test_run_shots_mid_circuit_measurements() {
+    // The first outcome is copied into bit 1 by a classically controlled gate, so both bits always agree
+    let circuit: Circuit = CircuitBuilder::with_bits(2, 2)
+        .h_gate(0)
+        .measure_into_gate(MeasurementBasis::Computational, vec![0], vec![0])
+        .conditional(ClassicalCondition::bit(0, true), |builder| {
+            builder.x_gate(1);
+        })
+        .measure_into_gate(MeasurementBasis::Computational, vec![1], vec![1])
+        .build_final();
+
+    let counts: Counts = circuit.run_shots(&State::new_zero(2).unwrap(), 400, Some(3)).unwrap();
+    assert_eq!(counts.shots(), 400);
+    assert_eq!(counts.get("00") + counts.get("11"), 400);
+    assert!(counts.get("00") > 0 && counts.get("11") > 0);
+    assert_eq!(counts.marginalise(&[1]).unwrap().get("1"), counts.get("11"));
+}
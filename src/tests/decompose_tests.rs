@@ -0,0 +1,195 @@
+use super::peephole_tests::assert_same_unitary;
+use crate::{
+    compiler::{
+        ir::{CircuitIR, InstructionIR},
+        pass::Pass,
+        passes::{BasisGates, DecomposeControlled},
+    },
+    components::measurement::MeasurementBasis,
+    errors::CompilerError,
+};
+use num_complex::Complex;
+use std::f64::consts::PI;
+
+const BASES: [BasisGates; 3] = [
+    BasisGates::CxSingleQubit,
+    BasisGates::CxRzSxX,
+    BasisGates::CzU3,
+];
+
+/// Returns whether an instruction belongs to a basis
+fn in_basis(instruction: &InstructionIR, basis: BasisGates) -> bool {
+    let sx: [[Complex<f64>; 2]; 2] = [
+        [Complex::new(0.5, 0.5), Complex::new(0.5, -0.5)],
+        [Complex::new(0.5, -0.5), Complex::new(0.5, 0.5)],
+    ];
+    match (basis, instruction) {
+        (_, InstructionIR::Measurement(_, _)) => true,
+        (BasisGates::CxSingleQubit, InstructionIR::PauliX(_, controls)) => controls.len() <= 1,
+        (BasisGates::CxSingleQubit, InstructionIR::Swap(_, _, _)) => false,
+        (BasisGates::CxSingleQubit, _) => instruction.controls().is_empty(),
+        (BasisGates::CxRzSxX, InstructionIR::PauliX(_, controls)) => controls.len() <= 1,
+        (BasisGates::CxRzSxX, InstructionIR::Rz(_, _, controls)) => controls.is_empty(),
+        (BasisGates::CxRzSxX, InstructionIR::Unitary(matrix, _, controls)) => {
+            controls.is_empty() && *matrix == sx
+        }
+        (BasisGates::CzU3, InstructionIR::PauliZ(_, controls)) => controls.len() == 1,
+        (BasisGates::CzU3, InstructionIR::Unitary(_, _, controls)) => controls.is_empty(),
+        _ => false,
+    }
+}
+
+/// Decomposes a circuit, checking the unitary is preserved and only basis gates remain
+fn decompose(ir: &CircuitIR, pass: &DecomposeControlled) -> CircuitIR {
+    let mut decomposed: CircuitIR = ir.clone();
+    pass.run(&mut decomposed).unwrap();
+    assert_same_unitary(ir, &decomposed);
+    for instruction in &decomposed.instructions {
+        assert!(
+            in_basis(instruction, pass.basis()),
+            "{:?} is not in {:?}",
+            instruction,
+            pass.basis()
+        );
+    }
+    decomposed
+}
+
+/// Counts the two-qubit gates in a circuit
+fn two_qubit_count(ir: &CircuitIR) -> usize {
+    ir.instructions
+        .iter()
+        .filter(|instruction| instruction.qubits().len() == 2)
+        .count()
+}
+
+#[test]
+fn test_decompose_controlled_gates_into_each_basis() {
+    let matrix: [[Complex<f64>; 2]; 2] = [
+        [Complex::new(0.6, 0.0), Complex::new(0.0, 0.8)],
+        [Complex::new(0.0, 0.8), Complex::new(0.6, 0.0)],
+    ];
+    for num_controls in 0..=3 {
+        let controls: Vec<usize> = (1..=num_controls).collect();
+        let instructions: Vec<InstructionIR> = vec![
+            InstructionIR::PauliX(0, controls.clone()),
+            InstructionIR::PauliY(0, controls.clone()),
+            InstructionIR::PauliZ(0, controls.clone()),
+            InstructionIR::Hadamard(0, controls.clone()),
+            InstructionIR::S(0, controls.clone()),
+            InstructionIR::Tdg(0, controls.clone()),
+            InstructionIR::Phase(0.7, 0, controls.clone()),
+            InstructionIR::Rx(1.1, 0, controls.clone()),
+            InstructionIR::Ry(-0.4, 0, controls.clone()),
+            InstructionIR::Rz(2.5, 0, controls.clone()),
+            InstructionIR::Id(0, controls.clone()),
+            InstructionIR::Unitary(matrix, 0, controls.clone()),
+            InstructionIR::Swap(0, num_controls + 1, controls.clone()),
+        ];
+        for instruction in instructions {
+            let ir: CircuitIR = CircuitIR::new(num_controls + 2, vec![instruction]);
+            for basis in BASES {
+                decompose(&ir, &DecomposeControlled::new(basis));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_decompose_toffoli_and_singly_controlled_gate_counts() {
+    let toffoli: CircuitIR = CircuitIR::new(3, vec![InstructionIR::PauliX(2, vec![0, 1])]);
+    let decomposed: CircuitIR = decompose(&toffoli, &DecomposeControlled::default());
+    assert_eq!(two_qubit_count(&decomposed), 6);
+
+    // A controlled rotation needs two CNOTs, and CZ is native to the CZ basis
+    let controlled: CircuitIR = CircuitIR::new(
+        2,
+        vec![
+            InstructionIR::Rz(0.3, 1, vec![0]),
+            InstructionIR::PauliZ(1, vec![0]),
+        ],
+    );
+    let decomposed: CircuitIR = decompose(&controlled, &DecomposeControlled::default());
+    assert_eq!(two_qubit_count(&decomposed), 3);
+    let decomposed: CircuitIR = decompose(&controlled, &DecomposeControlled::new(BasisGates::CzU3));
+    assert_eq!(two_qubit_count(&decomposed), 3);
+    assert_eq!(
+        decomposed.instructions.last(),
+        Some(&InstructionIR::PauliZ(1, vec![0]))
+    );
+}
+
+#[test]
+fn test_decompose_with_ancillas() {
+    let controls: Vec<usize> = (0..4).collect();
+    let ir: CircuitIR = CircuitIR::new(7, vec![InstructionIR::PauliX(4, controls.clone())]);
+
+    // Two ancillas allow a chain of 4(k - 2) = 8 Toffolis
+    let chain: CircuitIR = decompose(
+        &ir,
+        &DecomposeControlled::with_ancillas(BasisGates::CxSingleQubit, vec![5, 6]),
+    );
+    assert_eq!(two_qubit_count(&chain), 8 * 6);
+
+    // A single ancilla splits the gate into two halves
+    let split: CircuitIR = decompose(
+        &ir,
+        &DecomposeControlled::with_ancillas(BasisGates::CxRzSxX, vec![6]),
+    );
+    let without: CircuitIR = decompose(&ir, &DecomposeControlled::new(BasisGates::CxRzSxX));
+    assert!(two_qubit_count(&split) < two_qubit_count(&without));
+
+    // Qubits the gate acts on are never borrowed
+    let controlled_swap: CircuitIR = CircuitIR::new(
+        6,
+        vec![
+            InstructionIR::Swap(4, 5, vec![0, 1, 2]),
+            InstructionIR::Phase(PI / 3.0, 3, vec![0, 1, 2]),
+        ],
+    );
+    decompose(
+        &controlled_swap,
+        &DecomposeControlled::with_ancillas(BasisGates::CzU3, vec![3, 4, 5]),
+    );
+}
+
+#[test]
+fn test_decompose_preserves_measurements() {
+    let ir: CircuitIR = CircuitIR::new(
+        3,
+        vec![
+            InstructionIR::Hadamard(0, vec![]),
+            InstructionIR::PauliX(2, vec![0, 1]),
+            InstructionIR::Measurement(2, MeasurementBasis::Computational),
+            InstructionIR::Rx(0.5, 2, vec![]),
+        ],
+    );
+    let mut decomposed: CircuitIR = ir.clone();
+    DecomposeControlled::new(BasisGates::CxRzSxX)
+        .run(&mut decomposed)
+        .unwrap();
+
+    let measurement: usize = decomposed
+        .instructions
+        .iter()
+        .position(|instruction| matches!(instruction, InstructionIR::Measurement(_, _)))
+        .unwrap();
+    // The rotation after the measurement stays after it
+    assert!(decomposed.instructions[measurement + 1..]
+        .iter()
+        .any(|instruction| instruction.targets() == vec![2]));
+
+    // `SX` is emitted by name in OpenQASM 3.0
+    assert!(decomposed.to_qasm().unwrap().contains("sx q[2];"));
+}
+
+#[test]
+fn test_decompose_rejects_out_of_range_ancillas() {
+    let mut ir: CircuitIR = CircuitIR::new(2, vec![InstructionIR::PauliX(1, vec![0])]);
+    let pass: DecomposeControlled =
+        DecomposeControlled::with_ancillas(BasisGates::CxSingleQubit, vec![2]);
+    assert!(matches!(
+        pass.run(&mut ir),
+        Err(CompilerError::InvalidOperands(_, _))
+    ));
+}
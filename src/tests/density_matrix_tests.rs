@@ -0,0 +1,268 @@
+use super::helpers::{c, test_state};
+use crate::{
+    circuit::{Circuit, CircuitBuilder},
+    components::{
+        classical::ClassicalCondition,
+        density_matrix::DensityMatrix,
+        measurement::MeasurementBasis,
+        operator::{Hadamard, Pauli},
+        pauli_string::{PauliString, SumOp},
+        state::State,
+    },
+    errors::Error,
+};
+use num_complex::Complex;
+use rand::{SeedableRng, rngs::StdRng};
+
+/// A Hermitian observable with X, Y and Z terms
+fn test_observable() -> SumOp {
+    SumOp::new(vec![
+        PauliString::new(c(0.5, 0.0)).with_op(0, Pauli::X).with_op(2, Pauli::Y),
+        PauliString::new(c(-1.2, 0.0)).with_op(1, Pauli::Z),
+        PauliString::new(c(0.3, 0.0)).with_op(0, Pauli::Y).with_op(1, Pauli::X).with_op(2, Pauli::Z),
+        PauliString::new(c(0.1, 0.0)),
+    ])
+}
+
+#[test]
+fn test_density_matrix_construction() {
+    let state: State = test_state(3);
+    let rho: DensityMatrix = DensityMatrix::from_state(&state);
+    assert_eq!(rho.num_qubits(), 3);
+    assert!((rho.trace() - c(1.0, 0.0)).norm() < 1e-12);
+    assert!((rho.purity() - 1.0).abs() < 1e-12);
+    assert!((rho.fidelity(&state).unwrap() - 1.0).abs() < 1e-12);
+    assert!((rho.probability(5).unwrap() - state.probability(5).unwrap()).abs() < 1e-12);
+    assert_eq!(DensityMatrix::new(rho.to_matrix()).unwrap(), rho);
+
+    let mixed: DensityMatrix = DensityMatrix::maximally_mixed(2).unwrap();
+    assert!((mixed.purity() - 0.25).abs() < 1e-12);
+    let ensemble: DensityMatrix = DensityMatrix::from_ensemble(&[
+        (0.25, State::new_basis_n(2, 0).unwrap()),
+        (0.25, State::new_basis_n(2, 1).unwrap()),
+        (0.5, State::new_plus(2).unwrap().h(0).unwrap().h(1).unwrap()),
+    ])
+    .unwrap();
+    // The last state is |00⟩, so the ensemble is 3/4 |00⟩⟨00| + 1/4 |01⟩⟨01|
+    assert!((ensemble.trace().re - 1.0).abs() < 1e-12);
+    assert!((ensemble.purity() - 0.625).abs() < 1e-12);
+
+    assert_eq!(DensityMatrix::new(vec![]).err(), Some(Error::InvalidNumberOfQubits(0)));
+    assert_eq!(
+        DensityMatrix::new(vec![vec![c(1.0, 0.0), c(0.0, 0.0)], vec![c(0.0, 0.0)]]).err(),
+        Some(Error::InvalidMatrixDimensions(2, 1))
+    );
+    assert_eq!(
+        DensityMatrix::new(vec![vec![c(0.5, 0.0), c(0.1, 0.0)], vec![c(0.2, 0.0), c(0.5, 0.0)]]).err(),
+        Some(Error::NonHermitianMatrix)
+    );
+    assert_eq!(
+        DensityMatrix::new(vec![vec![c(0.5, 0.0), c(0.0, 0.0)], vec![c(0.0, 0.0), c(0.4, 0.0)]]).err(),
+        Some(Error::DensityMatrixNotNormalised)
+    );
+    let zero: State = State::new_zero(1).unwrap();
+    assert_eq!(
+        DensityMatrix::from_ensemble(&[(-0.5, zero.clone()), (1.5, zero.clone())]).err(),
+        Some(Error::InvalidProbability(-0.5))
+    );
+    assert_eq!(
+        DensityMatrix::from_ensemble(&[(0.5, zero.clone()), (0.5, State::new_zero(2).unwrap())]).err(),
+        Some(Error::InvalidNumberOfQubits(2))
+    );
+}
+
+#[test]
+fn test_density_matrix_operators_match_state_vector() {
+    let phase: f64 = 0.3;
+    let unitary2: [[Complex<f64>; 2]; 2] = [
+        [c(phase.cos(), 0.0), c(0.0, -phase.sin())],
+        [c(0.0, -phase.sin()), c(phase.cos(), 0.0)],
+    ];
+    let mut unitary4: [[Complex<f64>; 4]; 4] = [[c(0.0, 0.0); 4]; 4];
+    for (i, row) in unitary4.iter_mut().enumerate() {
+        row[(i + 1) % 4] = c(0.0, 1.0).powu(i as u32);
+    }
+    let unitary8: Vec<Vec<Complex<f64>>> = (0..8)
+        .map(|r| (0..8).map(|col| if col == (r * 3) % 8 { c(0.0, 1.0).powu(r as u32) } else { c(0.0, 0.0) }).collect())
+        .collect();
+
+    let circuit: Circuit = CircuitBuilder::new(4)
+        .h_gates(vec![0, 3])
+        .x_gate(1)
+        .y_gate(2)
+        .z_gate(0)
+        .s_gate(1)
+        .t_gate(2)
+        .sdag_gate(3)
+        .tdag_gate(0)
+        .p_gate(1, 0.8)
+        .rx_gate(2, 1.1)
+        .ry_gate(3, -0.6)
+        .rz_gate(0, 2.3)
+        .cnot_gate(1, 0)
+        .swap_gate(2, 3)
+        .toffoli_gate(0, 1, 3)
+        .cswap_gate(0, 2, vec![1])
+        .crx_gates(vec![2], vec![3], 0.9)
+        .cp_gates(vec![0], vec![2], 1.7)
+        .unitary_gate(1, unitary2)
+        .unitary4_gate(0, 2, unitary4)
+        .cunitary_n_gate(vec![0, 1, 3], vec![2], unitary8.clone())
+        .unitary_n_gate(vec![3, 1, 2], unitary8)
+        .barrier(vec![])
+        .build_final();
+
+    let initial: State = CircuitBuilder::new(4)
+        .ry_gates(vec![0, 1, 2, 3], 0.5)
+        .rx_gate(2, 0.7)
+        .build_final()
+        .execute(&State::new_zero(4).unwrap())
+        .unwrap();
+    let expected: DensityMatrix = DensityMatrix::from_state(&circuit.execute(&initial).unwrap());
+    let actual: DensityMatrix = circuit.execute_density_matrix(&DensityMatrix::from_state(&initial)).unwrap();
+    assert_eq!(actual, expected);
+
+    // Density matrices on 5 or more qubits are processed in parallel, with the same results
+    let wide: Circuit = CircuitBuilder::new(6)
+        .h_gate(0)
+        .cnot_gate(5, 0)
+        .ry_gates(vec![1, 2, 3, 4], 0.4)
+        .toffoli_gate(3, 1, 5)
+        .crx_gates(vec![2], vec![4], 0.9)
+        .build_final();
+    let state: State = wide.execute(&State::new_zero(6).unwrap()).unwrap();
+    let rho: DensityMatrix = wide.execute_density_matrix(&DensityMatrix::new_zero(6).unwrap()).unwrap();
+    let observable: SumOp = SumOp::new(vec![PauliString::new(c(0.5, 0.0)).with_op(0, Pauli::X).with_op(5, Pauli::X)]);
+    assert_eq!(rho, DensityMatrix::from_state(&state));
+    assert!((rho.trace() - c(1.0, 0.0)).norm() < 1e-12);
+    assert!((rho.purity() - 1.0).abs() < 1e-12);
+    assert!((rho.fidelity(&state).unwrap() - 1.0).abs() < 1e-12);
+    let expectation: Complex<f64> = observable.expectation_value(&state).unwrap();
+    assert!((rho.expectation_value(&observable).unwrap() - expectation).norm() < 1e-12);
+    let measured: DensityMatrix = rho.measure_non_selective(MeasurementBasis::Computational, &[0]).unwrap();
+    for n in 0..64 {
+        assert!((measured.probability(n).unwrap() - rho.probability(n).unwrap()).abs() < 1e-12);
+    }
+    assert_eq!(measured.element(0b100001, 0).unwrap(), c(0.0, 0.0));
+    assert!(measured.purity() < 1.0);
+
+    let rho: DensityMatrix = DensityMatrix::maximally_mixed(2).unwrap();
+    assert_eq!(
+        rho.apply_operator(&Hadamard, &[0, 1], &[]).err(),
+        Some(Error::InvalidNumberOfQubits(2))
+    );
+    assert_eq!(rho.apply_operator(&Hadamard, &[2], &[]).err(), Some(Error::InvalidQubitIndex(2, 2)));
+    assert_eq!(
+        rho.apply_operator(&Pauli::X, &[0], &[0]).err(),
+        Some(Error::OverlappingControlAndTargetQubits(0, 0))
+    );
+}
+
+#[test]
+fn test_density_matrix_measurement() {
+    let bell: State = State::new_zero(2).unwrap().h(0).unwrap().cnot(0, 1).unwrap();
+    let rho: DensityMatrix = DensityMatrix::from_state(&bell);
+
+    // Discarding the outcome leaves an equal mixture of |00⟩ and |11⟩
+    let measured: DensityMatrix = rho.measure_non_selective(MeasurementBasis::Computational, &[0]).unwrap();
+    let mixture: DensityMatrix = DensityMatrix::from_ensemble(&[
+        (0.5, State::new_basis_n(2, 0b00).unwrap()),
+        (0.5, State::new_basis_n(2, 0b11).unwrap()),
+    ])
+    .unwrap();
+    assert_eq!(measured, mixture);
+    assert!((measured.purity() - 0.5).abs() < 1e-12);
+
+    // |++⟩ is an eigenstate of both X measurements, so measuring it in the X basis does not change it
+    let plus: DensityMatrix = DensityMatrix::from_state(&State::new_plus(2).unwrap());
+    assert_eq!(plus.measure_non_selective(MeasurementBasis::X, &[]).unwrap(), plus);
+    let probabilities: Vec<f64> = rho.measurement_probabilities(MeasurementBasis::Y, &[0, 1]).unwrap();
+    assert!((probabilities[0b01] - 0.5).abs() < 1e-12 && (probabilities[0b10] - 0.5).abs() < 1e-12);
+
+    // Selective measurements collapse onto the outcome
+    let (outcomes, collapsed) = rho
+        .measure_with_rng(MeasurementBasis::Computational, &[1], &mut StdRng::seed_from_u64(4))
+        .unwrap();
+    let expected: State = State::new_basis_n(2, if outcomes[0] == 1 { 0b11 } else { 0b00 }).unwrap();
+    assert_eq!(collapsed, DensityMatrix::from_state(&expected));
+
+    // Resetting a qubit of the Bell state leaves the other one fully mixed
+    let reset: DensityMatrix = rho.reset(&[0]).unwrap();
+    let expected: DensityMatrix = DensityMatrix::from_ensemble(&[
+        (0.5, State::new_basis_n(2, 0b00).unwrap()),
+        (0.5, State::new_basis_n(2, 0b10).unwrap()),
+    ])
+    .unwrap();
+    assert_eq!(reset, expected);
+}
+
+#[test]
+fn test_density_matrix_expectation_value() {
+    let state: State = test_state(3);
+    let observable: SumOp = test_observable();
+    let from_state: Complex<f64> = observable.expectation_value(&state).unwrap();
+    let from_density: Complex<f64> = DensityMatrix::from_state(&state).expectation_value(&observable).unwrap();
+    assert!((from_state - from_density).norm() < 1e-12);
+
+    // The expectation value of an ensemble is the average over its states
+    let other: State = State::new_plus(3).unwrap().s(1).unwrap();
+    let ensemble: DensityMatrix = DensityMatrix::from_ensemble(&[(0.3, state.clone()), (0.7, other.clone())]).unwrap();
+    let expected: Complex<f64> = observable.expectation_value(&state).unwrap() * 0.3
+        + observable.expectation_value(&other).unwrap() * 0.7;
+    assert!((ensemble.expectation_value(&observable).unwrap() - expected).norm() < 1e-12);
+
+    let out_of_range: SumOp = SumOp::new(vec![PauliString::new(c(1.0, 0.0)).with_op(3, Pauli::Z)]);
+    assert_eq!(ensemble.expectation_value(&out_of_range).err(), Some(Error::InvalidQubitIndex(3, 3)));
+}
+
+#[test]
+fn test_density_matrix_executes_classically_controlled_circuit() {
+    // Teleportation with corrections: qubit 2 ends up in the state of qubit 0 for every outcome
+    let circuit: Circuit = CircuitBuilder::with_bits(3, 2)
+        .ry_gate(0, 0.7)
+        .rz_gate(0, 1.3)
+        .h_gate(1)
+        .cnot_gate(2, 1)
+        .cnot_gate(1, 0)
+        .h_gate(0)
+        .measure_into_gate(MeasurementBasis::Computational, vec![0, 1], vec![0, 1])
+        .conditional(ClassicalCondition::bit(1, true), |builder| {
+            builder.x_gate(2);
+        })
+        .conditional(ClassicalCondition::bit(0, true), |builder| {
+            builder.z_gate(2);
+        })
+        .build_final();
+
+    let psi: State = CircuitBuilder::new(1)
+        .ry_gate(0, 0.7)
+        .rz_gate(0, 1.3)
+        .build_final()
+        .execute(&State::new_zero(1).unwrap())
+        .unwrap();
+    let rho: DensityMatrix = circuit.execute_density_matrix(&DensityMatrix::new_zero(3).unwrap()).unwrap();
+    assert!((rho.trace().re - 1.0).abs() < 1e-12);
+
+    // Qubits 0 and 1 are fully mixed, and qubit 2 is in the pure state ψ
+    let expected: DensityMatrix = DensityMatrix::from_ensemble(
+        &(0..4)
+            .map(|outcome| {
+                let mut amplitudes: Vec<Complex<f64>> = vec![c(0.0, 0.0); 8];
+                amplitudes[outcome] = psi.state_vector[0];
+                amplitudes[outcome + 4] = psi.state_vector[1];
+                (0.25, State::new(amplitudes).unwrap())
+            })
+            .collect::<Vec<(f64, State)>>(),
+    )
+    .unwrap();
+    assert_eq!(rho, expected);
+
+    assert_eq!(
+        circuit.execute_density_matrix(&DensityMatrix::new_zero(2).unwrap()).err(),
+        Some(Error::InvalidNumberOfQubits(2))
+    );
+    assert_eq!(
+        DensityMatrix::new_zero(3).unwrap().apply_gate(&circuit.get_gates()[7]).err(),
+        Some(Error::MissingClassicalRegister)
+    );
+}
@@ -0,0 +1,210 @@
+use super::helpers::assert_matrices_close;
+use super::peephole_tests::{assert_same_unitary, dense_unitary};
+use crate::{
+    compiler::{
+        euler::{EulerBasis, EulerDecomposition},
+        ir::{CircuitIR, InstructionIR},
+        linalg::{instruction_matrix, multiply, sx_matrix, u_matrix},
+        pass::Pass,
+        passes::FuseSingleQubitGates,
+    },
+    components::{measurement::MeasurementBasis, operator::Unitary2},
+    errors::CompilerError,
+};
+use num_complex::Complex;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::f64::consts::{FRAC_PI_2, PI};
+
+const BASES: [EulerBasis; 4] = [
+    EulerBasis::ZYZ,
+    EulerBasis::ZXZ,
+    EulerBasis::XYX,
+    EulerBasis::U3,
+];
+
+/// Returns a set of single-qubit unitaries, including edge cases with zero or π middle angles
+fn test_unitaries() -> Vec<[[Complex<f64>; 2]; 2]> {
+    let mut rng: StdRng = StdRng::seed_from_u64(8);
+    let mut unitaries: Vec<[[Complex<f64>; 2]; 2]> = vec![
+        u_matrix(0.0, 0.0, 0.0, 0.0),
+        u_matrix(0.0, 0.3, 0.4, 1.0),
+        u_matrix(PI, 0.0, PI, 0.0),
+        u_matrix(PI, 0.7, -0.2, -2.0),
+        u_matrix(FRAC_PI_2, 0.0, PI, 0.0),
+    ];
+    for _ in 0..20 {
+        unitaries.push(u_matrix(
+            rng.random_range(0.0..PI),
+            rng.random_range(-PI..PI),
+            rng.random_range(-PI..PI),
+            rng.random_range(-PI..PI),
+        ));
+    }
+    unitaries
+}
+
+/// Returns the product of a sequence of single-qubit instructions, in order of application
+fn sequence_matrix(instructions: &[InstructionIR]) -> [[Complex<f64>; 2]; 2] {
+    instructions.iter().fold(u_matrix(0.0, 0.0, 0.0, 0.0), |product, instruction| {
+        multiply(&instruction_matrix(instruction).unwrap(), &product)
+    })
+}
+
+#[test]
+fn test_euler_decomposition_reconstructs_unitary_with_phase() {
+    for matrix in test_unitaries() {
+        for basis in BASES {
+            let decomposition: EulerDecomposition = EulerDecomposition::new(&matrix, basis);
+            assert_eq!(decomposition.basis, basis);
+            assert_matrices_close(&decomposition.matrix(), &matrix);
+        }
+    }
+}
+
+#[test]
+fn test_euler_instructions_match_up_to_global_phase() {
+    for matrix in test_unitaries() {
+        for basis in BASES {
+            let decomposition: EulerDecomposition = EulerDecomposition::new(&matrix, basis);
+            let instructions: Vec<InstructionIR> = decomposition.to_instructions(0);
+            assert!(instructions.len() <= 3);
+
+            // Reducing or dropping angles can only change the global phase by a sign
+            let phase: Complex<f64> = Complex::from_polar(1.0, decomposition.global_phase);
+            let product: [[Complex<f64>; 2]; 2] = sequence_matrix(&instructions)
+                .map(|row| row.map(|entry| entry * phase));
+            let sign: f64 = if (product[0][0] + matrix[0][0]).norm() < 1e-9
+                && (product[1][0] + matrix[1][0]).norm() < 1e-9
+            {
+                -1.0
+            } else {
+                1.0
+            };
+            assert_matrices_close(&product.map(|row| row.map(|entry| entry * sign)), &matrix);
+
+            for instruction in &instructions {
+                let allowed: bool = matches!(
+                    (basis, instruction),
+                    (EulerBasis::ZYZ, InstructionIR::Rz(..) | InstructionIR::Ry(..))
+                        | (EulerBasis::ZXZ, InstructionIR::Rz(..) | InstructionIR::Rx(..))
+                        | (EulerBasis::XYX, InstructionIR::Rx(..) | InstructionIR::Ry(..))
+                        | (EulerBasis::U3, InstructionIR::Unitary(..))
+                );
+                assert!(allowed, "{:?} is not in the {:?} basis", instruction, basis);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_euler_instructions_are_minimal() {
+    let identity: [[Complex<f64>; 2]; 2] = u_matrix(0.0, 0.0, 0.0, 0.7);
+    for basis in BASES {
+        assert!(EulerDecomposition::new(&identity, basis).to_instructions(0).is_empty());
+    }
+
+    // A diagonal unitary is a single Z rotation
+    let phase: [[Complex<f64>; 2]; 2] = instruction_matrix(&InstructionIR::T(0, vec![])).unwrap();
+    let zyz: Vec<InstructionIR> = EulerDecomposition::new(&phase, EulerBasis::ZYZ).to_instructions(2);
+    assert_eq!(zyz.len(), 1);
+    assert!(matches!(zyz[0], InstructionIR::Rz(angle, 2, _) if (angle - PI / 4.0).abs() < 1e-9));
+
+    // An X rotation is a single rotation in the XYX basis
+    let rx: [[Complex<f64>; 2]; 2] = instruction_matrix(&InstructionIR::Rx(0.4, 0, vec![])).unwrap();
+    let xyx: EulerDecomposition = EulerDecomposition::new(&rx, EulerBasis::XYX);
+    assert_eq!(xyx.to_instructions(0).len(), 1);
+    assert!(xyx.global_phase.abs() < 1e-9);
+}
+
+#[test]
+fn test_unitary2_euler_decomposition() {
+    let s: Complex<f64> = Complex::new(std::f64::consts::FRAC_1_SQRT_2, 0.0);
+    let hadamard: Unitary2 = Unitary2::new([[s, s], [s, -s]]).unwrap();
+    let decomposition: EulerDecomposition = hadamard.euler_decomposition(EulerBasis::ZYZ);
+    assert!((decomposition.theta - FRAC_PI_2).abs() < 1e-9);
+    assert_matrices_close(&decomposition.matrix(), &[[s, s], [s, -s]]);
+}
+
+#[test]
+fn test_fuse_single_qubit_gates_into_unitary() {
+    let ir: CircuitIR = CircuitIR::new(
+        3,
+        vec![
+            InstructionIR::Hadamard(0, vec![]),
+            InstructionIR::T(0, vec![]),
+            InstructionIR::Rx(0.3, 1, vec![]),
+            InstructionIR::S(0, vec![]),
+            InstructionIR::PauliX(1, vec![0]),
+            InstructionIR::Ry(0.2, 1, vec![]),
+            InstructionIR::Rz(-0.2, 1, vec![]),
+            InstructionIR::Hadamard(2, vec![]),
+            InstructionIR::Hadamard(2, vec![]),
+            InstructionIR::PauliY(0, vec![]),
+        ],
+    );
+    let mut fused: CircuitIR = ir.clone();
+    FuseSingleQubitGates::new().run(&mut fused).unwrap();
+
+    // The product of each run is kept exactly, including its global phase
+    let (expected, actual) = (dense_unitary(&ir), dense_unitary(&fused));
+    for (expected, actual) in expected.iter().flatten().zip(actual.iter().flatten()) {
+        assert!((expected - actual).norm() < 1e-9);
+    }
+    // H T S, Rx, CX, Ry Rz and Y, with the H H run removed
+    assert_eq!(fused.instructions.len(), 5);
+    assert!(matches!(fused.instructions[0], InstructionIR::Unitary(_, 0, _)));
+    assert!(matches!(fused.instructions[1], InstructionIR::Rx(_, 1, _)));
+    assert!(matches!(fused.instructions[2], InstructionIR::PauliX(1, _)));
+}
+
+#[test]
+fn test_fuse_single_qubit_gates_into_euler_basis() {
+    let mut rng: StdRng = StdRng::seed_from_u64(1);
+    let mut instructions: Vec<InstructionIR> = Vec::new();
+    for _ in 0..40 {
+        let qubit: usize = rng.random_range(0..3);
+        let angle: f64 = rng.random_range(-PI..PI);
+        instructions.push(match rng.random_range(0..6) {
+            0 => InstructionIR::Hadamard(qubit, vec![]),
+            1 => InstructionIR::Rx(angle, qubit, vec![]),
+            2 => InstructionIR::Ry(angle, qubit, vec![]),
+            3 => InstructionIR::Phase(angle, qubit, vec![]),
+            4 => InstructionIR::Unitary(sx_matrix(), qubit, vec![]),
+            _ => InstructionIR::PauliZ((qubit + 1) % 3, vec![qubit]),
+        });
+    }
+    let ir: CircuitIR = CircuitIR::new(3, instructions);
+
+    for basis in BASES {
+        let mut fused: CircuitIR = ir.clone();
+        FuseSingleQubitGates::with_basis(basis).run(&mut fused).unwrap();
+        assert_same_unitary(&ir, &fused);
+        assert!(fused.instructions.len() < ir.instructions.len());
+    }
+}
+
+#[test]
+fn test_fusion_stops_at_measurements_and_raw_qasm() {
+    let ir: CircuitIR = CircuitIR::new(
+        2,
+        vec![
+            InstructionIR::Hadamard(0, vec![]),
+            InstructionIR::Measurement(0, MeasurementBasis::Computational),
+            InstructionIR::Hadamard(0, vec![]),
+            InstructionIR::S(1, vec![]),
+            InstructionIR::RawQASM("barrier q;".to_string()),
+            InstructionIR::S(1, vec![]),
+        ],
+    );
+    let mut fused: CircuitIR = ir.clone();
+    FuseSingleQubitGates::new().run(&mut fused).unwrap();
+    assert_eq!(fused.instructions, ir.instructions);
+
+    let out_of_range: CircuitIR = CircuitIR::new(1, vec![InstructionIR::Hadamard(1, vec![])]);
+    let mut fused: CircuitIR = out_of_range.clone();
+    assert!(matches!(
+        FuseSingleQubitGates::new().run(&mut fused),
+        Err(CompilerError::InvalidOperands(_, _))
+    ));
+    assert_eq!(fused.instructions, out_of_range.instructions);
+}
@@ -0,0 +1,217 @@
+use crate::{
+    algorithms::gradient::{
+        GradientMethod, adjoint_gradient, expectation_gradient, parameter_shift_gradient,
+        parameter_shift_gradient_with,
+    },
+    circuit::{Circuit, CircuitBuilder},
+    components::{
+        gate::Gate,
+        measurement::MeasurementBasis,
+        operator::{Operator, Pauli},
+        parameter::Parameter,
+        pauli_string::{PauliString, SumOp},
+        state::State,
+    },
+    errors::Error,
+};
+use std::collections::HashMap;
+
+/// H = 0.5 Z0 Z1 - 0.8 X1 + 0.3 Y0 Y2 + 1.1 Z2
+fn observable() -> SumOp {
+    SumOp::new(vec![
+        PauliString::new(0.5.into()).with_op(0, Pauli::Z).with_op(1, Pauli::Z),
+        PauliString::new((-0.8).into()).with_op(1, Pauli::X),
+        PauliString::new(0.3.into()).with_op(0, Pauli::Y).with_op(2, Pauli::Y),
+        PauliString::new(1.1.into()).with_op(2, Pauli::Z),
+    ])
+}
+
+/// A circuit using every parameterised gate, with parameters shared between gates
+fn ansatz(theta: &Parameter, phi: &Parameter, gamma: &Parameter) -> Circuit {
+    CircuitBuilder::new(3)
+        .h_gate(0)
+        .ry_gate(1, 0.4)
+        .rx_gate(0, theta)
+        .ry_gates(vec![1, 2], 2.0 * theta.clone() - phi.clone())
+        .cnot_gate(0, 1)
+        .rz_gate(2, phi.clone() + 0.5)
+        .p_gate(0, -1.5 * gamma.clone())
+        .crx_gates(vec![2], vec![0], phi)
+        .cry_gates(vec![0], vec![1], theta.clone() * 0.5)
+        .crz_gates(vec![1], vec![2], theta.clone() + gamma.clone())
+        .cp_gates(vec![2], vec![0, 1], gamma)
+        .h_gate(2)
+        .build_final()
+}
+
+fn expectation(circuit: &Circuit, values: &HashMap<Parameter, f64>) -> f64 {
+    let state: State = circuit
+        .execute_with_params(&State::new_zero(3).unwrap(), values)
+        .unwrap();
+    observable().expectation_value(&state).unwrap().re
+}
+
+/// Central finite difference approximation of the gradient
+fn finite_difference(circuit: &Circuit, values: &HashMap<Parameter, f64>) -> Vec<f64> {
+    let epsilon: f64 = 1e-6;
+    circuit
+        .parameters()
+        .iter()
+        .map(|parameter| {
+            let mut plus: HashMap<Parameter, f64> = values.clone();
+            let mut minus: HashMap<Parameter, f64> = values.clone();
+            *plus.get_mut(parameter).unwrap() += epsilon;
+            *minus.get_mut(parameter).unwrap() -= epsilon;
+            (expectation(circuit, &plus) - expectation(circuit, &minus)) / (2.0 * epsilon)
+        })
+        .collect()
+}
+
+fn assert_gradient_eq(actual: &[f64], expected: &[f64], tolerance: f64) {
+    assert_eq!(actual.len(), expected.len());
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < tolerance, "{:?} != {:?}", actual, expected);
+    }
+}
+
+fn parameters() -> (Parameter, Parameter, Parameter) {
+    (Parameter::new("theta"), Parameter::new("phi"), Parameter::new("gamma"))
+}
+
+#[test]
+fn test_gradients_match_finite_differences() {
+    let (theta, phi, gamma) = parameters();
+    let circuit: Circuit = ansatz(&theta, &phi, &gamma);
+    let initial: State = State::new_zero(3).unwrap();
+
+    for (theta_value, phi_value, gamma_value) in [(0.3, -1.2, 0.9), (2.1, 0.7, -0.4)] {
+        let values: HashMap<Parameter, f64> = HashMap::from([
+            (theta.clone(), theta_value),
+            (phi.clone(), phi_value),
+            (gamma.clone(), gamma_value),
+        ]);
+        let expected: Vec<f64> = finite_difference(&circuit, &values);
+
+        let shift: Vec<f64> = parameter_shift_gradient(&circuit, &observable(), &initial, &values).unwrap();
+        let adjoint: Vec<f64> = adjoint_gradient(&circuit, &observable(), &initial, &values).unwrap();
+        assert_gradient_eq(&shift, &expected, 1e-6);
+        assert_gradient_eq(&adjoint, &expected, 1e-6);
+        assert_gradient_eq(&shift, &adjoint, 1e-10);
+    }
+}
+
+#[test]
+fn test_expectation_gradient_dispatches_methods() {
+    let (theta, phi, gamma) = parameters();
+    let circuit: Circuit = ansatz(&theta, &phi, &gamma);
+    let initial: State = State::new_plus(3).unwrap();
+    let values: HashMap<Parameter, f64> = HashMap::from([(theta, 1.0), (phi, 0.2), (gamma, -0.6)]);
+
+    let shift: Vec<f64> = expectation_gradient(
+        &circuit,
+        &observable(),
+        &initial,
+        &values,
+        GradientMethod::ParameterShift,
+    )
+    .unwrap();
+    let adjoint: Vec<f64> =
+        expectation_gradient(&circuit, &observable(), &initial, &values, GradientMethod::Adjoint)
+            .unwrap();
+    assert_eq!(shift.len(), 3);
+    assert_gradient_eq(&shift, &adjoint, 1e-10);
+}
+
+#[test]
+fn test_parameter_shift_with_estimator() {
+    // ⟨Z⟩ after Rx(θ) on |0> is cos θ
+    let theta: Parameter = Parameter::new("theta");
+    let circuit: Circuit = CircuitBuilder::new(1).rx_gate(0, &theta).build_final();
+    let values: HashMap<Parameter, f64> = HashMap::from([(theta, 0.7)]);
+
+    let estimator = |shifted: &Circuit| -> Result<f64, Error> {
+        assert!(shifted.parameters().is_empty());
+        let state: State = shifted.execute(&State::new_zero(1)?)?;
+        Ok(state.probability(0)? - state.probability(1)?)
+    };
+    let gradient: Vec<f64> = parameter_shift_gradient_with(&circuit, &values, estimator).unwrap();
+    assert_gradient_eq(&gradient, &[-(0.7f64).sin()], 1e-12);
+
+    // Errors of the estimator are propagated
+    let failing = |_: &Circuit| -> Result<f64, Error> { Err(Error::UnknownError) };
+    assert_eq!(
+        parameter_shift_gradient_with(&circuit, &values, failing),
+        Err(Error::UnknownError)
+    );
+}
+
+#[test]
+fn test_gradient_without_parameters_is_empty() {
+    let circuit: Circuit = CircuitBuilder::new(3).h_gate(0).rx_gate(1, 0.3).build_final();
+    let initial: State = State::new_zero(3).unwrap();
+    let values: HashMap<Parameter, f64> = HashMap::new();
+
+    assert!(parameter_shift_gradient(&circuit, &observable(), &initial, &values).unwrap().is_empty());
+    assert!(adjoint_gradient(&circuit, &observable(), &initial, &values).unwrap().is_empty());
+}
+
+#[test]
+fn test_gradient_errors() {
+    let (theta, phi, gamma) = parameters();
+    let circuit: Circuit = ansatz(&theta, &phi, &gamma);
+    let initial: State = State::new_zero(3).unwrap();
+    let partial: HashMap<Parameter, f64> = HashMap::from([(theta.clone(), 1.0), (phi.clone(), 0.5)]);
+
+    for method in [GradientMethod::ParameterShift, GradientMethod::Adjoint] {
+        assert_eq!(
+            expectation_gradient(&circuit, &observable(), &initial, &partial, method),
+            Err(Error::UnboundParameter("gamma".to_string()))
+        );
+    }
+
+    // The adjoint method cannot differentiate through measurements
+    let mut measured: Circuit = ansatz(&theta, &phi, &gamma);
+    measured.add_gate(Gate::Measurement(MeasurementBasis::Computational, vec![0])).unwrap();
+    let values: HashMap<Parameter, f64> = HashMap::from([(theta, 1.0), (phi, 0.5), (gamma, 0.1)]);
+    assert_eq!(
+        adjoint_gradient(&measured, &observable(), &initial, &values),
+        Err(Error::NonUnitaryCircuit)
+    );
+}
+
+/// An operator that reports a parameter but is not a `ParameterisedOperator`
+#[derive(Debug, Clone)]
+struct OpaqueParameterised;
+
+impl Operator for OpaqueParameterised {
+    fn apply(&self, state: &State, _: &[usize], _: &[usize]) -> Result<State, Error> {
+        Ok(state.clone())
+    }
+
+    fn base_qubits(&self) -> usize {
+        1
+    }
+
+    fn parameters(&self) -> Vec<Parameter> {
+        vec![Parameter::new("alpha")]
+    }
+}
+
+#[test]
+fn test_gradient_rejects_non_differentiable_operators() {
+    let circuit: Circuit = Circuit::with_gates(
+        vec![Gate::Operator(Box::new(OpaqueParameterised), vec![0], vec![])],
+        1,
+    )
+    .unwrap();
+    let observable: SumOp = SumOp::new(vec![PauliString::new(1.0.into()).with_op(0, Pauli::Z)]);
+    let initial: State = State::new_zero(1).unwrap();
+    let values: HashMap<Parameter, f64> = HashMap::from([(Parameter::new("alpha"), 1.0)]);
+
+    for method in [GradientMethod::ParameterShift, GradientMethod::Adjoint] {
+        assert!(matches!(
+            expectation_gradient(&circuit, &observable, &initial, &values, method),
+            Err(Error::NonDifferentiableOperator(_))
+        ));
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{circuit::CircuitBuilder, components::state::State};
+use num_complex::Complex;
+use std::fmt::Debug;
+
+/// Shorthand for a complex number
+pub fn c(re: f64, im: f64) -> Complex<f64> {
+    Complex::new(re, im)
+}
+
+/// Returns a state with non-trivial amplitudes on every basis state
+pub fn test_state(num_qubits: usize) -> State {
+    let mut builder: CircuitBuilder = CircuitBuilder::new(num_qubits);
+    for qubit in 0..num_qubits {
+        builder
+            .ry_gate(qubit, 0.3 + 0.4 * qubit as f64)
+            .rz_gate(qubit, 0.7 - 0.2 * qubit as f64);
+    }
+    builder
+        .build_final()
+        .execute(&State::new_zero(num_qubits).unwrap())
+        .unwrap()
+}
+
+/// Returns the statements following the register declarations of an emitted program
+pub fn statements(qasm: &str) -> Vec<&str> {
+    qasm.lines()
+        .skip_while(|line| !line.starts_with("qubit[") && !line.starts_with("qreg"))
+        .skip_while(|line| !line.is_empty())
+        .skip(1)
+        .collect()
+}
+
+/// Asserts that two states are equal up to a global phase
+pub fn assert_states_equivalent(a: &State, b: &State) {
+    let overlap: f64 = a.inner_product(b).unwrap().norm();
+    assert!((overlap - 1.0).abs() < 1e-10, "states differ: overlap {}", overlap);
+}
+
+/// Asserts that two matrices, given as rows, are equal up to rounding
+pub fn assert_matrices_close<R: AsRef<[Complex<f64>]> + Debug>(a: &[R], b: &[R]) {
+    assert_eq!(a.len(), b.len());
+    for (row_a, row_b) in a.iter().zip(b) {
+        assert_eq!(row_a.as_ref().len(), row_b.as_ref().len());
+        for (entry_a, entry_b) in row_a.as_ref().iter().zip(row_b.as_ref()) {
+            assert!((entry_a - entry_b).norm() < 1e-10, "matrices differ:\n{:?}\n{:?}", a, b);
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use super::helpers::{assert_matrices_close, c};
+use crate::{
+    circuit::{Circuit, CircuitBuilder},
+    compiler::{
+        ir::{CircuitIR, InstructionIR},
+        kak::KakDecomposition,
+    },
+    components::{
+        operator::{Operator, Unitary4},
+        state::State,
+    },
+    errors::{CompilerError, Error},
+};
+use num_complex::Complex;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_4};
+
+type Matrix4 = [[Complex<f64>; 4]; 4];
+
+/// Returns a random 4×4 unitary, by orthonormalising the rows of a random complex matrix
+pub(super) fn random_unitary(rng: &mut StdRng) -> Matrix4 {
+    let mut matrix: Matrix4 = std::array::from_fn(|_| {
+        std::array::from_fn(|_| c(rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0)))
+    });
+    for i in 0..4 {
+        for j in 0..i {
+            let previous: [Complex<f64>; 4] = matrix[j];
+            let overlap: Complex<f64> = (0..4).map(|k| matrix[i][k] * previous[k].conj()).sum();
+            for (entry, previous_entry) in matrix[i].iter_mut().zip(previous) {
+                *entry -= overlap * previous_entry;
+            }
+        }
+        let norm: f64 = matrix[i].iter().map(|entry| entry.norm_sqr()).sum::<f64>().sqrt();
+        matrix[i] = matrix[i].map(|entry| entry / norm);
+    }
+    matrix
+}
+
+/// Returns `exp(i(a XX + b YY + c ZZ))`, which is diagonal in the Bell basis
+fn interaction(a: f64, b: f64, cc: f64) -> Matrix4 {
+    KakDecomposition {
+        global_phase: 0.0,
+        before: [[[c(1.0, 0.0), c(0.0, 0.0)], [c(0.0, 0.0), c(1.0, 0.0)]]; 2],
+        a,
+        b,
+        c: cc,
+        after: [[[c(1.0, 0.0), c(0.0, 0.0)], [c(0.0, 0.0), c(1.0, 0.0)]]; 2],
+    }
+    .matrix()
+}
+
+fn cnot() -> Matrix4 {
+    // Control on the first qubit (bit 0), target on the second
+    let (one, zero) = (c(1.0, 0.0), c(0.0, 0.0));
+    [
+        [one, zero, zero, zero],
+        [zero, zero, zero, one],
+        [zero, zero, one, zero],
+        [zero, one, zero, zero],
+    ]
+}
+
+fn swap() -> Matrix4 {
+    let (one, zero) = (c(1.0, 0.0), c(0.0, 0.0));
+    [
+        [one, zero, zero, zero],
+        [zero, zero, one, zero],
+        [zero, one, zero, zero],
+        [zero, zero, zero, one],
+    ]
+}
+
+fn local() -> Matrix4 {
+    // H on the first qubit and S on the second
+    let h: f64 = FRAC_1_SQRT_2;
+    let s: [Complex<f64>; 2] = [c(1.0, 0.0), c(0.0, 1.0)];
+    std::array::from_fn(|i| {
+        std::array::from_fn(|j| {
+            let sign: f64 = if i & 1 == 1 && j & 1 == 1 { -h } else { h };
+            if i >> 1 == j >> 1 { s[i >> 1] * sign } else { c(0.0, 0.0) }
+        })
+    })
+}
+
+fn count_cnots(instructions: &[InstructionIR]) -> usize {
+    instructions.iter().filter(|instruction| instruction.qubits().len() > 1).count()
+}
+
+#[test]
+fn test_kak_reconstructs_unitary_with_phase() {
+    let mut rng: StdRng = StdRng::seed_from_u64(9);
+    let mut unitaries: Vec<Matrix4> = vec![cnot(), swap(), local(), interaction(0.3, -0.2, 0.1)];
+    unitaries.extend((0..20).map(|_| random_unitary(&mut rng)));
+    for matrix in unitaries {
+        let decomposition: KakDecomposition = KakDecomposition::new(&matrix);
+        assert_matrices_close(&decomposition.matrix(), &matrix);
+        for coefficient in [decomposition.a, decomposition.b, decomposition.c] {
+            assert!(coefficient > -FRAC_PI_4 - 1e-9 && coefficient <= FRAC_PI_4 + 1e-9);
+        }
+    }
+}
+
+#[test]
+fn test_kak_uses_minimal_number_of_cnots() {
+    let mut rng: StdRng = StdRng::seed_from_u64(3);
+    let cases: [(Matrix4, usize); 7] = [
+        (local(), 0),
+        (cnot(), 1),
+        (interaction(0.0, FRAC_PI_4, 0.0), 1),
+        (interaction(0.0, 0.0, -FRAC_PI_4), 1),
+        (interaction(FRAC_PI_4, FRAC_PI_4, 0.0), 2),
+        (interaction(0.0, 0.3, 0.1), 2),
+        (swap(), 3),
+    ];
+    for (matrix, expected) in cases.into_iter().chain([(random_unitary(&mut rng), 3)]) {
+        let decomposition: KakDecomposition = KakDecomposition::new(&matrix);
+        assert_eq!(decomposition.num_cnots(), expected);
+        assert_eq!(count_cnots(&decomposition.to_instructions([0, 1], &[])), expected);
+    }
+}
+
+#[test]
+fn test_unitary4_compiles_to_equivalent_circuit() {
+    let mut rng: StdRng = StdRng::seed_from_u64(4);
+    for matrix in [cnot(), swap(), local(), interaction(0.0, 0.3, 0.1), random_unitary(&mut rng)] {
+        let circuit: Circuit = CircuitBuilder::new(3)
+            .h_gate(1)
+            .unitary4_gate(2, 0, matrix)
+            .build_final();
+        let compiled: Circuit = CircuitIR::try_from(&circuit).unwrap().to_circuit().unwrap();
+        assert!(circuit.equivalent_to(&compiled, 1e-8).unwrap());
+
+        // Controlled unitaries are compiled exactly, including the global phase
+        let controlled: Circuit = CircuitBuilder::new(3)
+            .h_gate(1)
+            .cunitary4_gate(2, 0, vec![1], matrix)
+            .build_final();
+        let compiled: Circuit = CircuitIR::try_from(&controlled).unwrap().to_circuit().unwrap();
+        assert_matrices_close(&controlled.unitary().unwrap(), &compiled.unitary().unwrap());
+    }
+}
+
+#[test]
+fn test_unitary4_apply() {
+    let mut rng: StdRng = StdRng::seed_from_u64(5);
+    let matrix: Matrix4 = random_unitary(&mut rng);
+    let unitary: Unitary4 = Unitary4::new(matrix).unwrap();
+
+    // On qubits (2, 0) of a 3-qubit basis state |q2 q1 q0> = |0 1 1>, the local index is q0·2 + q2 = 2
+    let state: State = State::new_basis_n(3, 0b011).unwrap();
+    let output: State = unitary.apply(&state, &[2, 0], &[]).unwrap();
+    for (local, row) in matrix.iter().enumerate() {
+        let index: usize = 0b010 | ((local & 1) << 2) | (local >> 1);
+        assert!((output.state_vector[index] - row[2]).norm() < 1e-12);
+    }
+
+    // A set control applies the operator, and an unset one leaves the state unchanged
+    assert_eq!(unitary.apply(&state, &[2, 0], &[1]).unwrap(), output);
+    assert_eq!(unitary.apply(&state, &[1, 0], &[2]).unwrap(), state);
+
+    // The parallel implementation agrees with the compiled circuit
+    let circuit: Circuit = CircuitBuilder::new(10)
+        .h_gates((0..10).collect())
+        .cunitary4_gate(7, 2, vec![4], matrix)
+        .build_final();
+    let compiled: Circuit = CircuitIR::try_from(&circuit).unwrap().to_circuit().unwrap();
+    let initial: State = State::new_zero(10).unwrap();
+    assert_eq!(circuit.execute(&initial).unwrap(), compiled.execute(&initial).unwrap());
+}
+
+#[test]
+fn test_unitary4_validation_and_qasm_export() {
+    let mut not_unitary: Matrix4 = cnot();
+    not_unitary[0][0] = c(0.5, 0.0);
+    assert!(matches!(Unitary4::new(not_unitary), Err(Error::NonUnitaryMatrix)));
+
+    let state: State = State::new_zero(2).unwrap();
+    let unitary: Unitary4 = Unitary4::new(swap()).unwrap();
+    assert!(matches!(unitary.apply(&state, &[0, 0], &[]), Err(Error::InvalidQubitIndex(_, _))));
+    assert!(matches!(unitary.apply(&state, &[0], &[]), Err(Error::InvalidNumberOfQubits(1))));
+
+    let mut rng: StdRng = StdRng::seed_from_u64(6);
+    let circuit: Circuit = CircuitBuilder::new(2)
+        .unitary4_gate(0, 1, random_unitary(&mut rng))
+        .build_final();
+    let qasm: String = circuit.to_qasm().unwrap();
+    assert_eq!(qasm.matches("ctrl @ x ").count(), 3);
+    let parsed: Circuit = Circuit::from_qasm(&qasm).unwrap();
+    assert!(circuit.equivalent_to(&parsed, 1e-8).unwrap());
+}
+
+#[test]
+fn test_unitary_n_compiles_on_up_to_two_qubits() {
+    let mut rng: StdRng = StdRng::seed_from_u64(7);
+    let matrix: Vec<Vec<Complex<f64>>> =
+        random_unitary(&mut rng).iter().map(|row| row.to_vec()).collect();
+    let (cos, sin) = (0.4_f64.cos(), 0.4_f64.sin());
+    let rotation: Vec<Vec<Complex<f64>>> = vec![
+        vec![c(cos, 0.0), c(0.0, -sin)],
+        vec![c(0.0, -sin), c(cos, 0.0)],
+    ];
+    let circuit: Circuit = CircuitBuilder::new(3)
+        .h_gate(0)
+        .unitary_n_gate(vec![1, 0], matrix)
+        .cunitary_n_gate(vec![2], vec![0], rotation)
+        .build_final();
+    let compiled: Circuit = CircuitIR::try_from(&circuit).unwrap().to_circuit().unwrap();
+    assert!(circuit.equivalent_to(&compiled, 1e-8).unwrap());
+
+    let three_qubit: Vec<Vec<Complex<f64>>> = (0..8)
+        .map(|i| (0..8).map(|j| c(if i == j { 1.0 } else { 0.0 }, 0.0)).collect())
+        .collect();
+    let circuit: Circuit = CircuitBuilder::new(3)
+        .unitary_n_gate(vec![0, 1, 2], three_qubit)
+        .build_final();
+    assert!(matches!(circuit.to_qasm(), Err(CompilerError::UnsupportedOperator(_))));
+}
@@ -0,0 +1,192 @@
+use crate::{
+    algorithms::{
+        lindblad::{LindbladIntegrator, LindbladTimeSeries, Lindbladian, lindblad_evolve},
+        time_evolution::{TrotterOrder, trotter_evolve_state},
+    },
+    components::{
+        channel::Channel,
+        density_matrix::DensityMatrix,
+        operator::Pauli,
+        pauli_string::{PauliString, SumOp},
+        state::State,
+    },
+    errors::Error,
+    models::{heisenberg::heisenberg_1d, ising::ising_1d_uniform},
+};
+use num_complex::Complex;
+
+fn pauli(qubit: usize, op: Pauli, coefficient: f64) -> SumOp {
+    SumOp::new(vec![PauliString::new(Complex::new(coefficient, 0.0)).with_op(qubit, op)])
+}
+
+/// The jump operator √γ σ⁻ = √γ (X + iY) / 2, which decays |1⟩ to |0⟩ at rate γ
+fn decay(qubit: usize, gamma: f64) -> SumOp {
+    SumOp::new(vec![
+        PauliString::new(Complex::new(gamma.sqrt() / 2.0, 0.0)).with_op(qubit, Pauli::X),
+        PauliString::new(Complex::new(0.0, gamma.sqrt() / 2.0)).with_op(qubit, Pauli::Y),
+    ])
+}
+
+fn max_difference(a: &DensityMatrix, b: &DensityMatrix) -> f64 {
+    a.matrix.iter().zip(&b.matrix).map(|(x, y)| (x - y).norm()).fold(0.0, f64::max)
+}
+
+#[test]
+fn test_decay_and_precession() {
+    // H = ω/2 Z with decay at rate γ: ⟨Z⟩ relaxes to 1 at rate γ, and the coherence precesses and decays at γ / 2
+    let (omega, gamma): (f64, f64) = (3.0, 0.4);
+    let lindbladian: Lindbladian = Lindbladian::new(&pauli(0, Pauli::Z, omega / 2.0), &[decay(0, gamma)]);
+    let initial: DensityMatrix = DensityMatrix::from_state(&State::new_plus(1).unwrap());
+    let times: Vec<f64> = (0..=10).map(|i| 0.3 * i as f64).collect();
+    let observables: Vec<SumOp> = vec![pauli(0, Pauli::Z, 1.0), pauli(0, Pauli::X, 1.0)];
+
+    for (integrator, tolerance) in [
+        (LindbladIntegrator::Rk4 { dt: 0.01 }, 1e-7),
+        (LindbladIntegrator::Rk45 { tolerance: 1e-10, initial_dt: 0.1 }, 1e-7),
+    ] {
+        let result: LindbladTimeSeries =
+            lindblad_evolve(&lindbladian, &initial, &times, &observables, integrator).unwrap();
+        assert_eq!(result.times, times);
+        assert!(result.steps > 0);
+        for (t, values) in times.iter().zip(&result.expectations) {
+            assert!((values[0] - (1.0 - (-gamma * t).exp())).abs() < tolerance, "{:?} at {}", integrator, t);
+            assert!((values[1] - (-gamma * t / 2.0).exp() * (omega * t).cos()).abs() < tolerance);
+        }
+        assert!((result.final_state.trace().re - 1.0).abs() < 1e-10);
+    }
+
+    // Dephasing at rate γ is the jump operator √(γ / 2) Z, which leaves the populations alone
+    let lindbladian: Lindbladian = Lindbladian::new(&SumOp::new(vec![]), &[pauli(0, Pauli::Z, (gamma / 2.0).sqrt())]);
+    let result: LindbladTimeSeries =
+        lindblad_evolve(&lindbladian, &initial, &[2.0], &observables, LindbladIntegrator::Rk4 { dt: 0.01 }).unwrap();
+    assert!(result.expectations[0][0].abs() < 1e-12);
+    assert!((result.series(1)[0] - (-gamma * 2.0).exp()).abs() < 1e-8);
+    assert!(result.series(2).is_empty());
+}
+
+#[test]
+fn test_lindblad_matches_channels() {
+    // Decay for a time t is the amplitude damping channel with γ = 1 - exp(-Γt), on any qubit of a register
+    let (rate, time): (f64, f64) = (0.7, 1.3);
+    let initial: DensityMatrix = DensityMatrix::from_state(&State::new_plus(2).unwrap().x(1).unwrap().s(1).unwrap());
+    let lindbladian: Lindbladian = Lindbladian::new(&SumOp::new(vec![]), &[decay(1, rate)]);
+    let integrator: LindbladIntegrator = LindbladIntegrator::Rk45 { tolerance: 1e-12, initial_dt: 0.01 };
+    let result: LindbladTimeSeries = lindblad_evolve(&lindbladian, &initial, &[time], &[], integrator).unwrap();
+    let expected: DensityMatrix = initial
+        .apply_channel(&Channel::amplitude_damping(1.0 - (-rate * time).exp()).unwrap(), &[1])
+        .unwrap();
+    assert!(max_difference(&result.final_state, &expected) < 1e-9);
+
+    // The derivative of a steady state vanishes
+    let steady: DensityMatrix = DensityMatrix::new_zero(2).unwrap();
+    assert!(lindbladian.derivative(&steady).unwrap().iter().all(|element| element.norm() < 1e-15));
+}
+
+#[test]
+fn test_closed_evolution_matches_trotter() {
+    // Without jump operators, the solver evolves a pure state under the Heisenberg chain, conserving the energy
+    let hamiltonian: SumOp = heisenberg_1d(3, 1.0, 0.8, 0.6, 0.5, 1.0).unwrap();
+    let initial: State = State::new_basis_n(3, 0b001).unwrap();
+    let lindbladian: Lindbladian = Lindbladian::new(&hamiltonian, &[]);
+    let observables: Vec<SumOp> = vec![pauli(0, Pauli::Z, 1.0), pauli(2, Pauli::Z, 1.0), hamiltonian.clone()];
+    let integrator: LindbladIntegrator = LindbladIntegrator::Rk45 { tolerance: 1e-10, initial_dt: 0.05 };
+    let result: LindbladTimeSeries = lindblad_evolve(
+        &lindbladian,
+        &DensityMatrix::from_state(&initial),
+        &[0.0, 1.0],
+        &observables,
+        integrator,
+    )
+    .unwrap();
+
+    let evolved: State = trotter_evolve_state(&hamiltonian, &initial, 0.001, 1000, TrotterOrder::Second).unwrap();
+    for (k, observable) in observables.iter().enumerate() {
+        assert!((result.expectations[0][k] - observable.expectation_value(&initial).unwrap().re).abs() < 1e-12);
+        assert!((result.expectations[1][k] - observable.expectation_value(&evolved).unwrap().re).abs() < 1e-5);
+    }
+    assert!((result.expectations[1][2] - result.expectations[0][2]).abs() < 1e-8);
+    assert!((result.final_state.purity() - 1.0).abs() < 1e-8);
+}
+
+#[test]
+fn test_dissipative_ising_chain() {
+    // A transverse-field Ising chain with decay on every site: both integrators agree and keep ρ a density matrix
+    let hamiltonian: SumOp = ising_1d_uniform(4, 0.8, 1.0, 1.0).unwrap();
+    let jumps: Vec<SumOp> = (0..4).map(|qubit| decay(qubit, 0.3)).collect();
+    let lindbladian: Lindbladian = Lindbladian::new(&hamiltonian, &jumps);
+    let initial: DensityMatrix = DensityMatrix::from_state(&State::new_basis_n(4, 0b1111).unwrap());
+    let times: [f64; 3] = [0.5, 1.0, 2.0];
+    let magnetisation: SumOp = (0..4).fold(SumOp::new(vec![]), |sum, qubit| sum + pauli(qubit, Pauli::Z, 0.25));
+
+    let observables: &[SumOp] = std::slice::from_ref(&magnetisation);
+    let fixed: LindbladTimeSeries =
+        lindblad_evolve(&lindbladian, &initial, &times, observables, LindbladIntegrator::Rk4 { dt: 0.005 }).unwrap();
+    let integrator: LindbladIntegrator = LindbladIntegrator::Rk45 { tolerance: 1e-9, initial_dt: 0.01 };
+    let adaptive: LindbladTimeSeries = lindblad_evolve(&lindbladian, &initial, &times, observables, integrator).unwrap();
+    assert_eq!(fixed.steps, 400);
+    assert!(adaptive.steps < fixed.steps);
+    assert!(max_difference(&fixed.final_state, &adaptive.final_state) < 1e-6);
+
+    let rho: &DensityMatrix = &adaptive.final_state;
+    assert!((rho.trace() - Complex::new(1.0, 0.0)).norm() < 1e-8);
+    assert!(rho.purity() < 1.0);
+    for r in 0..16 {
+        for c in 0..16 {
+            assert!((rho.element(r, c).unwrap() - rho.element(c, r).unwrap().conj()).norm() < 1e-10);
+        }
+    }
+    // The decay drives the magnetisation up from -1 towards its steady state
+    let series: Vec<f64> = adaptive.series(0);
+    assert!(series[0] > -1.0 && series[0] < series[1] && series[1] < series[2]);
+}
+
+#[test]
+fn test_lindblad_validation() {
+    let lindbladian: Lindbladian = Lindbladian::new(&pauli(0, Pauli::X, 1.0), &[decay(3, 0.1)]);
+    let initial: DensityMatrix = DensityMatrix::new_zero(3).unwrap();
+    let rk4: LindbladIntegrator = LindbladIntegrator::Rk4 { dt: 0.1 };
+    assert_eq!(lindblad_evolve(&lindbladian, &initial, &[1.0], &[], rk4).err(), Some(Error::InvalidQubitIndex(3, 3)));
+    assert_eq!(lindbladian.derivative(&initial).err(), Some(Error::InvalidQubitIndex(3, 3)));
+
+    let initial: DensityMatrix = DensityMatrix::new_zero(4).unwrap();
+    assert_eq!(
+        lindblad_evolve(&lindbladian, &initial, &[1.0, 0.5], &[], rk4).err(),
+        Some(Error::InvalidEvolutionTime(0.5))
+    );
+    assert_eq!(
+        lindblad_evolve(&lindbladian, &initial, &[-1.0], &[], rk4).err(),
+        Some(Error::InvalidEvolutionTime(-1.0))
+    );
+    assert_eq!(
+        lindblad_evolve(&lindbladian, &initial, &[1.0], &[], LindbladIntegrator::Rk4 { dt: 0.0 }).err(),
+        Some(Error::InvalidTimeStep(0.0))
+    );
+    let integrator: LindbladIntegrator = LindbladIntegrator::Rk45 { tolerance: -1.0, initial_dt: 0.1 };
+    assert_eq!(
+        lindblad_evolve(&lindbladian, &initial, &[1.0], &[], integrator).err(),
+        Some(Error::InvalidTolerance(-1.0))
+    );
+    let out_of_range: SumOp = pauli(4, Pauli::Z, 1.0);
+    assert!(lindblad_evolve(&lindbladian, &initial, &[1.0], &[out_of_range], rk4).is_err());
+
+    // Repeated times and time 0 record the current state without integrating
+    let result: LindbladTimeSeries =
+        lindblad_evolve(&lindbladian, &initial, &[0.0, 0.0], &[pauli(0, Pauli::Z, 1.0)], rk4).unwrap();
+    assert_eq!(result.steps, 0);
+    assert_eq!(result.expectations, vec![vec![1.0], vec![1.0]]);
+    assert_eq!(result.final_state, initial);
+}
+
+#[test]
+fn test_rk45_reaches_targets_exactly() {
+    // The state is stationary, so every step is accepted, and the step shortened to reach the second target
+    // used to leave the time an ulp below it, so the next step was rejected as too small
+    let lindbladian: Lindbladian = Lindbladian::new(&pauli(0, Pauli::Z, 1.0), &[]);
+    let initial: DensityMatrix = DensityMatrix::new_zero(1).unwrap();
+    let integrator: LindbladIntegrator = LindbladIntegrator::Rk45 { tolerance: 1e-6, initial_dt: 10.0 };
+
+    let times: [f64; 2] = [0.29690082353810837, 0.9295773023775288];
+    let result: LindbladTimeSeries = lindblad_evolve(&lindbladian, &initial, &times, &[], integrator).unwrap();
+    assert_eq!(result.steps, 2);
+    assert_eq!(result.final_state, initial);
+}
@@ -405,3 +405,54 @@ fn test_measurement_measure_all_qubits_y_basis_success() {
         _ => panic!("Unexpected outcome: {:?}", outcome),
     }
 }
+
+#[test]
+fn test_measurement_sample_marginal_distribution() {
+    // |ψ> = sqrt(0.1)|000> + sqrt(0.2)|011> + sqrt(0.3)|101> + sqrt(0.4)|110>
+    let mut state_vector: Vec<Complex<f64>> = vec![Complex::new(0.0, 0.0); 8];
+    state_vector[0b000] = Complex::new(0.1_f64.sqrt(), 0.0);
+    state_vector[0b011] = Complex::new(0.2_f64.sqrt(), 0.0);
+    state_vector[0b101] = Complex::new(0.0, 0.3_f64.sqrt());
+    state_vector[0b110] = Complex::new(0.4_f64.sqrt(), 0.0);
+    let state: State = State::new(state_vector).unwrap();
+
+    let shots: usize = 20000;
+    let counts = state.sample(MeasurementBasis::Computational, &[], shots).unwrap();
+    assert_eq!(counts.shots(), shots);
+    assert_eq!(counts.len(), 4);
+    for (bitstring, probability) in [("000", 0.1), ("011", 0.2), ("101", 0.3), ("110", 0.4)] {
+        assert!((counts.get(bitstring) as f64 / shots as f64 - probability).abs() < 0.02);
+    }
+
+    // Bit k of the outcome is the value of the k-th measured qubit, so qubits [2, 0] of |011> read as "10"
+    let counts = state.sample(MeasurementBasis::Computational, &[2, 0], shots).unwrap();
+    assert_eq!(counts.num_bits(), 2);
+    for (bitstring, probability) in [("00", 0.1), ("10", 0.2), ("11", 0.3), ("01", 0.4)] {
+        assert!((counts.get(bitstring) as f64 / shots as f64 - probability).abs() < 0.02);
+    }
+
+    // The state is not collapsed
+    assert!((state.probability(0b011).unwrap() - 0.2).abs() < 1e-12);
+}
+
+#[test]
+fn test_measurement_sample_bases_and_errors() {
+    // |+>|i+> gives certain outcomes in the X basis on qubit 0 and the Y basis on qubit 1
+    let state: State = State::new_zero(2).unwrap().h(0).unwrap().h(1).unwrap().s(1).unwrap();
+    assert_eq!(state.sample(MeasurementBasis::X, &[0], 100).unwrap().get("0"), 100);
+    assert_eq!(state.sample(MeasurementBasis::Y, &[1], 100).unwrap().get("0"), 100);
+    let hadamard: [[Complex<f64>; 2]; 2] = [
+        [Complex::new(1.0 / 2.0_f64.sqrt(), 0.0), Complex::new(1.0 / 2.0_f64.sqrt(), 0.0)],
+        [Complex::new(1.0 / 2.0_f64.sqrt(), 0.0), Complex::new(-1.0 / 2.0_f64.sqrt(), 0.0)],
+    ];
+    assert_eq!(state.sample(MeasurementBasis::Custom(hadamard), &[0], 100).unwrap().get("0"), 100);
+
+    assert_eq!(
+        state.sample(MeasurementBasis::Computational, &[0], 0).err(),
+        Some(Error::InvalidNumberOfMeasurements(0))
+    );
+    assert_eq!(
+        state.sample(MeasurementBasis::Computational, &[2], 10).err(),
+        Some(Error::InvalidQubitIndex(2, 2))
+    );
+}
@@ -1,3 +1,4 @@
+pub mod helpers;
 pub mod gate_tests;
 pub mod measurement_tests;
 pub mod operator_tests;
@@ -5,4 +6,25 @@ pub mod state_tests;
 pub mod pauli_string_tests;
 pub mod ising_tests;
 pub mod heisenberg_tests;
-pub mod time_evolution_tests;
\ No newline at end of file
+pub mod time_evolution_tests;
+pub mod qasm_tests;
+pub mod qasm_parser_tests;
+pub mod qasm2_tests;
+pub mod pass_tests;
+pub mod peephole_tests;
+pub mod decompose_tests;
+pub mod routing_tests;
+pub mod euler_tests;
+pub mod kak_tests;
+pub mod unitary_tests;
+pub mod parameter_tests;
+pub mod gradient_tests;
+pub mod classical_tests;
+pub mod reset_barrier_tests;
+pub mod counts_tests;
+pub mod seed_tests;
+pub mod density_matrix_tests;
+pub mod channel_tests;
+pub mod noise_tests;
+pub mod trajectory_tests;
+pub mod lindblad_tests;
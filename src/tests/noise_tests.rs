@@ -0,0 +1,209 @@
+use crate::{
+    circuit::{Circuit, CircuitBuilder},
+    components::{
+        channel::Channel,
+        classical::ClassicalCondition,
+        counts::Counts,
+        density_matrix::DensityMatrix,
+        measurement::MeasurementBasis,
+        noise::{GateKind, NoiseModel},
+        operator::{CNOT, Hadamard},
+        state::State,
+    },
+    errors::Error,
+};
+
+fn bell_circuit() -> Circuit {
+    CircuitBuilder::new(2)
+        .h_gate(0)
+        .cnot_gate(1, 0)
+        .measure_gate(MeasurementBasis::Computational, vec![])
+        .build_final()
+}
+
+#[test]
+fn test_noise_model_validation() {
+    let mut model: NoiseModel = NoiseModel::new();
+    assert_eq!(
+        model.add_gate_error(GateKind::NumQubits(2), Channel::depolarising(0.1, 3).unwrap()).err(),
+        Some(Error::InvalidNumberOfQubits(3))
+    );
+    assert_eq!(
+        model.add_gate_error(GateKind::NumQubits(0), Channel::bit_flip(0.1).unwrap()).err(),
+        Some(Error::InvalidNumberOfQubits(0))
+    );
+    assert_eq!(
+        model.add_qubit_error(GateKind::All, &[0, 1], Channel::bit_flip(0.1).unwrap()).err(),
+        Some(Error::InvalidNumberOfQubits(2))
+    );
+    assert_eq!(
+        model.add_readout_error([[0.9, 0.2], [0.0, 1.0]], &[0]).err(),
+        Some(Error::InvalidProbability(0.9 + 0.2))
+    );
+    assert_eq!(
+        model.add_readout_error([[1.5, -0.5], [0.0, 1.0]], &[0]).err(),
+        Some(Error::InvalidProbability(1.5))
+    );
+
+    // Qubits with their own readout error override the default
+    model
+        .add_readout_error([[0.9, 0.1], [0.1, 0.9]], &[])
+        .unwrap()
+        .add_readout_error([[1.0, 0.0], [0.3, 0.7]], &[1])
+        .unwrap();
+    assert_eq!(model.readout_error(0), Some([[0.9, 0.1], [0.1, 0.9]]));
+    assert_eq!(model.readout_error(1), Some([[1.0, 0.0], [0.3, 0.7]]));
+    assert_eq!(NoiseModel::new().readout_error(0), None);
+
+    model.add_qubit_error(GateKind::All, &[5], Channel::phase_flip(0.1).unwrap()).unwrap();
+    let initial: DensityMatrix = DensityMatrix::new_zero(2).unwrap();
+    assert_eq!(bell_circuit().execute_noisy(&initial, &model).err(), Some(Error::InvalidQubitIndex(5, 2)));
+    assert_eq!(
+        bell_circuit().run_shots_noisy(&State::new_zero(2).unwrap(), 0, &model, None).err(),
+        Some(Error::InvalidNumberOfMeasurements(0))
+    );
+}
+
+#[test]
+fn test_execute_noisy_applies_gate_errors() {
+    let depolarising: Channel = Channel::depolarising(0.2, 2).unwrap();
+    let bit_flip: Channel = Channel::bit_flip(0.05).unwrap();
+    let relaxation: Channel = Channel::thermal_relaxation(30.0, 20.0, 2.0, 0.0).unwrap();
+    let mut model: NoiseModel = NoiseModel::new();
+    model
+        .add_gate_error(GateKind::NumQubits(2), depolarising.clone())
+        .unwrap()
+        .add_gate_error(GateKind::NumQubits(1), bit_flip.clone())
+        .unwrap()
+        .add_qubit_error(GateKind::All, &[1], relaxation.clone())
+        .unwrap();
+
+    // The Hadamard on qubit 0 is followed by the bit flip, and the CNOT by the depolarising channel on its target and
+    // control and the relaxation of qubit 1, before the outcomes of the measurement are discarded
+    let initial: DensityMatrix = DensityMatrix::new_zero(2).unwrap();
+    let expected: DensityMatrix = initial
+        .apply_operator(&Hadamard, &[0], &[])
+        .unwrap()
+        .apply_channel(&bit_flip, &[0])
+        .unwrap()
+        .apply_operator(&CNOT, &[1], &[0])
+        .unwrap()
+        .apply_channel(&depolarising, &[1, 0])
+        .unwrap()
+        .apply_channel(&relaxation, &[1])
+        .unwrap()
+        .measure_non_selective(MeasurementBasis::Computational, &[])
+        .unwrap();
+    assert_eq!(bell_circuit().execute_noisy(&initial, &model).unwrap(), expected);
+
+    // Without errors, noisy execution is the ideal density matrix simulation
+    assert_eq!(
+        bell_circuit().execute_noisy(&initial, &NoiseModel::new()).unwrap(),
+        bell_circuit().execute_density_matrix(&initial).unwrap()
+    );
+}
+
+#[test]
+fn test_gate_errors_by_name() {
+    let circuit: Circuit = Circuit::from_qasm(
+        "OPENQASM 3.0; include \"stdgates.inc\"; qubit[3] q; sx q[0]; rz(0.3) q[0]; cx q[0], q[1]; ccx q[0], q[1], q[2];",
+    )
+    .unwrap();
+    let bit_flip: Channel = Channel::bit_flip(0.1).unwrap();
+    let depolarising: Channel = Channel::depolarising(0.2, 2).unwrap();
+    let mut model: NoiseModel = NoiseModel::new();
+    model
+        .add_gate_error(GateKind::Named("sx".to_string()), bit_flip.clone())
+        .unwrap()
+        .add_gate_error(GateKind::Named("cx".to_string()), depolarising.clone())
+        .unwrap();
+
+    // Only the SX and CNOT gates are noisy: the virtual RZ and the Toffoli are not followed by any channel
+    let gates = circuit.get_gates();
+    let initial: DensityMatrix = DensityMatrix::new_zero(3).unwrap();
+    let expected: DensityMatrix = initial
+        .apply_gate(&gates[0])
+        .unwrap()
+        .apply_channel(&bit_flip, &[0])
+        .unwrap()
+        .apply_gate(&gates[1])
+        .unwrap()
+        .apply_gate(&gates[2])
+        .unwrap()
+        .apply_channel(&depolarising, &[1, 0])
+        .unwrap()
+        .apply_gate(&gates[3])
+        .unwrap();
+    assert_eq!(circuit.execute_noisy(&initial, &model).unwrap(), expected);
+
+    // Controlled gates are named with a prefix per control qubit
+    let mut model: NoiseModel = NoiseModel::new();
+    model.add_gate_error(GateKind::Named("ccx".to_string()), bit_flip.clone()).unwrap();
+    let expected: DensityMatrix = circuit
+        .execute_density_matrix(&initial)
+        .unwrap()
+        .apply_channel(&bit_flip, &[2])
+        .unwrap()
+        .apply_channel(&bit_flip, &[0])
+        .unwrap()
+        .apply_channel(&bit_flip, &[1])
+        .unwrap();
+    assert_eq!(circuit.execute_noisy(&initial, &model).unwrap(), expected);
+}
+
+#[test]
+fn test_readout_errors() {
+    let circuit: Circuit = CircuitBuilder::new(2)
+        .x_gate(0)
+        .measure_gate(MeasurementBasis::Computational, vec![])
+        .build_final();
+    let mut model: NoiseModel = NoiseModel::new();
+    model.add_readout_error([[1.0, 0.0], [0.2, 0.8]], &[0]).unwrap();
+
+    let counts: Counts = circuit.run_shots_noisy(&State::new_zero(2).unwrap(), 4000, &model, Some(8)).unwrap();
+    assert_eq!(counts.get("01") + counts.get("00"), 4000);
+    assert!((counts.get("00") as f64 / 4000.0 - 0.2).abs() < 0.03);
+
+    // Readout errors change the recorded bit, and so the classically controlled gates that read it, but not the state
+    let circuit: Circuit = CircuitBuilder::new(2)
+        .x_gate(0)
+        .measure_into_gate(MeasurementBasis::Computational, vec![0], vec![0])
+        .conditional(ClassicalCondition::bit(0, true), |builder| {
+            builder.x_gate(1);
+        })
+        .build_final();
+    let model: NoiseModel = {
+        let mut model: NoiseModel = NoiseModel::new();
+        model.add_readout_error([[1.0, 0.0], [0.25, 0.75]], &[]).unwrap();
+        model
+    };
+    let rho: DensityMatrix = circuit.execute_noisy(&DensityMatrix::new_zero(2).unwrap(), &model).unwrap();
+    assert!((rho.probability(0b11).unwrap() - 0.75).abs() < 1e-12);
+    assert!((rho.probability(0b01).unwrap() - 0.25).abs() < 1e-12);
+}
+
+#[test]
+fn test_noisy_shots_match_exact_execution() {
+    // Every single-qubit gate is followed by amplitude damping, so |1⟩ survives the X and three identities with
+    // probability 0.9^4
+    let circuit: Circuit = CircuitBuilder::new(1)
+        .x_gate(0)
+        .id_gates(vec![0, 0, 0])
+        .measure_gate(MeasurementBasis::Computational, vec![0])
+        .build_final();
+    let mut model: NoiseModel = NoiseModel::new();
+    model.add_gate_error(GateKind::All, Channel::amplitude_damping(0.1).unwrap()).unwrap();
+
+    let rho: DensityMatrix = circuit.execute_noisy(&DensityMatrix::new_zero(1).unwrap(), &model).unwrap();
+    assert!((rho.probability(1).unwrap() - 0.9f64.powi(4)).abs() < 1e-12);
+
+    let initial: State = State::new_zero(1).unwrap();
+    let counts: Counts = circuit.run_shots_noisy(&initial, 4000, &model, Some(21)).unwrap();
+    assert!((counts.probabilities()["1"] - 0.9f64.powi(4)).abs() < 0.03);
+    assert_eq!(circuit.run_shots_noisy(&initial, 4000, &model, Some(21)).unwrap(), counts);
+
+    // Without errors, noisy shots only contain the ideal outcomes
+    let counts: Counts =
+        bell_circuit().run_shots_noisy(&State::new_zero(2).unwrap(), 200, &NoiseModel::new(), Some(2)).unwrap();
+    assert_eq!(counts.get("00") + counts.get("11"), 200);
+}
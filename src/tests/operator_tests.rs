@@ -3,10 +3,11 @@ use crate::{
         ChainableState,
         operator::{
             CNOT, Hadamard, Identity, Operator, Pauli, PhaseS, PhaseSdag, PhaseShift, PhaseT,
-            PhaseTdag, RotateX, RotateY, RotateZ, SWAP, Toffoli, Unitary2
+            PhaseTdag, RotateX, RotateY, RotateZ, SWAP, Toffoli, Unitary2, Unitary4, UnitaryN
         },
         state::State,
     },
+    circuit::{Circuit, CircuitBuilder},
     errors::Error,
 };
 use num_complex::Complex;
@@ -2152,6 +2153,210 @@ fn test_operate_operate_success() {
 }
 
 
+/// Returns the 8×8 matrix of a Toffoli gate that flips bit 2 of the index when bits 0 and 1 are set
+fn toffoli_matrix() -> Vec<Vec<Complex<f64>>> {
+    (0..8)
+        .map(|row: usize| {
+            (0..8)
+                .map(|column: usize| {
+                    let image: usize = if column & 0b011 == 0b011 { column ^ 0b100 } else { column };
+                    Complex::new(if image == row { 1.0 } else { 0.0 }, 0.0)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_operator_unitary_n_success() {
+    let toffoli: UnitaryN = UnitaryN::new(toffoli_matrix()).unwrap();
+    assert_eq!(toffoli.base_qubits(), 3);
+
+    // The j-th target qubit corresponds to bit j of the matrix index
+    let state: State = State::new_plus(4).unwrap().rz(1, 0.3).unwrap().ry(3, 1.1).unwrap();
+    for targets in [[0, 1, 2], [3, 1, 0], [2, 0, 3]] {
+        let expected: State = Pauli::X.apply(&state, &[targets[2]], &targets[..2]).unwrap();
+        assert_eq!(toffoli.apply(&state, &targets, &[]).unwrap(), expected);
+    }
+
+    // Controls are added to those in the matrix
+    let expected: State = Pauli::X.apply(&state, &[1], &[3, 0, 2]).unwrap();
+    assert_eq!(toffoli.apply(&state, &[3, 0, 1], &[2]).unwrap(), expected);
+
+    // A single-qubit UnitaryN matches Unitary2
+    let h: f64 = 1.0 / 2.0_f64.sqrt();
+    let hadamard: Vec<Vec<Complex<f64>>> = vec![
+        vec![Complex::new(h, 0.0), Complex::new(h, 0.0)],
+        vec![Complex::new(h, 0.0), Complex::new(-h, 0.0)],
+    ];
+    let unitary_n: UnitaryN = UnitaryN::new(hadamard.clone()).unwrap();
+    let unitary_2: Unitary2 =
+        Unitary2::new([[hadamard[0][0], hadamard[0][1]], [hadamard[1][0], hadamard[1][1]]]).unwrap();
+    assert_eq!(
+        unitary_n.apply(&state, &[2], &[1]).unwrap(),
+        unitary_2.apply(&state, &[2], &[1]).unwrap()
+    );
+
+    // The parallel implementation matches the Toffoli gate
+    let num_qubits: usize = 11;
+    let state: State = State::new_plus(num_qubits).unwrap().rx(4, 0.7).unwrap().rz(9, 0.2).unwrap();
+    let expected: State = Pauli::X.apply(&state, &[4], &[9, 0, 7]).unwrap();
+    assert_eq!(toffoli.apply(&state, &[9, 0, 4], &[7]).unwrap(), expected);
+}
+
+#[test]
+fn test_operator_unitary_n_errors() {
+    let identity = |dim: usize| -> Vec<Vec<Complex<f64>>> {
+        (0..dim)
+            .map(|i| (0..dim).map(|j| Complex::new(if i == j { 1.0 } else { 0.0 }, 0.0)).collect())
+            .collect()
+    };
+    assert!(matches!(UnitaryN::new(identity(1)), Err(Error::InvalidMatrixDimensions(1, 1))));
+    assert!(matches!(UnitaryN::new(identity(6)), Err(Error::InvalidMatrixDimensions(6, 6))));
+    let mut ragged: Vec<Vec<Complex<f64>>> = identity(4);
+    ragged[2].pop();
+    assert!(matches!(UnitaryN::new(ragged), Err(Error::InvalidMatrixDimensions(4, 3))));
+    let mut non_unitary: Vec<Vec<Complex<f64>>> = identity(4);
+    non_unitary[3][0] = Complex::new(0.1, 0.0);
+    assert!(matches!(UnitaryN::new(non_unitary), Err(Error::NonUnitaryMatrix)));
+
+    let state: State = State::new_zero(3).unwrap();
+    let unitary: UnitaryN = UnitaryN::new(identity(4)).unwrap();
+    assert!(matches!(unitary.apply(&state, &[0], &[]), Err(Error::InvalidNumberOfQubits(1))));
+    assert!(matches!(unitary.apply(&state, &[1, 1], &[]), Err(Error::InvalidQubitIndex(1, 3))));
+    assert!(matches!(unitary.apply(&state, &[0, 3], &[]), Err(Error::InvalidQubitIndex(3, 3))));
+    assert!(matches!(
+        unitary.apply(&state, &[0, 1], &[1]),
+        Err(Error::OverlappingControlAndTargetQubits(1, 1))
+    ));
+}
+
+/// Returns every built-in operator, with its number of target and (required) control qubits
+fn all_operators() -> Vec<(Box<dyn Operator>, usize, usize)> {
+    let (cos, sin) = (0.3_f64.cos(), 0.3_f64.sin());
+    let unitary2: [[Complex<f64>; 2]; 2] = [
+        [Complex::new(cos, 0.0), -Complex::from_polar(sin, 0.5)],
+        [Complex::from_polar(sin, 1.1), Complex::from_polar(cos, 1.6)],
+    ];
+    // Unitary2 on the first target and a phase on the second
+    let unitary4: [[Complex<f64>; 4]; 4] = std::array::from_fn(|i| {
+        std::array::from_fn(|j| {
+            let phase: Complex<f64> = Complex::from_polar(1.0, 0.7 * (i >> 1) as f64);
+            if i >> 1 == j >> 1 { unitary2[i & 1][j & 1] * phase } else { Complex::new(0.0, 0.0) }
+        })
+    });
+    vec![
+        (Box::new(Hadamard), 1, 0),
+        (Box::new(Pauli::X), 1, 0),
+        (Box::new(Pauli::Y), 1, 0),
+        (Box::new(Pauli::Z), 1, 0),
+        (Box::new(CNOT), 1, 1),
+        (Box::new(SWAP), 2, 0),
+        (Box::new(Toffoli), 1, 2),
+        (Box::new(Identity), 1, 0),
+        (Box::new(PhaseS), 1, 0),
+        (Box::new(PhaseT), 1, 0),
+        (Box::new(PhaseSdag), 1, 0),
+        (Box::new(PhaseTdag), 1, 0),
+        (Box::new(PhaseShift::new(0.4)), 1, 0),
+        (Box::new(RotateX::new(0.5)), 1, 0),
+        (Box::new(RotateY::new(0.6)), 1, 0),
+        (Box::new(RotateZ::new(0.7)), 1, 0),
+        (Box::new(Unitary2::new(unitary2).unwrap()), 1, 0),
+        (Box::new(Unitary4::new(unitary4).unwrap()), 2, 0),
+        (Box::new(UnitaryN::new(toffoli_matrix()).unwrap()), 3, 0),
+    ]
+}
+
+#[test]
+fn test_operator_matrix_matches_apply() {
+    for (operator, num_targets, num_controls) in all_operators() {
+        // The targets are the lowest qubits, and the controls are set
+        let targets: Vec<usize> = (0..num_targets).collect();
+        let controls: Vec<usize> = (num_targets..num_targets + num_controls).collect();
+        let control_mask: usize = ((1 << num_controls) - 1) << num_targets;
+        let matrix: Vec<Vec<Complex<f64>>> = operator.matrix().unwrap();
+        assert_eq!(matrix.len(), 1 << num_targets);
+
+        for column in 0..1 << num_targets {
+            let index: usize = column | control_mask;
+            let state: State = State::new_basis_n(num_targets + num_controls, index).unwrap();
+            let output: State = operator.apply(&state, &targets, &controls).unwrap();
+            for (row, row_entries) in matrix.iter().enumerate() {
+                assert!(
+                    (output.state_vector[row | control_mask] - row_entries[column]).norm() < 1e-12,
+                    "matrix of {:?} does not match its application",
+                    operator
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_operator_adjoint_inverts_apply() {
+    for (operator, num_targets, num_controls) in all_operators() {
+        let num_qubits: usize = num_targets + num_controls + 1;
+        let targets: Vec<usize> = (1..=num_targets).collect();
+        let controls: Vec<usize> = (num_targets + 1..num_qubits).collect();
+        let state: State =
+            State::new_plus(num_qubits).unwrap().ry(1, 0.3).unwrap().rz(0, 0.9).unwrap();
+
+        let adjoint: Box<dyn Operator> = operator.adjoint().unwrap();
+        let output: State = operator.apply(&state, &targets, &controls).unwrap();
+        assert_eq!(adjoint.apply(&output, &targets, &controls).unwrap(), state, "{:?}", operator);
+    }
+}
+
+/// A custom operator that only provides its matrix
+#[derive(Debug, Clone, Copy)]
+struct MatrixOperator([[Complex<f64>; 2]; 2]);
+
+impl Operator for MatrixOperator {
+    fn apply(
+        &self,
+        state: &State,
+        target_qubits: &[usize],
+        control_qubits: &[usize],
+    ) -> Result<State, Error> {
+        Unitary2::new(self.0)?.apply(state, target_qubits, control_qubits)
+    }
+
+    fn base_qubits(&self) -> usize {
+        1
+    }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        Some(self.0.iter().map(|row| row.to_vec()).collect())
+    }
+}
+
+#[test]
+fn test_operator_default_adjoint_and_export() {
+    let half: Complex<f64> = Complex::new(0.5, 0.5);
+    let sqrt_x: MatrixOperator = MatrixOperator([[half, half.conj()], [half.conj(), half]]);
+    let circuit: Circuit = CircuitBuilder::new(2)
+        .h_gate(1)
+        .add_operator_gate(Box::new(sqrt_x), vec![0], vec![1])
+        .build_final();
+
+    // The default adjoint is derived from the matrix
+    let adjoint: Box<dyn Operator> = sqrt_x.adjoint().unwrap();
+    let state: State = State::new_plus(2).unwrap().rz(0, 0.4).unwrap();
+    let output: State = sqrt_x.apply(&state, &[0], &[]).unwrap();
+    assert_eq!(adjoint.apply(&output, &[0], &[]).unwrap(), state);
+    let mut round_trip: Circuit = Circuit::new(2);
+    round_trip.add_gates(circuit.gates.clone());
+    round_trip.add_gates(circuit.inverse().unwrap().gates);
+    assert!(round_trip.equivalent_to(&Circuit::new(2), 1e-10).unwrap());
+
+    // The operator is exported through its matrix
+    let qasm: String = circuit.to_qasm().unwrap();
+    assert!(qasm.contains("ctrl @ sx q[1], q[0];"));
+    let parsed: Circuit = Circuit::from_qasm(&qasm).unwrap();
+    assert!(parsed.equivalent_to(&circuit, 1e-10).unwrap());
+}
+
 // -- TEST ALL ERRORS --
 
 // Macro to generate error test logic for single-qubit gates
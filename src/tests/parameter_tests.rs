@@ -0,0 +1,248 @@
+use crate::{
+    circuit::{Circuit, CircuitBuilder},
+    components::{
+        parameter::{Parameter, ParameterExpression},
+        state::State,
+    },
+    errors::{CompilerError, Error},
+};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+/// Builds a circuit using every parameterised gate, with angles depending on `theta` and `phi`
+fn symbolic_circuit(theta: &Parameter, phi: &Parameter) -> Circuit {
+    CircuitBuilder::new(3)
+        .h_gate(0)
+        .h_gate(1)
+        .rx_gate(0, theta)
+        .ry_gates(vec![1, 2], 2.0 * theta.clone() - phi.clone())
+        .rz_gate(2, phi.clone() + 0.5)
+        .p_gate(0, -phi.clone())
+        .crx_gates(vec![2], vec![0], phi)
+        .cry_gates(vec![0], vec![1], theta.clone() * 0.5)
+        .crz_gates(vec![1], vec![2], theta.clone() + phi.clone())
+        .cp_gates(vec![2], vec![0, 1], theta)
+        .build_final()
+}
+
+/// Builds `symbolic_circuit` with the angles evaluated at the given values
+fn fixed_circuit(theta: f64, phi: f64) -> Circuit {
+    CircuitBuilder::new(3)
+        .h_gate(0)
+        .h_gate(1)
+        .rx_gate(0, theta)
+        .ry_gates(vec![1, 2], 2.0 * theta - phi)
+        .rz_gate(2, phi + 0.5)
+        .p_gate(0, -phi)
+        .crx_gates(vec![2], vec![0], phi)
+        .cry_gates(vec![0], vec![1], theta * 0.5)
+        .crz_gates(vec![1], vec![2], theta + phi)
+        .cp_gates(vec![2], vec![0, 1], theta)
+        .build_final()
+}
+
+#[test]
+fn test_parameter_expression_arithmetic() {
+    let theta: Parameter = Parameter::new("theta");
+    let phi: Parameter = Parameter::new("phi");
+
+    let expression: ParameterExpression = 2.0 * theta.clone() - phi.clone() + 0.5 + theta.clone();
+    assert_eq!(expression.parameters(), vec![theta.clone(), phi.clone()]);
+    assert_eq!(expression.coefficient(&theta), 3.0);
+    assert_eq!(expression.coefficient(&phi), -1.0);
+    assert_eq!(expression.coefficient(&Parameter::new("lambda")), 0.0);
+    assert_eq!(expression.value(), None);
+
+    // Cancelling a term removes the parameter from the expression
+    let cancelled: ParameterExpression = expression.clone() - 3.0 * theta.clone();
+    assert_eq!(cancelled.parameters(), vec![phi.clone()]);
+    assert_eq!((cancelled + phi.clone()).value(), Some(0.5));
+
+    assert_eq!((theta.clone() * 0.0).value(), Some(0.0));
+    assert_eq!((-expression).coefficient(&theta), -3.0);
+}
+
+#[test]
+fn test_parameter_expression_display() {
+    let theta: Parameter = Parameter::new("theta");
+    let phi: Parameter = Parameter::new("phi");
+
+    assert_eq!(ParameterExpression::from(&theta).to_string(), "theta");
+    assert_eq!((2.0 * theta.clone() - phi.clone() + 0.5).to_string(), "2.0*theta - phi + 0.5");
+    assert_eq!((-phi.clone() - 0.25).to_string(), "-phi - 0.25");
+    assert_eq!((theta.clone() - 1.5 * phi).to_string(), "theta - 1.5*phi");
+    assert_eq!(ParameterExpression::constant(PI).to_string(), PI.to_string());
+}
+
+#[test]
+fn test_parameter_expression_bind_and_evaluate() {
+    let theta: Parameter = Parameter::new("theta");
+    let phi: Parameter = Parameter::new("phi");
+    let expression: ParameterExpression = 2.0 * theta.clone() - phi.clone() + 0.5;
+
+    let partial: HashMap<Parameter, f64> = HashMap::from([(theta.clone(), 1.0)]);
+    let bound: ParameterExpression = expression.bind(&partial);
+    assert_eq!(bound.parameters(), vec![phi.clone()]);
+    assert_eq!(bound.coefficient(&phi), -1.0);
+    assert_eq!(
+        expression.evaluate(&partial),
+        Err(Error::UnboundParameter("phi".to_string()))
+    );
+
+    let values: HashMap<Parameter, f64> = HashMap::from([(theta, 1.0), (phi, 0.25)]);
+    assert_eq!(expression.evaluate(&values), Ok(2.25));
+    assert_eq!(bound.evaluate(&values), Ok(2.25));
+}
+
+#[test]
+fn test_circuit_parameters_and_unbound_execution() {
+    let theta: Parameter = Parameter::new("theta");
+    let phi: Parameter = Parameter::new("phi");
+    let circuit: Circuit = symbolic_circuit(&theta, &phi);
+
+    assert_eq!(circuit.parameters(), vec![phi.clone(), theta.clone()]);
+    assert_eq!(
+        circuit.execute(&State::new_zero(3).unwrap()).err(),
+        Some(Error::UnboundParameter("theta".to_string()))
+    );
+
+    // Binding only some parameters leaves the rest unbound
+    let partial: Circuit = circuit.bind(&HashMap::from([(theta, 0.3)]));
+    assert_eq!(partial.parameters(), vec![phi.clone()]);
+    assert_eq!(
+        partial.execute(&State::new_zero(3).unwrap()).err(),
+        Some(Error::UnboundParameter("phi".to_string()))
+    );
+}
+
+#[test]
+fn test_circuit_bind_matches_fixed_angles() {
+    let theta: Parameter = Parameter::new("theta");
+    let phi: Parameter = Parameter::new("phi");
+    let circuit: Circuit = symbolic_circuit(&theta, &phi);
+    let initial: State = State::new_zero(3).unwrap();
+
+    for (theta_value, phi_value) in [(0.3, -1.2), (PI / 3.0, 0.7), (2.5, 0.0)] {
+        let values: HashMap<Parameter, f64> =
+            HashMap::from([(theta.clone(), theta_value), (phi.clone(), phi_value)]);
+        let fixed: Circuit = fixed_circuit(theta_value, phi_value);
+
+        let bound: Circuit = circuit.bind(&values);
+        assert!(bound.parameters().is_empty());
+        assert!(bound.equivalent_to(&fixed, 1e-10).unwrap());
+
+        let expected: State = fixed.execute(&initial).unwrap();
+        assert_eq!(circuit.execute_with_params(&initial, &values).unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_symbolic_circuit_inverse() {
+    let theta: Parameter = Parameter::new("theta");
+    let phi: Parameter = Parameter::new("phi");
+    let inverse: Circuit = symbolic_circuit(&theta, &phi).inverse().unwrap();
+    assert_eq!(inverse.parameters(), vec![phi.clone(), theta.clone()]);
+
+    let values: HashMap<Parameter, f64> = HashMap::from([(theta, 0.8), (phi, -0.4)]);
+    let expected: Circuit = fixed_circuit(0.8, -0.4).inverse().unwrap();
+    assert!(inverse.bind(&values).equivalent_to(&expected, 1e-10).unwrap());
+}
+
+#[test]
+fn test_qasm_input_parameters() {
+    let theta: Parameter = Parameter::new("theta");
+    let phi: Parameter = Parameter::new("phi");
+    let circuit: Circuit = CircuitBuilder::new(2)
+        .rx_gate(0, &theta)
+        .crz_gates(vec![1], vec![0], 2.0 * theta.clone() - phi.clone())
+        .p_gate(1, 0.5)
+        .build_final();
+
+    let qasm: String = circuit.to_qasm().unwrap();
+    let expected: &str = "OPENQASM 3.0;\n\
+                          include \"stdgates.inc\";\n\
+                          \n\
+                          input float[64] phi;\n\
+                          input float[64] theta;\n\
+                          qubit[2] q;\n\
+                          \n\
+                          rx(theta) q[0];\n\
+                          ctrl @ rz(2.0*theta - phi) q[0], q[1];\n\
+                          p(0.5) q[1];\n";
+    assert_eq!(qasm, expected);
+
+    // Bound circuits have no inputs
+    let bound: Circuit = circuit.bind(&HashMap::from([(theta, 1.0), (phi, 0.5)]));
+    assert!(!bound.to_qasm().unwrap().contains("input"));
+}
+
+#[test]
+fn test_qasm_input_parameters_round_trip() {
+    let theta: Parameter = Parameter::new("theta");
+    let phi: Parameter = Parameter::new("phi");
+    let circuit: Circuit = symbolic_circuit(&theta, &phi);
+
+    let parsed: Circuit = Circuit::from_qasm(&circuit.to_qasm().unwrap()).unwrap();
+    assert_eq!(parsed.parameters(), vec![phi.clone(), theta.clone()]);
+
+    let values: HashMap<Parameter, f64> = HashMap::from([(theta, 0.9), (phi, -0.6)]);
+    assert!(parsed.bind(&values).equivalent_to(&fixed_circuit(0.9, -0.6), 1e-10).unwrap());
+}
+
+#[test]
+fn test_qasm_input_parameter_errors() {
+    let parse = |body: &str| {
+        Circuit::from_qasm(&format!("OPENQASM 3.0;\ninput float[64] theta;\nqubit[2] q;\n{}", body))
+    };
+
+    // Parameters work in linear arguments and in the bodies of custom gates
+    let circuit: Circuit = parse("gate g(a) r { rx(2 * a - pi / 2) r; }\ng(theta / 4) q[0];\n").unwrap();
+    assert_eq!(circuit.parameters(), vec![Parameter::new("theta")]);
+
+    for (body, line, column) in [
+        ("rx(theta * theta) q[0];\n", 4, 12),
+        ("rx(sin(theta)) q[0];\n", 4, 8),
+        ("u(theta, 0, 0) q[0];\n", 4, 1),
+        ("qubit[theta] r;\n", 4, 7),
+        ("input float[64] theta;\n", 4, 17),
+        ("input int[32] n;\n", 4, 7),
+    ] {
+        assert!(
+            matches!(parse(body), Err(CompilerError::ParseError(l, c, _)) if (l, c) == (line, column)),
+            "{:?}: {:?}",
+            body,
+            parse(body)
+        );
+    }
+}
+
+#[test]
+fn test_qasm_rejects_invalid_parameter_names() {
+    for name in ["1 bad;name", "theta;", "", "pi", "rx", "input", "q", "c"] {
+        let circuit: Circuit = CircuitBuilder::new(1)
+            .rx_gate(0, Parameter::new(name))
+            .build_final();
+        assert!(
+            matches!(circuit.to_qasm(), Err(CompilerError::UnsupportedOperator(_))),
+            "parameter name {:?}",
+            name
+        );
+    }
+
+    let circuit: Circuit = CircuitBuilder::new(1)
+        .rx_gate(0, Parameter::new("_theta1"))
+        .build_final();
+    assert!(circuit.to_qasm().unwrap().contains("input float[64] _theta1;\n"));
+}
+
+#[test]
+fn test_qasm2_rejects_unbound_parameters() {
+    let circuit: Circuit = CircuitBuilder::new(1)
+        .ry_gate(0, Parameter::new("theta"))
+        .build_final();
+
+    assert!(matches!(
+        circuit.to_qasm2(),
+        Err(CompilerError::UnsupportedOperator(_))
+    ));
+}
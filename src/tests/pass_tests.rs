@@ -0,0 +1,191 @@
+use crate::{
+    circuit::{Circuit, CircuitBuilder},
+    compiler::{
+        ir::{CircuitIR, InstructionIR},
+        pass::{Pass, PassManager, PassStatistics},
+    },
+    components::{measurement::MeasurementBasis, state::State},
+    errors::CompilerError,
+};
+use num_complex::Complex;
+
+/// A user-defined pass that removes identity instructions
+#[derive(Clone)]
+struct RemoveIdentities;
+
+impl Pass for RemoveIdentities {
+    fn name(&self) -> &str {
+        "remove-identities"
+    }
+
+    fn run(&self, circuit: &mut CircuitIR) -> Result<(), CompilerError> {
+        circuit
+            .instructions
+            .retain(|instruction| !matches!(instruction, InstructionIR::Id(_, _)));
+        Ok(())
+    }
+}
+
+/// A user-defined pass that appends an inverse for every instruction, doubling the circuit
+#[derive(Clone)]
+struct AppendInverse;
+
+impl Pass for AppendInverse {
+    fn name(&self) -> &str {
+        "append-inverse"
+    }
+
+    fn run(&self, circuit: &mut CircuitIR) -> Result<(), CompilerError> {
+        let inverse: Vec<InstructionIR> = circuit
+            .instructions
+            .iter()
+            .rev()
+            .map(|instruction| {
+                instruction.inverse().ok_or_else(|| {
+                    CompilerError::UnsupportedOperator(format!("{:?} has no inverse", instruction))
+                })
+            })
+            .collect::<Result<Vec<InstructionIR>, CompilerError>>()?;
+        circuit.instructions.extend(inverse);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_circuit_ir_round_trip() {
+    let circuit: Circuit = CircuitBuilder::new(3)
+        .h_gate(0)
+        .cnot_gate(1, 0)
+        .rz_gate(2, 0.5)
+        .toffoli_gate(0, 1, 2)
+        .measure_gate(MeasurementBasis::X, vec![1])
+        .build_final();
+
+    let ir: CircuitIR = CircuitIR::try_from(&circuit).unwrap();
+    assert_eq!(ir.num_qubits, 3);
+    assert_eq!(
+        ir.instructions,
+        vec![
+            InstructionIR::Hadamard(0, vec![]),
+            InstructionIR::PauliX(1, vec![0]),
+            InstructionIR::Rz(0.5, 2, vec![]),
+            InstructionIR::PauliX(2, vec![0, 1]),
+            InstructionIR::Measurement(1, MeasurementBasis::X),
+        ]
+    );
+
+    let rebuilt: Circuit = ir.to_circuit().unwrap();
+    assert_eq!(rebuilt.num_qubits, 3);
+    assert_eq!(CircuitIR::try_from(&rebuilt).unwrap(), ir);
+
+    // Raw QASM cannot be turned back into a gate
+    let raw: CircuitIR = CircuitIR::new(1, vec![InstructionIR::RawQASM("sx q[0];".to_string())]);
+    assert!(matches!(
+        raw.to_circuit(),
+        Err(CompilerError::UnsupportedOperator(_))
+    ));
+
+    // Out-of-range qubits are rejected when building the circuit
+    let invalid: CircuitIR = CircuitIR::new(1, vec![InstructionIR::PauliX(1, vec![])]);
+    assert!(invalid.to_circuit().is_err());
+
+    // Matrices of unitary instructions are validated
+    let one: Complex<f64> = Complex::new(1.0, 0.0);
+    let non_unitary: CircuitIR = CircuitIR::new(1, vec![InstructionIR::Unitary([[one, one], [one, one]], 0, vec![])]);
+    assert!(matches!(
+        non_unitary.to_circuit(),
+        Err(CompilerError::InvalidOperands(_, _))
+    ));
+}
+
+#[test]
+fn test_circuit_ir_depth() {
+    assert_eq!(CircuitIR::new(2, vec![]).depth(), 0);
+
+    let ir: CircuitIR = CircuitIR::new(
+        3,
+        vec![
+            InstructionIR::Hadamard(0, vec![]),
+            InstructionIR::Hadamard(1, vec![]),
+            InstructionIR::PauliX(1, vec![0]),
+            InstructionIR::Rz(0.1, 2, vec![]),
+            InstructionIR::Swap(1, 2, vec![]),
+            InstructionIR::Measurement(0, MeasurementBasis::Computational),
+        ],
+    );
+    assert_eq!(ir.depth(), 3);
+    assert_eq!(ir.instructions[2].qubits(), vec![0, 1]);
+    assert_eq!(ir.instructions[4].targets(), vec![1, 2]);
+
+    // Raw QASM acts as a barrier across all qubits
+    let mut ir: CircuitIR = ir;
+    ir.instructions.push(InstructionIR::RawQASM("barrier q;".to_string()));
+    ir.instructions.push(InstructionIR::PauliZ(0, vec![]));
+    assert_eq!(ir.depth(), 5);
+}
+
+#[test]
+fn test_pass_manager_statistics() {
+    let circuit: Circuit = CircuitBuilder::new(2)
+        .h_gate(0)
+        .id_gate(0)
+        .id_gate(1)
+        .cnot_gate(1, 0)
+        .build_final();
+    let mut ir: CircuitIR = CircuitIR::try_from(&circuit).unwrap();
+
+    let mut manager: PassManager = PassManager::new();
+    manager
+        .add_pass(Box::new(RemoveIdentities))
+        .add_pass(Box::new(AppendInverse));
+    assert_eq!(manager.pass_names(), vec!["remove-identities", "append-inverse"]);
+
+    let statistics: Vec<PassStatistics> = manager.run(&mut ir).unwrap();
+    assert_eq!(statistics.len(), 2);
+
+    assert_eq!(statistics[0].name, "remove-identities");
+    assert_eq!(statistics[0].instructions_before, 4);
+    assert_eq!(statistics[0].instructions_after, 2);
+    assert_eq!(statistics[0].instructions_removed(), 2);
+    assert_eq!(statistics[0].depth_before, 3);
+    assert_eq!(statistics[0].depth_after, 2);
+    assert_eq!(statistics[0].depth_reduction(), 1);
+
+    assert_eq!(statistics[1].name, "append-inverse");
+    assert_eq!(statistics[1].instructions_removed(), -2);
+    assert_eq!(statistics[1].depth_after, 4);
+
+    // The rewritten circuit is the identity
+    let state: State = State::new_basis_n(2, 1).unwrap();
+    let rewritten: Circuit = ir.to_circuit().unwrap();
+    assert_eq!(rewritten.execute(&state).unwrap(), state);
+}
+
+#[test]
+fn test_pass_manager_propagates_errors() {
+    let circuit: Circuit = CircuitBuilder::new(1)
+        .id_gate(0)
+        .measure_gate(MeasurementBasis::Computational, vec![0])
+        .build_final();
+    let mut ir: CircuitIR = CircuitIR::try_from(&circuit).unwrap();
+
+    let mut manager: PassManager = PassManager::new();
+    manager
+        .add_pass(Box::new(RemoveIdentities))
+        .add_pass(Box::new(AppendInverse));
+
+    assert!(matches!(
+        manager.run(&mut ir),
+        Err(CompilerError::UnsupportedOperator(_))
+    ));
+    // Passes before the failing one have been applied
+    assert_eq!(
+        ir.instructions,
+        vec![InstructionIR::Measurement(0, MeasurementBasis::Computational)]
+    );
+
+    // An empty pass manager leaves the circuit unchanged
+    let statistics: Vec<PassStatistics> = PassManager::new().run(&mut ir).unwrap();
+    assert!(statistics.is_empty());
+    assert_eq!(ir.instructions.len(), 1);
+}
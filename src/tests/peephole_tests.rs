@@ -0,0 +1,259 @@
+use crate::{
+    circuit::{Circuit, CircuitBuilder},
+    compiler::{
+        ir::{CircuitIR, InstructionIR},
+        pass::{Pass, PassManager, PassStatistics},
+        passes::{CancelInverses, MergeRotations, PeepholeOptimiser, RemoveIdentities},
+    },
+    components::{measurement::MeasurementBasis, state::State},
+    subroutine::Subroutine,
+};
+use num_complex::Complex;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::f64::consts::PI;
+
+/// Computes the dense unitary of a circuit by simulating it on every basis state
+pub(super) fn dense_unitary(ir: &CircuitIR) -> Vec<Vec<Complex<f64>>> {
+    let circuit: Circuit = ir.to_circuit().unwrap();
+    (0..1 << ir.num_qubits)
+        .map(|n| {
+            let state: State = State::new_basis_n(ir.num_qubits, n).unwrap();
+            circuit.execute(&state).unwrap().state_vector
+        })
+        .collect()
+}
+
+/// Asserts that two circuits have the same unitary, up to a global phase
+pub(super) fn assert_same_unitary(a: &CircuitIR, b: &CircuitIR) {
+    let (ua, ub) = (dense_unitary(a), dense_unitary(b));
+    let (column, row) = (0..ua.len())
+        .flat_map(|column| (0..ua.len()).map(move |row| (column, row)))
+        .find(|&(column, row)| ua[column][row].norm() > 1e-6)
+        .unwrap();
+    let phase: Complex<f64> = ub[column][row] / ua[column][row];
+    assert!((phase.norm() - 1.0).abs() < 1e-8, "unitaries differ in norm");
+    for column in 0..ua.len() {
+        for row in 0..ua.len() {
+            assert!(
+                (ua[column][row] * phase - ub[column][row]).norm() < 1e-8,
+                "unitaries differ at ({}, {}):\n{:?}\n{:?}",
+                row,
+                column,
+                a.instructions,
+                b.instructions
+            );
+        }
+    }
+}
+
+/// Runs a pass on a copy of the circuit and checks that the unitary is preserved
+fn optimise(ir: &CircuitIR, pass: &dyn Pass) -> CircuitIR {
+    let mut optimised: CircuitIR = ir.clone();
+    pass.run(&mut optimised).unwrap();
+    assert_same_unitary(ir, &optimised);
+    optimised
+}
+
+#[test]
+fn test_cancel_self_inverse_gates() {
+    let ir: CircuitIR = CircuitIR::new(
+        3,
+        vec![
+            InstructionIR::Hadamard(0, vec![]),
+            InstructionIR::PauliX(1, vec![]),
+            InstructionIR::Hadamard(0, vec![]),
+            InstructionIR::PauliX(2, vec![1]),
+            InstructionIR::Rz(0.3, 0, vec![]),
+            InstructionIR::PauliX(2, vec![1]),
+            InstructionIR::PauliX(1, vec![]),
+            InstructionIR::Swap(0, 2, vec![]),
+            InstructionIR::Swap(2, 0, vec![]),
+            InstructionIR::S(1, vec![0]),
+            InstructionIR::Sdg(1, vec![0]),
+            InstructionIR::PauliZ(2, vec![0, 1]),
+            InstructionIR::PauliZ(2, vec![1, 0]),
+        ],
+    );
+
+    let optimised: CircuitIR = optimise(&ir, &CancelInverses);
+    assert_eq!(optimised.instructions, vec![InstructionIR::Rz(0.3, 0, vec![])]);
+}
+
+#[test]
+fn test_non_commuting_gates_block_cancellation() {
+    let ir: CircuitIR = CircuitIR::new(
+        2,
+        vec![
+            InstructionIR::Hadamard(0, vec![]),
+            InstructionIR::PauliX(0, vec![]),
+            InstructionIR::Hadamard(0, vec![]),
+            InstructionIR::Rz(0.5, 1, vec![]),
+            InstructionIR::PauliX(1, vec![0]),
+            InstructionIR::Rz(0.5, 1, vec![]),
+            InstructionIR::PauliX(0, vec![]),
+            InstructionIR::Measurement(0, MeasurementBasis::Computational),
+            InstructionIR::PauliX(0, vec![]),
+        ],
+    );
+
+    let mut optimised: CircuitIR = ir.clone();
+    PeepholeOptimiser.run(&mut optimised).unwrap();
+    assert_eq!(optimised, ir);
+}
+
+#[test]
+fn test_merge_rotations_and_phases() {
+    let ir: CircuitIR = CircuitIR::new(
+        3,
+        vec![
+            InstructionIR::T(0, vec![]),
+            InstructionIR::Hadamard(1, vec![]),
+            InstructionIR::T(0, vec![]),
+            InstructionIR::Rz(0.25, 1, vec![]),
+            InstructionIR::Rx(0.5, 2, vec![]),
+            // The control of a CNOT commutes with Z rotations, and its target with X rotations
+            InstructionIR::PauliX(2, vec![1]),
+            InstructionIR::Rz(-0.75, 1, vec![]),
+            InstructionIR::Rx(-0.25, 2, vec![]),
+            InstructionIR::S(0, vec![2]),
+            InstructionIR::Phase(-PI / 2.0, 0, vec![2]),
+            InstructionIR::Ry(PI, 1, vec![]),
+            InstructionIR::Ry(PI, 1, vec![]),
+            InstructionIR::Ry(PI, 1, vec![0]),
+            InstructionIR::Ry(PI, 1, vec![0]),
+        ],
+    );
+
+    let optimised: CircuitIR = optimise(&ir, &MergeRotations);
+    assert_eq!(
+        optimised.instructions,
+        vec![
+            InstructionIR::Hadamard(1, vec![]),
+            InstructionIR::S(0, vec![]),
+            InstructionIR::PauliX(2, vec![1]),
+            InstructionIR::Rz(-0.5, 1, vec![]),
+            InstructionIR::Rx(0.25, 2, vec![]),
+            // A controlled rotation by 2π is not the identity
+            InstructionIR::Ry(2.0 * PI, 1, vec![0]),
+        ]
+    );
+}
+
+#[test]
+fn test_remove_identities() {
+    let ir: CircuitIR = CircuitIR::new(
+        2,
+        vec![
+            InstructionIR::Id(0, vec![]),
+            InstructionIR::Id(1, vec![0]),
+            InstructionIR::Phase(2.0 * PI, 0, vec![1]),
+            InstructionIR::Rz(-2.0 * PI, 1, vec![]),
+            InstructionIR::Rz(2.0 * PI, 1, vec![0]),
+            InstructionIR::Rx(4.0 * PI, 1, vec![0]),
+            InstructionIR::Unitary(
+                [
+                    [Complex::new(0.0, 1.0), Complex::new(0.0, 0.0)],
+                    [Complex::new(0.0, 0.0), Complex::new(0.0, 1.0)],
+                ],
+                0,
+                vec![],
+            ),
+            InstructionIR::Hadamard(0, vec![]),
+        ],
+    );
+
+    let optimised: CircuitIR = optimise(&ir, &RemoveIdentities);
+    assert_eq!(
+        optimised.instructions,
+        vec![
+            InstructionIR::Rz(2.0 * PI, 1, vec![0]),
+            InstructionIR::Hadamard(0, vec![]),
+        ]
+    );
+}
+
+#[test]
+fn test_peephole_trotter_ladder() {
+    // Two Trotter steps of exp(-iθ Z0 Z1 Z2 / 2), each built from a CNOT ladder
+    let mut builder: CircuitBuilder = CircuitBuilder::new(3);
+    for _ in 0..2 {
+        builder
+            .cnot_gate(1, 0)
+            .cnot_gate(2, 1)
+            .rz_gate(2, 0.2)
+            .cnot_gate(2, 1)
+            .cnot_gate(1, 0);
+    }
+    let ir: CircuitIR = CircuitIR::try_from(&builder.build_final()).unwrap();
+
+    let optimised: CircuitIR = optimise(&ir, &PeepholeOptimiser);
+    assert_eq!(
+        optimised.instructions,
+        vec![
+            InstructionIR::PauliX(1, vec![0]),
+            InstructionIR::PauliX(2, vec![1]),
+            InstructionIR::Rz(0.4, 2, vec![]),
+            InstructionIR::PauliX(2, vec![1]),
+            InstructionIR::PauliX(1, vec![0]),
+        ]
+    );
+}
+
+#[test]
+fn test_peephole_qft_followed_by_inverse() {
+    let circuit: Circuit = CircuitBuilder::new(3)
+        .add_subroutine(Subroutine::qft(vec![0, 1, 2], 3))
+        .add_subroutine(Subroutine::iqft(vec![0, 1, 2], 3))
+        .build_final();
+    let ir: CircuitIR = CircuitIR::try_from(&circuit).unwrap();
+
+    let mut manager: PassManager = PassManager::new();
+    manager.add_pass(Box::new(PeepholeOptimiser));
+    let mut optimised: CircuitIR = ir.clone();
+    let statistics: Vec<PassStatistics> = manager.run(&mut optimised).unwrap();
+
+    assert_same_unitary(&ir, &optimised);
+    assert!(optimised.instructions.is_empty(), "{:?}", optimised.instructions);
+    assert_eq!(statistics[0].instructions_removed(), ir.instructions.len() as isize);
+    assert_eq!(statistics[0].depth_after, 0);
+}
+
+#[test]
+fn test_peephole_random_circuits_preserve_unitary() {
+    let mut rng: StdRng = StdRng::seed_from_u64(7);
+    let angles: [f64; 4] = [PI / 4.0, -PI / 2.0, PI, 0.3];
+
+    for _ in 0..50 {
+        let mut instructions: Vec<InstructionIR> = Vec::new();
+        for _ in 0..40 {
+            let target: usize = rng.random_range(0..3);
+            let other: usize = (target + rng.random_range(1..3)) % 3;
+            let controls: Vec<usize> = if rng.random_bool(0.3) { vec![other] } else { vec![] };
+            let angle: f64 = angles[rng.random_range(0..angles.len())];
+            let instruction: InstructionIR = match rng.random_range(0..14) {
+                0 => InstructionIR::Hadamard(target, controls),
+                1 => InstructionIR::PauliX(target, controls),
+                2 => InstructionIR::PauliY(target, controls),
+                3 => InstructionIR::PauliZ(target, controls),
+                4 => InstructionIR::S(target, controls),
+                5 => InstructionIR::Sdg(target, controls),
+                6 => InstructionIR::T(target, controls),
+                7 => InstructionIR::Tdg(target, controls),
+                8 => InstructionIR::Phase(angle, target, controls),
+                9 => InstructionIR::Rx(angle, target, controls),
+                10 => InstructionIR::Ry(angle, target, controls),
+                11 => InstructionIR::Rz(angle, target, controls),
+                12 => InstructionIR::Id(target, controls),
+                _ => InstructionIR::Swap(target, other, vec![]),
+            };
+            instructions.push(instruction);
+        }
+        let ir: CircuitIR = CircuitIR::new(3, instructions);
+
+        optimise(&ir, &RemoveIdentities);
+        optimise(&ir, &CancelInverses);
+        optimise(&ir, &MergeRotations);
+        let optimised: CircuitIR = optimise(&ir, &PeepholeOptimiser);
+        assert!(optimised.instructions.len() <= ir.instructions.len());
+    }
+}
@@ -0,0 +1,191 @@
+use super::helpers::{assert_states_equivalent, statements, test_state};
+use crate::{
+    circuit::{Circuit, CircuitBuilder},
+    compiler::{ir::InstructionIR, parser::parse_qasm2},
+    components::{measurement::MeasurementBasis, state::State},
+    errors::CompilerError,
+    subroutine::Subroutine,
+};
+use num_complex::Complex;
+use std::f64::consts::PI;
+
+#[test]
+fn test_qasm2_header_and_registers() {
+    let circuit: Circuit = CircuitBuilder::new(2)
+        .h_gate(0)
+        .cnot_gate(1, 0)
+        .measure_gate(MeasurementBasis::Computational, vec![0, 1])
+        .build_final();
+
+    let qasm: String = circuit.to_qasm2().unwrap();
+    let expected: &str = "OPENQASM 2.0;\n\
+                          include \"qelib1.inc\";\n\
+                          \n\
+                          qreg q[2];\n\
+                          creg c[2];\n\
+                          \n\
+                          h q[0];\n\
+                          cx q[0], q[1];\n\
+                          measure q[0] -> c[0];\n\
+                          measure q[1] -> c[1];\n";
+    assert_eq!(qasm, expected);
+}
+
+#[test]
+fn test_qasm2_controlled_gates_lower_to_qelib1() {
+    let circuit: Circuit = CircuitBuilder::new(3)
+        .p_gate(0, 0.25)
+        .toffoli_gate(0, 1, 2)
+        .cp_gates(vec![1], vec![0], 0.5)
+        .crz_gates(vec![2], vec![0], 0.75)
+        .crx_gates(vec![2], vec![1], -0.5)
+        .cz_gates(vec![1], vec![2])
+        .cswap_gate(1, 2, vec![0])
+        .cs_gates(vec![0], vec![1])
+        .ctdag_gates(vec![0], vec![2])
+        .build_final();
+
+    let qasm: String = circuit.to_qasm2().unwrap();
+    assert_eq!(
+        statements(&qasm),
+        vec![
+            "u1(0.25) q[0];",
+            "ccx q[0], q[1], q[2];",
+            "cu1(0.5) q[0], q[1];",
+            "crz(0.75) q[0], q[2];",
+            "crx(-0.5) q[1], q[2];",
+            "cz q[2], q[1];",
+            "cswap q[0], q[1], q[2];",
+            &format!("cu1({:?}) q[1], q[0];", PI / 2.0),
+            &format!("cu1({:?}) q[2], q[0];", -PI / 4.0),
+        ]
+    );
+}
+
+#[test]
+fn test_qasm2_unsupported_constructs() {
+    // More than two controls
+    let circuit: Circuit = CircuitBuilder::new(4)
+        .cx_gates(vec![3], vec![0, 1, 2])
+        .build_final();
+    assert!(matches!(
+        circuit.to_qasm2(),
+        Err(CompilerError::UnsupportedOperator(_))
+    ));
+
+    // Doubly-controlled gates other than Toffoli
+    let circuit: Circuit = CircuitBuilder::new(3)
+        .crz_gates(vec![2], vec![0, 1], 0.5)
+        .build_final();
+    assert!(matches!(
+        circuit.to_qasm2(),
+        Err(CompilerError::UnsupportedOperator(_))
+    ));
+
+    // Measurement outside the computational basis
+    let circuit: Circuit = CircuitBuilder::new(1)
+        .measure_gate(MeasurementBasis::Y, vec![0])
+        .build_final();
+    assert!(matches!(
+        circuit.to_qasm2(),
+        Err(CompilerError::UnsupportedOperator(_))
+    ));
+}
+
+#[test]
+fn test_qasm2_round_trip() {
+    let s: f64 = 1.0 / 2.0_f64.sqrt();
+    let matrix: [[Complex<f64>; 2]; 2] = [
+        [Complex::new(s, 0.0), Complex::new(0.0, s)],
+        [Complex::new(0.0, s), Complex::new(s, 0.0)],
+    ];
+    let circuit: Circuit = CircuitBuilder::new(3)
+        .h_gate(0)
+        .y_gate(1)
+        .sdag_gate(2)
+        .rx_gate(0, 0.1)
+        .ry_gate(1, 0.2)
+        .unitary_gate(2, matrix)
+        .cunitary_gates(vec![0], vec![1], matrix)
+        .ch_gates(vec![2], vec![0])
+        .cy_gates(vec![1], vec![2])
+        .cry_gates(vec![0], vec![2], 1.3)
+        .toffoli_gate(2, 0, 1)
+        .swap_gate(0, 1)
+        .add_subroutine(Subroutine::qft(vec![0, 1, 2], 3))
+        .build_final();
+
+    let parsed: Circuit = Circuit::from_qasm2(&circuit.to_qasm2().unwrap()).unwrap();
+    assert_eq!(parsed.num_qubits, 3);
+
+    let state: State = test_state(3);
+    let expected: State = circuit.execute(&state).unwrap();
+    let actual: State = parsed.execute(&state).unwrap();
+    // Only the phase of the uncontrolled unitary is lost
+    assert_states_equivalent(&expected, &actual);
+}
+
+#[test]
+fn test_qasm2_parse_benchmark_style_program() {
+    let source: &str = r#"
+        // Generated by a benchmark suite
+        OPENQASM 2.0;
+        include "qelib1.inc";
+        qreg q[2];
+        qreg anc[1];
+        creg meas[3];
+        gate majority a, b, c {
+            CX c, b;
+            CX c, a;
+            ccx a, b, c;
+        }
+        gate rot(theta) a { U(0, 0, theta / 2) a; }
+        u2(0, pi) q[0];
+        majority q[0], q[1], anc[0];
+        rot(pi^2) anc[0];
+        cu1(-pi/4) q[1], anc[0];
+        barrier q;
+        measure q -> meas[0:1];
+    "#;
+    // Slices are not supported; the error points at the ':'
+    match parse_qasm2(source) {
+        Err(CompilerError::ParseError(line, column, _)) => assert_eq!((line, column), (19, 28)),
+        other => panic!("expected a parse error, got {:?}", other.map(|ir| ir.instructions)),
+    }
+
+    let source: String = source.replace("meas[0:1]", "meas[0]").replace("measure q", "measure q[1]");
+    let ir = parse_qasm2(&source).unwrap();
+    assert_eq!(ir.num_qubits, 3);
+    assert_eq!(ir.instructions.len(), 8);
+    assert_eq!(ir.instructions[1], InstructionIR::PauliX(1, vec![2]));
+    assert_eq!(ir.instructions[2], InstructionIR::PauliX(0, vec![2]));
+    assert_eq!(ir.instructions[3], InstructionIR::PauliX(2, vec![0, 1]));
+    assert!(matches!(&ir.instructions[4], InstructionIR::Unitary(matrix, 2, _)
+        if (matrix[1][1] - Complex::from_polar(1.0, PI * PI / 2.0)).norm() < 1e-12));
+    assert_eq!(ir.instructions[5], InstructionIR::Phase(-PI / 4.0, 2, vec![1]));
+    assert_eq!(ir.instructions[6], InstructionIR::Barrier(vec![0, 1]));
+    assert_eq!(
+        ir.instructions[7],
+        InstructionIR::MeasurementInto(1, 0, MeasurementBasis::Computational)
+    );
+}
+
+#[test]
+fn test_qasm2_rejects_qasm3_syntax() {
+    let errors: Vec<(&str, (usize, usize))> = vec![
+        ("OPENQASM 3.0;\n", (1, 10)),
+        ("include \"stdgates.inc\";\n", (1, 9)),
+        ("qreg q[2];\nctrl @ x q[0], q[1];\n", (2, 6)),
+        ("qreg q[1];\ncreg c[1];\nmeasure q[0];\n", (3, 13)),
+        ("qreg q[1];\nrx(2 ** 2) q[0];\n", (2, 6)),
+        ("qreg q[2];\ncu(0, 0, 0, 0) q[0], q[1];\n", (2, 1)),
+    ];
+    for (source, position) in errors {
+        match parse_qasm2(source) {
+            Err(CompilerError::ParseError(line, column, _)) => {
+                assert_eq!((line, column), position, "for source:\n{}", source)
+            }
+            other => panic!("expected a parse error for:\n{}\ngot {:?}", source, other.is_ok()),
+        }
+    }
+}
@@ -0,0 +1,307 @@
+use super::helpers::{assert_matrices_close, assert_states_equivalent, test_state};
+use crate::{
+    circuit::{Circuit, CircuitBuilder},
+    compiler::{compilable::CompilableCircuit, ir::InstructionIR, parser::parse_qasm3},
+    components::{measurement::MeasurementBasis, state::State},
+    errors::CompilerError,
+    subroutine::Subroutine,
+};
+use num_complex::Complex;
+use std::f64::consts::PI;
+
+/// Compiles a circuit to its IR instructions
+fn circuit_ir(circuit: &Circuit) -> Vec<InstructionIR> {
+    CompilableCircuit::try_from(circuit).unwrap().to_ir()
+}
+
+fn assert_parse_error(source: &str, line: usize, column: usize) {
+    match parse_qasm3(source) {
+        Err(CompilerError::ParseError(l, c, message)) => {
+            assert_eq!((l, c), (line, column), "unexpected position for '{}'", message)
+        }
+        Err(e) => panic!("expected a parse error, got {:?}", e),
+        Ok(_) => panic!("expected a parse error for:\n{}", source),
+    }
+}
+
+#[test]
+fn test_qasm_round_trip_standard_gates() {
+    let circuit: Circuit = CircuitBuilder::new(4)
+        .h_gate(0)
+        .x_gate(1)
+        .y_gate(2)
+        .z_gate(3)
+        .s_gate(0)
+        .sdag_gate(1)
+        .t_gate(2)
+        .tdag_gate(3)
+        .id_gate(0)
+        .p_gate(1, 0.25)
+        .rx_gate(2, -0.5)
+        .ry_gate(3, PI / 3.0)
+        .rz_gate(0, 1e-7)
+        .cnot_gate(1, 0)
+        .toffoli_gate(0, 1, 3)
+        .swap_gate(0, 2)
+        .cswap_gate(1, 2, vec![3])
+        .crz_gates(vec![2], vec![0, 1, 3], 0.75)
+        .add_subroutine(Subroutine::qft(vec![0, 1, 2], 4))
+        .measure_gate(MeasurementBasis::Computational, vec![0, 3])
+        .build_final();
+
+    let qasm: String = circuit.to_qasm().unwrap();
+    let parsed: Circuit = Circuit::from_qasm(&qasm).unwrap();
+
+    assert_eq!(parsed.num_qubits, 4);
+    assert_eq!(circuit_ir(&parsed), circuit_ir(&circuit));
+}
+
+#[test]
+fn test_qasm_round_trip_unitaries_and_bases() {
+    let s: f64 = 1.0 / 2.0_f64.sqrt();
+    let matrix: [[Complex<f64>; 2]; 2] = [
+        [Complex::new(s, 0.0), Complex::new(0.0, s)],
+        [Complex::new(0.0, s), Complex::new(s, 0.0)],
+    ];
+    let circuit: Circuit = CircuitBuilder::new(3)
+        .unitary_gate(0, matrix)
+        .cunitary_gates(vec![2], vec![0, 1], matrix)
+        .build_final();
+
+    let parsed: Circuit = Circuit::from_qasm(&circuit.to_qasm().unwrap()).unwrap();
+    let state: State = test_state(3);
+    // Controlled global phases are preserved, so the states agree exactly up to the uncontrolled phase
+    assert_states_equivalent(
+        &parsed.execute(&state).unwrap(),
+        &circuit.execute(&state).unwrap(),
+    );
+
+    // Measurements in the X basis are emitted as a basis change around a computational measurement
+    let circuit: Circuit = CircuitBuilder::new(1)
+        .measure_gate(MeasurementBasis::X, vec![0])
+        .build_final();
+    let parsed: Circuit = Circuit::from_qasm(&circuit.to_qasm().unwrap()).unwrap();
+    let plus: State = State::new_plus(1).unwrap();
+    assert_eq!(parsed.execute(&plus).unwrap(), plus);
+}
+
+#[test]
+fn test_qasm_parse_gate_definitions_and_modifiers() {
+    let source: &str = r#"
+        OPENQASM 3.0;
+        include "stdgates.inc";
+
+        // Constants and expressions
+        const float theta = pi / 4;
+        qubit[2] q;
+        qubit r;
+
+        gate bell a, b {
+            h a;
+            cx a, b;
+        }
+        gate rot(t) a { rz(2 * t) a; }
+
+        bell q[0], q[1];
+        ctrl @ rot(theta) r, q[0];
+        inv @ s q[1];
+        pow(2) @ t r;
+        negctrl @ x q[0], q[1];
+        ctrl @ inv @ bell q[0], q[1], r;
+        p(-tau / 2 + 2 ** 0) q[0];
+        ctrl @ gphase(theta) q[1];
+    "#;
+
+    let ir = parse_qasm3(source).unwrap();
+    assert_eq!(ir.num_qubits, 3);
+    assert_eq!(
+        ir.instructions,
+        vec![
+            InstructionIR::Hadamard(0, vec![]),
+            InstructionIR::PauliX(1, vec![0]),
+            InstructionIR::Rz(PI / 2.0, 0, vec![2]),
+            InstructionIR::Sdg(1, vec![]),
+            InstructionIR::T(2, vec![]),
+            InstructionIR::T(2, vec![]),
+            InstructionIR::PauliX(0, vec![]),
+            InstructionIR::PauliX(1, vec![0]),
+            InstructionIR::PauliX(0, vec![]),
+            InstructionIR::PauliX(2, vec![0, 1]),
+            InstructionIR::Hadamard(1, vec![0]),
+            InstructionIR::Phase(1.0 - PI, 0, vec![]),
+            InstructionIR::Phase(PI / 4.0, 1, vec![]),
+        ]
+    );
+}
+
+#[test]
+fn test_qasm_parse_fractional_power() {
+    let source: &str = "OPENQASM 3;\nqubit[1] q;\npow(0.5) @ x q[0];\n";
+    let sx: Circuit = Circuit::from_qasm(source).unwrap();
+    let x: Circuit = CircuitBuilder::new(1).x_gate(0).build_final();
+
+    let state: State = test_state(1);
+    let once: State = sx.execute(&state).unwrap();
+    assert_eq!(sx.execute(&once).unwrap(), x.execute(&state).unwrap());
+
+    // Fractional powers of rotations scale the angle
+    let ir = parse_qasm3("OPENQASM 3;\nqubit[1] q;\npow(1.5) @ rx(0.2) q[0];\n").unwrap();
+    assert_eq!(ir.instructions.len(), 1);
+    assert!(matches!(ir.instructions[0], InstructionIR::Rx(angle, 0, _) if (angle - 0.3).abs() < 1e-12));
+
+    // Diagonal unitaries need an eigenvector from the row of (M - λI) that is not zero
+    for gate in ["s", "U(2 * pi, 0, pi / 2)", "U(0, 0, -pi / 3)"] {
+        let root: Circuit =
+            Circuit::from_qasm(&format!("OPENQASM 3;\nqubit[1] q;\npow(0.5) @ {} q[0];\n", gate)).unwrap();
+        let full: Circuit = Circuit::from_qasm(&format!("OPENQASM 3;\nqubit[1] q;\n{} q[0];\n", gate)).unwrap();
+        let once: State = root.execute(&state).unwrap();
+        let twice: State = root.execute(&once).unwrap();
+        let expected: State = full.execute(&state).unwrap();
+        assert!((twice.inner_product(&expected).unwrap() - Complex::new(1.0, 0.0)).norm() < 1e-10, "{}", gate);
+    }
+}
+
+/// Returns e^{iγ} U(θ, φ, λ)
+fn u_gate(theta: f64, phi: f64, lambda: f64, gamma: f64) -> [[Complex<f64>; 2]; 2] {
+    let (sin, cos) = (theta / 2.0).sin_cos();
+    [
+        [Complex::from_polar(cos, gamma), -Complex::from_polar(sin, gamma + lambda)],
+        [Complex::from_polar(sin, gamma + phi), Complex::from_polar(cos, gamma + phi + lambda)],
+    ]
+}
+
+#[test]
+fn test_qasm_controlled_u3_round_trip() {
+    // stdgates.inc defines u3 and u2 with a global phase, which `ctrl @` turns into a relative phase
+    let (theta, phi, lambda): (f64, f64, f64) = (0.7, 0.4, -1.1);
+    let source: String = format!(
+        "OPENQASM 3;\ninclude \"stdgates.inc\";\nqubit[2] q;\n\
+         ctrl @ u3({theta}, {phi}, {lambda}) q[0], q[1];\nctrl @ u2({phi}, {lambda}) q[1], q[0];\n"
+    );
+    let parsed: Circuit = Circuit::from_qasm(&source).unwrap();
+    let expected: Circuit = CircuitBuilder::new(2)
+        .cunitary_gates(vec![1], vec![0], u_gate(theta, phi, lambda, -(phi + lambda) / 2.0))
+        .cunitary_gates(vec![0], vec![1], u_gate(PI / 2.0, phi, lambda, -(phi + lambda + PI) / 2.0))
+        .build_final();
+    let round_trip: Circuit = Circuit::from_qasm(&parsed.to_qasm().unwrap()).unwrap();
+    assert_matrices_close(&parsed.unitary().unwrap(), &expected.unitary().unwrap());
+    assert_matrices_close(&round_trip.unitary().unwrap(), &expected.unitary().unwrap());
+
+    // The phase is raised to a power with the gate
+    let source: String =
+        format!("OPENQASM 3;\nqubit[2] q;\nctrl @ pow(0.5) @ u3({theta}, {phi}, {lambda}) q[0], q[1];\n");
+    let mut squared: Circuit = Circuit::from_qasm(&source).unwrap();
+    squared.add_gates(Circuit::from_qasm(&source).unwrap().gates);
+    let u3: Circuit = CircuitBuilder::new(2)
+        .cunitary_gates(vec![1], vec![0], u_gate(theta, phi, lambda, -(phi + lambda) / 2.0))
+        .build_final();
+    assert_matrices_close(&squared.unitary().unwrap(), &u3.unitary().unwrap());
+
+    // The u3 of qelib1.inc in OpenQASM 2 is U without a phase
+    let source: String =
+        format!("OPENQASM 2.0;\ninclude \"qelib1.inc\";\nqreg q[1];\nu3({theta}, {phi}, {lambda}) q[0];\n");
+    let expected: Circuit = CircuitBuilder::new(1).unitary_gate(0, u_gate(theta, phi, lambda, 0.0)).build_final();
+    let parsed: Circuit = Circuit::from_qasm2(&source).unwrap();
+    assert_matrices_close(&parsed.unitary().unwrap(), &expected.unitary().unwrap());
+}
+
+#[test]
+fn test_qasm_parse_broadcast_and_measurement() {
+    let source: &str = r#"
+        OPENQASM 3.0;
+        include "stdgates.inc";
+        qreg a[2];
+        qreg b[2];
+        creg c[2];
+        bit d;
+        h a;
+        cx a, b;
+        barrier a, b;
+        measure b -> c;
+        d = measure a[1];
+    "#;
+
+    let ir = parse_qasm3(source).unwrap();
+    assert_eq!(ir.num_qubits, 4);
+    assert_eq!(
+        ir.instructions,
+        vec![
+            InstructionIR::Hadamard(0, vec![]),
+            InstructionIR::Hadamard(1, vec![]),
+            InstructionIR::PauliX(2, vec![0]),
+            InstructionIR::PauliX(3, vec![1]),
+            InstructionIR::Barrier(vec![0, 1, 2, 3]),
+            InstructionIR::MeasurementInto(2, 0, MeasurementBasis::Computational),
+            InstructionIR::MeasurementInto(3, 1, MeasurementBasis::Computational),
+            InstructionIR::MeasurementInto(1, 2, MeasurementBasis::Computational),
+        ]
+    );
+    assert_eq!(ir.num_bits, 3);
+}
+
+#[test]
+fn test_qasm_parse_rejects_discarded_measurements() {
+    // A bare measure discards its outcome, so it must not overwrite the outcome stored in c[0]
+    let source: &str = "OPENQASM 3.0;\nqubit[1] q;\nbit[1] c;\nx q[0];\nc[0] = measure q[0];\nx q[0];\nmeasure q[0];\n";
+    assert_parse_error(source, 7, 1);
+
+    let stored: Circuit = Circuit::from_qasm(source.strip_suffix("measure q[0];\n").unwrap()).unwrap();
+    let round_trip: Circuit = Circuit::from_qasm(&stored.to_qasm().unwrap()).unwrap();
+    for circuit in [stored, round_trip] {
+        let (_, register) = circuit.execute_with_register(&State::new_zero(1).unwrap()).unwrap();
+        assert_eq!(register.get(0), Some(1));
+    }
+}
+
+#[test]
+fn test_qasm_parse_error_positions() {
+    // Unexpected character
+    assert_parse_error("OPENQASM 3.0;\nqubit[1] q;\nh q[0] $;\n", 3, 8);
+    // Unknown gate
+    assert_parse_error("OPENQASM 3.0;\nqubit[1] q;\n  foo q[0];\n", 3, 3);
+    // Unsupported version
+    assert_parse_error("OPENQASM 2.0;\n", 1, 10);
+    // Missing semicolon
+    assert_parse_error("qubit[1] q;\nh q[0]\nx q[0];\n", 3, 1);
+    // Wrong number of qubits
+    assert_parse_error("qubit[2] q;\ncx q[0];\n", 2, 1);
+    // Wrong number of parameters
+    assert_parse_error("qubit[1] q;\nrx q[0];\n", 2, 1);
+    // Index out of range
+    assert_parse_error("qubit[2] q;\nx q[2];\n", 2, 3);
+    // Unknown register
+    assert_parse_error("qubit[2] q;\nx r[0];\n", 2, 3);
+    // Repeated qubit
+    assert_parse_error("qubit[2] q;\ncx q[1], q[1];\n", 2, 1);
+    // Unknown identifier in an expression
+    assert_parse_error("qubit[1] q;\nrz(2 * phi) q[0];\n", 2, 8);
+    // Arguments that are not finite
+    assert_parse_error("qubit[1] q;\nrx(1/0) q[0];\n", 2, 4);
+    assert_parse_error("qubit[1] q;\nU(0, sqrt(-1), 0) q[0];\n", 2, 6);
+    assert_parse_error("qubit[1] q;\ngate g(a) r { rz(ln(a)) r; }\ng(0) q[0];\n", 2, 18);
+    assert_parse_error("qubit[1] q;\npow(1/0) @ x q[0];\n", 2, 12);
+    // Unsupported statement
+    assert_parse_error("qubit[1] q;\ndelay[100ns] q[0];\n", 2, 1);
+    // Gate body referring to a global qubit
+    assert_parse_error("qubit[1] q;\ngate g a { x q[0]; }\n", 2, 14);
+    // Unsupported include
+    assert_parse_error("include \"qelib1.inc\";\n", 1, 9);
+}
+
+#[test]
+fn test_qasm_from_file() {
+    let path = std::env::temp_dir().join(format!("quant_iron_{}.qasm", std::process::id()));
+    std::fs::write(&path, "OPENQASM 3.0;\nqubit[2] q;\nh q[0];\ncx q[0], q[1];\n").unwrap();
+    let circuit: Result<Circuit, CompilerError> = Circuit::from_qasm_file(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    let circuit: Circuit = circuit.unwrap();
+    assert_eq!(circuit.num_qubits, 2);
+    assert_eq!(circuit.gates.len(), 2);
+
+    let missing = std::env::temp_dir().join("quant_iron_missing_file.qasm");
+    assert!(matches!(
+        Circuit::from_qasm_file(&missing),
+        Err(CompilerError::IOError(_))
+    ));
+}
@@ -0,0 +1,276 @@
+use super::helpers::{assert_matrices_close, statements};
+use crate::{
+    circuit::{Circuit, CircuitBuilder},
+    compiler::{compilable::Compilable, ir::InstructionIR, qasm::unitary_to_u_angles},
+    components::{
+        measurement::MeasurementBasis,
+        operator::{Operator, Pauli},
+        state::State,
+    },
+    errors::{CompilerError, Error},
+    subroutine::Subroutine,
+};
+use num_complex::Complex;
+use std::f64::consts::PI;
+
+/// Builds the matrix e^{iα} U(θ, φ, λ) using the OpenQASM 3.0 definition of `U`
+fn u_matrix(theta: f64, phi: f64, lambda: f64, global_phase: f64) -> [[Complex<f64>; 2]; 2] {
+    let phase: Complex<f64> = Complex::from_polar(1.0, global_phase);
+    let (sin_half, cos_half) = (theta / 2.0).sin_cos();
+    [
+        [
+            phase * cos_half,
+            -phase * Complex::from_polar(sin_half, lambda),
+        ],
+        [
+            phase * Complex::from_polar(sin_half, phi),
+            phase * Complex::from_polar(cos_half, phi + lambda),
+        ],
+    ]
+}
+
+#[test]
+fn test_qasm_header_and_registers() {
+    let circuit: Circuit = CircuitBuilder::new(3)
+        .h_gate(0)
+        .measure_gate(MeasurementBasis::Computational, vec![0, 2])
+        .build_final();
+
+    let qasm: String = circuit.to_qasm().unwrap();
+    let expected: &str = "OPENQASM 3.0;\n\
+                          include \"stdgates.inc\";\n\
+                          \n\
+                          qubit[3] q;\n\
+                          bit[3] c;\n\
+                          \n\
+                          h q[0];\n\
+                          c[0] = measure q[0];\n\
+                          c[2] = measure q[2];\n";
+    assert_eq!(qasm, expected);
+
+    // No classical register without measurements
+    let circuit: Circuit = CircuitBuilder::new(2).x_gate(1).build_final();
+    let qasm: String = circuit.to_qasm().unwrap();
+    assert!(qasm.contains("qubit[2] q;"));
+    assert!(!qasm.contains("bit[2] c;"));
+    assert!(qasm.contains("x q[1];"));
+}
+
+#[test]
+fn test_qasm_gates_and_control_modifiers() {
+    let circuit: Circuit = CircuitBuilder::new(4)
+        .y_gate(0)
+        .z_gate(1)
+        .s_gate(2)
+        .sdag_gate(3)
+        .t_gate(0)
+        .tdag_gate(1)
+        .id_gate(2)
+        .p_gate(0, 0.25)
+        .rx_gate(1, -0.5)
+        .ry_gate(2, PI)
+        .rz_gate(3, 1.5)
+        .cnot_gate(1, 0)
+        .toffoli_gate(0, 1, 3)
+        .swap_gate(0, 2)
+        .cswap_gate(1, 2, vec![3])
+        .crz_gates(vec![2], vec![0, 1, 3], 0.75)
+        .build_final();
+
+    let qasm: String = circuit.to_qasm().unwrap();
+    let body: Vec<&str> = statements(&qasm);
+    assert_eq!(
+        body,
+        vec![
+            "y q[0];",
+            "z q[1];",
+            "s q[2];",
+            "sdg q[3];",
+            "t q[0];",
+            "tdg q[1];",
+            "id q[2];",
+            "p(0.25) q[0];",
+            "rx(-0.5) q[1];",
+            &format!("ry({:?}) q[2];", PI),
+            "rz(1.5) q[3];",
+            "ctrl @ x q[0], q[1];",
+            "ctrl(2) @ x q[0], q[1], q[3];",
+            "swap q[0], q[2];",
+            "ctrl @ swap q[3], q[1], q[2];",
+            "ctrl(3) @ rz(0.75) q[0], q[1], q[3], q[2];",
+        ]
+    );
+}
+
+#[test]
+fn test_qasm_unitary_angles_reconstruct_matrix() {
+    let s: f64 = 1.0 / 2.0_f64.sqrt();
+    let matrices: Vec<[[Complex<f64>; 2]; 2]> = vec![
+        // Hadamard
+        [
+            [Complex::new(s, 0.0), Complex::new(s, 0.0)],
+            [Complex::new(s, 0.0), Complex::new(-s, 0.0)],
+        ],
+        // Pauli-Y (anti-diagonal)
+        [
+            [Complex::new(0.0, 0.0), Complex::new(0.0, -1.0)],
+            [Complex::new(0.0, 1.0), Complex::new(0.0, 0.0)],
+        ],
+        // Diagonal with a global phase
+        [
+            [Complex::new(0.0, 1.0), Complex::new(0.0, 0.0)],
+            [Complex::new(0.0, 0.0), Complex::new(-s, s)],
+        ],
+        // Generic unitary
+        u_matrix(0.3, -1.2, 2.5, 0.7),
+    ];
+
+    for matrix in matrices {
+        let (theta, phi, lambda, global_phase) = unitary_to_u_angles(&matrix);
+        assert_matrices_close(&u_matrix(theta, phi, lambda, global_phase), &matrix);
+    }
+}
+
+#[test]
+fn test_qasm_unitary_gates() {
+    let matrix: [[Complex<f64>; 2]; 2] = u_matrix(0.3, -1.2, 2.5, 0.7);
+    let circuit: Circuit = CircuitBuilder::new(2)
+        .unitary_gate(0, matrix)
+        .cunitary_gates(vec![1], vec![0], matrix)
+        .build_final();
+
+    let qasm: String = circuit.to_qasm().unwrap();
+    let body: Vec<&str> = statements(&qasm);
+    assert_eq!(body.len(), 3);
+    assert!(body[0].starts_with("U("));
+    assert!(body[0].ends_with(") q[0];"));
+    assert!(body[1].starts_with("ctrl @ U("));
+    assert!(body[1].ends_with(") q[0], q[1];"));
+    // The global phase of a controlled unitary is applied to the control qubit
+    assert!(body[2].starts_with("ctrl @ gphase(0.7"));
+    assert!(body[2].ends_with(") q[0];"));
+}
+
+#[test]
+fn test_qasm_measurement_bases() {
+    let circuit: Circuit = CircuitBuilder::new(1)
+        .measure_gate(MeasurementBasis::X, vec![0])
+        .measure_gate(MeasurementBasis::Y, vec![0])
+        .build_final();
+
+    let qasm: String = circuit.to_qasm().unwrap();
+    let body: Vec<&str> = statements(&qasm);
+    assert_eq!(
+        body,
+        vec![
+            "h q[0];",
+            "c[0] = measure q[0];",
+            "h q[0];",
+            "sdg q[0];",
+            "h q[0];",
+            "c[0] = measure q[0];",
+            "h q[0];",
+            "s q[0];",
+        ]
+    );
+
+    let s: f64 = 1.0 / 2.0_f64.sqrt();
+    let basis: [[Complex<f64>; 2]; 2] = [
+        [Complex::new(s, 0.0), Complex::new(0.0, s)],
+        [Complex::new(0.0, s), Complex::new(s, 0.0)],
+    ];
+    let circuit: Circuit = CircuitBuilder::new(1)
+        .measure_gate(MeasurementBasis::Custom(basis), vec![0])
+        .build_final();
+    let qasm: String = circuit.to_qasm().unwrap();
+    let body: Vec<&str> = statements(&qasm);
+    assert_eq!(body.len(), 3);
+    assert!(body[0].starts_with("U("));
+    assert_eq!(body[1], "c[0] = measure q[0];");
+    assert!(body[2].starts_with("U("));
+}
+
+#[test]
+fn test_qasm_qft_subroutine() {
+    let circuit: Circuit = CircuitBuilder::new(3)
+        .add_subroutine(Subroutine::qft(vec![0, 1, 2], 3))
+        .build_final();
+
+    let qasm: String = circuit.to_qasm().unwrap();
+    assert!(qasm.contains("h q[0];"));
+    assert!(qasm.contains(&format!("ctrl @ p({:?}) q[1], q[0];", PI / 2.0)));
+    assert!(qasm.contains(&format!("ctrl @ p({:?}) q[2], q[0];", PI / 4.0)));
+    assert!(qasm.contains("swap q[0], q[2];"));
+}
+
+/// A custom operator that compiles to raw QASM
+#[derive(Debug, Clone, Copy)]
+struct RawOperator;
+
+impl Operator for RawOperator {
+    fn apply(&self, state: &State, target_qubits: &[usize], _control_qubits: &[usize]) -> Result<State, Error> {
+        Pauli::X.apply(state, target_qubits, &[])
+    }
+
+    fn base_qubits(&self) -> usize {
+        1
+    }
+
+    fn to_compilable(&self) -> Option<&dyn Compilable> {
+        Some(self)
+    }
+}
+
+impl Compilable for RawOperator {
+    fn to_ir(&self, targets: Vec<usize>, _controls: Vec<usize>) -> Vec<InstructionIR> {
+        targets
+            .iter()
+            .map(|&target| InstructionIR::RawQASM(format!("sx q[{}]; sx q[{}];", target, target)))
+            .collect()
+    }
+}
+
+/// A custom operator without a compilable representation
+#[derive(Debug, Clone, Copy)]
+struct OpaqueOperator;
+
+impl Operator for OpaqueOperator {
+    fn apply(&self, state: &State, _target_qubits: &[usize], _control_qubits: &[usize]) -> Result<State, Error> {
+        Ok(state.clone())
+    }
+
+    fn base_qubits(&self) -> usize {
+        1
+    }
+}
+
+#[test]
+fn test_qasm_raw_qasm_passthrough() {
+    let circuit: Circuit = CircuitBuilder::new(2)
+        .add_operator_gate(Box::new(RawOperator), vec![1], vec![])
+        .build_final();
+
+    let qasm: String = circuit.to_qasm().unwrap();
+    assert!(qasm.ends_with("sx q[1]; sx q[1];\n"));
+}
+
+#[test]
+fn test_qasm_errors() {
+    // Operator without a compilable representation
+    let circuit: Circuit = CircuitBuilder::new(1)
+        .add_operator_gate(Box::new(OpaqueOperator), vec![0], vec![])
+        .build_final();
+    assert!(matches!(
+        circuit.to_qasm(),
+        Err(CompilerError::UnsupportedOperator(_))
+    ));
+
+    // Overlapping control and target qubits
+    let circuit: Circuit = CircuitBuilder::new(2)
+        .cx_gates(vec![0], vec![0])
+        .build_final();
+    assert!(matches!(
+        circuit.to_qasm(),
+        Err(CompilerError::InvalidOperands(_, _))
+    ));
+}
@@ -0,0 +1,211 @@
+use super::helpers::assert_states_equivalent;
+use crate::{
+    circuit::{Circuit, CircuitBuilder},
+    compiler::{
+        coupling_map::CouplingMap,
+        ir::{CircuitIR, InstructionIR},
+        pass::Pass,
+        passes::{CancelInverses, FuseSingleQubitGates, MergeRotations},
+        routing::RoutedCircuit,
+    },
+    components::{gate::Gate, measurement::MeasurementBasis, state::State},
+    errors::Error,
+};
+
+#[test]
+fn test_reset_returns_qubits_to_zero() {
+    let circuit: Circuit = CircuitBuilder::new(3)
+        .h_gate(0)
+        .x_gate(1)
+        .ry_gate(2, 1.1)
+        .cnot_gate(2, 0)
+        .reset(vec![0, 1])
+        .build_final();
+
+    for _ in 0..20 {
+        let state: State = circuit.execute(&State::new_zero(3).unwrap()).unwrap();
+        // Qubits 0 and 1 are |0>, and qubit 2 is left in the state selected by the collapse of qubit 0
+        let probability: f64 = state.probability(0).unwrap() + state.probability(4).unwrap();
+        assert!((probability - 1.0).abs() < 1e-12);
+    }
+
+    let reset: Gate = Gate::new_reset(vec![0]);
+    let state: State = reset.apply(&State::new_basis_n(1, 1).unwrap()).unwrap();
+    assert_states_equivalent(&state, &State::new_zero(1).unwrap());
+}
+
+#[test]
+fn test_reset_reuses_ancilla() {
+    // The ancilla copies qubit 0 twice, and is reset in between
+    let circuit: Circuit = CircuitBuilder::with_bits(2, 2)
+        .x_gate(0)
+        .cnot_gate(1, 0)
+        .measure_into_gate(MeasurementBasis::Computational, vec![1], vec![0])
+        .reset(vec![1])
+        .cnot_gate(1, 0)
+        .measure_into_gate(MeasurementBasis::Computational, vec![1], vec![1])
+        .build_final();
+
+    let (state, register) = circuit.execute_with_register(&State::new_zero(2).unwrap()).unwrap();
+    assert_eq!(register.bits(), &[1, 1]);
+    assert_states_equivalent(&state, &State::new_basis_n(2, 0b11).unwrap());
+}
+
+#[test]
+fn test_barrier_is_unitary_and_kept_by_inverse() {
+    let with_barrier: Circuit = CircuitBuilder::new(2)
+        .h_gate(0)
+        .barrier(vec![])
+        .cnot_gate(1, 0)
+        .barrier(vec![1])
+        .build_final();
+    let without_barrier: Circuit = CircuitBuilder::new(2).h_gate(0).cnot_gate(1, 0).build_final();
+
+    assert!(with_barrier.equivalent_to(&without_barrier, 1e-12).unwrap());
+    let inverse: Circuit = with_barrier.inverse().unwrap();
+    assert!(matches!(&inverse.get_gates()[0], Gate::Barrier(qubits) if qubits == &vec![1]));
+    assert!(matches!(&inverse.get_gates()[2], Gate::Barrier(qubits) if qubits.is_empty()));
+    assert!(with_barrier.controlled(&[]).is_ok());
+
+    let with_reset: Circuit = CircuitBuilder::new(2).h_gate(0).reset(vec![0]).build_final();
+    assert_eq!(with_reset.unitary().err(), Some(Error::NonUnitaryCircuit));
+    assert_eq!(with_reset.inverse().err(), Some(Error::NonUnitaryCircuit));
+    assert_eq!(with_reset.power(2).err(), Some(Error::NonUnitaryCircuit));
+}
+
+#[test]
+fn test_passes_do_not_cross_barriers() {
+    let ir: CircuitIR = CircuitIR::new(
+        2,
+        vec![
+            InstructionIR::Rz(0.3, 0, vec![]),
+            InstructionIR::PauliX(1, vec![]),
+            InstructionIR::Barrier(vec![0]),
+            InstructionIR::Rz(0.2, 0, vec![]),
+            InstructionIR::Barrier(vec![]),
+            InstructionIR::PauliX(1, vec![]),
+            InstructionIR::Barrier(vec![1]),
+            InstructionIR::Barrier(vec![1]),
+        ],
+    );
+
+    let mut merged: CircuitIR = ir.clone();
+    MergeRotations.run(&mut merged).unwrap();
+    assert_eq!(merged, ir);
+
+    let mut cancelled: CircuitIR = ir.clone();
+    CancelInverses.run(&mut cancelled).unwrap();
+    assert_eq!(cancelled, ir);
+
+    // Runs of single-qubit gates are fused up to the barriers only
+    let fusable: CircuitIR = CircuitIR::new(
+        2,
+        vec![
+            InstructionIR::Hadamard(0, vec![]),
+            InstructionIR::Barrier(vec![0, 1]),
+            InstructionIR::Hadamard(0, vec![]),
+        ],
+    );
+    let mut fused: CircuitIR = fusable.clone();
+    FuseSingleQubitGates::new().run(&mut fused).unwrap();
+    assert_eq!(fused.instructions.len(), 3);
+    assert_eq!(fused.instructions[1], InstructionIR::Barrier(vec![0, 1]));
+}
+
+#[test]
+fn test_barrier_depth() {
+    let ir: CircuitIR = CircuitIR::new(
+        3,
+        vec![
+            InstructionIR::Hadamard(0, vec![]),
+            InstructionIR::Hadamard(0, vec![]),
+            InstructionIR::Hadamard(2, vec![]),
+            InstructionIR::Barrier(vec![0, 1]),
+            InstructionIR::Hadamard(1, vec![]),
+            InstructionIR::Hadamard(2, vec![]),
+        ],
+    );
+    // The barrier adds no layer, but the gate on qubit 1 waits for qubit 0
+    assert_eq!(ir.depth(), 3);
+
+    let mut all: CircuitIR = ir.clone();
+    all.instructions[3] = InstructionIR::Barrier(vec![]);
+    all.instructions.push(InstructionIR::Hadamard(2, vec![]));
+    assert_eq!(all.depth(), 4);
+}
+
+#[test]
+fn test_qasm_reset_and_barrier() {
+    let circuit: Circuit = CircuitBuilder::new(3)
+        .h_gate(0)
+        .barrier(vec![0, 2])
+        .reset(vec![1])
+        .barrier(vec![])
+        .build_final();
+
+    let qasm: String = circuit.to_qasm().unwrap();
+    let expected: &str = "OPENQASM 3.0;\n\
+                          include \"stdgates.inc\";\n\
+                          \n\
+                          qubit[3] q;\n\
+                          \n\
+                          h q[0];\n\
+                          barrier q[0], q[2];\n\
+                          reset q[1];\n\
+                          barrier q;\n";
+    assert_eq!(qasm, expected);
+    assert!(
+        circuit
+            .to_qasm2()
+            .unwrap()
+            .ends_with("h q[0];\nbarrier q[0], q[2];\nreset q[1];\nbarrier q;\n")
+    );
+
+    let expected_ir: Vec<InstructionIR> = vec![
+        InstructionIR::Hadamard(0, vec![]),
+        InstructionIR::Barrier(vec![0, 2]),
+        InstructionIR::Reset(1),
+        InstructionIR::Barrier(vec![0, 1, 2]),
+    ];
+    for parsed in [
+        Circuit::from_qasm(&qasm).unwrap(),
+        Circuit::from_qasm2(&circuit.to_qasm2().unwrap()).unwrap(),
+    ] {
+        assert_eq!(CircuitIR::try_from(&parsed).unwrap().instructions, expected_ir);
+    }
+}
+
+#[test]
+fn test_routing_keeps_barriers() {
+    let circuit: Circuit = CircuitBuilder::new(3)
+        .h_gate(0)
+        .cnot_gate(2, 0)
+        .barrier(vec![])
+        .cnot_gate(0, 2)
+        .barrier(vec![0, 1, 2])
+        .h_gate(1)
+        .build_final();
+
+    let routed: RoutedCircuit = circuit.route(&CouplingMap::line(3)).unwrap();
+    let routed_ir: CircuitIR = CircuitIR::try_from(&routed.circuit).unwrap();
+    let barriers: Vec<usize> = routed_ir
+        .instructions
+        .iter()
+        .enumerate()
+        .filter(|(_, instruction)| matches!(instruction, InstructionIR::Barrier(_)))
+        .map(|(i, _)| i)
+        .collect();
+    assert_eq!(barriers.len(), 2);
+
+    // Both CNOTs stay on their side of the first barrier
+    let is_cnot = |instruction: &InstructionIR| {
+        matches!(instruction, InstructionIR::PauliX(_, controls) if controls.len() == 1)
+    };
+    assert_eq!(routed_ir.instructions[..barriers[0]].iter().filter(|i| is_cnot(i)).count(), 1);
+    assert_eq!(routed_ir.instructions[barriers[0]..].iter().filter(|i| is_cnot(i)).count(), 1);
+    assert!(matches!(routed_ir.instructions.last(), Some(InstructionIR::Hadamard(_, _))));
+
+    let state: State = State::new_zero(3).unwrap();
+    let output: State = routed.circuit.execute(&routed.to_physical_state(&state).unwrap()).unwrap();
+    assert_eq!(routed.to_logical_state(&output).unwrap(), circuit.execute(&state).unwrap());
+}
@@ -0,0 +1,232 @@
+use crate::{
+    circuit::{Circuit, CircuitBuilder},
+    compiler::{
+        coupling_map::CouplingMap,
+        ir::{CircuitIR, InstructionIR},
+        routing::{Layout, RoutedCircuit, SabreRouter},
+    },
+    components::{measurement::MeasurementBasis, state::State},
+    errors::CompilerError,
+};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+/// Builds a random circuit of single- and two-qubit gates between arbitrary pairs of qubits
+fn random_circuit(num_qubits: usize, num_gates: usize, seed: u64) -> Circuit {
+    let mut rng: StdRng = StdRng::seed_from_u64(seed);
+    let mut builder: CircuitBuilder = CircuitBuilder::new(num_qubits);
+    for _ in 0..num_gates {
+        let a: usize = rng.random_range(0..num_qubits);
+        let b: usize = (a + rng.random_range(1..num_qubits)) % num_qubits;
+        let angle: f64 = rng.random_range(-3.0..3.0);
+        match rng.random_range(0..6) {
+            0 => builder.h_gate(a),
+            1 => builder.rx_gate(a, angle),
+            2 => builder.cnot_gate(a, b),
+            3 => builder.cz_gates(vec![a], vec![b]),
+            4 => builder.crz_gates(vec![a], vec![b], angle),
+            _ => builder.swap_gate(a, b),
+        };
+    }
+    builder.build_final()
+}
+
+/// Routes a circuit and checks that it is valid for the device and simulates to the same state
+fn assert_routes(circuit: &Circuit, coupling_map: &CouplingMap) -> RoutedCircuit {
+    let routed: RoutedCircuit = circuit.route(coupling_map).unwrap();
+    assert_eq!(routed.circuit.num_qubits, coupling_map.num_qubits());
+
+    let routed_ir: CircuitIR = CircuitIR::try_from(&routed.circuit).unwrap();
+    for instruction in &routed_ir.instructions {
+        let qubits: Vec<usize> = instruction.qubits();
+        assert!(qubits.len() <= 2, "{:?}", instruction);
+        if let [a, b] = qubits[..] {
+            assert!(coupling_map.are_adjacent(a, b), "{:?} is not on an edge", instruction);
+        }
+    }
+
+    for n in [0, 5, (1 << circuit.num_qubits) - 1] {
+        let state: State = State::new_basis_n(circuit.num_qubits, n).unwrap();
+        let expected: State = circuit.execute(&state).unwrap();
+        let output: State = routed
+            .circuit
+            .execute(&routed.to_physical_state(&state).unwrap())
+            .unwrap();
+        assert_eq!(routed.to_logical_state(&output).unwrap(), expected);
+    }
+    routed
+}
+
+#[test]
+fn test_coupling_map_generators() {
+    let line: CouplingMap = CouplingMap::line(4);
+    assert_eq!(line.edges(), &[(0, 1), (1, 2), (2, 3)]);
+    assert_eq!(line.distance(0, 3), Some(3));
+    assert!(line.are_adjacent(2, 1));
+    assert!(!line.are_adjacent(0, 2));
+
+    let grid: CouplingMap = CouplingMap::grid(2, 3);
+    assert_eq!(grid.num_qubits(), 6);
+    assert_eq!(grid.edges().len(), 7);
+    assert_eq!(grid.neighbours(1), &[0, 2, 4]);
+    assert_eq!(grid.distance(0, 5), Some(3));
+
+    // 2 × 3 vertices, 4 horizontal and 2 vertical edges, each with a qubit in the middle
+    let heavy_hex: CouplingMap = CouplingMap::heavy_hex(2, 3);
+    assert_eq!(heavy_hex.num_qubits(), 12);
+    assert_eq!(heavy_hex.edges().len(), 12);
+    assert!(heavy_hex.is_connected());
+    assert!((0..12).all(|qubit| heavy_hex.neighbours(qubit).len() <= 3));
+    assert_eq!(heavy_hex.neighbours(6), &[0, 1]);
+    assert_eq!(heavy_hex.distance(0, 3), Some(2));
+}
+
+#[test]
+fn test_coupling_map_from_edges() {
+    let map: CouplingMap = CouplingMap::from_edges(4, &[(1, 0), (0, 1), (2, 3)]).unwrap();
+    assert_eq!(map.edges(), &[(0, 1), (2, 3)]);
+    assert!(!map.is_connected());
+    assert_eq!(map.distance(0, 3), None);
+
+    assert!(matches!(
+        CouplingMap::from_edges(2, &[(0, 2)]),
+        Err(CompilerError::InvalidOperands(_, _))
+    ));
+    assert!(matches!(
+        CouplingMap::from_edges(2, &[(1, 1)]),
+        Err(CompilerError::InvalidOperands(_, _))
+    ));
+
+    let layout: Layout = Layout::new(vec![3, 0]).unwrap();
+    assert_eq!(layout.physical(0), 3);
+    assert_eq!(layout.logical(0), Some(1));
+    assert_eq!(layout.logical(1), None);
+    assert!(Layout::new(vec![1, 1]).is_err());
+}
+
+#[test]
+fn test_route_random_circuits_onto_devices() {
+    let devices: [CouplingMap; 3] = [
+        CouplingMap::line(5),
+        CouplingMap::grid(2, 3),
+        CouplingMap::heavy_hex(2, 3),
+    ];
+    for (seed, coupling_map) in devices.iter().enumerate() {
+        let circuit: Circuit = random_circuit(5, 60, seed as u64);
+        let routed: RoutedCircuit = assert_routes(&circuit, coupling_map);
+        assert!(routed.num_swaps > 0);
+    }
+}
+
+#[test]
+fn test_route_nearest_neighbour_circuit_needs_no_swaps() {
+    let circuit: Circuit = CircuitBuilder::new(4)
+        .h_gate(3)
+        .cnot_gate(2, 3)
+        .cnot_gate(0, 2)
+        .cnot_gate(1, 0)
+        .cnot_gate(0, 2)
+        .build_final();
+    let routed: RoutedCircuit = assert_routes(&circuit, &CouplingMap::line(4));
+    assert_eq!(routed.num_swaps, 0);
+}
+
+#[test]
+fn test_route_decomposes_multi_qubit_gates() {
+    let circuit: Circuit = CircuitBuilder::new(3)
+        .h_gate(0)
+        .h_gate(1)
+        .toffoli_gate(0, 1, 2)
+        .cswap_gate(0, 2, vec![1])
+        .build_final();
+    assert_routes(&circuit, &CouplingMap::line(3));
+}
+
+#[test]
+fn test_route_keeps_measurements_into_a_bit_in_order() {
+    // The measurement of qubit 3 waits for the CNOT to be routed, while the later measurement of qubit 1 into
+    // the same bit is ready at once, so it would be applied first without a dependency through the bit
+    let circuit: CircuitIR = CircuitIR::new(
+        4,
+        vec![
+            InstructionIR::PauliX(3, vec![0]),
+            InstructionIR::MeasurementInto(3, 0, MeasurementBasis::Computational),
+            InstructionIR::MeasurementInto(1, 0, MeasurementBasis::X),
+        ],
+    );
+    let router: SabreRouter = SabreRouter::new(CouplingMap::line(4));
+    let (routed, _, num_swaps) = router.route(&circuit, &Layout::trivial(4)).unwrap();
+    assert!(num_swaps > 0);
+
+    let bases: Vec<MeasurementBasis> = routed
+        .instructions
+        .iter()
+        .filter_map(|instruction| match instruction {
+            InstructionIR::MeasurementInto(_, 0, basis) => Some(*basis),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(bases, vec![MeasurementBasis::Computational, MeasurementBasis::X]);
+}
+
+#[test]
+fn test_route_keeps_measured_bits() {
+    // The long-range CNOTs move logical qubits, but each measurement still writes the bit of its logical qubit
+    let circuit: Circuit = CircuitBuilder::new(4)
+        .x_gate(0)
+        .cnot_gate(0, 3)
+        .cnot_gate(3, 1)
+        .cnot_gate(0, 2)
+        .x_gate(2)
+        .cnot_gate(1, 2)
+        .measure_gate(MeasurementBasis::Computational, vec![0, 1, 2, 3])
+        .build_final();
+    let routed: RoutedCircuit = circuit.route(&CouplingMap::line(4)).unwrap();
+    assert!(routed.num_swaps > 0);
+
+    let initial: State = State::new_zero(4).unwrap();
+    assert_eq!(
+        routed.circuit.run_shots(&initial, 10, Some(3)).unwrap(),
+        circuit.run_shots(&initial, 10, Some(3)).unwrap()
+    );
+
+    // Extra bits of the circuit are kept on a larger device
+    let circuit: Circuit = CircuitBuilder::with_bits(2, 4)
+        .x_gate(1)
+        .measure_into_gate(MeasurementBasis::Computational, vec![1], vec![3])
+        .build_final();
+    let routed: RoutedCircuit = circuit.route(&CouplingMap::line(3)).unwrap();
+    assert_eq!(routed.circuit.num_bits, 4);
+    assert_eq!(
+        routed.circuit.run_shots(&State::new_zero(3).unwrap(), 10, Some(3)).unwrap(),
+        circuit.run_shots(&State::new_zero(2).unwrap(), 10, Some(3)).unwrap()
+    );
+}
+
+#[test]
+fn test_routing_errors() {
+    let circuit: Circuit = random_circuit(4, 10, 0);
+    assert!(matches!(
+        circuit.route(&CouplingMap::line(3)),
+        Err(CompilerError::InvalidOperands(_, _))
+    ));
+
+    let disconnected: CouplingMap = CouplingMap::from_edges(4, &[(0, 1), (2, 3)]).unwrap();
+    assert!(matches!(
+        circuit.route(&disconnected),
+        Err(CompilerError::InvalidOperands(_, _))
+    ));
+
+    let router: SabreRouter = SabreRouter::new(CouplingMap::line(3));
+    let raw: CircuitIR = CircuitIR::new(2, vec![InstructionIR::RawQASM("barrier q;".to_string())]);
+    assert!(matches!(
+        router.route(&raw, &Layout::trivial(2)),
+        Err(CompilerError::UnsupportedOperator(_))
+    ));
+    let toffoli: CircuitIR = CircuitIR::new(3, vec![InstructionIR::PauliX(2, vec![0, 1])]);
+    assert!(matches!(
+        router.layout(&toffoli),
+        Err(CompilerError::UnsupportedOperator(_))
+    ));
+    let empty: CircuitIR = CircuitIR::new(2, vec![]);
+    assert!(router.route(&empty, &Layout::trivial(3)).is_err());
+}
@@ -0,0 +1,120 @@
+use crate::{
+    circuit::{Circuit, CircuitBuilder},
+    components::{
+        classical::ClassicalCondition,
+        counts::Counts,
+        gate::Gate,
+        measurement::{MeasurementBasis, MeasurementResult},
+        state::State,
+    },
+};
+use rand::{SeedableRng, rngs::StdRng};
+
+/// Runs a closure in a rayon thread pool with the given number of threads
+fn with_threads<T: Send>(threads: usize, f: impl FnOnce() -> T + Send) -> T {
+    rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap().install(f)
+}
+
+/// A circuit with mid-circuit measurements, resets and classically controlled gates
+fn feedback_circuit() -> Circuit {
+    CircuitBuilder::with_bits(3, 3)
+        .h_gates(vec![0, 1, 2])
+        .measure_into_gate(MeasurementBasis::Computational, vec![0], vec![0])
+        .conditional(ClassicalCondition::bit(0, true), |builder| {
+            builder.ry_gate(1, 0.4);
+        })
+        .reset(vec![0])
+        .ry_gate(0, 1.1)
+        .measure_into_gate(MeasurementBasis::X, vec![1], vec![1])
+        .measure_gate(MeasurementBasis::Computational, vec![2, 0])
+        .build_final()
+}
+
+fn plus_state(num_qubits: usize) -> State {
+    State::new_plus(num_qubits).unwrap()
+}
+
+#[test]
+fn test_measure_with_rng_is_reproducible() {
+    let state: State = plus_state(4);
+    for basis in [MeasurementBasis::Computational, MeasurementBasis::Y] {
+        let first: MeasurementResult =
+            state.measure_with_rng(basis, &[0, 2, 3], &mut StdRng::seed_from_u64(11)).unwrap();
+        let second: MeasurementResult =
+            state.measure_with_rng(basis, &[0, 2, 3], &mut StdRng::seed_from_u64(11)).unwrap();
+        assert_eq!(first, second);
+    }
+
+    // Different seeds give different sequences of outcomes
+    let outcomes = |seed: u64| -> Vec<Vec<u8>> {
+        let mut rng: StdRng = StdRng::seed_from_u64(seed);
+        (0..20)
+            .map(|_| state.measure_with_rng(MeasurementBasis::Computational, &[], &mut rng).unwrap().outcomes)
+            .collect()
+    };
+    assert_eq!(outcomes(1), outcomes(1));
+    assert_ne!(outcomes(1), outcomes(2));
+}
+
+#[test]
+fn test_parallel_measurements_do_not_depend_on_thread_count() {
+    let state: State = plus_state(3);
+    let measure = || {
+        state
+            .measure_n_with_rng(MeasurementBasis::X, &[1, 2], 200, &mut StdRng::seed_from_u64(5))
+            .unwrap()
+    };
+    let single: Vec<MeasurementResult> = with_threads(1, measure);
+    let many: Vec<MeasurementResult> = with_threads(4, measure);
+    assert_eq!(single, many);
+
+    let sample = || {
+        state
+            .sample_with_rng(MeasurementBasis::Computational, &[], 1000, &mut StdRng::seed_from_u64(5))
+            .unwrap()
+    };
+    assert_eq!(with_threads(1, sample), with_threads(4, sample));
+}
+
+#[test]
+fn test_seeded_circuit_execution() {
+    let circuit: Circuit = feedback_circuit();
+    let initial: State = State::new_zero(3).unwrap();
+
+    let (first_state, first_register) =
+        circuit.execute_with_rng(&initial, &mut StdRng::seed_from_u64(42)).unwrap();
+    let (second_state, second_register) =
+        circuit.execute_with_rng(&initial, &mut StdRng::seed_from_u64(42)).unwrap();
+    assert_eq!(first_state, second_state);
+    assert_eq!(first_register, second_register);
+
+    let reset: Gate = Gate::new_reset(vec![0, 1]);
+    let state: State = plus_state(2);
+    assert_eq!(
+        reset.apply_with_rng(&state, &mut StdRng::seed_from_u64(3)).unwrap(),
+        reset.apply_with_rng(&state, &mut StdRng::seed_from_u64(3)).unwrap()
+    );
+}
+
+#[test]
+fn test_seeded_shots_do_not_depend_on_thread_count() {
+    let circuit: Circuit = feedback_circuit();
+    let initial: State = State::new_zero(3).unwrap();
+
+    let run = || circuit.run_shots(&initial, 500, Some(2024)).unwrap();
+    let single: Counts = with_threads(1, run);
+    let many: Counts = with_threads(4, run);
+    assert_eq!(single, many);
+    assert_eq!(single.shots(), 500);
+    assert!(single.len() > 1);
+    assert_ne!(circuit.run_shots(&initial, 500, Some(2025)).unwrap(), single);
+
+    // Terminal measurements are sampled from a single stream
+    let bell: Circuit = CircuitBuilder::new(2)
+        .h_gate(0)
+        .cnot_gate(1, 0)
+        .measure_gate(MeasurementBasis::Computational, vec![])
+        .build_final();
+    let run = || bell.run_shots(&State::new_zero(2).unwrap(), 500, Some(9)).unwrap();
+    assert_eq!(with_threads(1, run), with_threads(3, run));
+}
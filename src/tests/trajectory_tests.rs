@@ -0,0 +1,147 @@
+use crate::{
+    algorithms::trajectory::{Estimate, TrajectoryResult, run_trajectories},
+    circuit::{Circuit, CircuitBuilder},
+    components::{
+        channel::Channel,
+        density_matrix::DensityMatrix,
+        measurement::MeasurementBasis,
+        noise::{GateKind, NoiseModel},
+        operator::Pauli,
+        pauli_string::{PauliString, SumOp},
+        state::State,
+    },
+    errors::Error,
+    models::ising::ising_1d_uniform,
+};
+use num_complex::Complex;
+
+/// A GHZ circuit with rotations, so every Pauli term has a non-trivial expectation value
+fn noisy_circuit() -> Circuit {
+    CircuitBuilder::new(3)
+        .h_gate(0)
+        .cnot_gate(1, 0)
+        .cnot_gate(2, 1)
+        .ry_gates(vec![0, 1, 2], 0.4)
+        .rx_gate(2, 0.3)
+        .build_final()
+}
+
+fn noise_model() -> NoiseModel {
+    let mut model: NoiseModel = NoiseModel::new();
+    model
+        .add_gate_error(GateKind::NumQubits(2), Channel::depolarising(0.05, 2).unwrap())
+        .unwrap()
+        .add_gate_error(GateKind::All, Channel::thermal_relaxation(40.0, 30.0, 2.0, 0.0).unwrap())
+        .unwrap()
+        .add_readout_error([[0.97, 0.03], [0.06, 0.94]], &[])
+        .unwrap();
+    model
+}
+
+fn x_observable() -> SumOp {
+    SumOp::new(vec![PauliString::new(Complex::new(1.0, 0.0)).with_op(0, Pauli::X).with_op(2, Pauli::X)])
+}
+
+#[test]
+fn test_estimate_from_samples() {
+    let estimate: Estimate = Estimate::from_samples(&[1.0, 2.0, 3.0, 4.0]);
+    assert!((estimate.mean - 2.5).abs() < 1e-12);
+    assert!((estimate.standard_error - (5.0f64 / 12.0).sqrt()).abs() < 1e-12);
+    assert!(estimate.is_consistent_with(3.0, 1.0));
+    assert!(!estimate.is_consistent_with(4.0, 2.0));
+
+    let single: Estimate = Estimate::from_samples(&[0.5]);
+    assert_eq!(single.mean, 0.5);
+    assert_eq!(single.standard_error, f64::INFINITY);
+}
+
+#[test]
+fn test_trajectories_match_density_matrix() {
+    let circuit: Circuit = noisy_circuit();
+    let model: NoiseModel = noise_model();
+    let observables: Vec<SumOp> = vec![ising_1d_uniform(3, 0.7, 1.0, 0.0).unwrap(), x_observable()];
+
+    let exact: DensityMatrix = circuit.execute_noisy(&DensityMatrix::new_zero(3).unwrap(), &model).unwrap();
+    let result: TrajectoryResult =
+        run_trajectories(&circuit, &State::new_zero(3).unwrap(), &model, &observables, 3000, Some(12)).unwrap();
+    assert_eq!(result.trajectories, 3000);
+    assert_eq!(result.counts.shots(), 3000);
+    for (observable, estimate) in observables.iter().zip(&result.expectations) {
+        let expected: f64 = exact.expectation_value(observable).unwrap().re;
+        assert!(estimate.standard_error > 0.0 && estimate.standard_error < 0.05);
+        assert!(estimate.is_consistent_with(expected, 5.0), "{:?} is not consistent with {}", estimate, expected);
+    }
+
+    // Measuring every qubit records the diagonal of the density matrix, flipped by the readout errors
+    let circuit: Circuit = CircuitBuilder::new(2)
+        .h_gate(0)
+        .cnot_gate(1, 0)
+        .measure_gate(MeasurementBasis::Computational, vec![])
+        .build_final();
+    let mut model: NoiseModel = NoiseModel::new();
+    let confusion: [[f64; 2]; 2] = [[0.9, 0.1], [0.2, 0.8]];
+    model
+        .add_gate_error(GateKind::All, Channel::amplitude_damping(0.2).unwrap())
+        .unwrap()
+        .add_readout_error(confusion, &[])
+        .unwrap();
+    let exact: DensityMatrix = circuit.execute_noisy(&DensityMatrix::new_zero(2).unwrap(), &model).unwrap();
+    let result: TrajectoryResult =
+        run_trajectories(&circuit, &State::new_zero(2).unwrap(), &model, &[], 3000, Some(5)).unwrap();
+    assert!(result.expectations.is_empty());
+    let probabilities = result.probabilities();
+    for (outcome, bitstring) in ["00", "01", "10", "11"].iter().enumerate() {
+        let expected: f64 = (0..4)
+            .map(|actual: usize| {
+                let flips: f64 = (0..2).map(|bit| confusion[(actual >> bit) & 1][(outcome >> bit) & 1]).product();
+                exact.probability(actual).unwrap() * flips
+            })
+            .sum();
+        match probabilities.get(*bitstring) {
+            Some(estimate) => assert!(estimate.is_consistent_with(expected, 5.0)),
+            None => assert!(expected < 1e-3),
+        }
+    }
+}
+
+#[test]
+fn test_trajectory_standard_errors() {
+    let circuit: Circuit = noisy_circuit();
+    let model: NoiseModel = noise_model();
+    let observables: Vec<SumOp> = vec![x_observable()];
+    let initial: State = State::new_zero(3).unwrap();
+
+    // Four times as many trajectories roughly halve the standard error
+    let few: TrajectoryResult = run_trajectories(&circuit, &initial, &model, &observables, 500, Some(1)).unwrap();
+    let many: TrajectoryResult = run_trajectories(&circuit, &initial, &model, &observables, 2000, Some(1)).unwrap();
+    let ratio: f64 = few.expectations[0].standard_error / many.expectations[0].standard_error;
+    assert!((ratio - 2.0).abs() < 0.3, "ratio {}", ratio);
+
+    // Without noise every trajectory is the same, so the standard error vanishes
+    let ideal: TrajectoryResult =
+        run_trajectories(&circuit, &initial, &NoiseModel::new(), &observables, 10, Some(1)).unwrap();
+    let expected: f64 = x_observable().expectation_value(&circuit.execute(&initial).unwrap()).unwrap().re;
+    assert!((ideal.expectations[0].mean - expected).abs() < 1e-12);
+    assert!(ideal.expectations[0].standard_error < 1e-12);
+    assert_eq!(ideal.counts.get("000"), 10);
+}
+
+#[test]
+fn test_trajectories_are_reproducible() {
+    let circuit: Circuit = noisy_circuit();
+    let model: NoiseModel = noise_model();
+    let observables: Vec<SumOp> = vec![x_observable()];
+    let initial: State = State::new_zero(3).unwrap();
+
+    let run = || run_trajectories(&circuit, &initial, &model, &observables, 200, Some(77)).unwrap();
+    let single: TrajectoryResult = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap().install(run);
+    let many: TrajectoryResult = rayon::ThreadPoolBuilder::new().num_threads(4).build().unwrap().install(run);
+    assert_eq!(single, many);
+
+    assert_eq!(
+        run_trajectories(&circuit, &initial, &model, &observables, 0, None).err(),
+        Some(Error::InvalidNumberOfMeasurements(0))
+    );
+    let out_of_range: SumOp = SumOp::new(vec![PauliString::new(Complex::new(1.0, 0.0)).with_op(3, Pauli::Z)]);
+    assert!(run_trajectories(&circuit, &initial, &model, &[out_of_range], 4, None).is_err());
+}
@@ -0,0 +1,360 @@
+use super::helpers::assert_matrices_close;
+use super::kak_tests::random_unitary;
+use crate::{
+    circuit::{Circuit, CircuitBuilder},
+    components::{
+        measurement::MeasurementBasis,
+        operator::{CNOT, Hadamard, Operator, Pauli, Toffoli},
+        state::State,
+    },
+    errors::Error,
+    subroutine::Subroutine,
+};
+use num_complex::Complex;
+use rand::{SeedableRng, rngs::StdRng};
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+fn identity(dim: usize) -> Vec<Vec<Complex<f64>>> {
+    (0..dim)
+        .map(|i| (0..dim).map(|j| Complex::new(if i == j { 1.0 } else { 0.0 }, 0.0)).collect())
+        .collect()
+}
+
+#[test]
+fn test_circuit_unitary_success() {
+    // CNOT with control on qubit 0 maps |01> (index 1) to |11> (index 3)
+    let circuit: Circuit = CircuitBuilder::new(2).cx_gates(vec![1], vec![0]).build_final();
+    let (one, zero) = (Complex::new(1.0, 0.0), Complex::new(0.0, 0.0));
+    let expected: Vec<Vec<Complex<f64>>> = vec![
+        vec![one, zero, zero, zero],
+        vec![zero, zero, zero, one],
+        vec![zero, zero, one, zero],
+        vec![zero, one, zero, zero],
+    ];
+    assert_matrices_close(&circuit.unitary().unwrap(), &expected);
+
+    let h: Complex<f64> = Complex::new(FRAC_1_SQRT_2, 0.0);
+    let circuit: Circuit = CircuitBuilder::new(1).h_gate(0).build_final();
+    assert_matrices_close(&circuit.unitary().unwrap(), &[vec![h, h], vec![h, -h]]);
+
+    assert_matrices_close(&Circuit::new(3).unitary().unwrap(), &identity(8));
+}
+
+#[test]
+fn test_qft_unitary_is_dft() {
+    // The QFT treats its first qubit as the most significant, so the matrix is the discrete
+    // Fourier transform on the bit-reversed indices
+    let num_qubits: usize = 4;
+    let dim: usize = 1 << num_qubits;
+    let reverse = |index: usize| index.reverse_bits() >> (usize::BITS as usize - num_qubits);
+    let expected: Vec<Vec<Complex<f64>>> = (0..dim)
+        .map(|row| {
+            (0..dim)
+                .map(|column| {
+                    let angle: f64 = 2.0 * PI * (reverse(row) * reverse(column)) as f64 / dim as f64;
+                    Complex::from_polar(1.0 / (dim as f64).sqrt(), angle)
+                })
+                .collect()
+        })
+        .collect();
+
+    let qubits: Vec<usize> = (0..num_qubits).collect();
+    let qft: Vec<Vec<Complex<f64>>> = Subroutine::qft(qubits.clone(), num_qubits).unitary().unwrap();
+    assert_matrices_close(&qft, &expected);
+
+    // The inverse QFT is the conjugate transpose
+    let iqft: Vec<Vec<Complex<f64>>> = Subroutine::iqft(qubits, num_qubits).unitary().unwrap();
+    let adjoint: Vec<Vec<Complex<f64>>> = (0..dim)
+        .map(|row| (0..dim).map(|column| expected[column][row].conj()).collect())
+        .collect();
+    assert_matrices_close(&iqft, &adjoint);
+}
+
+#[test]
+fn test_qft_followed_by_iqft_is_identity() {
+    let circuit: Circuit = CircuitBuilder::new(4)
+        .add_subroutine(Subroutine::qft(vec![3, 0, 2], 4))
+        .add_subroutine(Subroutine::iqft(vec![3, 0, 2], 4))
+        .build_final();
+    assert_matrices_close(&circuit.unitary().unwrap(), &identity(16));
+    assert!(circuit.equivalent_to(&Circuit::new(4), 1e-10).unwrap());
+}
+
+#[test]
+fn test_circuit_equivalent_to() {
+    // HZH = X, and Rz(θ) = e^{-iθ/2} P(θ) differs from a phase gate only by a global phase
+    let hzh: Circuit = CircuitBuilder::new(2).h_gate(1).z_gate(1).h_gate(1).build_final();
+    let x: Circuit = CircuitBuilder::new(2).x_gate(1).build_final();
+    assert!(hzh.equivalent_to(&x, 1e-10).unwrap());
+
+    let rz: Circuit = CircuitBuilder::new(1).rz_gate(0, 0.7).build_final();
+    let p: Circuit = CircuitBuilder::new(1).p_gate(0, 0.7).build_final();
+    assert!(rz.equivalent_to(&p, 1e-10).unwrap());
+    assert_ne!(rz.unitary().unwrap(), p.unitary().unwrap());
+
+    // A relative phase is not a global phase
+    let s: Circuit = CircuitBuilder::new(1).s_gate(0).build_final();
+    let z: Circuit = CircuitBuilder::new(1).z_gate(0).build_final();
+    assert!(!s.equivalent_to(&z, 1e-10).unwrap());
+
+    // Circuits on different numbers of qubits are never equivalent
+    let x_wide: Circuit = CircuitBuilder::new(3).x_gate(1).build_final();
+    assert!(!x.equivalent_to(&x_wide, 1e-10).unwrap());
+}
+
+#[test]
+fn test_circuit_unitary_errors() {
+    let measured: Circuit = CircuitBuilder::new(2)
+        .h_gate(0)
+        .measure_gate(MeasurementBasis::Computational, vec![0])
+        .build_final();
+    assert_eq!(measured.unitary(), Err(Error::NonUnitaryCircuit));
+    assert_eq!(
+        measured.equivalent_to(&Circuit::new(2), 1e-10),
+        Err(Error::NonUnitaryCircuit)
+    );
+    assert_eq!(
+        Circuit::new(2).equivalent_to(&measured, 1e-10),
+        Err(Error::NonUnitaryCircuit)
+    );
+
+    let mut subroutine: Subroutine = Subroutine::new(1);
+    subroutine.add_gates(measured.gates);
+    assert!(subroutine.unitary().is_err());
+}
+
+/// A custom operator that does not provide its adjoint
+#[derive(Debug, Clone, Copy)]
+struct OpaqueOperator;
+
+impl Operator for OpaqueOperator {
+    fn apply(
+        &self,
+        state: &State,
+        target_qubits: &[usize],
+        control_qubits: &[usize],
+    ) -> Result<State, Error> {
+        Hadamard.apply(state, target_qubits, control_qubits)
+    }
+
+    fn base_qubits(&self) -> usize {
+        1
+    }
+}
+
+/// A custom operator that flips its target when its first control qubit is |0⟩ and any others are |1⟩
+#[derive(Debug, Clone, Copy)]
+struct NegatedCNOT;
+
+impl Operator for NegatedCNOT {
+    fn apply(
+        &self,
+        state: &State,
+        target_qubits: &[usize],
+        control_qubits: &[usize],
+    ) -> Result<State, Error> {
+        let negated: &[usize] = &control_qubits[..1];
+        let flipped: State = Pauli::X.apply(state, negated, &[])?;
+        let applied: State = Pauli::X.apply(&flipped, target_qubits, control_qubits)?;
+        Pauli::X.apply(&applied, negated, &[])
+    }
+
+    fn base_qubits(&self) -> usize {
+        2
+    }
+
+    fn matrix(&self) -> Option<Vec<Vec<Complex<f64>>>> {
+        Pauli::X.matrix()
+    }
+}
+
+/// Returns a circuit on three qubits that uses every built-in operator
+fn all_operators_circuit() -> Circuit {
+    let mut rng: StdRng = StdRng::seed_from_u64(12);
+    let (cos, sin) = (0.3_f64.cos(), 0.3_f64.sin());
+    let unitary2: [[Complex<f64>; 2]; 2] = [
+        [Complex::new(cos, 0.0), -Complex::from_polar(sin, 0.5)],
+        [Complex::from_polar(sin, 1.1), Complex::from_polar(cos, 1.6)],
+    ];
+    // A cyclic shift of the basis states with a phase on each
+    let unitary_n: Vec<Vec<Complex<f64>>> = (0..8)
+        .map(|i| {
+            (0..8)
+                .map(|j| {
+                    let entry: f64 = if (j + 1) % 8 == i { 1.0 } else { 0.0 };
+                    Complex::from_polar(entry, j as f64)
+                })
+                .collect()
+        })
+        .collect();
+
+    CircuitBuilder::new(3)
+        .h_gate(0)
+        .x_gate(1)
+        .y_gate(2)
+        .z_gate(0)
+        .id_gate(1)
+        .s_gate(0)
+        .sdag_gate(1)
+        .t_gate(2)
+        .tdag_gate(0)
+        .p_gate(1, 0.4)
+        .rx_gate(2, 0.5)
+        .ry_gate(0, 0.6)
+        .rz_gate(1, 0.7)
+        .unitary_gate(2, unitary2)
+        .cnot_gate(0, 1)
+        .swap_gate(1, 2)
+        .toffoli_gate(0, 1, 2)
+        .crz_gates(vec![0], vec![2], 0.8)
+        .unitary4_gate(2, 0, random_unitary(&mut rng))
+        .unitary_n_gate(vec![1, 2, 0], unitary_n)
+        .build_final()
+}
+
+#[test]
+fn test_circuit_inverse() {
+    // The inverse undoes the circuit exactly, including the global phase
+    let circuit: Circuit = all_operators_circuit();
+    let mut round_trip: Circuit = Circuit::new(3);
+    round_trip.add_gates(circuit.gates.clone());
+    round_trip.add_gates(circuit.inverse().unwrap().gates);
+    assert_matrices_close(&round_trip.unitary().unwrap(), &identity(8));
+
+    let inverse: Vec<Vec<Complex<f64>>> = circuit.inverse().unwrap().unitary().unwrap();
+    let unitary: Vec<Vec<Complex<f64>>> = circuit.unitary().unwrap();
+    let adjoint: Vec<Vec<Complex<f64>>> = (0..8)
+        .map(|row| (0..8).map(|column| unitary[column][row].conj()).collect())
+        .collect();
+    assert_matrices_close(&inverse, &adjoint);
+
+    // The inverse QFT subroutine is the inverse of the QFT
+    let qft: Subroutine = Subroutine::qft(vec![0, 1, 2], 3);
+    assert_matrices_close(
+        &qft.inverse().unwrap().unitary().unwrap(),
+        &Subroutine::iqft(vec![0, 1, 2], 3).unitary().unwrap(),
+    );
+}
+
+#[test]
+fn test_circuit_power() {
+    let circuit: Circuit = CircuitBuilder::new(2).rx_gate(0, 0.3).cnot_gate(1, 0).build_final();
+    assert_eq!(circuit.power(3).unwrap().gates.len(), 6);
+    assert!(circuit.power(0).unwrap().gates.is_empty());
+    assert_matrices_close(&circuit.power(1).unwrap().unitary().unwrap(), &circuit.unitary().unwrap());
+
+    // Rx(θ)^4 = Rx(4θ)
+    let rx: Circuit = CircuitBuilder::new(1).rx_gate(0, 0.3).build_final();
+    let expected: Circuit = CircuitBuilder::new(1).rx_gate(0, 1.2).build_final();
+    assert_matrices_close(&rx.power(4).unwrap().unitary().unwrap(), &expected.unitary().unwrap());
+
+    // The QFT has order 4
+    let qft: Subroutine = Subroutine::qft(vec![0, 1, 2], 3);
+    assert_matrices_close(&qft.power(4).unwrap().unitary().unwrap(), &identity(8));
+
+    // Operators without an adjoint can still be repeated
+    let opaque: Circuit = CircuitBuilder::new(1)
+        .add_operator_gate(Box::new(OpaqueOperator), vec![0], vec![])
+        .build_final();
+    assert_matrices_close(&opaque.power(2).unwrap().unitary().unwrap(), &identity(2));
+}
+
+#[test]
+fn test_circuit_controlled() {
+    // The circuit acts on qubits 0-2, controlled by qubit 3
+    let circuit: Circuit = all_operators_circuit();
+    let mut wide: Circuit = Circuit::new(4);
+    wide.add_gates(circuit.gates.clone());
+    let controlled: Vec<Vec<Complex<f64>>> = wide.controlled(&[3]).unwrap().unitary().unwrap();
+
+    let unitary: Vec<Vec<Complex<f64>>> = circuit.unitary().unwrap();
+    let expected: Vec<Vec<Complex<f64>>> = (0..16)
+        .map(|row| {
+            (0..16)
+                .map(|column| match (row >> 3, column >> 3) {
+                    (0, 0) => identity(8)[row][column],
+                    (1, 1) => unitary[row & 7][column & 7],
+                    _ => Complex::new(0.0, 0.0),
+                })
+                .collect()
+        })
+        .collect();
+    assert_matrices_close(&controlled, &expected);
+
+    // Existing controls are kept without being repeated
+    let cnot: Circuit = CircuitBuilder::new(3).cnot_gate(0, 1).build_final();
+    let toffoli: Circuit = CircuitBuilder::new(3).toffoli_gate(1, 2, 0).build_final();
+    let controlled: Circuit = cnot.controlled(&[2, 1]).unwrap();
+    assert_eq!(controlled.gates[0].get_control_qubits(), Some(&vec![1, 2]));
+    assert_matrices_close(&controlled.unitary().unwrap(), &toffoli.unitary().unwrap());
+
+    // CNOT and Toffoli keep their exact number of controls, so controlling them adds Pauli-X gates
+    let state: State = State::new_zero(3).unwrap();
+    assert_eq!(CNOT.apply(&state, &[0], &[1, 2]).err(), Some(Error::InvalidNumberOfQubits(2)));
+    assert_eq!(Toffoli.apply(&state, &[0], &[1]).err(), Some(Error::InvalidNumberOfQubits(1)));
+    let controlled: Circuit = CircuitBuilder::new(4).toffoli_gate(1, 2, 0).build_final().controlled(&[3]).unwrap();
+    let expected: Circuit = CircuitBuilder::new(4).cx_gates(vec![0], vec![1, 2, 3]).build_final();
+    assert_matrices_close(&controlled.unitary().unwrap(), &expected.unitary().unwrap());
+    assert!(controlled.to_qasm().unwrap().contains("ctrl(3) @ x q[1], q[2], q[3], q[0];"));
+    assert!(cnot.controlled(&[2]).unwrap().to_qasm().unwrap().contains("ctrl(2) @ x q[1], q[2], q[0];"));
+
+    // A controlled subroutine is also exact
+    let qft: Subroutine = Subroutine::qft(vec![0, 1], 3);
+    let controlled: Vec<Vec<Complex<f64>>> = qft.controlled(&[2]).unwrap().unitary().unwrap();
+    let unitary: Vec<Vec<Complex<f64>>> = qft.unitary().unwrap();
+    for row in 0..8 {
+        for column in 0..8 {
+            let expected: Complex<f64> = if row >> 2 == 1 && column >> 2 == 1 {
+                unitary[row][column]
+            } else {
+                identity(8)[row][column]
+            };
+            assert!((controlled[row][column] - expected).norm() < 1e-10);
+        }
+    }
+}
+
+#[test]
+fn test_circuit_controlled_custom_operator() {
+    // A custom operator that requires a control and has the matrix of Pauli-X is not a CNOT, so it keeps its
+    // negated control
+    let circuit: Circuit = CircuitBuilder::new(3)
+        .add_operator_gate(Box::new(NegatedCNOT), vec![1], vec![0])
+        .build_final();
+    let controlled: Circuit = circuit.controlled(&[2]).unwrap();
+    assert_eq!(controlled.gates[0].get_control_qubits(), Some(&vec![0, 2]));
+
+    let initial: State = State::new_basis_n(3, 0b100).unwrap();
+    assert_eq!(controlled.execute(&initial).unwrap(), State::new_basis_n(3, 0b110).unwrap());
+    let initial: State = State::new_basis_n(3, 0b101).unwrap();
+    assert_eq!(controlled.execute(&initial).unwrap(), initial);
+}
+
+#[test]
+fn test_circuit_transformation_errors() {
+    let measured: Circuit = CircuitBuilder::new(2)
+        .h_gate(0)
+        .measure_gate(MeasurementBasis::Computational, vec![0])
+        .build_final();
+    assert_eq!(measured.inverse().unwrap_err(), Error::NonUnitaryCircuit);
+    assert_eq!(measured.power(2).unwrap_err(), Error::NonUnitaryCircuit);
+    assert_eq!(measured.controlled(&[1]).unwrap_err(), Error::NonUnitaryCircuit);
+
+    let opaque: Circuit = CircuitBuilder::new(2)
+        .h_gate(0)
+        .add_operator_gate(Box::new(OpaqueOperator), vec![0], vec![])
+        .build_final();
+    assert_eq!(opaque.inverse().unwrap_err(), Error::UnknownAdjoint);
+    assert!(opaque.controlled(&[1]).is_ok());
+
+    assert_eq!(
+        opaque.controlled(&[0]).unwrap_err(),
+        Error::OverlappingControlAndTargetQubits(0, 0)
+    );
+    assert_eq!(opaque.controlled(&[2]).unwrap_err(), Error::InvalidQubitIndex(2, 2));
+    assert_eq!(opaque.controlled(&[1, 1]).unwrap_err(), Error::InvalidQubitIndex(1, 2));
+
+    let mut subroutine: Subroutine = Subroutine::new(2);
+    subroutine.add_gates(opaque.gates);
+    assert_eq!(subroutine.inverse().err(), Some(Error::UnknownAdjoint));
+}